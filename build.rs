@@ -0,0 +1,101 @@
+// build.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Semua file di folder static/ ditanam ke binary (lihat src/assets.rs):
+// build script menulis daftar include_bytes! beserta hash isi (FNV-1a)
+// ke $OUT_DIR/static_assets.rs. File vendor (Bootstrap, vis-network) ikut
+// di-commit di static/vendor/ bersama SHA256SUMS-nya; build gagal bila ada
+// yang hilang atau isinya tidak cocok (tree rusak), karena UI tidak punya
+// cadangan CDN.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+/// File vendor yang wajib ada di static/ (versi dipatok di scripts/fetch_vendor.sh).
+const VENDOR: [&str; 3] = [
+    "vendor/bootstrap/bootstrap.min.css",
+    "vendor/bootstrap/bootstrap.bundle.min.js",
+    "vendor/vis-network/vis-network.min.js",
+];
+
+fn collect(dir: &Path, root: &Path, out: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect(&path, root, out);
+        } else if path.file_name().and_then(|n| n.to_str()).is_some_and(|n| !n.starts_with('.')) {
+            let rel = path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/");
+            out.push((rel, path));
+        }
+    }
+}
+
+/// static/vendor/SHA256SUMS (format `sha256sum`): path di bawah static/ → hash hex.
+fn vendor_sums(root: &Path) -> HashMap<String, String> {
+    let Ok(text) = std::fs::read_to_string(root.join("vendor/SHA256SUMS")) else {
+        return HashMap::new();
+    };
+    text.lines()
+        .filter(|l| !l.trim().is_empty() && !l.starts_with('#'))
+        .filter_map(|l| {
+            let (hash, file) = l.split_once(char::is_whitespace)?;
+            Some((format!("vendor/{}", file.trim().trim_start_matches('*')), hash.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325u64, |h, b| (h ^ u64::from(*b)).wrapping_mul(0x100000001b3))
+}
+
+fn main() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("static");
+    println!("cargo:rerun-if-changed=static");
+    let mut files = Vec::new();
+    collect(&root, &root, &mut files);
+    files.retain(|(rel, _)| rel != "vendor/SHA256SUMS");
+    files.sort();
+
+    let sums = vendor_sums(&root);
+    let mut broken = Vec::new();
+    for v in VENDOR {
+        let status = match (std::fs::read(root.join(v)), sums.get(v)) {
+            (Err(_), _) => "tidak ada",
+            (Ok(_), None) => "tidak tercatat di SHA256SUMS",
+            (Ok(bytes), Some(want)) if format!("{:x}", Sha256::digest(&bytes)) != *want => "checksum tidak cocok",
+            _ => continue,
+        };
+        broken.push(format!("{v} ({status})"));
+    }
+    if !broken.is_empty() {
+        panic!(
+            "file vendor di static/ rusak: {}\nambil ulang dari git (git checkout -- static/vendor) \
+             atau jalankan ./scripts/fetch_vendor.sh",
+            broken.join(", ")
+        );
+    }
+
+    let mut code = String::from("pub static ASSETS: &[(&str, &[u8], &str)] = &[\n");
+    for (rel, path) in &files {
+        let bytes = std::fs::read(path).expect("baca file static");
+        code.push_str(&format!(
+            "    ({rel:?}, include_bytes!({:?}), \"{:016x}\"),\n",
+            path.display().to_string(),
+            fnv1a(&bytes)
+        ));
+    }
+    code.push_str("];\n");
+    let out = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("static_assets.rs");
+    std::fs::write(out, code).expect("tulis static_assets.rs");
+}
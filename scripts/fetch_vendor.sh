@@ -0,0 +1,33 @@
+#!/bin/sh
+# scripts/fetch_vendor.sh
+# Ambil ulang file vendor front-end (versi dipatok di bawah) ke static/vendor/
+# dan tulis static/vendor/SHA256SUMS. Hasilnya di-commit, jadi `cargo build`
+# dari checkout bersih tetap jalan tanpa internet; skrip ini hanya perlu
+# dijalankan (di mesin yang punya internet) saat menaikkan versi.
+set -eu
+cd "$(dirname "$0")/.."
+
+BOOTSTRAP=5.3.3
+VIS_NETWORK=9.1.9
+
+fetch() {
+  mkdir -p "$(dirname "static/$1")"
+  echo "-> static/$1"
+  curl -fsSL "$2" -o "static/$1"
+}
+
+fetch vendor/bootstrap/bootstrap.min.css \
+  "https://cdn.jsdelivr.net/npm/bootstrap@$BOOTSTRAP/dist/css/bootstrap.min.css"
+fetch vendor/bootstrap/bootstrap.bundle.min.js \
+  "https://cdn.jsdelivr.net/npm/bootstrap@$BOOTSTRAP/dist/js/bootstrap.bundle.min.js"
+fetch vendor/vis-network/vis-network.min.js \
+  "https://unpkg.com/vis-network@$VIS_NETWORK/standalone/umd/vis-network.min.js"
+
+cd static/vendor
+{
+  echo "# bootstrap $BOOTSTRAP, vis-network $VIS_NETWORK (scripts/fetch_vendor.sh)"
+  sha256sum bootstrap/bootstrap.min.css bootstrap/bootstrap.bundle.min.js vis-network/vis-network.min.js
+} > SHA256SUMS
+cat SHA256SUMS
+
+echo "Selesai. Commit static/vendor/ lalu build ulang: cargo build --release"
@@ -0,0 +1,33 @@
+-- Job analisa (bulk / file / summary) + checkpoint per item.
+-- Dipakai untuk cancel, resume setelah server restart, dan status "interrupted".
+
+CREATE TABLE `analysis_jobs` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `app_id` bigint(20) NOT NULL,
+  `job_kind` varchar(16) NOT NULL,                    -- bulk | file | summary
+  `status` varchar(16) NOT NULL DEFAULT 'running',    -- running | done | cancelled | interrupted
+  `force_refresh` tinyint(1) NOT NULL DEFAULT 0,
+  `total` int(11) NOT NULL DEFAULT 0,
+  `done` int(11) NOT NULL DEFAULT 0,
+  `skipped` int(11) NOT NULL DEFAULT 0,
+  `failed` int(11) NOT NULL DEFAULT 0,
+  `created_at` timestamp NOT NULL DEFAULT current_timestamp(),
+  `updated_at` timestamp NOT NULL DEFAULT current_timestamp() ON UPDATE current_timestamp(),
+  PRIMARY KEY (`id`),
+  KEY `idx_jobs_app` (`app_id`, `id`),
+  KEY `idx_jobs_status` (`status`),
+  CONSTRAINT `fk_jobs_app` FOREIGN KEY (`app_id`) REFERENCES `applications` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+CREATE TABLE `analysis_job_items` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `job_id` bigint(20) NOT NULL,
+  `file_id` bigint(20) DEFAULT NULL,                  -- NULL untuk item summary
+  `kind` varchar(32) NOT NULL,
+  `status` varchar(16) NOT NULL DEFAULT 'pending',    -- pending | done | skipped | failed | cancelled
+  `error` text DEFAULT NULL,
+  `updated_at` timestamp NOT NULL DEFAULT current_timestamp() ON UPDATE current_timestamp(),
+  PRIMARY KEY (`id`),
+  KEY `idx_job_items_job` (`job_id`, `status`),
+  CONSTRAINT `fk_job_items_job` FOREIGN KEY (`job_id`) REFERENCES `analysis_jobs` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
@@ -0,0 +1,43 @@
+-- Jenis analisa sebagai data + hasil analisa ternormalisasi.
+-- Jalankan setelah sql/prompts.sql. Hasil lama di tabel `analysis`
+-- disalin ke `analysis_results`; tabel `analysis` tidak ditulis lagi.
+
+CREATE TABLE `analysis_kinds` (
+  `name` varchar(32) NOT NULL,                        -- dipakai di URL /analyze/:file_id/:kind
+  `label` varchar(100) NOT NULL,
+  `prompt` mediumtext DEFAULT NULL,                   -- template awal (v0); NULL = file prompts/<name>.txt
+  `output_format` varchar(16) NOT NULL DEFAULT 'text', -- text | markdown | json
+  `badge` varchar(16) NOT NULL DEFAULT 'secondary',
+  `sort_order` int(11) NOT NULL DEFAULT 100,
+  `enabled` tinyint(1) NOT NULL DEFAULT 1,
+  `builtin` tinyint(1) NOT NULL DEFAULT 0,
+  `created_at` timestamp NOT NULL DEFAULT current_timestamp(),
+  PRIMARY KEY (`name`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+INSERT INTO `analysis_kinds` (`name`, `label`, `badge`, `sort_order`, `builtin`) VALUES
+  ('fungsi', 'Fungsi', 'primary', 10, 1),
+  ('relasi_file', 'Relasi File', 'dark', 20, 1),
+  ('relasi_db', 'Relasi DB', 'success', 30, 1);
+
+CREATE TABLE `analysis_results` (
+  `file_id` bigint(20) NOT NULL,
+  `kind` varchar(32) NOT NULL,
+  `content` mediumtext NOT NULL,
+  `prompt_version` int(11) DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT current_timestamp(),
+  `updated_at` timestamp NOT NULL DEFAULT current_timestamp() ON UPDATE current_timestamp(),
+  PRIMARY KEY (`file_id`, `kind`),
+  KEY `idx_results_kind` (`kind`),
+  CONSTRAINT `fk_results_file` FOREIGN KEY (`file_id`) REFERENCES `files` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+INSERT INTO `analysis_results` (`file_id`, `kind`, `content`, `prompt_version`, `created_at`)
+  SELECT `file_id`, 'fungsi', `analisa_fungsi`, `analisa_fungsi_prompt`, `created_at`
+  FROM `analysis` WHERE `analisa_fungsi` IS NOT NULL;
+INSERT INTO `analysis_results` (`file_id`, `kind`, `content`, `prompt_version`, `created_at`)
+  SELECT `file_id`, 'relasi_file', `analisa_relasi_file`, `analisa_relasi_file_prompt`, `created_at`
+  FROM `analysis` WHERE `analisa_relasi_file` IS NOT NULL;
+INSERT INTO `analysis_results` (`file_id`, `kind`, `content`, `prompt_version`, `created_at`)
+  SELECT `file_id`, 'relasi_db', `analisa_relasi_db`, `analisa_relasi_db_prompt`, `created_at`
+  FROM `analysis` WHERE `analisa_relasi_db` IS NOT NULL;
@@ -0,0 +1,53 @@
+-- Call graph tingkat fungsi hasil parsing tanpa LLM (lihat src/callgraph.rs).
+-- Jalankan setelah sql/layer_rules.sql.
+-- code_symbols     : pohon definisi per file — fungsi/method dan kontainernya
+--                    (class, impl, trait, struct, enum, module), parent_id = induk
+-- code_calls       : pemanggilan di body fungsi; callee_id NULL = tidak
+--                    ter-resolve (library luar / receiver tak dikenal),
+--                    resolution = local | import | global
+-- call_index_state : penanda isi aplikasi saat terakhir diindeks; bila berubah
+--                    indeks dibangun ulang saat dibutuhkan
+
+CREATE TABLE `code_symbols` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `app_id` bigint(20) NOT NULL,
+  `file_id` bigint(20) NOT NULL,
+  `parent_id` bigint(20) DEFAULT NULL,
+  `name` varchar(255) NOT NULL,
+  `kind` varchar(16) NOT NULL,
+  `container` varchar(255) DEFAULT NULL,
+  `signature` varchar(1024) NOT NULL DEFAULT '',
+  `line_start` int(11) NOT NULL,
+  `line_end` int(11) NOT NULL,
+  PRIMARY KEY (`id`),
+  KEY `idx_symbols_app_name` (`app_id`, `name`),
+  KEY `idx_symbols_file` (`file_id`),
+  CONSTRAINT `fk_symbols_app` FOREIGN KEY (`app_id`) REFERENCES `applications` (`id`) ON DELETE CASCADE,
+  CONSTRAINT `fk_symbols_file` FOREIGN KEY (`file_id`) REFERENCES `files` (`id`) ON DELETE CASCADE,
+  CONSTRAINT `fk_symbols_parent` FOREIGN KEY (`parent_id`) REFERENCES `code_symbols` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+CREATE TABLE `code_calls` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `caller_id` bigint(20) NOT NULL,
+  `callee_id` bigint(20) DEFAULT NULL,
+  `name` varchar(255) NOT NULL,
+  `qualifier` varchar(255) DEFAULT NULL,
+  `line_no` int(11) NOT NULL,
+  `resolution` varchar(8) DEFAULT NULL,
+  PRIMARY KEY (`id`),
+  KEY `idx_calls_caller` (`caller_id`),
+  KEY `idx_calls_callee` (`callee_id`),
+  CONSTRAINT `fk_calls_caller` FOREIGN KEY (`caller_id`) REFERENCES `code_symbols` (`id`) ON DELETE CASCADE,
+  CONSTRAINT `fk_calls_callee` FOREIGN KEY (`callee_id`) REFERENCES `code_symbols` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+CREATE TABLE `call_index_state` (
+  `app_id` bigint(20) NOT NULL,
+  `signature` varchar(255) NOT NULL,
+  `symbols` int(11) NOT NULL DEFAULT 0,
+  `calls` int(11) NOT NULL DEFAULT 0,
+  `indexed_at` timestamp NOT NULL DEFAULT current_timestamp(),
+  PRIMARY KEY (`app_id`),
+  CONSTRAINT `fk_call_state_app` FOREIGN KEY (`app_id`) REFERENCES `applications` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
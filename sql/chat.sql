@@ -0,0 +1,29 @@
+-- Chat per aplikasi (lihat src/chat.rs).
+-- Jalankan setelah sql/file_imports.sql.
+-- chat_messages.sources berisi daftar sumber yang dikutip jawaban
+-- ([{n, file_id, path, line_start, line_end}]) untuk tautan sitasi.
+
+CREATE TABLE `chat_conversations` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `app_id` bigint(20) NOT NULL,
+  `title` varchar(255) NOT NULL,
+  `lang` varchar(8) NOT NULL DEFAULT 'id',
+  `created_by` varchar(100) DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT current_timestamp(),
+  PRIMARY KEY (`id`),
+  KEY `idx_chat_app` (`app_id`),
+  CONSTRAINT `fk_chat_app` FOREIGN KEY (`app_id`) REFERENCES `applications` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+CREATE TABLE `chat_messages` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `conversation_id` bigint(20) NOT NULL,
+  `role` enum('user','assistant') NOT NULL,
+  `content` mediumtext NOT NULL,
+  `sources` JSON DEFAULT NULL,
+  `prompt_version` int(11) DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT current_timestamp(),
+  PRIMARY KEY (`id`),
+  KEY `idx_chat_messages_conv` (`conversation_id`),
+  CONSTRAINT `fk_chat_messages_conv` FOREIGN KEY (`conversation_id`) REFERENCES `chat_conversations` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
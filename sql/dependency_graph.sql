@@ -0,0 +1,19 @@
+-- Graph dependensi modul per aplikasi (lihat src/depgraph.rs).
+-- Jalankan setelah sql/graph_json.sql.
+-- Satu baris per snapshot isi aplikasi: `signature` menandai jumlah/id file
+-- dan import saat graph dihitung; graph baru disimpan hanya bila berubah.
+-- `graph` berisi nodes (file, folder, bahasa), edges (file → file yang
+-- diimpor beserta spesifier import) dan jumlah file per folder.
+
+CREATE TABLE `dependency_graphs` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `app_id` bigint(20) NOT NULL,
+  `signature` varchar(255) NOT NULL,
+  `file_count` int(11) NOT NULL DEFAULT 0,
+  `edge_count` int(11) NOT NULL DEFAULT 0,
+  `graph` JSON NOT NULL,
+  `created_at` timestamp NOT NULL DEFAULT current_timestamp(),
+  PRIMARY KEY (`id`),
+  KEY `idx_depgraph_app` (`app_id`, `id`),
+  CONSTRAINT `fk_depgraph_app` FOREIGN KEY (`app_id`) REFERENCES `applications` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
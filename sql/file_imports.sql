@@ -0,0 +1,18 @@
+-- Import antar file hasil ekstraksi tanpa LLM (lihat src/imports.rs).
+-- Jalankan setelah sql/structured_output.sql.
+-- file_metadata diisi saat upload (line_count, daftar spesifier import);
+-- file_imports menyimpan edge file → file (target NULL = library luar /
+-- tidak ter-resolve). Aplikasi lama diindeks otomatis saat pertama dibutuhkan.
+
+CREATE TABLE `file_imports` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `file_id` bigint(20) NOT NULL,
+  `target_file_id` bigint(20) DEFAULT NULL,
+  `spec` varchar(512) NOT NULL,
+  `line_no` int(11) DEFAULT NULL,
+  PRIMARY KEY (`id`),
+  KEY `idx_imports_file` (`file_id`),
+  KEY `idx_imports_target` (`target_file_id`),
+  CONSTRAINT `fk_imports_file` FOREIGN KEY (`file_id`) REFERENCES `files` (`id`) ON DELETE CASCADE,
+  CONSTRAINT `fk_imports_target` FOREIGN KEY (`target_file_id`) REFERENCES `files` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
@@ -0,0 +1,12 @@
+-- Graph per file sebagai data JSON nodes/edges (lihat src/graph.rs).
+-- Jalankan setelah sql/search_index.sql.
+-- Isi lama files.json_graph berupa kode JavaScript vis-network dari LLM dan
+-- tidak lagi dijalankan; dikosongkan agar bisa di-generate ulang.
+-- Prompt `graph` hasil edit di /admin/prompts yang masih meminta JavaScript
+-- perlu disesuaikan (instruksi JSON Schema tetap ditambahkan otomatis).
+
+UPDATE files SET json_graph = NULL, json_graph_prompt = NULL
+WHERE json_graph IS NOT NULL
+  AND CASE WHEN JSON_VALID(json_graph) THEN JSON_TYPE(json_graph) <> 'OBJECT' ELSE TRUE END;
+
+ALTER TABLE files MODIFY `json_graph` JSON NULL;
@@ -0,0 +1,19 @@
+-- Aturan layering per aplikasi (lihat src/architecture.rs).
+-- Jalankan setelah sql/dependency_graph.sql.
+-- source_pattern / target_pattern : nama/awalan folder (mis. `services`,
+--                                   `src/api`) atau glob (`*`, `**`)
+-- kind = 'deny'  : file sumber tidak boleh mengimpor file tujuan
+-- kind = 'allow' : pengecualian untuk edge yang juga cocok dengan aturan deny
+
+CREATE TABLE `layer_rules` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `app_id` bigint(20) NOT NULL,
+  `source_pattern` varchar(255) NOT NULL,
+  `target_pattern` varchar(255) NOT NULL,
+  `kind` enum('deny','allow') NOT NULL DEFAULT 'deny',
+  `note` varchar(255) DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT current_timestamp(),
+  PRIMARY KEY (`id`),
+  KEY `idx_layer_rules_app` (`app_id`),
+  CONSTRAINT `fk_layer_rules_app` FOREIGN KEY (`app_id`) REFERENCES `applications` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
@@ -0,0 +1,16 @@
+-- Budget pemakaian LLM: global (app_id NULL) atau per aplikasi,
+-- per bulan kalender atau total. Panggilan LLM yang akan melewati
+-- batas token / biaya ditolak.
+
+CREATE TABLE `llm_budgets` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `app_id` bigint(20) DEFAULT NULL,                   -- NULL = global
+  `period` varchar(16) NOT NULL DEFAULT 'monthly',    -- monthly | total
+  `token_limit` bigint(20) DEFAULT NULL,
+  `cost_limit_usd` decimal(12,4) DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT current_timestamp(),
+  `updated_at` timestamp NOT NULL DEFAULT current_timestamp() ON UPDATE current_timestamp(),
+  PRIMARY KEY (`id`),
+  KEY `idx_budgets_app` (`app_id`, `period`),
+  CONSTRAINT `fk_budgets_app` FOREIGN KEY (`app_id`) REFERENCES `applications` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
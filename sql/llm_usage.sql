@@ -0,0 +1,26 @@
+-- Pencatatan setiap panggilan LLM (token, latency, estimasi biaya).
+-- Jalankan setelah sql/analysis_jobs.sql.
+
+CREATE TABLE `llm_calls` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `app_id` bigint(20) DEFAULT NULL,
+  `file_id` bigint(20) DEFAULT NULL,
+  `kind` varchar(32) NOT NULL,                     -- fungsi | relasi_file | relasi_db | summary | graph
+  `model` varchar(64) NOT NULL,
+  `prompt_tokens` int(11) NOT NULL DEFAULT 0,
+  `completion_tokens` int(11) NOT NULL DEFAULT 0,
+  `latency_ms` int(11) NOT NULL DEFAULT 0,
+  `cost_usd` decimal(12,6) NOT NULL DEFAULT 0,
+  `status` varchar(16) NOT NULL DEFAULT 'ok',      -- ok | error | cancelled
+  `error` text DEFAULT NULL,
+  `requested_by` varchar(128) DEFAULT NULL,        -- header X-User
+  `created_at` timestamp NOT NULL DEFAULT current_timestamp(),
+  PRIMARY KEY (`id`),
+  KEY `idx_llm_calls_created` (`created_at`),
+  KEY `idx_llm_calls_app` (`app_id`, `created_at`),
+  CONSTRAINT `fk_llm_calls_app` FOREIGN KEY (`app_id`) REFERENCES `applications` (`id`) ON DELETE SET NULL,
+  CONSTRAINT `fk_llm_calls_file` FOREIGN KEY (`file_id`) REFERENCES `files` (`id`) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+ALTER TABLE `analysis_jobs`
+  ADD COLUMN `requested_by` varchar(128) DEFAULT NULL AFTER `status`;
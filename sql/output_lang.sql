@@ -0,0 +1,17 @@
+-- Bahasa output analisa LLM (lihat src/lang.rs).
+-- applications.output_lang : default bahasa per aplikasi (NULL = env OUTPUT_LANG / "id")
+-- Hasil analisa & summary disimpan per bahasa, jadi PK / unique key ikut kolom lang.
+
+ALTER TABLE applications ADD COLUMN output_lang VARCHAR(8) NULL;
+
+ALTER TABLE analysis_results
+  ADD COLUMN lang VARCHAR(8) NOT NULL DEFAULT 'id' AFTER kind,
+  DROP PRIMARY KEY,
+  ADD PRIMARY KEY (file_id, kind, lang);
+
+ALTER TABLE app_summary
+  ADD COLUMN lang VARCHAR(8) NOT NULL DEFAULT 'id' AFTER app_id,
+  DROP INDEX uq_summary_app,
+  ADD UNIQUE KEY uq_summary_app_lang (app_id, lang);
+
+ALTER TABLE analysis_jobs ADD COLUMN lang VARCHAR(8) NOT NULL DEFAULT 'id' AFTER status;
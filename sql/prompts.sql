@@ -0,0 +1,24 @@
+-- Template prompt LLM yang sudah diedit (versi 1, 2, ...). Tanpa baris di
+-- tabel ini, template default dari folder prompts/ dipakai sebagai versi 0.
+-- Kolom *_prompt mencatat versi prompt yang menghasilkan setiap analisa.
+
+CREATE TABLE `prompts` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `name` varchar(32) NOT NULL,                        -- fungsi | relasi_file | relasi_db | summary | graph
+  `version` int(11) NOT NULL,
+  `template` mediumtext NOT NULL,
+  `note` varchar(255) DEFAULT NULL,
+  `created_by` varchar(100) DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT current_timestamp(),
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uniq_prompt_version` (`name`, `version`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+ALTER TABLE `analysis`
+  ADD COLUMN `analisa_fungsi_prompt` int(11) DEFAULT NULL,
+  ADD COLUMN `analisa_relasi_file_prompt` int(11) DEFAULT NULL,
+  ADD COLUMN `analisa_relasi_db_prompt` int(11) DEFAULT NULL;
+
+ALTER TABLE `app_summary` ADD COLUMN `prompt_version` int(11) DEFAULT NULL;
+
+ALTER TABLE `files` ADD COLUMN `json_graph_prompt` int(11) DEFAULT NULL;
@@ -0,0 +1,8 @@
+-- Indeks import dibangun ulang (lihat src/imports.rs).
+-- Jalankan setelah sql/file_imports.sql.
+-- Grup `use crate::{a, b::C};` di Rust kini dipecah menjadi satu import per
+-- path; file_metadata dikosongkan agar setiap aplikasi diindeks ulang saat
+-- pertama dibutuhkan.
+
+DELETE FROM `file_imports`;
+DELETE FROM `file_metadata`;
@@ -0,0 +1,36 @@
+-- Indeks pencarian semantik per aplikasi (lihat src/semantic.rs).
+-- Jalankan setelah sql/chat.sql.
+-- search_chunks      : potongan isi file (source = 'code', rentang baris) dan
+--                      hasil analisa (source = 'analysis', kind), plus embedding
+--                      (JSON array) bila EMBEDDING_MODEL diisi
+-- search_index_state : penanda isi aplikasi saat terakhir diindeks; bila berubah
+--                      (file / hasil analisa baru) indeks dibangun ulang saat dicari
+
+CREATE TABLE `search_chunks` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `app_id` bigint(20) NOT NULL,
+  `file_id` bigint(20) NOT NULL,
+  `source` enum('code','analysis') NOT NULL,
+  `kind` varchar(32) DEFAULT NULL,
+  `line_start` int(11) NOT NULL,
+  `line_end` int(11) NOT NULL,
+  `content` mediumtext NOT NULL,
+  `content_hash` char(16) NOT NULL,
+  `embedding` JSON DEFAULT NULL,
+  PRIMARY KEY (`id`),
+  KEY `idx_chunks_app` (`app_id`),
+  KEY `idx_chunks_hash` (`app_id`, `content_hash`),
+  CONSTRAINT `fk_chunks_app` FOREIGN KEY (`app_id`) REFERENCES `applications` (`id`) ON DELETE CASCADE,
+  CONSTRAINT `fk_chunks_file` FOREIGN KEY (`file_id`) REFERENCES `files` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+CREATE TABLE `search_index_state` (
+  `app_id` bigint(20) NOT NULL,
+  `signature` varchar(255) NOT NULL,
+  `backend` varchar(16) NOT NULL,             -- bm25 | embedding
+  `model` varchar(100) DEFAULT NULL,
+  `chunks` int(11) NOT NULL DEFAULT 0,
+  `indexed_at` timestamp NOT NULL DEFAULT current_timestamp(),
+  PRIMARY KEY (`app_id`),
+  CONSTRAINT `fk_index_state_app` FOREIGN KEY (`app_id`) REFERENCES `applications` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
@@ -0,0 +1,75 @@
+-- Output terstruktur (JSON) untuk jenis analisa (lihat src/schema.rs).
+-- Jalankan setelah sql/output_lang.sql.
+-- analysis_kinds.json_schema   : JSON Schema jawaban untuk output_format = 'json'
+-- analysis_results.structured  : data JSON yang lolos validasi; `content` berisi tampilan teksnya
+-- Jenis bawaan dipindah ke format json dengan schema di bawah; hasil lama
+-- tetap berupa teks (structured NULL) sampai dianalisa ulang.
+
+ALTER TABLE analysis_kinds ADD COLUMN json_schema TEXT NULL AFTER output_format;
+
+ALTER TABLE analysis_results ADD COLUMN structured JSON NULL AFTER content;
+
+UPDATE analysis_kinds SET output_format = 'json', json_schema = '{
+  "type": "object",
+  "required": ["ringkasan", "functions"],
+  "properties": {
+    "ringkasan": {"type": "string"},
+    "functions": {
+      "type": "array",
+      "items": {
+        "type": "object",
+        "required": ["name", "description"],
+        "properties": {
+          "name": {"type": "string"},
+          "params": {"type": "array", "items": {"type": "string"}},
+          "returns": {"type": ["string", "null"]},
+          "description": {"type": "string"}
+        }
+      }
+    }
+  }
+}' WHERE name = 'fungsi';
+
+UPDATE analysis_kinds SET output_format = 'json', json_schema = '{
+  "type": "object",
+  "required": ["ringkasan", "dependencies"],
+  "properties": {
+    "ringkasan": {"type": "string"},
+    "dependencies": {
+      "type": "array",
+      "items": {
+        "type": "object",
+        "required": ["path", "usage"],
+        "properties": {
+          "path": {"type": "string"},
+          "kind": {"type": "string", "enum": ["import", "include", "call", "http", "other"]},
+          "usage": {"type": "string"}
+        }
+      }
+    }
+  }
+}' WHERE name = 'relasi_file';
+
+UPDATE analysis_kinds SET output_format = 'json', json_schema = '{
+  "type": "object",
+  "required": ["ringkasan", "tables"],
+  "properties": {
+    "ringkasan": {"type": "string"},
+    "database": {"type": ["string", "null"]},
+    "tables": {
+      "type": "array",
+      "items": {
+        "type": "object",
+        "required": ["table", "operations"],
+        "properties": {
+          "table": {"type": "string"},
+          "operations": {
+            "type": "array",
+            "items": {"type": "string", "enum": ["SELECT", "INSERT", "UPDATE", "DELETE", "CREATE", "ALTER", "DROP", "OTHER"]}
+          },
+          "description": {"type": "string"}
+        }
+      }
+    }
+  }
+}' WHERE name = 'relasi_db';
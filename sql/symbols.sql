@@ -0,0 +1,12 @@
+-- Indeks simbol per file (lihat src/symbols.rs).
+-- Jalankan setelah sql/call_graph.sql.
+-- code_symbols.visibility : public | protected | private | internal
+--                           (crate / package / modul saja)
+-- Indeks call graph yang sudah ada dihapus penandanya agar dibangun ulang
+-- (dengan visibilitas) saat pertama dibutuhkan.
+
+ALTER TABLE `code_symbols`
+  ADD COLUMN `visibility` varchar(16) NOT NULL DEFAULT 'public' AFTER `container`,
+  ADD KEY `idx_symbols_file_line` (`file_id`, `line_start`);
+
+DELETE FROM `call_index_state`;
@@ -0,0 +1,449 @@
+// src/architecture.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Pemeriksaan arsitektur di atas graph dependensi (depgraph.rs):
+// - siklus import: strongly connected component (Tarjan) beserta jalur
+//   siklus terpendek di dalamnya;
+// - aturan layering per aplikasi (tabel layer_rules): pasangan pola path
+//   sumber → tujuan dengan jenis `deny` / `allow`. Edge yang cocok dengan
+//   aturan deny dan tidak dikecualikan aturan allow dilaporkan sebagai temuan.
+// Pola berupa nama/awalan folder (`controllers`, `src/api`) atau glob
+// (`*` dalam satu segmen, `**` lintas folder), dicocokkan ke path file.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+use crate::depgraph::DepGraph;
+
+/// Siklus yang lebih besar dari ini tidak dicari jalur terpendeknya per node
+/// (cukup dari satu node awal) supaya tetap cepat.
+const MAX_SCC_FULL_SEARCH: usize = 300;
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct LayerRule {
+    pub id: i64,
+    pub source_pattern: String,
+    pub target_pattern: String,
+    /// `deny` = dependensi dilarang, `allow` = pengecualian dari deny.
+    pub kind: String,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileRef {
+    pub file_id: i64,
+    pub path: String,
+    pub href: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Cycle {
+    /// Semua file di strongly connected component.
+    pub files: Vec<FileRef>,
+    /// Jalur siklus terpendek; file terakhir mengimpor file pertama.
+    pub shortest: Vec<FileRef>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Violation {
+    pub rule_id: i64,
+    pub source_pattern: String,
+    pub target_pattern: String,
+    pub note: Option<String>,
+    pub from: FileRef,
+    pub to: FileRef,
+    pub specs: Vec<String>,
+}
+
+/// Satu temuan (untuk API): siklus atau edge yang melanggar aturan layering.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Finding<'a> {
+    Cycle { size: usize, path: &'a [FileRef] },
+    LayerViolation { rule_id: i64, from: &'a FileRef, to: &'a FileRef, specs: &'a [String] },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub snapshot_id: i64,
+    pub rules: Vec<LayerRule>,
+    pub cycles: Vec<Cycle>,
+    pub violations: Vec<Violation>,
+}
+
+impl Report {
+    pub fn findings(&self) -> Vec<Finding<'_>> {
+        let mut out: Vec<Finding> = self
+            .cycles
+            .iter()
+            .map(|c| Finding::Cycle { size: c.files.len(), path: &c.shortest })
+            .collect();
+        out.extend(self.violations.iter().map(|v| Finding::LayerViolation {
+            rule_id: v.rule_id,
+            from: &v.from,
+            to: &v.to,
+            specs: &v.specs,
+        }));
+        out
+    }
+
+    /// Pelanggaran untuk satu aturan (tampilan per aturan).
+    pub fn violations_of(&self, rule_id: &i64) -> Vec<&Violation> {
+        self.violations.iter().filter(|v| v.rule_id == *rule_id).collect()
+    }
+}
+
+/// Strongly connected component (Tarjan, iteratif) yang membentuk siklus:
+/// lebih dari satu node, atau satu node dengan self-loop.
+pub fn strongly_connected(nodes: &[i64], adj: &HashMap<i64, Vec<i64>>) -> Vec<Vec<i64>> {
+    let mut index: HashMap<i64, usize> = HashMap::new();
+    let mut low: HashMap<i64, usize> = HashMap::new();
+    let mut on_stack: HashSet<i64> = HashSet::new();
+    let mut stack: Vec<i64> = Vec::new();
+    let mut next = 0;
+    let mut out = Vec::new();
+    let empty = Vec::new();
+
+    for &root in nodes {
+        if index.contains_key(&root) {
+            continue;
+        }
+        // (node, posisi tetangga berikutnya)
+        let mut work: Vec<(i64, usize)> = vec![(root, 0)];
+        while let Some(&mut (v, ref mut pos)) = work.last_mut() {
+            if *pos == 0 && !index.contains_key(&v) {
+                index.insert(v, next);
+                low.insert(v, next);
+                next += 1;
+                stack.push(v);
+                on_stack.insert(v);
+            }
+            let succ = adj.get(&v).unwrap_or(&empty);
+            if let Some(&w) = succ.get(*pos) {
+                *pos += 1;
+                if !index.contains_key(&w) {
+                    work.push((w, 0));
+                } else if on_stack.contains(&w) {
+                    let lw = index[&w].min(low[&v]);
+                    low.insert(v, lw);
+                }
+                continue;
+            }
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                let lp = low[&parent].min(low[&v]);
+                low.insert(parent, lp);
+            }
+            if low[&v] == index[&v] {
+                let mut comp = Vec::new();
+                while let Some(w) = stack.pop() {
+                    on_stack.remove(&w);
+                    comp.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                if comp.len() > 1 || succ.contains(&v) {
+                    out.push(comp);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Jalur terpendek `start` → ... → `start` di dalam komponen (BFS).
+fn shortest_cycle_from(start: i64, members: &HashSet<i64>, adj: &HashMap<i64, Vec<i64>>) -> Option<Vec<i64>> {
+    let mut prev: HashMap<i64, i64> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(v) = queue.pop_front() {
+        for &w in adj.get(&v).into_iter().flatten() {
+            if !members.contains(&w) {
+                continue;
+            }
+            if w == start {
+                let mut path = vec![v];
+                let mut cur = v;
+                while cur != start {
+                    cur = prev[&cur];
+                    path.push(cur);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if let std::collections::hash_map::Entry::Vacant(e) = prev.entry(w) {
+                e.insert(v);
+                queue.push_back(w);
+            }
+        }
+    }
+    None
+}
+
+/// Siklus terpendek di komponen: dicoba dari setiap node (atau satu node
+/// untuk komponen yang sangat besar), ambil yang paling pendek.
+pub fn shortest_cycle(comp: &[i64], adj: &HashMap<i64, Vec<i64>>) -> Vec<i64> {
+    let members: HashSet<i64> = comp.iter().copied().collect();
+    let starts = if comp.len() > MAX_SCC_FULL_SEARCH { &comp[..1] } else { comp };
+    let mut best: Option<Vec<i64>> = None;
+    for &s in starts {
+        if let Some(p) = shortest_cycle_from(s, &members, adj) {
+            if best.as_ref().map(|b| p.len() < b.len()).unwrap_or(true) {
+                let done = p.len() <= 2;
+                best = Some(p);
+                if done {
+                    break;
+                }
+            }
+        }
+    }
+    best.unwrap_or_default()
+}
+
+/// Pola aturan → regex terhadap path file.
+fn pattern_regex(pattern: &str) -> Option<Regex> {
+    let p = pattern.trim().trim_matches('/');
+    if p.is_empty() {
+        return None;
+    }
+    let re = if p.contains('*') {
+        let mut out = String::from("^");
+        let mut rest = p;
+        while !rest.is_empty() {
+            if let Some(r) = rest.strip_prefix("**") {
+                out.push_str(".*");
+                rest = r;
+            } else if let Some(r) = rest.strip_prefix('*') {
+                out.push_str("[^/]*");
+                rest = r;
+            } else {
+                let c = rest.chars().next().unwrap_or_default();
+                out.push_str(&regex::escape(&c.to_string()));
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        out.push('$');
+        out
+    } else {
+        // Nama/awalan folder di mana saja dalam path, atau path file persis
+        let e = regex::escape(p);
+        format!("(^|/){e}(/|$)")
+    };
+    Regex::new(&re).ok()
+}
+
+/// Periksa graph: siklus dan pelanggaran aturan layering.
+pub fn check(graph: &DepGraph, rules: &[LayerRule]) -> (Vec<Cycle>, Vec<Violation>) {
+    let by_id: HashMap<i64, &crate::depgraph::ModuleNode> = graph.nodes.iter().map(|n| (n.file_id, n)).collect();
+    let file_ref = |id: i64| {
+        let n = by_id.get(&id);
+        FileRef {
+            file_id: id,
+            path: n.map(|n| n.path.clone()).unwrap_or_default(),
+            href: n.map(|n| n.href.clone()).unwrap_or_default(),
+        }
+    };
+
+    let mut adj: HashMap<i64, Vec<i64>> = HashMap::new();
+    for e in &graph.edges {
+        adj.entry(e.from).or_default().push(e.to);
+    }
+    let ids: Vec<i64> = graph.nodes.iter().map(|n| n.file_id).collect();
+    let mut cycles: Vec<Cycle> = strongly_connected(&ids, &adj)
+        .into_iter()
+        .map(|comp| {
+            let shortest = shortest_cycle(&comp, &adj).into_iter().map(file_ref).collect();
+            let mut files: Vec<FileRef> = comp.into_iter().map(file_ref).collect();
+            files.sort_by(|a, b| a.path.cmp(&b.path));
+            Cycle { files, shortest }
+        })
+        .collect();
+    cycles.sort_by_key(|c| std::cmp::Reverse(c.files.len()));
+
+    let compiled: Vec<(&LayerRule, Option<Regex>, Option<Regex>)> = rules
+        .iter()
+        .map(|r| (r, pattern_regex(&r.source_pattern), pattern_regex(&r.target_pattern)))
+        .collect();
+    let hit = |re: &Option<Regex>, path: &str| re.as_ref().map(|r| r.is_match(path)).unwrap_or(false);
+    let mut violations = Vec::new();
+    for e in &graph.edges {
+        let (Some(from), Some(to)) = (by_id.get(&e.from), by_id.get(&e.to)) else {
+            continue;
+        };
+        let matching: Vec<&LayerRule> = compiled
+            .iter()
+            .filter(|(_, s, t)| hit(s, &from.path) && hit(t, &to.path))
+            .map(|(r, _, _)| *r)
+            .collect();
+        if matching.iter().any(|r| r.kind == "allow") {
+            continue;
+        }
+        for r in matching {
+            violations.push(Violation {
+                rule_id: r.id,
+                source_pattern: r.source_pattern.clone(),
+                target_pattern: r.target_pattern.clone(),
+                note: r.note.clone(),
+                from: file_ref(e.from),
+                to: file_ref(e.to),
+                specs: e.specs.clone(),
+            });
+        }
+    }
+    (cycles, violations)
+}
+
+pub async fn rules(pool: &MySqlPool, app_id: i64) -> anyhow::Result<Vec<LayerRule>> {
+    Ok(sqlx::query_as(
+        "SELECT id, source_pattern, target_pattern, kind, note, created_at FROM layer_rules
+         WHERE app_id=? ORDER BY kind DESC, id",
+    )
+    .bind(app_id)
+    .fetch_all(pool)
+    .await?)
+}
+
+/// Tambah aturan layering. Pola kosong / jenis tidak dikenal ditolak.
+pub async fn add_rule(
+    pool: &MySqlPool,
+    app_id: i64,
+    source: &str,
+    target: &str,
+    kind: &str,
+    note: Option<&str>,
+) -> anyhow::Result<()> {
+    if pattern_regex(source).is_none() || pattern_regex(target).is_none() {
+        anyhow::bail!("Pola sumber dan tujuan wajib diisi");
+    }
+    if kind != "deny" && kind != "allow" {
+        anyhow::bail!("Jenis aturan harus deny atau allow");
+    }
+    sqlx::query("INSERT INTO layer_rules (app_id, source_pattern, target_pattern, kind, note) VALUES (?, ?, ?, ?, ?)")
+        .bind(app_id)
+        .bind(source.trim().trim_matches('/'))
+        .bind(target.trim().trim_matches('/'))
+        .bind(kind)
+        .bind(note.map(str::trim).filter(|n| !n.is_empty()))
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_rule(pool: &MySqlPool, app_id: i64, rule_id: i64) -> anyhow::Result<()> {
+    sqlx::query("DELETE FROM layer_rules WHERE id=? AND app_id=?")
+        .bind(rule_id)
+        .bind(app_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Laporan arsitektur untuk snapshot graph terbaru aplikasi.
+pub async fn report(pool: &MySqlPool, app_id: i64) -> anyhow::Result<Report> {
+    let snapshot = crate::depgraph::current(pool, app_id).await?;
+    let rules = rules(pool, app_id).await?;
+    let (cycles, violations) = check(&snapshot.graph, &rules);
+    Ok(Report { snapshot_id: snapshot.id, rules, cycles, violations })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(i64, i64)]) -> HashMap<i64, Vec<i64>> {
+        let mut adj: HashMap<i64, Vec<i64>> = HashMap::new();
+        for &(a, b) in edges {
+            adj.entry(a).or_default().push(b);
+        }
+        adj
+    }
+
+    fn sccs(nodes: &[i64], edges: &[(i64, i64)]) -> Vec<Vec<i64>> {
+        let mut out = strongly_connected(nodes, &graph(edges));
+        for c in &mut out {
+            c.sort();
+        }
+        out.sort();
+        out
+    }
+
+    #[test]
+    fn self_loop_is_a_cycle() {
+        assert_eq!(sccs(&[1, 2], &[(1, 1), (1, 2)]), [vec![1]]);
+        assert_eq!(shortest_cycle(&[1], &graph(&[(1, 1)])), [1]);
+    }
+
+    #[test]
+    fn components_with_several_nodes() {
+        let edges = [(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 4), (6, 1)];
+        assert_eq!(sccs(&[1, 2, 3, 4, 5, 6], &edges), [vec![1, 2, 3], vec![4, 5]]);
+        assert!(sccs(&[1, 2, 3], &[(1, 2), (2, 3), (1, 3)]).is_empty());
+    }
+
+    #[test]
+    fn shortest_cycle_prefers_fewest_files() {
+        let adj = graph(&[(1, 2), (2, 3), (3, 1), (2, 1)]);
+        assert_eq!(shortest_cycle(&[1, 2, 3], &adj).len(), 2);
+    }
+
+    #[test]
+    fn rule_patterns() {
+        let m = |p: &str, path: &str| pattern_regex(p).map(|r| r.is_match(path)).unwrap_or(false);
+        assert!(m("domain", "src/domain/user.rs"));
+        assert!(m("/src/domain/", "src/domain/user.rs"));
+        assert!(!m("domain", "src/domains/user.rs"));
+        assert!(m("src/*/mod.rs", "src/db/mod.rs"));
+        assert!(!m("src/*/mod.rs", "src/db/x/mod.rs"));
+        assert!(m("src/**/*.rs", "src/db/x/mod.rs"));
+        assert!(m("a.b", "a.b") && !m("a.b", "axb"));
+        assert!(pattern_regex(" / ").is_none());
+    }
+
+    #[test]
+    fn deny_rules_report_violations_unless_allowed() {
+        let node = |id: i64, path: &str| crate::depgraph::ModuleNode {
+            file_id: id,
+            path: path.into(),
+            folder: path.rsplit_once('/').map(|(d, _)| d.to_string()).unwrap_or_default(),
+            language: None,
+            line_count: None,
+            imports: 0,
+            imported_by: 0,
+            href: String::new(),
+        };
+        let edge = |from: i64, to: i64| crate::depgraph::ModuleEdge { from, to, specs: vec![format!("{from}->{to}")] };
+        let graph = DepGraph {
+            nodes: vec![node(1, "src/domain/a.rs"), node(2, "src/web/h.rs"), node(3, "src/web/dto.rs")],
+            edges: vec![edge(1, 2), edge(1, 3), edge(2, 1)],
+            ..Default::default()
+        };
+        let rule = |id: i64, source: &str, target: &str, kind: &str| LayerRule {
+            id,
+            source_pattern: source.into(),
+            target_pattern: target.into(),
+            kind: kind.into(),
+            note: None,
+            created_at: Utc::now(),
+        };
+        let rules = [rule(1, "domain", "web", "deny"), rule(2, "domain", "src/web/dto.rs", "allow")];
+        let (cycles, violations) = check(&graph, &rules);
+        assert_eq!(violations.len(), 1);
+        assert_eq!((violations[0].from.file_id, violations[0].to.file_id, violations[0].rule_id), (1, 2, 1));
+        assert_eq!(cycles.len(), 1);
+        let paths: Vec<&str> = cycles[0].files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["src/domain/a.rs", "src/web/h.rs"]);
+    }
+}
@@ -0,0 +1,54 @@
+// src/assets.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Aset front-end yang ditanam di binary (folder static/, lihat build.rs) dan
+// dilayani lewat /static/. URL di template memakai `?v=<hash isi>` sehingga
+// bisa di-cache lama oleh browser. File vendor (Bootstrap, vis-network)
+// wajib ada saat build, jadi UI tidak pernah bergantung pada CDN.
+
+include!(concat!(env!("OUT_DIR"), "/static_assets.rs"));
+
+/// Cache-Control untuk URL ber-versi (`?v=` cocok dengan hash isi).
+pub const CACHE_IMMUTABLE: &str = "public, max-age=31536000, immutable";
+/// Cache-Control untuk URL tanpa versi; divalidasi ulang lewat ETag.
+pub const CACHE_SHORT: &str = "public, max-age=3600";
+
+/// Isi dan hash aset.
+pub fn get(path: &str) -> Option<(&'static [u8], &'static str)> {
+    ASSETS.iter().find(|(p, _, _)| *p == path).map(|(_, bytes, hash)| (*bytes, *hash))
+}
+
+/// URL aset untuk template: ber-versi bila ditanam, selain itu URL polos.
+pub fn url(path: &str) -> String {
+    match get(path) {
+        Some((_, hash)) => format!("/static/{path}?v={hash}"),
+        None => format!("/static/{path}"),
+    }
+}
+
+pub fn content_type(path: &str) -> &'static str {
+    let ext = path.rsplit_once('.').map(|(_, e)| e.to_ascii_lowercase()).unwrap_or_default();
+    match ext.as_str() {
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "application/javascript; charset=utf-8",
+        "json" | "map" => "application/json; charset=utf-8",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "txt" => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,87 @@
+// src/auth.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Akses admin (budget, template prompt, jenis analisa). Header X-User hanya
+// untuk atribusi biaya dan bisa dipalsukan, jadi aksi admin memakai token
+// rahasia dari env ADMIN_TOKEN: dikirim lewat header `Authorization: Bearer`
+// / `X-Admin-Token`, atau cookie yang diisi form login /admin/login.
+// Tanpa ADMIN_TOKEN semua aksi admin ditolak.
+
+use crate::i18n::Locale;
+
+pub const COOKIE: &str = "admin_token";
+
+/// Token admin (env ADMIN_TOKEN); None = admin nonaktif.
+pub fn admin_token() -> Option<String> {
+    std::env::var("ADMIN_TOKEN")
+        .ok()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+/// Bandingkan tanpa berhenti di byte pertama yang berbeda.
+fn same(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Apakah salah satu kredensial yang dikirim cocok dengan token admin.
+pub fn authorized(expected: Option<&str>, presented: &[Option<&str>]) -> bool {
+    let Some(expected) = expected else {
+        return false;
+    };
+    presented
+        .iter()
+        .flatten()
+        .map(|p| p.trim())
+        .map(|p| p.strip_prefix("Bearer ").unwrap_or(p).trim())
+        .any(|p| same(p.as_bytes(), expected.as_bytes()))
+}
+
+/// Request ke rute admin tanpa token yang valid; `back` = halaman tujuan
+/// setelah login.
+#[derive(Debug)]
+pub struct Forbidden {
+    pub loc: Locale,
+    pub back: String,
+}
+
+impl warp::reject::Reject for Forbidden {}
+
+/// Halaman 403: alasan penolakan plus form login token.
+pub fn forbidden_page(loc: Locale, back: &str) -> String {
+    let reason = if admin_token().is_some() { loc.t("admin.forbidden") } else { loc.t("admin.disabled") };
+    format!(
+        "<!doctype html><meta charset=\"utf-8\"><title>403</title>\
+         <p>{reason}</p>\
+         <form method=\"post\" action=\"/admin/login\">\
+         <input type=\"hidden\" name=\"back\" value=\"{}\"/>\
+         <input type=\"password\" name=\"token\" placeholder=\"{}\" autocomplete=\"off\"/> \
+         <button type=\"submit\">{}</button></form>",
+        crate::export::xml_escape(back),
+        loc.t("admin.token"),
+        loc.t("admin.login"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_must_be_configured_and_match() {
+        assert!(!authorized(None, &[Some("x")]));
+        assert!(!authorized(Some("s3cret"), &[None, None]));
+        assert!(!authorized(Some("s3cret"), &[Some("s3cre"), Some("s3cret!")]));
+        assert!(authorized(Some("s3cret"), &[None, Some("s3cret")]));
+        assert!(authorized(Some("s3cret"), &[Some("Bearer s3cret"), None]));
+        assert!(!authorized(Some("s3cret"), &[Some("Basic s3cret")]));
+    }
+}
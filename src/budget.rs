@@ -0,0 +1,516 @@
+// src/budget.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Batas pemakaian LLM (token / biaya) global atau per aplikasi, per bulan
+// atau total. Sebelum setiap panggilan LLM (openai.rs) perkiraan biayanya
+// dipesan (Reservation); panggilan yang akan melewati batas ditolak dengan
+// BudgetExceeded. Pesanan dilepas setelah pemakaian sebenarnya tercatat di
+// llm_calls. Juga menyediakan estimasi pre-flight untuk analisa bulk dan
+// summary.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+use crate::i18n::Locale;
+use crate::usage::{estimate_cost, estimate_tokens, LlmCtx, TokenUsage};
+
+/// Perkiraan token jawaban per panggilan (untuk estimasi pre-flight).
+const EST_COMPLETION_ANALYSIS: i64 = 700;
+const EST_COMPLETION_SUMMARY: i64 = 1500;
+/// Overhead prompt sistem + instruksi per panggilan.
+const EST_PROMPT_OVERHEAD: i64 = 40;
+
+/// Token prompt analisa satu file berukuran `chars` karakter, termasuk
+/// batas atas konteks file terkait (lihat context.rs).
+fn file_prompt_tokens(chars: i64) -> i64 {
+    (chars + 3) / 4 + EST_PROMPT_OVERHEAD + crate::context::token_budget().max(0)
+}
+
+/// Panggilan ditolak karena akan melewati `budget`.
+#[derive(Debug)]
+pub struct BudgetExceeded {
+    pub budget: BudgetStatus,
+}
+
+impl BudgetExceeded {
+    pub fn message(&self, loc: Locale) -> String {
+        loc.tf("err.budget_exceeded", &[("budget", &self.budget.describe(&loc))])
+    }
+}
+
+impl std::fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message(Locale::default()))
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+/// Perkiraan pemakaian panggilan yang sedang berjalan (sudah lolos cek,
+/// belum tercatat di llm_calls), per id pesanan.
+struct Ledger {
+    next: u64,
+    held: BTreeMap<u64, Held>,
+}
+
+#[derive(Clone, Copy)]
+struct Held {
+    app_id: Option<i64>,
+    tokens: i64,
+    cost: f64,
+}
+
+static LEDGER: Mutex<Ledger> = Mutex::new(Ledger { next: 0, held: BTreeMap::new() });
+/// Cek + pesan dijalankan satu per satu, supaya dua panggilan bersamaan
+/// tidak sama-sama lolos dari sisa budget yang hanya cukup untuk satu.
+static RESERVE: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Pesanan budget satu panggilan LLM; dilepas saat di-drop. CallRecorder
+/// menyimpannya sampai pemakaian sebenarnya masuk ke llm_calls.
+#[derive(Debug)]
+pub struct Reservation {
+    id: u64,
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        LEDGER.lock().unwrap().held.remove(&self.id);
+    }
+}
+
+/// Total pesanan yang dihitung untuk budget dengan scope `app_id`
+/// (None = global, mencakup semua aplikasi).
+fn reserved(held: &[Held], app_id: Option<i64>) -> (i64, f64) {
+    held.iter()
+        .filter(|h| app_id.is_none() || h.app_id == app_id)
+        .fold((0, 0.0), |(t, c), h| (t + h.tokens, c + h.cost))
+}
+
+/// Pesan BudgetExceeded bila error berasal dari penolakan budget.
+pub fn exceeded_message(e: &anyhow::Error, loc: Locale) -> Option<String> {
+    e.downcast_ref::<BudgetExceeded>().map(|b| b.message(loc))
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct BudgetRow {
+    pub id: i64,
+    pub app_id: Option<i64>,
+    pub nama_aplikasi: Option<String>,
+    pub period: String, // monthly | total
+    pub token_limit: Option<i64>,
+    pub cost_limit_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetStatus {
+    #[serde(flatten)]
+    pub budget: BudgetRow,
+    pub spent_tokens: i64,
+    pub spent_cost_usd: f64,
+    pub exceeded: bool,
+}
+
+impl BudgetStatus {
+    /// Apakah pemakaian tambahan `usage` (biaya `cost`) masih muat.
+    fn allows(&self, usage: TokenUsage, cost: f64) -> bool {
+        let tokens = usage.prompt_tokens + usage.completion_tokens;
+        let token_ok = self
+            .budget
+            .token_limit
+            .map(|l| self.spent_tokens + tokens <= l)
+            .unwrap_or(true);
+        let cost_ok = self
+            .budget
+            .cost_limit_usd
+            .map(|l| self.spent_cost_usd + cost <= l)
+            .unwrap_or(true);
+        token_ok && cost_ok
+    }
+
+    /// Scope + periode, mis. "Aplikasi Kasir — bulan ini".
+    pub fn label(&self, loc: &Locale) -> String {
+        let scope = match (&self.budget.app_id, &self.budget.nama_aplikasi) {
+            (None, _) => loc.t("budget.global").to_string(),
+            (Some(_), Some(n)) => loc.tf("budget.app", &[("name", n)]),
+            (Some(id), None) => loc.tf("budget.app", &[("name", &format!("#{id}"))]),
+        };
+        let period = if self.budget.period == "monthly" { "budget.monthly" } else { "budget.total" };
+        format!("{scope} — {}", loc.t(period))
+    }
+
+    fn describe(&self, loc: &Locale) -> String {
+        let mut parts = Vec::new();
+        if let Some(l) = self.budget.token_limit {
+            parts.push(format!("{} / {} {}", self.spent_tokens, l, loc.t("estimate.tokens")));
+        }
+        if let Some(l) = self.budget.cost_limit_usd {
+            parts.push(format!("${:.4} / ${:.2}", self.spent_cost_usd, l));
+        }
+        format!("{} ({})", self.label(loc), parts.join(", "))
+    }
+}
+
+const BUDGET_SELECT: &str = "SELECT b.id, b.app_id, a.nama_aplikasi, b.period,
+        b.token_limit, CAST(b.cost_limit_usd AS DOUBLE) AS cost_limit_usd
+    FROM llm_budgets b LEFT JOIN applications a ON a.id = b.app_id";
+
+/// Budget global dari env (LLM_MONTHLY_TOKEN_BUDGET / LLM_MONTHLY_BUDGET_USD),
+/// dipakai bila belum ada budget global di tabel.
+fn env_global_budget() -> Option<BudgetRow> {
+    let token_limit = std::env::var("LLM_MONTHLY_TOKEN_BUDGET").ok().and_then(|s| s.parse().ok());
+    let cost_limit_usd = std::env::var("LLM_MONTHLY_BUDGET_USD").ok().and_then(|s| s.parse().ok());
+    if token_limit.is_none() && cost_limit_usd.is_none() {
+        return None;
+    }
+    Some(BudgetRow {
+        id: 0,
+        app_id: None,
+        nama_aplikasi: None,
+        period: "monthly".into(),
+        token_limit,
+        cost_limit_usd,
+    })
+}
+
+async fn with_spending(pool: &MySqlPool, b: BudgetRow) -> anyhow::Result<BudgetStatus> {
+    let since = if b.period == "monthly" {
+        "DATE_FORMAT(NOW(), '%Y-%m-01')"
+    } else {
+        "'1970-01-01'"
+    };
+    let q = format!(
+        "SELECT CAST(COALESCE(SUM(prompt_tokens + completion_tokens),0) AS SIGNED),
+                CAST(COALESCE(SUM(cost_usd),0) AS DOUBLE)
+         FROM llm_calls
+         WHERE created_at >= {since} AND (? IS NULL OR app_id = ?)"
+    );
+    let (spent_tokens, spent_cost_usd): (i64, f64) = sqlx::query_as(&q)
+        .bind(b.app_id)
+        .bind(b.app_id)
+        .fetch_one(pool)
+        .await?;
+
+    let mut st = BudgetStatus {
+        budget: b,
+        spent_tokens,
+        spent_cost_usd,
+        exceeded: false,
+    };
+    st.exceeded = !st.allows(TokenUsage::default(), 0.0);
+    Ok(st)
+}
+
+/// Semua budget beserta pemakaiannya (halaman /usage).
+pub async fn list(pool: &MySqlPool) -> anyhow::Result<Vec<BudgetStatus>> {
+    let q = format!("{BUDGET_SELECT} ORDER BY b.app_id IS NOT NULL, b.app_id, b.period");
+    let mut rows: Vec<BudgetRow> = sqlx::query_as(&q).fetch_all(pool).await?;
+    if !rows.iter().any(|b| b.app_id.is_none()) {
+        rows.extend(env_global_budget());
+    }
+    let mut out = Vec::with_capacity(rows.len());
+    for b in rows {
+        out.push(with_spending(pool, b).await?);
+    }
+    Ok(out)
+}
+
+/// Budget yang berlaku untuk panggilan milik `app_id`: global + aplikasi itu.
+pub async fn applicable(pool: &MySqlPool, app_id: Option<i64>) -> anyhow::Result<Vec<BudgetStatus>> {
+    let q = format!("{BUDGET_SELECT} WHERE b.app_id IS NULL OR b.app_id <=> ?");
+    let mut rows: Vec<BudgetRow> = sqlx::query_as(&q).bind(app_id).fetch_all(pool).await?;
+    if !rows.iter().any(|b| b.app_id.is_none()) {
+        rows.extend(env_global_budget());
+    }
+    let mut out = Vec::with_capacity(rows.len());
+    for b in rows {
+        out.push(with_spending(pool, b).await?);
+    }
+    Ok(out)
+}
+
+/// Pesan perkiraan pemakaian panggilan; ditolak bila bersama pemakaian
+/// tercatat dan pesanan lain yang masih berjalan akan melewati salah satu
+/// budget.
+async fn reserve(
+    pool: &MySqlPool,
+    app_id: Option<i64>,
+    model: &str,
+    usage: TokenUsage,
+) -> anyhow::Result<Reservation> {
+    let cost = estimate_cost(model, usage);
+    let _turn = RESERVE.lock().await;
+    // Pesanan dibaca sebelum pemakaian di DB: pesanan yang dilepas di
+    // antaranya sudah tercatat di llm_calls, jadi tidak ada yang terlewat.
+    let held: Vec<Held> = LEDGER.lock().unwrap().held.values().copied().collect();
+    for mut st in applicable(pool, app_id).await? {
+        let (tokens, reserved_cost) = reserved(&held, st.budget.app_id);
+        st.spent_tokens += tokens;
+        st.spent_cost_usd += reserved_cost;
+        if !st.allows(usage, cost) {
+            return Err(BudgetExceeded { budget: st }.into());
+        }
+    }
+    let mut ledger = LEDGER.lock().unwrap();
+    let id = ledger.next;
+    ledger.next += 1;
+    let tokens = usage.prompt_tokens + usage.completion_tokens;
+    ledger.held.insert(id, Held { app_id, tokens, cost });
+    Ok(Reservation { id })
+}
+
+/// Pesan budget sebelum panggilan: prompt dihitung dari teks, jawaban dari
+/// perkiraan rata-rata per jenis.
+pub async fn reserve_prompt(ctx: &LlmCtx<'_>, model: &str, prompt: &str) -> anyhow::Result<Reservation> {
+    let completion_tokens = match ctx.kind {
+        "summary" => EST_COMPLETION_SUMMARY,
+        "embedding" => 0,
+        _ => EST_COMPLETION_ANALYSIS,
+    };
+    let usage = TokenUsage {
+        prompt_tokens: estimate_tokens(prompt) + EST_PROMPT_OVERHEAD,
+        completion_tokens,
+    };
+    reserve(ctx.pool, ctx.app_id, model, usage).await
+}
+
+/// Simpan / ganti budget untuk scope (global bila app_id None) + periode.
+/// Kedua batas kosong = hapus budget tersebut.
+pub async fn save(
+    pool: &MySqlPool,
+    app_id: Option<i64>,
+    period: &str,
+    token_limit: Option<i64>,
+    cost_limit_usd: Option<f64>,
+) -> anyhow::Result<()> {
+    let period = if period == "total" { "total" } else { "monthly" };
+    sqlx::query("DELETE FROM llm_budgets WHERE app_id <=> ? AND period = ?")
+        .bind(app_id)
+        .bind(period)
+        .execute(pool)
+        .await?;
+    if token_limit.is_none() && cost_limit_usd.is_none() {
+        return Ok(());
+    }
+    sqlx::query(
+        "INSERT INTO llm_budgets (app_id, period, token_limit, cost_limit_usd) VALUES (?, ?, ?, ?)",
+    )
+    .bind(app_id)
+    .bind(period)
+    .bind(token_limit)
+    .bind(cost_limit_usd)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// ===== Estimasi pre-flight =====
+
+#[derive(Debug, Serialize)]
+pub struct Estimate {
+    pub calls: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_tokens: i64,
+    pub cost_usd: f64,
+    pub allowed: bool,
+    pub message: Option<String>,
+    pub budgets: Vec<BudgetStatus>,
+}
+
+async fn finish_estimate(
+    pool: &MySqlPool,
+    app_id: i64,
+    calls: i64,
+    usage: TokenUsage,
+    loc: Locale,
+) -> anyhow::Result<Estimate> {
+    let model = crate::usage::model();
+    let cost = estimate_cost(&model, usage);
+    let budgets = applicable(pool, Some(app_id)).await?;
+    let blocking = budgets.iter().find(|b| !b.allows(usage, cost));
+    let message = blocking.map(|b| loc.tf("err.estimate_over_budget", &[("budget", &b.describe(&loc))]));
+    Ok(Estimate {
+        calls,
+        prompt_tokens: usage.prompt_tokens,
+        completion_tokens: usage.completion_tokens,
+        total_tokens: usage.prompt_tokens + usage.completion_tokens,
+        cost_usd: cost,
+        allowed: blocking.is_none(),
+        message,
+        budgets,
+    })
+}
+
+/// Estimasi analisa bulk untuk `file_ids` (hasil yang sudah di-cache tidak
+/// dihitung kecuali `force`).
+pub async fn estimate_bulk(
+    pool: &MySqlPool,
+    app_id: i64,
+    file_ids: &[i64],
+    lang: &str,
+    force: bool,
+    loc: Locale,
+) -> anyhow::Result<Estimate> {
+    let (kinds,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM analysis_kinds WHERE enabled=1")
+        .fetch_one(pool)
+        .await?;
+    let mut usage = TokenUsage::default();
+    let mut calls = 0;
+    for chunk in file_ids.chunks(500) {
+        let mut qb = sqlx::QueryBuilder::new(
+            "SELECT CAST(CHAR_LENGTH(COALESCE(f.content_file,'')) AS SIGNED),
+                    (SELECT COUNT(*) FROM analysis_results r
+                     JOIN analysis_kinds k ON k.name = r.kind AND k.enabled = 1
+                     WHERE r.file_id = f.id AND r.lang = ",
+        );
+        qb.push_bind(lang);
+        qb.push(
+            ")
+             FROM files f
+             WHERE f.id IN (",
+        );
+        let mut sep = qb.separated(", ");
+        for id in chunk {
+            sep.push_bind(id);
+        }
+        qb.push(")");
+        let rows: Vec<(i64, i64)> = qb.build_query_as().fetch_all(pool).await?;
+        for (chars, done) in rows {
+            let pending = if force { kinds } else { (kinds - done).max(0) };
+            calls += pending;
+            usage.prompt_tokens += pending * file_prompt_tokens(chars);
+            usage.completion_tokens += pending * EST_COMPLETION_ANALYSIS;
+        }
+    }
+    finish_estimate(pool, app_id, calls, usage, loc).await
+}
+
+/// Estimasi untuk daftar item (file, jenis) yang pasti dijalankan;
+/// file None = summary aplikasi.
+pub async fn estimate_items(
+    pool: &MySqlPool,
+    app_id: i64,
+    items: &[(Option<i64>, String)],
+    loc: Locale,
+) -> anyhow::Result<Estimate> {
+    let mut per_file: std::collections::HashMap<i64, i64> = std::collections::HashMap::new();
+    let mut with_summary = false;
+    for (file_id, _) in items {
+        match file_id {
+            Some(f) => *per_file.entry(*f).or_default() += 1,
+            None => with_summary = true,
+        }
+    }
+
+    let mut usage = TokenUsage::default();
+    let ids: Vec<i64> = per_file.keys().copied().collect();
+    for chunk in ids.chunks(500) {
+        let mut qb = sqlx::QueryBuilder::new(
+            "SELECT id, CAST(CHAR_LENGTH(COALESCE(content_file,'')) AS SIGNED) FROM files WHERE id IN (",
+        );
+        let mut sep = qb.separated(", ");
+        for id in chunk {
+            sep.push_bind(id);
+        }
+        qb.push(")");
+        let rows: Vec<(i64, i64)> = qb.build_query_as().fetch_all(pool).await?;
+        for (id, chars) in rows {
+            let n = per_file.get(&id).copied().unwrap_or(0);
+            usage.prompt_tokens += n * file_prompt_tokens(chars);
+            usage.completion_tokens += n * EST_COMPLETION_ANALYSIS;
+        }
+    }
+    if with_summary {
+        let payload = crate::services::summary_payload(pool, app_id).await?;
+        usage.prompt_tokens += estimate_tokens(&payload) + EST_PROMPT_OVERHEAD;
+        usage.completion_tokens += EST_COMPLETION_SUMMARY;
+    }
+    finish_estimate(pool, app_id, items.len() as i64, usage, loc).await
+}
+
+/// Estimasi summary aplikasi.
+pub async fn estimate_summary(pool: &MySqlPool, app_id: i64, loc: Locale) -> anyhow::Result<Estimate> {
+    let payload = crate::services::summary_payload(pool, app_id).await?;
+    let usage = TokenUsage {
+        prompt_tokens: estimate_tokens(&payload) + EST_PROMPT_OVERHEAD,
+        completion_tokens: EST_COMPLETION_SUMMARY,
+    };
+    finish_estimate(pool, app_id, 1, usage, loc).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(app_id: Option<i64>, token_limit: Option<i64>, spent_tokens: i64) -> BudgetStatus {
+        BudgetStatus {
+            budget: BudgetRow {
+                id: 1,
+                app_id,
+                nama_aplikasi: None,
+                period: "monthly".into(),
+                token_limit,
+                cost_limit_usd: None,
+            },
+            spent_tokens,
+            spent_cost_usd: 0.0,
+            exceeded: false,
+        }
+    }
+
+    #[test]
+    fn allows_checks_remaining_tokens() {
+        let st = status(None, Some(1000), 900);
+        assert!(st.allows(TokenUsage { prompt_tokens: 60, completion_tokens: 40 }, 0.0));
+        assert!(!st.allows(TokenUsage { prompt_tokens: 60, completion_tokens: 41 }, 0.0));
+    }
+
+    #[test]
+    fn exceeded_message_is_localized() {
+        let mut st = status(Some(3), Some(1000), 1200);
+        st.budget.nama_aplikasi = Some("Kasir".into());
+        let e = BudgetExceeded { budget: st };
+        assert!(e.message(Locale::Id).starts_with("Budget LLM terlampaui: Aplikasi Kasir — bulan ini (1200 / 1000 token)"));
+        assert!(e.message(Locale::En).starts_with("LLM budget exceeded: Application Kasir — this month (1200 / 1000 tokens)"));
+        let global = status(None, None, 0);
+        assert_eq!(global.label(&Locale::En), "Global — this month");
+    }
+
+    #[test]
+    fn reserved_counts_scope() {
+        let held = [
+            Held { app_id: Some(1), tokens: 100, cost: 0.5 },
+            Held { app_id: Some(2), tokens: 10, cost: 0.25 },
+            Held { app_id: None, tokens: 1, cost: 0.0 },
+        ];
+        assert_eq!(reserved(&held, None), (111, 0.75));
+        assert_eq!(reserved(&held, Some(1)), (100, 0.5));
+        assert_eq!(reserved(&held, Some(3)), (0, 0.0));
+    }
+
+    #[test]
+    fn reservation_released_on_drop() {
+        let id = {
+            let mut ledger = LEDGER.lock().unwrap();
+            let id = ledger.next;
+            ledger.next += 1;
+            ledger.held.insert(id, Held { app_id: Some(9), tokens: 5, cost: 0.0 });
+            id
+        };
+        let r = Reservation { id };
+        assert!(LEDGER.lock().unwrap().held.contains_key(&id));
+        drop(r);
+        assert!(!LEDGER.lock().unwrap().held.contains_key(&id));
+    }
+}
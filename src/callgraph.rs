@@ -0,0 +1,1645 @@
+// src/callgraph.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Call graph tingkat fungsi tanpa LLM untuk Rust, Python, JavaScript/
+// TypeScript, PHP, Go dan Java. Setiap file di-tokenisasi (komentar dan
+// string dibuang), lalu disusun pohon definisi: fungsi/method beserta
+// kontainernya (impl, class, trait, struct, module) dan rentang barisnya —
+// lewat kurung kurawal, atau indentasi untuk Python. Pemanggilan `f(`,
+// `obj.f(`, `Tipe::f(`, `new Kelas(` di dalam body fungsi di-resolve ke
+// definisi: file yang sama dulu, lalu file yang diimpor (file_imports), lalu
+// nama yang unik di seluruh aplikasi. Hasilnya disimpan di code_symbols /
+// code_calls dan diindeks ulang bila isi aplikasi berubah (lihat
+// `ensure_indexed`).
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+/// Kedalaman default / maksimum graph yang berakar di satu fungsi.
+pub const DEFAULT_DEPTH: usize = 2;
+pub const MAX_DEPTH: usize = 5;
+/// Batas node graph agar tetap terbaca (sisanya dipotong, `truncated`).
+const MAX_NODES: usize = 150;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lang {
+    Rust,
+    Python,
+    Js,
+    Php,
+    Go,
+    Java,
+}
+
+fn lang_of(path: &str) -> Option<Lang> {
+    match path.rsplit_once('.').map(|(_, e)| e.to_ascii_lowercase()).as_deref() {
+        Some("rs") => Some(Lang::Rust),
+        Some("py") => Some(Lang::Python),
+        Some("js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx") => Some(Lang::Js),
+        Some("php") => Some(Lang::Php),
+        Some("go") => Some(Lang::Go),
+        Some("java") => Some(Lang::Java),
+        _ => None,
+    }
+}
+
+/// Pemisah nama berkualifikasi: `Tipe::f` (Rust, PHP) atau `Kelas.f`.
+fn separator(path: &str) -> &'static str {
+    match lang_of(path) {
+        Some(Lang::Rust | Lang::Php) => "::",
+        _ => ".",
+    }
+}
+
+/// `Kontainer::nama` / `Kontainer.nama` untuk fungsi/method, selain itu `nama`.
+pub fn qualified(path: &str, kind: &str, container: Option<&str>, name: &str) -> String {
+    match container {
+        Some(c) if kind == "function" || kind == "method" => format!("{c}{}{name}", separator(path)),
+        _ => name.to_string(),
+    }
+}
+
+// ===== Tokenizer =====
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tk {
+    Ident,
+    Punct,
+    /// String / angka; isinya tidak dipakai.
+    Literal,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: Tk,
+    text: String,
+    line: usize,
+}
+
+/// Lewati string berkutip `quote` mulai `i` (tepat setelah kutip pembuka).
+/// Mengembalikan posisi setelah kutip penutup; `line` ikut dimajukan.
+fn skip_quoted(chars: &[char], mut i: usize, quote: &[char], escapes: bool, line: &mut usize) -> usize {
+    while i < chars.len() {
+        if escapes && chars[i] == '\\' {
+            if chars.get(i + 1) == Some(&'\n') {
+                *line += 1;
+            }
+            i += 2;
+            continue;
+        }
+        if chars[i..].starts_with(quote) {
+            return i + quote.len();
+        }
+        if chars[i] == '\n' {
+            *line += 1;
+        }
+        i += 1;
+    }
+    i
+}
+
+/// Apakah `/` setelah token `prev` membuka literal regex JS (`/"/g`), bukan
+/// pembagian: di awal file atau baris, setelah operator / `(` / `,` / `=`,
+/// atau setelah kata kunci seperti `return`.
+fn regex_allowed(prev: Option<&Token>, line: usize) -> bool {
+    let Some(prev) = prev else {
+        return true;
+    };
+    if prev.line != line {
+        return true;
+    }
+    match prev.kind {
+        Tk::Literal => false,
+        Tk::Ident => matches!(
+            prev.text.as_str(),
+            "return" | "typeof" | "case" | "do" | "else" | "in" | "of" | "new" | "delete" | "void" | "throw" | "yield"
+                | "await" | "instanceof"
+        ),
+        Tk::Punct => !matches!(prev.text.as_str(), ")" | "]" | "}"),
+    }
+}
+
+/// Lewati literal regex mulai `i` (tepat setelah `/` pembuka) beserta flag-nya.
+/// Kutip di dalamnya bukan string; `/` di dalam `[...]` tidak menutup.
+fn skip_regex(chars: &[char], mut i: usize) -> usize {
+    let mut class = false;
+    while i < chars.len() && chars[i] != '\n' {
+        match chars[i] {
+            '\\' => i += 1,
+            '[' => class = true,
+            ']' => class = false,
+            '/' if !class => {
+                i += 1;
+                while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                return i;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+fn is_ident_start(c: char, lang: Lang) -> bool {
+    c.is_alphabetic() || c == '_' || (c == '$' && matches!(lang, Lang::Js | Lang::Php))
+}
+
+fn is_ident_char(c: char, lang: Lang) -> bool {
+    c.is_alphanumeric() || c == '_' || (c == '$' && lang == Lang::Js)
+}
+
+/// Token identifier/punct; komentar dibuang, string & angka jadi `Literal`.
+fn lex(lang: Lang, code: &str) -> Vec<Token> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut out = Vec::new();
+    let (mut i, mut line) = (0, 1);
+    let slash_comments = lang != Lang::Python;
+    let hash_comments = matches!(lang, Lang::Python | Lang::Php);
+    let literal = |line: usize| Token { kind: Tk::Literal, text: String::new(), line };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied().unwrap_or('\0');
+        if c == '\n' {
+            line += 1;
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        // Komentar
+        if (slash_comments && c == '/' && next == '/') || (hash_comments && c == '#' && !(lang == Lang::Php && next == '[')) {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        if slash_comments && c == '/' && next == '*' {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            i += 2;
+            continue;
+        }
+        // Regex JS: kutip di dalamnya tidak boleh membuka string
+        if lang == Lang::Js && c == '/' && regex_allowed(out.last(), line) {
+            i = skip_regex(&chars, i + 1);
+            out.push(literal(line));
+            continue;
+        }
+        // String
+        if c == '"' || c == '`' || (c == '\'' && lang != Lang::Rust) {
+            let start = line;
+            let triple = matches!(lang, Lang::Python | Lang::Java) && chars[i..].starts_with(&[c, c, c]);
+            i = if triple {
+                skip_quoted(&chars, i + 3, &[c, c, c], true, &mut line)
+            } else {
+                // Backtick Go = raw string, tanpa escape
+                skip_quoted(&chars, i + 1, &[c], !(c == '`' && lang == Lang::Go), &mut line)
+            };
+            out.push(literal(start));
+            continue;
+        }
+        if c == '\'' {
+            // Rust: char literal ('a', '\n') atau lifetime ('a) yang dilewati saja
+            if next == '\\' || chars.get(i + 2) == Some(&'\'') {
+                i = skip_quoted(&chars, i + 1, &['\''], true, &mut line);
+                out.push(literal(line));
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+        if c.is_ascii_digit() {
+            // Titik hanya bila diikuti digit (`1.5`), bukan range `0..n`
+            while i < chars.len()
+                && (chars[i].is_alphanumeric()
+                    || chars[i] == '_'
+                    || (chars[i] == '.' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())))
+            {
+                i += 1;
+            }
+            out.push(literal(line));
+            continue;
+        }
+        if is_ident_start(c, lang) {
+            let start = i;
+            i += 1;
+            while i < chars.len() && is_ident_char(chars[i], lang) {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            // Prefix string: r"", b"", f'' (Python), r#""# / br"" (Rust)
+            let quote = chars.get(i).copied().unwrap_or('\0');
+            let prefix = match lang {
+                Lang::Python => text.len() <= 2 && text.chars().all(|c| "rbfuRBFU".contains(c)),
+                Lang::Rust => matches!(text.as_str(), "r" | "b" | "br"),
+                _ => false,
+            };
+            let hashes = chars[i..].iter().take_while(|c| **c == '#').count();
+            // `r#"…"#` = raw string, `r#type` = raw identifier
+            let raw = lang == Lang::Rust && text.contains('r') && chars.get(i + hashes) == Some(&'"');
+            if prefix && (quote == '"' || quote == '\'' || raw) {
+                let start_line = line;
+                if raw {
+                    let mut close = vec!['"'];
+                    close.extend(std::iter::repeat_n('#', hashes));
+                    i = skip_quoted(&chars, i + hashes + 1, &close, false, &mut line);
+                } else if lang == Lang::Python && chars[i..].starts_with(&[quote, quote, quote]) {
+                    i = skip_quoted(&chars, i + 3, &[quote, quote, quote], true, &mut line);
+                } else {
+                    i = skip_quoted(&chars, i + 1, &[quote], !text.contains(['r', 'R']), &mut line);
+                }
+                out.push(literal(start_line));
+                continue;
+            }
+            out.push(Token { kind: Tk::Ident, text, line });
+            continue;
+        }
+        let two: String = chars[i..chars.len().min(i + 2)].iter().collect();
+        let len = if matches!(two.as_str(), "::" | "->" | "=>" | "?.") { 2 } else { 1 };
+        out.push(Token { kind: Tk::Punct, text: chars[i..i + len].iter().collect(), line });
+        i += len;
+    }
+    out
+}
+
+// ===== Pohon definisi =====
+
+/// Definisi di satu file: fungsi, method, atau kontainernya.
+#[derive(Debug, Clone, Serialize)]
+pub struct Symbol {
+    pub name: String,
+    /// function | method | class | interface | trait | struct | enum | impl | module
+    pub kind: &'static str,
+    /// Kontainer terdekat, mis. `Parser` untuk method `Parser::parse`.
+    pub container: Option<String>,
+    /// public | protected | private | internal (crate / package / modul saja)
+    pub visibility: &'static str,
+    /// Indeks `Symbol` induk di file yang sama (pohon definisi).
+    pub parent: Option<usize>,
+    pub line_start: usize,
+    pub line_end: usize,
+    /// Baris deklarasi (dipangkas), ringkasan di daftar simbol.
+    pub signature: String,
+}
+
+impl Symbol {
+    pub fn callable(&self) -> bool {
+        matches!(self.kind, "function" | "method")
+    }
+}
+
+/// Pemanggilan di dalam body fungsi `caller` (indeks `Symbol`).
+#[derive(Debug, Clone)]
+pub struct Call {
+    pub caller: usize,
+    pub name: String,
+    /// None = panggilan langsung `f()`; `self` = receiver milik kontainer
+    /// pemanggil (self/this/$this/Self/cls, receiver Go); `Foo` dari
+    /// `Foo::f()` / `Foo.f()` / `new Foo()`; string kosong = ekspresi lain.
+    pub qualifier: Option<String>,
+    pub line: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct Parsed {
+    pub symbols: Vec<Symbol>,
+    pub calls: Vec<Call>,
+}
+
+const CONTAINERS: &[&str] = &["class", "interface", "trait", "struct", "enum", "impl", "module"];
+
+/// Kata kunci / konstruksi bahasa yang tampak seperti `nama(`.
+const NOT_CALLS: &[&str] = &[
+    "if", "else", "elif", "for", "foreach", "while", "do", "switch", "case", "catch", "return", "match", "loop",
+    "sizeof", "typeof", "instanceof", "function", "fn", "func", "def", "class", "new", "super", "this", "self",
+    "and", "or", "not", "in", "is", "with", "assert", "lambda", "yield", "await", "async", "throw", "throws",
+    "synchronized", "isset", "empty", "unset", "echo", "print", "array", "list", "exit", "die", "eval", "defer",
+    "go", "select", "where", "as", "del", "except", "raise", "try", "finally", "import", "from", "use", "include",
+    "include_once", "require_once", "static", "public", "private", "protected", "final", "abstract", "const", "let",
+    "var", "void", "delete", "default", "impl", "struct", "enum", "trait", "interface", "mod", "pub", "unsafe",
+    "extern", "crate", "Self", "$this", "constructor",
+];
+
+/// Nama receiver yang berarti "objek/kontainer ini".
+const SELF_NAMES: &[&str] = &["self", "Self", "this", "$this", "cls", "static"];
+
+fn text(toks: &[Token], j: usize) -> &str {
+    toks.get(j).map(|t| t.text.as_str()).unwrap_or("")
+}
+
+fn ident(toks: &[Token], j: usize) -> Option<&str> {
+    toks.get(j).filter(|t| t.kind == Tk::Ident).map(|t| t.text.as_str())
+}
+
+/// Posisi kurung penutup pasangan `toks[open]` (`(`, `[`, `{` atau `<`).
+fn matching(toks: &[Token], open: usize) -> Option<usize> {
+    let (o, c) = match text(toks, open) {
+        "(" => ("(", ")"),
+        "[" => ("[", "]"),
+        "{" => ("{", "}"),
+        "<" => ("<", ">"),
+        _ => return None,
+    };
+    let mut depth = 0;
+    for (j, t) in toks.iter().enumerate().skip(open) {
+        if t.kind != Tk::Punct {
+            continue;
+        }
+        if t.text == o {
+            depth += 1;
+        } else if t.text == c {
+            depth -= 1;
+            if depth == 0 {
+                return Some(j);
+            }
+        } else if o == "<" && matches!(t.text.as_str(), ";" | "{" | "}") {
+            return None;
+        }
+    }
+    None
+}
+
+/// Definisi yang dikenali di token `i`.
+struct Found {
+    name: String,
+    kind: &'static str,
+    /// Token nama (bukan pemanggilan).
+    name_idx: usize,
+    /// Kontainer eksplisit (receiver Go); default = kontainer di scope.
+    container: Option<String>,
+    /// Nama variabel receiver Go (`r` di `func (r *Repo) f()`).
+    receiver: Option<String>,
+    /// false = tanpa body `{}` (arrow function berupa ekspresi).
+    body: bool,
+    /// Visibilitas tetap (impl trait Rust); default dihitung dari modifier.
+    visibility: Option<&'static str>,
+}
+
+impl Found {
+    fn new(name: &str, kind: &'static str, name_idx: usize) -> Self {
+        Found { name: name.to_string(), kind, name_idx, container: None, receiver: None, body: true, visibility: None }
+    }
+}
+
+/// `impl<T> Trait for Tipe<T>` / `impl Tipe` → nama tipe.
+fn rust_impl(toks: &[Token], i: usize) -> Option<Found> {
+    let mut j = i + 1;
+    if text(toks, j) == "<" {
+        j = matching(toks, j)? + 1;
+    }
+    let mut last: Option<usize> = None;
+    let mut depth = 0;
+    let mut for_trait = false;
+    while j < toks.len() {
+        match text(toks, j) {
+            "<" => depth += 1,
+            ">" => depth -= 1,
+            "{" | ";" | "where" if depth == 0 => break,
+            "for" if depth == 0 => {
+                last = None;
+                for_trait = true;
+            }
+            _ if depth == 0 && toks[j].kind == Tk::Ident && !matches!(text(toks, j), "dyn" | "unsafe" | "const") => {
+                last = Some(j)
+            }
+            _ => {}
+        }
+        j += 1;
+    }
+    let name_idx = last?;
+    let mut f = Found::new(&toks[name_idx].text, "impl", name_idx);
+    // Anggota impl trait mengikuti visibilitas trait-nya
+    f.visibility = Some(if for_trait { "public" } else { "private" });
+    Some(f)
+}
+
+/// Receiver Go `(r *Repo[T])` → (variabel, tipe).
+fn go_receiver(toks: &[Token], open: usize, close: usize) -> (Option<String>, Option<String>) {
+    let mut names = Vec::new();
+    let mut depth = 0;
+    for t in &toks[open + 1..close] {
+        match t.text.as_str() {
+            "[" => depth += 1,
+            "]" => depth -= 1,
+            _ if depth == 0 && t.kind == Tk::Ident => names.push(t.text.clone()),
+            _ => {}
+        }
+    }
+    match names.len() {
+        0 => (None, None),
+        1 => (None, names.pop()),
+        _ => (names.first().cloned(), names.pop()),
+    }
+}
+
+/// `= [async] function (…) {` atau `= [async] (…) =>` / `= x =>` mulai
+/// token `j`. Mengembalikan (apakah body `{}`, token `function` bila ada).
+fn js_function_value(toks: &[Token], mut j: usize) -> Option<(bool, Option<usize>)> {
+    if text(toks, j) == "async" {
+        j += 1;
+    }
+    if text(toks, j) == "function" {
+        return Some((true, Some(j)));
+    }
+    let arrow = if text(toks, j) == "(" {
+        let close = matching(toks, j)?;
+        (close + 1..toks.len().min(close + 16))
+            .take_while(|k| !matches!(text(toks, *k), ";" | "{"))
+            .find(|k| text(toks, *k) == "=>")?
+    } else if ident(toks, j).is_some() && text(toks, j + 1) == "=>" {
+        j + 1
+    } else {
+        return None;
+    };
+    Some((text(toks, arrow + 1) == "{", None))
+}
+
+/// Header method di body class (Java, JS/TS): `nama(…) [throws X | : T] {`
+/// atau deklarasi `;`. Mengembalikan apakah ada body.
+fn method_header(toks: &[Token], i: usize) -> Option<bool> {
+    let prev = if i == 0 { "" } else { text(toks, i - 1) };
+    if matches!(prev, "." | "=" | "@" | "->" | "::" | "?." | "new" | "(" | "," | ":" | "!" | "return" | "throw") {
+        return None;
+    }
+    let mut j = i + 1;
+    if text(toks, j) == "<" {
+        j = matching(toks, j)? + 1;
+    }
+    if text(toks, j) != "(" {
+        return None;
+    }
+    let close = matching(toks, j)?;
+    for (n, t) in toks.iter().enumerate().skip(close + 1) {
+        match t.text.as_str() {
+            "{" => return Some(true),
+            // Deklarasi tanpa body hanya bila didahului tipe/modifier (`void f();`)
+            ";" => {
+                let typed = i > 0 && (toks[i - 1].kind == Tk::Ident || matches!(prev, ">" | "]"));
+                return typed.then_some(false);
+            }
+            "." | "," | "<" | ">" | "[" | "]" | ":" | "?" | "|" | "&" => {}
+            _ if t.kind == Tk::Ident => {}
+            _ => return None,
+        }
+        if n > close + 24 {
+            return None;
+        }
+    }
+    None
+}
+
+/// Definisi di token `i` untuk bahasa berkurung kurawal. `class_body` =
+/// token berada langsung di body class/interface (bukan di dalam method).
+fn definition(lang: Lang, toks: &[Token], i: usize, class_body: bool) -> Option<(Found, Option<usize>)> {
+    let word = text(toks, i);
+    let prev = if i == 0 { "" } else { text(toks, i - 1) };
+    if matches!(prev, "." | "::" | "->" | "?.") {
+        return None;
+    }
+    let named = |kind: &'static str| ident(toks, i + 1).map(|n| Found::new(n, kind, i + 1));
+    let found = match (lang, word) {
+        (Lang::Rust, "fn") => named("function"),
+        // Bukan `x: impl Trait` / `-> impl Trait`
+        (Lang::Rust, "impl") if matches!(prev, "" | "}" | ";" | "{" | "]" | "unsafe" | "default") => rust_impl(toks, i),
+        (Lang::Rust, "trait") => named("trait"),
+        (Lang::Rust, "struct") => named("struct"),
+        (Lang::Rust, "enum") => named("enum"),
+        (Lang::Rust, "mod") => named("module"),
+        (Lang::Go, "func") => {
+            if text(toks, i + 1) == "(" {
+                let close = matching(toks, i + 1)?;
+                let name = ident(toks, close + 1)?;
+                if !matches!(text(toks, close + 2), "(" | "[") {
+                    return None; // function literal `func(x int) error {`
+                }
+                let (receiver, ty) = go_receiver(toks, i + 1, close);
+                let mut f = Found::new(name, "method", close + 1);
+                f.container = ty;
+                f.receiver = receiver;
+                Some(f)
+            } else {
+                named("function").filter(|_| matches!(text(toks, i + 2), "(" | "["))
+            }
+        }
+        (Lang::Go, "type") => {
+            let name = ident(toks, i + 1)?;
+            let mut j = i + 2;
+            if text(toks, j) == "[" {
+                j = matching(toks, j)? + 1;
+            }
+            match text(toks, j) {
+                "struct" => Some(Found::new(name, "struct", i + 1)),
+                "interface" => Some(Found::new(name, "interface", i + 1)),
+                _ => None,
+            }
+        }
+        (Lang::Js | Lang::Php, "function") => {
+            let mut j = i + 1;
+            if matches!(text(toks, j), "*" | "&") {
+                j += 1;
+            }
+            let name = ident(toks, j).filter(|_| matches!(text(toks, j + 1), "(" | "<"))?;
+            Some(Found::new(name, "function", j))
+        }
+        (Lang::Js | Lang::Php | Lang::Java, "class") if prev != "new" => named("class"),
+        (Lang::Js | Lang::Php | Lang::Java, "interface") => {
+            named("interface").filter(|_| matches!(text(toks, i + 2), "{" | "<" | "extends"))
+        }
+        (Lang::Php, "trait") => named("trait"),
+        (Lang::Php | Lang::Java, "enum") => named("enum"),
+        (Lang::Java, "record") => named("class").filter(|_| matches!(text(toks, i + 2), "(" | "<")),
+        (Lang::Js, "const" | "let" | "var") => {
+            let name = ident(toks, i + 1)?;
+            let mut j = i + 2;
+            if text(toks, j) == ":" {
+                j = (j..toks.len().min(j + 12)).find(|k| text(toks, *k) == "=")?;
+            }
+            if text(toks, j) != "=" {
+                return None;
+            }
+            let (body, func) = js_function_value(toks, j + 1)?;
+            let mut f = Found::new(name, "function", i + 1);
+            f.body = body;
+            return Some((f, func));
+        }
+        _ => None,
+    };
+    if found.is_some() {
+        return found.map(|f| (f, None));
+    }
+    // Anggota class: method `nama(…) {` (Java, JS/TS) atau field `nama = (…) =>` (JS)
+    // Hanya kata kunci yang bisa muncul di body class; nama lain (`print`, `delete`, `go`) boleh jadi method
+    let keyword = matches!(
+        word,
+        "if" | "for" | "while" | "switch" | "catch" | "return" | "new" | "super" | "this" | "function" | "synchronized"
+    );
+    if class_body && matches!(lang, Lang::Js | Lang::Java) && !keyword {
+        if lang == Lang::Js && text(toks, i + 1) == "=" {
+            let (body, func) = js_function_value(toks, i + 2)?;
+            let mut f = Found::new(word, "method", i);
+            f.body = body;
+            return Some((f, func));
+        }
+        let body = method_header(toks, i)?;
+        let mut f = Found::new(word, "method", i);
+        f.body = body;
+        return Some((f, None));
+    }
+    None
+}
+
+/// Nama & kualifier pemanggilan di token `i` (sudah pasti diikuti `(`).
+fn call_at(lang: Lang, toks: &[Token], i: usize) -> Option<(String, Option<String>)> {
+    let name = toks[i].text.as_str();
+    if name.starts_with('$') {
+        return None;
+    }
+    let prev = if i == 0 { "" } else { text(toks, i - 1) };
+    // `.` di PHP = sambung string; `->` hanya akses anggota di PHP
+    let member = match prev {
+        "." => lang != Lang::Php,
+        "->" => lang == Lang::Php,
+        "?." | "::" => true,
+        _ => false,
+    };
+    if member {
+        let qualifier = match ident(toks, i.wrapping_sub(2)) {
+            Some(q) if SELF_NAMES.contains(&q) => "self".to_string(),
+            Some(q) => q.to_string(),
+            None => String::new(),
+        };
+        return Some((name.to_string(), Some(qualifier)));
+    }
+    if NOT_CALLS.contains(&name) {
+        return None;
+    }
+    match prev {
+        "fn" | "function" | "def" | "func" | "class" | "@" => None,
+        // Konstruktor: `new Foo(` → method konstruktor milik Foo
+        "new" if matches!(lang, Lang::Java | Lang::Js | Lang::Php) => {
+            let ctor = match lang {
+                Lang::Php => "__construct",
+                Lang::Js => "constructor",
+                _ => name,
+            };
+            Some((ctor.to_string(), Some(name.to_string())))
+        }
+        // Rust: `Some(`, `Ok(`, `Foo(` = varian enum / tuple struct, bukan fungsi
+        _ if lang == Lang::Rust && name.starts_with(char::is_uppercase) => None,
+        _ => Some((name.to_string(), None)),
+    }
+}
+
+/// Visibilitas definisi yang dimulai di token `start`: modifier di depannya
+/// (`pub`, `public`, `private`, `export`, `#nama`), konvensi nama (Go,
+/// Python), atau default bahasa untuk definisi tanpa modifier.
+fn visibility(lang: Lang, toks: &[Token], start: usize, name: &str, parent: Option<&Symbol>) -> &'static str {
+    // Fungsi di dalam fungsi hanya terlihat di situ
+    if parent.is_some_and(|p| p.callable()) {
+        return "private";
+    }
+    match lang {
+        Lang::Go if name.starts_with(char::is_uppercase) => return "public",
+        Lang::Go => return "internal",
+        Lang::Python if name.starts_with('_') && !name.ends_with("__") => return "private",
+        Lang::Python => return "public",
+        _ => {}
+    }
+    // Modifier: token sebelum `start` sampai akhir deklarasi sebelumnya
+    let mut mods: Vec<usize> = Vec::new();
+    let mut j = start;
+    while j > 0 && start - j < 16 {
+        j -= 1;
+        if matches!(text(toks, j), ";" | "{" | "}") {
+            break;
+        }
+        mods.push(j);
+    }
+    let has = |m: &str| mods.iter().any(|k| text(toks, *k) == m);
+    if lang == Lang::Rust {
+        if let Some(k) = mods.iter().find(|k| text(toks, **k) == "pub") {
+            // pub(crate) / pub(super) / pub(in path)
+            return if text(toks, k + 1) == "(" { "internal" } else { "public" };
+        }
+        return match parent {
+            Some(p) if p.kind == "trait" || (p.kind == "impl" && p.visibility == "public") => "public",
+            _ => "private",
+        };
+    }
+    if has("private") || (lang == Lang::Js && start > 0 && text(toks, start - 1) == "#") {
+        "private"
+    } else if has("protected") {
+        "protected"
+    } else if has("public") || has("export") {
+        "public"
+    } else {
+        match (lang, parent) {
+            (Lang::Java, Some(p)) if p.kind == "interface" => "public",
+            // Java tanpa modifier = package-private; JS/TS tanpa export = modul saja
+            (Lang::Java, _) | (Lang::Js, None) => "internal",
+            _ => "public",
+        }
+    }
+}
+
+fn signature_of(lines: &[&str], line: usize) -> String {
+    let s = lines.get(line.wrapping_sub(1)).map(|l| l.trim()).unwrap_or("");
+    match s.char_indices().nth(200) {
+        Some((cut, _)) => format!("{}…", &s[..cut]),
+        None => s.to_string(),
+    }
+}
+
+fn push_symbol(
+    out: &mut Parsed,
+    f: &Found,
+    parent: Option<usize>,
+    visibility: &'static str,
+    lines: &[&str],
+    line: usize,
+) -> usize {
+    let parent_container = parent.filter(|p| CONTAINERS.contains(&out.symbols[*p].kind));
+    let kind = match (f.kind, parent_container) {
+        ("function", Some(p)) if out.symbols[p].kind != "module" => "method",
+        (k, _) => k,
+    };
+    let container = f.container.clone().or_else(|| {
+        // Kontainer terdekat ke atas pohon (method → class/impl induknya)
+        let mut p = parent;
+        while let Some(idx) = p {
+            let s = &out.symbols[idx];
+            if CONTAINERS.contains(&s.kind) && s.kind != "module" {
+                return Some(s.name.clone());
+            }
+            p = s.parent;
+        }
+        None
+    });
+    out.symbols.push(Symbol {
+        name: f.name.clone(),
+        kind,
+        container,
+        visibility: f.visibility.unwrap_or(visibility),
+        parent,
+        line_start: line,
+        line_end: line,
+        signature: signature_of(lines, line),
+    });
+    out.symbols.len() - 1
+}
+
+/// Bahasa berkurung kurawal: scope definisi dibuka oleh `{` pertama setelah
+/// header (pada kedalaman kurung yang sama) dan ditutup `}` pasangannya.
+fn parse_braces(lang: Lang, toks: &[Token], lines: &[&str]) -> Parsed {
+    let mut out = Parsed::default();
+    // Some(sym) = body definisi, None = blok biasa
+    let mut stack: Vec<Option<usize>> = Vec::new();
+    let mut pending: Option<(usize, i32)> = None;
+    let mut paren = 0i32;
+    let mut skip: HashSet<usize> = HashSet::new();
+    let mut receivers: HashMap<usize, String> = HashMap::new();
+
+    let mut i = 0;
+    while i < toks.len() {
+        let t = &toks[i];
+        if t.kind == Tk::Punct {
+            match t.text.as_str() {
+                "(" | "[" => paren += 1,
+                ")" | "]" => paren -= 1,
+                "{" => {
+                    let def = match pending {
+                        Some((s, p)) if p == paren => {
+                            pending = None;
+                            Some(s)
+                        }
+                        _ => None,
+                    };
+                    stack.push(def);
+                }
+                "}" => {
+                    if let Some(Some(s)) = stack.pop() {
+                        out.symbols[s].line_end = t.line;
+                    }
+                }
+                ";" => {
+                    if let Some((s, p)) = pending {
+                        if p == paren {
+                            out.symbols[s].line_end = t.line;
+                            pending = None;
+                        }
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+            continue;
+        }
+        if t.kind != Tk::Ident || skip.contains(&i) {
+            i += 1;
+            continue;
+        }
+
+        let parent = stack.iter().rev().find_map(|s| *s);
+        let class_body = matches!(stack.last(), Some(Some(s)) if CONTAINERS.contains(&out.symbols[*s].kind));
+        if let Some((found, func)) = definition(lang, toks, i, class_body) {
+            let vis = visibility(lang, toks, i, &found.name, parent.map(|p| &out.symbols[p]));
+            let s = push_symbol(&mut out, &found, parent, vis, lines, t.line);
+            skip.insert(found.name_idx);
+            if let Some(f) = func {
+                skip.insert(f);
+            }
+            if let Some(r) = &found.receiver {
+                receivers.insert(s, r.clone());
+            }
+            if found.body {
+                pending = Some((s, paren));
+            }
+            i = found.name_idx + 1;
+            continue;
+        }
+
+        if text(toks, i + 1) == "(" {
+            let caller = stack.iter().rev().find_map(|s| s.filter(|s| out.symbols[*s].callable()));
+            if let (Some(caller), Some((name, mut qualifier))) = (caller, call_at(lang, toks, i)) {
+                if qualifier.is_some() && qualifier.as_deref() == receivers.get(&caller).map(String::as_str) {
+                    qualifier = Some("self".to_string());
+                }
+                out.calls.push(Call { caller, name, qualifier, line: t.line });
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+fn indent_of(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Python: scope definisi = baris berikutnya dengan indentasi lebih dalam.
+fn parse_python(toks: &[Token], lines: &[&str]) -> Parsed {
+    let mut out = Parsed::default();
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    let mut paren = 0i32;
+
+    for (i, t) in toks.iter().enumerate() {
+        let first_on_line = i == 0 || toks[i - 1].line != t.line;
+        if first_on_line && paren == 0 {
+            let indent = indent_of(lines.get(t.line - 1).copied().unwrap_or(""));
+            while let Some(&(s, level)) = stack.last() {
+                if level < indent {
+                    break;
+                }
+                out.symbols[s].line_end = toks[i - 1].line;
+                stack.pop();
+            }
+        }
+        match t.text.as_str() {
+            "(" | "[" | "{" if t.kind == Tk::Punct => paren += 1,
+            ")" | "]" | "}" if t.kind == Tk::Punct => paren -= 1,
+            "def" | "class" if t.kind == Tk::Ident => {
+                if let Some(name) = ident(toks, i + 1) {
+                    let kind = if t.text == "def" { "function" } else { "class" };
+                    let parent = stack.last().map(|(s, _)| *s);
+                    let vis = visibility(Lang::Python, toks, i, name, parent.map(|p| &out.symbols[p]));
+                    let s = push_symbol(&mut out, &Found::new(name, kind, i + 1), parent, vis, lines, t.line);
+                    stack.push((s, indent_of(lines.get(t.line - 1).copied().unwrap_or(""))));
+                }
+            }
+            _ if t.kind == Tk::Ident && text(toks, i + 1) == "(" => {
+                let caller = stack.iter().rev().map(|(s, _)| *s).find(|s| out.symbols[*s].callable());
+                if let (Some(caller), Some((name, qualifier))) = (caller, call_at(Lang::Python, toks, i)) {
+                    out.calls.push(Call { caller, name, qualifier, line: t.line });
+                }
+            }
+            _ => {}
+        }
+    }
+    let last = toks.last().map(|t| t.line).unwrap_or(1);
+    for (s, _) in stack {
+        out.symbols[s].line_end = last;
+    }
+    out
+}
+
+/// Pohon definisi dan pemanggilan satu file (None = bahasa tidak didukung).
+pub fn parse(path: &str, code: &str) -> Option<Parsed> {
+    let lang = lang_of(path)?;
+    let toks = lex(lang, code);
+    let lines: Vec<&str> = code.lines().collect();
+    Some(match lang {
+        Lang::Python => parse_python(&toks, &lines),
+        _ => parse_braces(lang, &toks, &lines),
+    })
+}
+
+// ===== Resolusi =====
+
+struct DefRef {
+    id: i64,
+    file_id: i64,
+    method: bool,
+    container: Option<String>,
+}
+
+/// Definisi yang bisa dipanggil, dikelompokkan per nama.
+#[derive(Default)]
+struct DefIndex {
+    by_name: HashMap<String, Vec<DefRef>>,
+    containers: HashSet<String>,
+}
+
+/// Callee untuk satu pemanggilan beserta cara resolusinya
+/// (`local`, `import`, `global`), atau None bila tidak ter-resolve.
+fn resolve(
+    index: &DefIndex,
+    file_id: i64,
+    imports: Option<&HashSet<i64>>,
+    caller_container: Option<&str>,
+    call: &Call,
+) -> Option<(i64, &'static str)> {
+    // Python `Foo()` = konstruktor `Foo.__init__`
+    if call.qualifier.is_none() && !index.by_name.contains_key(&call.name) && index.containers.contains(&call.name) {
+        let ctor = Call { name: "__init__".to_string(), qualifier: Some(call.name.clone()), ..call.clone() };
+        return resolve(index, file_id, imports, caller_container, &ctor);
+    }
+    let cands = index.by_name.get(&call.name)?;
+    let same_container = |d: &&DefRef| d.method && d.container.as_deref() == caller_container;
+    let (pool, global): (Vec<&DefRef>, bool) = match call.qualifier.as_deref() {
+        Some("self") => (cands.iter().filter(same_container).collect(), true),
+        // `Tipe::f()` / `Kelas.f()` / `new Kelas()`
+        Some(q) if index.containers.contains(q) => {
+            (cands.iter().filter(|d| d.container.as_deref() == Some(q)).collect(), true)
+        }
+        // Receiver tak dikenal (`obj.f()`, `pkg.F()`): hanya file sendiri / yang diimpor
+        Some(_) => (cands.iter().collect(), false),
+        None => (cands.iter().filter(|d| !d.method || same_container(d)).collect(), true),
+    };
+    if let Some(d) = pool.iter().find(|d| d.file_id == file_id) {
+        return Some((d.id, "local"));
+    }
+    let imported: Vec<&&DefRef> = pool.iter().filter(|d| imports.is_some_and(|i| i.contains(&d.file_id))).collect();
+    if imported.len() == 1 {
+        return Some((imported[0].id, "import"));
+    }
+    if global && imported.is_empty() && pool.len() == 1 {
+        return Some((pool[0].id, "global"));
+    }
+    None
+}
+
+async fn signature(pool: &MySqlPool, app_id: i64) -> anyhow::Result<String> {
+    let (files, max_file): (i64, Option<i64>) =
+        sqlx::query_as("SELECT COUNT(*), MAX(id) FROM files WHERE app_id=?")
+            .bind(app_id)
+            .fetch_one(pool)
+            .await?;
+    Ok(format!("{files}:{}", max_file.unwrap_or(0)))
+}
+
+/// Indeks ulang simbol dan pemanggilan seluruh file aplikasi:
+/// tulis code_symbols, code_calls dan call_index_state.
+pub async fn index_app(pool: &MySqlPool, app_id: i64) -> anyhow::Result<()> {
+    crate::imports::ensure_indexed(pool, app_id).await?;
+    let signature = signature(pool, app_id).await?;
+    let files: Vec<(i64, String, Option<String>)> =
+        sqlx::query_as("SELECT id, full_path, content_file FROM files WHERE app_id=? ORDER BY id")
+            .bind(app_id)
+            .fetch_all(pool)
+            .await?;
+    let edges: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT i.file_id, i.target_file_id FROM file_imports i JOIN files f ON f.id = i.file_id
+         WHERE f.app_id=? AND i.target_file_id IS NOT NULL",
+    )
+    .bind(app_id)
+    .fetch_all(pool)
+    .await?;
+    let mut imports: HashMap<i64, HashSet<i64>> = HashMap::new();
+    for (from, to) in edges {
+        imports.entry(from).or_default().insert(to);
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM code_symbols WHERE app_id=?")
+        .bind(app_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // Simbol dulu (induk sebelum anak), lalu resolusi dengan indeks seluruh aplikasi
+    let mut parsed: Vec<(i64, Parsed, Vec<i64>)> = Vec::new();
+    let mut index = DefIndex::default();
+    let mut symbols = 0usize;
+    for (file_id, path, content) in &files {
+        let Some(p) = parse(path, content.as_deref().unwrap_or("")) else {
+            continue;
+        };
+        let mut ids: Vec<i64> = Vec::with_capacity(p.symbols.len());
+        for s in &p.symbols {
+            let res = sqlx::query(
+                "INSERT INTO code_symbols (app_id, file_id, parent_id, name, kind, container, visibility, signature, line_start, line_end)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(app_id)
+            .bind(file_id)
+            .bind(s.parent.map(|p| ids[p]))
+            .bind(&s.name)
+            .bind(s.kind)
+            .bind(&s.container)
+            .bind(s.visibility)
+            .bind(&s.signature)
+            .bind(s.line_start as i32)
+            .bind(s.line_end as i32)
+            .execute(&mut *tx)
+            .await?;
+            let id = res.last_insert_id() as i64;
+            ids.push(id);
+            if s.callable() {
+                index.by_name.entry(s.name.clone()).or_default().push(DefRef {
+                    id,
+                    file_id: *file_id,
+                    method: s.kind == "method",
+                    container: s.container.clone(),
+                });
+            } else if s.kind != "module" {
+                // Modul bukan kualifier tipe: `db::connect()` dicari lewat import
+                index.containers.insert(s.name.clone());
+            }
+        }
+        symbols += ids.len();
+        parsed.push((*file_id, p, ids));
+    }
+
+    let mut calls = 0usize;
+    for (file_id, p, ids) in &parsed {
+        for c in &p.calls {
+            let caller = &p.symbols[c.caller];
+            let callee = resolve(&index, *file_id, imports.get(file_id), caller.container.as_deref(), c);
+            sqlx::query(
+                "INSERT INTO code_calls (caller_id, callee_id, name, qualifier, line_no, resolution) VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(ids[c.caller])
+            .bind(callee.map(|(id, _)| id))
+            .bind(&c.name)
+            .bind(&c.qualifier)
+            .bind(c.line as i32)
+            .bind(callee.map(|(_, how)| how))
+            .execute(&mut *tx)
+            .await?;
+            calls += 1;
+        }
+    }
+
+    sqlx::query(
+        "REPLACE INTO call_index_state (app_id, signature, symbols, calls, indexed_at) VALUES (?, ?, ?, ?, NOW())",
+    )
+    .bind(app_id)
+    .bind(&signature)
+    .bind(symbols as i32)
+    .bind(calls as i32)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Indeks aplikasi bila belum pernah atau isinya berubah sejak diindeks.
+pub async fn ensure_indexed(pool: &MySqlPool, app_id: i64) -> anyhow::Result<()> {
+    let stored: Option<(String,)> = sqlx::query_as("SELECT signature FROM call_index_state WHERE app_id=?")
+        .bind(app_id)
+        .fetch_optional(pool)
+        .await?;
+    if stored.map(|(s,)| s) != Some(signature(pool, app_id).await?) {
+        index_app(pool, app_id).await?;
+    }
+    Ok(())
+}
+
+// ===== Tampilan: caller/callee dan graph berakar =====
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolInfo {
+    pub id: i64,
+    pub file_id: i64,
+    pub path: String,
+    pub name: String,
+    pub kind: String,
+    pub container: Option<String>,
+    /// `Kontainer::nama` / `Kontainer.nama`, atau `nama`.
+    pub qualified: String,
+    pub signature: String,
+    pub line_start: i32,
+    pub line_end: i32,
+    pub href: String,
+}
+
+impl SymbolInfo {
+    pub fn callable(&self) -> bool {
+        matches!(self.kind.as_str(), "function" | "method")
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CallSite {
+    caller: i64,
+    callee: Option<i64>,
+    name: String,
+    qualifier: Option<String>,
+    line: i32,
+    resolution: Option<String>,
+}
+
+/// Caller / callee yang ter-resolve, dengan baris-baris pemanggilannya.
+#[derive(Debug, Clone, Serialize)]
+pub struct Link {
+    pub symbol: SymbolInfo,
+    pub lines: Vec<i32>,
+    pub resolution: String,
+}
+
+/// Pemanggilan yang tidak ter-resolve (library luar, receiver tak dikenal).
+#[derive(Debug, Clone, Serialize)]
+pub struct Unresolved {
+    pub name: String,
+    pub qualifier: Option<String>,
+    pub lines: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Focus {
+    pub symbol: SymbolInfo,
+    pub callers: Vec<Link>,
+    pub callees: Vec<Link>,
+    pub unresolved: Vec<Unresolved>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Both,
+    Callees,
+    Callers,
+}
+
+impl Direction {
+    pub fn parse(s: &str) -> Direction {
+        match s {
+            "callees" => Direction::Callees,
+            "callers" => Direction::Callers,
+            _ => Direction::Both,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Both => "both",
+            Direction::Callees => "callees",
+            Direction::Callers => "callers",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CallNode {
+    pub id: i64,
+    pub label: String,
+    pub kind: String,
+    pub file_id: i64,
+    pub path: String,
+    pub line: i32,
+    pub href: String,
+    /// Jarak dari akar: negatif = caller, positif = callee, 0 = akar.
+    pub depth: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CallEdge {
+    pub from: i64,
+    pub to: i64,
+    /// Jumlah titik pemanggilan from → to.
+    pub calls: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CallGraph {
+    pub root: i64,
+    pub nodes: Vec<CallNode>,
+    pub edges: Vec<CallEdge>,
+    /// Node dipotong di MAX_NODES.
+    pub truncated: bool,
+}
+
+/// Seluruh simbol dan pemanggilan satu aplikasi, dimuat dari DB.
+pub struct CallIndex {
+    pub symbols: Vec<SymbolInfo>,
+    by_id: HashMap<i64, usize>,
+    calls: Vec<CallSite>,
+}
+
+type SymbolRow = (i64, i64, String, String, String, Option<String>, String, i32, i32);
+type CallRow = (i64, Option<i64>, String, Option<String>, i32, Option<String>);
+
+pub async fn load(pool: &MySqlPool, app_id: i64) -> anyhow::Result<CallIndex> {
+    ensure_indexed(pool, app_id).await?;
+    let rows: Vec<SymbolRow> = sqlx::query_as(
+        "SELECT s.id, s.file_id, f.full_path, s.name, s.kind, s.container, s.signature, s.line_start, s.line_end
+         FROM code_symbols s JOIN files f ON f.id = s.file_id
+         WHERE s.app_id=? ORDER BY f.full_path, s.line_start, s.id",
+    )
+    .bind(app_id)
+    .fetch_all(pool)
+    .await?;
+    let symbols: Vec<SymbolInfo> = rows
+        .into_iter()
+        .map(|(id, file_id, path, name, kind, container, signature, line_start, line_end)| SymbolInfo {
+            qualified: qualified(&path, &kind, container.as_deref(), &name),
+            href: crate::services::line_href(file_id, line_start, line_end),
+            id,
+            file_id,
+            path,
+            name,
+            kind,
+            container,
+            signature,
+            line_start,
+            line_end,
+        })
+        .collect();
+    let calls: Vec<CallRow> = sqlx::query_as(
+        "SELECT c.caller_id, c.callee_id, c.name, c.qualifier, c.line_no, c.resolution
+         FROM code_calls c JOIN code_symbols s ON s.id = c.caller_id
+         WHERE s.app_id=? ORDER BY c.id",
+    )
+    .bind(app_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(CallIndex {
+        by_id: symbols.iter().enumerate().map(|(i, s)| (s.id, i)).collect(),
+        symbols,
+        calls: calls
+            .into_iter()
+            .map(|(caller, callee, name, qualifier, line, resolution)| CallSite {
+                caller,
+                callee,
+                name,
+                qualifier,
+                line,
+                resolution,
+            })
+            .collect(),
+    })
+}
+
+impl CallIndex {
+    pub fn get(&self, id: i64) -> Option<&SymbolInfo> {
+        self.by_id.get(&id).map(|i| &self.symbols[*i])
+    }
+
+    /// Fungsi/method yang nama berkualifikasinya memuat `q` (tanpa beda huruf).
+    pub fn functions(&self, q: &str, limit: usize) -> Vec<&SymbolInfo> {
+        let q = q.trim().to_lowercase();
+        self.symbols
+            .iter()
+            .filter(|s| s.callable() && (q.is_empty() || s.qualified.to_lowercase().contains(&q)))
+            .take(limit)
+            .collect()
+    }
+
+    pub fn function_count(&self) -> usize {
+        self.symbols.iter().filter(|s| s.callable()).count()
+    }
+
+    pub fn call_count(&self) -> (usize, usize) {
+        (self.calls.iter().filter(|c| c.callee.is_some()).count(), self.calls.len())
+    }
+
+    fn links(&self, pairs: impl Iterator<Item = (i64, i32, Option<String>)>) -> Vec<Link> {
+        let mut grouped: BTreeMap<i64, (Vec<i32>, String)> = BTreeMap::new();
+        for (id, line, how) in pairs {
+            let e = grouped.entry(id).or_insert_with(|| (Vec::new(), how.unwrap_or_default()));
+            e.0.push(line);
+        }
+        let mut out: Vec<Link> = grouped
+            .into_iter()
+            .filter_map(|(id, (lines, resolution))| {
+                Some(Link { symbol: self.get(id)?.clone(), lines, resolution })
+            })
+            .collect();
+        out.sort_by(|a, b| a.symbol.qualified.cmp(&b.symbol.qualified));
+        out
+    }
+
+    /// Caller, callee dan pemanggilan yang tidak ter-resolve dari satu fungsi.
+    pub fn focus(&self, id: i64) -> Option<Focus> {
+        let symbol = self.get(id)?.clone();
+        let callers = self.links(
+            self.calls.iter().filter(|c| c.callee == Some(id)).map(|c| (c.caller, c.line, c.resolution.clone())),
+        );
+        let callees = self.links(
+            self.calls
+                .iter()
+                .filter(|c| c.caller == id)
+                .filter_map(|c| c.callee.map(|to| (to, c.line, c.resolution.clone()))),
+        );
+        let mut unresolved: BTreeMap<(String, Option<String>), Vec<i32>> = BTreeMap::new();
+        for c in self.calls.iter().filter(|c| c.caller == id && c.callee.is_none()) {
+            unresolved.entry((c.name.clone(), c.qualifier.clone())).or_default().push(c.line);
+        }
+        Some(Focus {
+            symbol,
+            callers,
+            callees,
+            unresolved: unresolved
+                .into_iter()
+                .map(|((name, qualifier), lines)| Unresolved { name, qualifier, lines })
+                .collect(),
+        })
+    }
+
+    /// Graph berakar di `root`: callee (maju) dan/atau caller (mundur) sampai
+    /// `depth` langkah. Edge = semua pemanggilan ter-resolve antar node.
+    pub fn graph(&self, root: i64, depth: usize, dir: Direction) -> CallGraph {
+        let mut forward: HashMap<i64, Vec<i64>> = HashMap::new();
+        let mut backward: HashMap<i64, Vec<i64>> = HashMap::new();
+        let mut counts: BTreeMap<(i64, i64), usize> = BTreeMap::new();
+        for c in &self.calls {
+            if let Some(to) = c.callee {
+                forward.entry(c.caller).or_default().push(to);
+                backward.entry(to).or_default().push(c.caller);
+                *counts.entry((c.caller, to)).or_default() += 1;
+            }
+        }
+
+        let mut level: BTreeMap<i64, i32> = BTreeMap::new();
+        level.insert(root, 0);
+        let mut truncated = false;
+        let walks: Vec<(&HashMap<i64, Vec<i64>>, i32)> = match dir {
+            Direction::Both => vec![(&forward, 1), (&backward, -1)],
+            Direction::Callees => vec![(&forward, 1)],
+            Direction::Callers => vec![(&backward, -1)],
+        };
+        for (adj, sign) in walks {
+            let mut queue = VecDeque::from([(root, 0usize)]);
+            while let Some((id, d)) = queue.pop_front() {
+                if d >= depth {
+                    continue;
+                }
+                for next in adj.get(&id).into_iter().flatten() {
+                    if level.contains_key(next) {
+                        continue;
+                    }
+                    if level.len() >= MAX_NODES {
+                        truncated = true;
+                        break;
+                    }
+                    level.insert(*next, sign * (d as i32 + 1));
+                    queue.push_back((*next, d + 1));
+                }
+            }
+        }
+
+        let nodes = level
+            .iter()
+            .filter_map(|(id, depth)| {
+                let s = self.get(*id)?;
+                Some(CallNode {
+                    id: s.id,
+                    label: s.qualified.clone(),
+                    kind: s.kind.clone(),
+                    file_id: s.file_id,
+                    path: s.path.clone(),
+                    line: s.line_start,
+                    href: s.href.clone(),
+                    depth: *depth,
+                })
+            })
+            .collect();
+        let edges = counts
+            .into_iter()
+            .filter(|((from, to), _)| level.contains_key(from) && level.contains_key(to))
+            .map(|((from, to), calls)| CallEdge { from, to, calls })
+            .collect();
+        CallGraph { root, nodes, edges, truncated }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (kind, nama, kontainer, baris awal, baris akhir, visibilitas)
+    type Sym = (&'static str, String, Option<String>, usize, usize, &'static str);
+
+    fn symbols(path: &str, code: &str) -> Vec<Sym> {
+        parse(path, code)
+            .unwrap()
+            .symbols
+            .into_iter()
+            .map(|s| (s.kind, s.name, s.container, s.line_start, s.line_end, s.visibility))
+            .collect()
+    }
+
+    /// (pemanggil, nama, kualifier)
+    fn calls(path: &str, code: &str) -> Vec<(String, String, Option<String>)> {
+        let p = parse(path, code).unwrap();
+        p.calls
+            .iter()
+            .map(|c| (p.symbols[c.caller].name.clone(), c.name.clone(), c.qualifier.clone()))
+            .collect()
+    }
+
+    fn call(caller: &str, name: &str, qualifier: Option<&str>) -> (String, String, Option<String>) {
+        (caller.to_string(), name.to_string(), qualifier.map(str::to_string))
+    }
+
+    #[test]
+    fn js_regex_literal_with_quote() {
+        let code = "function a() { const r = /\"/g; return r; }\nfunction b() { c(); }\nfunction c() { return 4 / 2 / 1; }\nfunction d() {}\n";
+        let s = symbols("x.js", code);
+        let names: Vec<(&str, usize, usize)> = s.iter().map(|s| (s.1.as_str(), s.3, s.4)).collect();
+        assert_eq!(names, vec![("a", 1, 1), ("b", 2, 2), ("c", 3, 3), ("d", 4, 4)]);
+        assert_eq!(calls("x.js", code), vec![call("b", "c", None)]);
+    }
+
+    #[test]
+    fn js_regex_after_return_and_in_arguments() {
+        let code = "function a(s) {\n  if (s.match(/[/'\"]+/)) return /'/.test(s);\n  return s.replace(/\\/\"/g, '');\n}\nfunction b() { a('x'); }\n";
+        let s = symbols("x.ts", code);
+        assert_eq!(s.iter().map(|s| (s.1.as_str(), s.3, s.4)).collect::<Vec<_>>(), vec![("a", 1, 4), ("b", 5, 5)]);
+        assert!(calls("x.ts", code).contains(&call("b", "a", None)));
+    }
+
+    #[test]
+    fn ts_class_members_and_visibility() {
+        let code = "export class Repo {\n  constructor() { this.load(); }\n  private load(): void { helper(); }\n  protected static find(id: number) {}\n  #secret() {}\n  go = () => { db.query(); };\n}\nconst helper = async () => { new Repo(); };\nexport function main() {}\n";
+        let s = symbols("repo.ts", code);
+        let got: Vec<(&str, &str, &str)> = s.iter().map(|s| (s.0, s.1.as_str(), s.5)).collect();
+        assert_eq!(
+            got,
+            vec![
+                ("class", "Repo", "public"),
+                ("method", "constructor", "public"),
+                ("method", "load", "private"),
+                ("method", "find", "protected"),
+                ("method", "secret", "private"),
+                ("method", "go", "public"),
+                ("function", "helper", "internal"),
+                ("function", "main", "public"),
+            ]
+        );
+        let c = calls("repo.ts", code);
+        assert!(c.contains(&call("constructor", "load", Some("self"))));
+        assert!(c.contains(&call("load", "helper", None)));
+        assert!(c.contains(&call("go", "query", Some("db"))));
+        assert!(c.contains(&call("helper", "constructor", Some("Repo"))));
+    }
+
+    #[test]
+    fn rust_impl_trait_and_literals() {
+        let code = "pub struct A;\nimpl A {\n    pub fn new() -> Self {\n        let c = '{';\n        let s = r#\"fn fake() {\"#;\n        Self::init(s);\n        Some(helper(c))\n    }\n    pub(crate) fn init<'a>(s: &'a str) {}\n}\nimpl Display for A {\n    fn fmt(&self) { self.new(); }\n}\nfn helper(c: char) -> char { c }\n";
+        let s = symbols("a.rs", code);
+        assert_eq!(
+            s,
+            vec![
+                ("struct", "A".into(), None, 1, 1, "public"),
+                ("impl", "A".into(), None, 2, 10, "private"),
+                ("method", "new".into(), Some("A".into()), 3, 8, "public"),
+                ("method", "init".into(), Some("A".into()), 9, 9, "internal"),
+                ("impl", "A".into(), None, 11, 13, "public"),
+                ("method", "fmt".into(), Some("A".into()), 12, 12, "public"),
+                ("function", "helper".into(), None, 14, 14, "private"),
+            ]
+        );
+        // `Some(` = varian enum, bukan pemanggilan
+        assert_eq!(
+            calls("a.rs", code),
+            vec![call("new", "init", Some("self")), call("new", "helper", None), call("fmt", "new", Some("self"))]
+        );
+    }
+
+    #[test]
+    fn visibility_modifiers_and_defaults() {
+        let vis = |path: &str, code: &str| -> Vec<(String, &'static str)> {
+            symbols(path, code).into_iter().map(|s| (s.1, s.5)).collect()
+        };
+        let pairs = |v: &[(&str, &'static str)]| v.iter().map(|(n, v)| (n.to_string(), *v)).collect::<Vec<_>>();
+
+        let rust = "pub(super) fn a() {}\npub(in crate::x) fn b() {}\nfn outer() {\n    fn inner() {}\n}\n\
+                    pub trait T {\n    fn m(&self);\n}\npub enum E {}\n";
+        assert_eq!(
+            vis("v.rs", rust),
+            pairs(&[
+                ("a", "internal"),
+                ("b", "internal"),
+                ("outer", "private"),
+                ("inner", "private"),
+                ("T", "public"),
+                ("m", "public"),
+                ("E", "public"),
+            ])
+        );
+
+        let java = "class Box {\n  protected void p() {}\n  private int q() { return 1; }\n  public static void r() {}\n}\n";
+        assert_eq!(
+            vis("Box.java", java),
+            pairs(&[("Box", "internal"), ("p", "protected"), ("q", "private"), ("r", "public")])
+        );
+
+        let js = "export default function d() {}\nfunction m() {}\nclass K {\n  #h() {}\n  v() {}\n}\n";
+        assert_eq!(
+            vis("m.js", js),
+            pairs(&[("d", "public"), ("m", "internal"), ("K", "internal"), ("h", "private"), ("v", "public")])
+        );
+
+        let py = "def __call__():\n    pass\n\ndef _hidden():\n    pass\n\ndef __mangled():\n    pass\n";
+        assert_eq!(vis("v.py", py), pairs(&[("__call__", "public"), ("_hidden", "private"), ("__mangled", "private")]));
+
+        let go = "package x\n\nfunc Open() {}\nfunc close() {}\n";
+        assert_eq!(vis("x.go", go), pairs(&[("Open", "public"), ("close", "internal")]));
+    }
+
+    #[test]
+    fn python_indentation_scopes() {
+        let code = "class A:\n    def __init__(self):\n        \"\"\"def fake():\n        \"\"\"\n        self.load()\n\n    def _load(self):\n        helper(1)\n\ndef helper(x):\n    def inner():\n        pass\n    return A()\n";
+        let s = symbols("a.py", code);
+        assert_eq!(
+            s,
+            vec![
+                ("class", "A".into(), None, 1, 8, "public"),
+                ("method", "__init__".into(), Some("A".into()), 2, 5, "public"),
+                ("method", "_load".into(), Some("A".into()), 7, 8, "private"),
+                ("function", "helper".into(), None, 10, 13, "public"),
+                ("function", "inner".into(), None, 11, 12, "private"),
+            ]
+        );
+        assert_eq!(
+            calls("a.py", code),
+            vec![call("__init__", "load", Some("self")), call("_load", "helper", None), call("helper", "A", None)]
+        );
+    }
+
+    #[test]
+    fn go_receivers_and_func_literals() {
+        let code = "package repo\n\ntype Repo struct{}\n\nfunc (r *Repo) Get() error {\n\tq := `SELECT { FROM`\n\tr.load(q)\n\tf := func() { fmt.Println() }\n\treturn New()\n}\n\nfunc (r *Repo) load(q string) {}\n\nfunc New() *Repo { return &Repo{} }\n";
+        let s = symbols("repo.go", code);
+        assert_eq!(
+            s,
+            vec![
+                ("struct", "Repo".into(), None, 3, 3, "public"),
+                ("method", "Get".into(), Some("Repo".into()), 5, 10, "public"),
+                ("method", "load".into(), Some("Repo".into()), 12, 12, "internal"),
+                ("function", "New".into(), None, 14, 14, "public"),
+            ]
+        );
+        let c = calls("repo.go", code);
+        assert!(c.contains(&call("Get", "load", Some("self"))));
+        assert!(c.contains(&call("Get", "Println", Some("fmt"))));
+        assert!(c.contains(&call("Get", "New", None)));
+    }
+
+    #[test]
+    fn java_methods_constructors_and_interfaces() {
+        let code = "public class Service {\n  private final String s = \"}\";\n  public Service() { init(); }\n  void init() throws IOException {\n    Repo r = new Repo();\n    r.save(s);\n  }\n  abstract int size();\n}\ninterface Repo { void save(String s); }\n";
+        let s = symbols("Service.java", code);
+        assert_eq!(
+            s,
+            vec![
+                ("class", "Service".into(), None, 1, 9, "public"),
+                ("method", "Service".into(), Some("Service".into()), 3, 3, "public"),
+                ("method", "init".into(), Some("Service".into()), 4, 7, "internal"),
+                ("method", "size".into(), Some("Service".into()), 8, 8, "internal"),
+                ("interface", "Repo".into(), None, 10, 10, "internal"),
+                ("method", "save".into(), Some("Repo".into()), 10, 10, "public"),
+            ]
+        );
+        assert_eq!(
+            calls("Service.java", code),
+            vec![call("Service", "init", None), call("init", "Repo", Some("Repo")), call("init", "save", Some("r"))]
+        );
+    }
+
+    #[test]
+    fn php_members_and_string_concat() {
+        let code = "<?php\n# komentar { \nclass User {\n  private function load() { return 'a' . trim($x); }\n  public static function find($id) { $u = new User(); $u->load(); self::make(); }\n  function make() {}\n}\nfunction helper() { User::find(1); }\n";
+        let s = symbols("User.php", code);
+        assert_eq!(
+            s.iter().map(|s| (s.0, s.1.as_str(), s.3, s.4, s.5)).collect::<Vec<_>>(),
+            vec![
+                ("class", "User", 3, 7, "public"),
+                ("method", "load", 4, 4, "private"),
+                ("method", "find", 5, 5, "public"),
+                ("method", "make", 6, 6, "public"),
+                ("function", "helper", 8, 8, "public"),
+            ]
+        );
+        assert_eq!(
+            calls("User.php", code),
+            vec![
+                call("load", "trim", None),
+                call("find", "__construct", Some("User")),
+                call("find", "load", Some("$u")),
+                call("find", "make", Some("self")),
+                call("helper", "find", Some("User")),
+            ]
+        );
+    }
+
+    fn def(id: i64, file_id: i64, container: Option<&str>) -> DefRef {
+        DefRef { id, file_id, method: container.is_some(), container: container.map(str::to_string) }
+    }
+
+    fn call_to(name: &str, qualifier: Option<&str>) -> Call {
+        Call { caller: 0, name: name.to_string(), qualifier: qualifier.map(str::to_string), line: 1 }
+    }
+
+    #[test]
+    fn resolve_prefers_local_then_import_then_unique_name() {
+        let mut index = DefIndex::default();
+        index.by_name.insert("save".into(), vec![def(1, 10, None), def(2, 20, None)]);
+        index.by_name.insert("load".into(), vec![def(3, 30, Some("Repo"))]);
+        index.by_name.insert("init".into(), vec![def(4, 40, None)]);
+        index.containers.insert("Repo".into());
+        let imports: HashSet<i64> = [20].into_iter().collect();
+
+        assert_eq!(resolve(&index, 10, Some(&imports), None, &call_to("save", None)), Some((1, "local")));
+        assert_eq!(resolve(&index, 99, Some(&imports), None, &call_to("save", None)), Some((2, "import")));
+        // Dua kandidat tanpa import: ambigu
+        assert_eq!(resolve(&index, 99, None, None, &call_to("save", None)), None);
+        assert_eq!(resolve(&index, 99, None, None, &call_to("init", None)), Some((4, "global")));
+        assert_eq!(resolve(&index, 99, None, None, &call_to("load", Some("Repo"))), Some((3, "global")));
+        assert_eq!(resolve(&index, 99, None, Some("Repo"), &call_to("load", Some("self"))), Some((3, "global")));
+        // Receiver tak dikenal hanya di file sendiri / yang diimpor
+        assert_eq!(resolve(&index, 99, None, None, &call_to("init", Some("obj"))), None);
+        assert_eq!(resolve(&index, 40, None, None, &call_to("init", Some("obj"))), Some((4, "local")));
+    }
+
+    #[test]
+    fn python_class_call_resolves_to_init() {
+        let mut index = DefIndex::default();
+        index.by_name.insert("__init__".into(), vec![def(7, 1, Some("A"))]);
+        index.containers.insert("A".into());
+        assert_eq!(resolve(&index, 2, None, None, &call_to("A", None)), Some((7, "global")));
+    }
+}
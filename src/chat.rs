@@ -0,0 +1,479 @@
+// src/chat.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Tanya-jawab tentang satu aplikasi. Untuk setiap pertanyaan, file yang
+// relevan dicari lewat indeks pencarian aplikasi (semantic.rs: potongan isi
+// file dan hasil analisa), potongan kodenya dikirim ke LLM sebagai sumber
+// bernomor [1], [2], ... dan jawabannya wajib memberi sitasi. Percakapan disimpan di chat_conversations / chat_messages
+// beserta sumber tiap jawaban, sehingga sitasi bisa ditautkan kembali ke
+// baris file di /apps/:id.
+
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+use crate::i18n::Locale;
+use crate::usage::{estimate_tokens, LlmCtx};
+
+/// Batas token sumber per pertanyaan (env CHAT_CONTEXT_TOKENS, default 6000).
+fn context_budget() -> i64 {
+    std::env::var("CHAT_CONTEXT_TOKENS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(6000)
+}
+
+const MAX_SOURCES: usize = 8;
+const HISTORY_MESSAGES: i64 = 6;
+/// Baris di sekitar kemunculan kata kunci yang ikut dikirim.
+const EXCERPT_RADIUS: usize = 12;
+
+/// Satu sumber yang dikirim ke LLM dan dikutip di jawaban.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Source {
+    pub n: usize,
+    pub file_id: i64,
+    pub path: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    #[serde(skip)]
+    pub excerpt: String,
+    #[serde(skip)]
+    pub summary: Option<String>,
+}
+
+impl Source {
+    /// Tautan ke baris file di halaman aplikasi.
+    pub fn href(&self, app_id: i64) -> String {
+        crate::services::file_href(app_id, self.file_id, &self.path)
+    }
+}
+
+const STOPWORDS: [&str; 40] = [
+    "yang", "dan", "atau", "di", "ke", "dari", "ini", "itu", "apa", "mana", "bagaimana", "kapan", "siapa",
+    "dengan", "untuk", "pada", "ada", "adalah", "jika", "saat", "the", "and", "what", "where", "how", "when",
+    "which", "who", "does", "is", "are", "for", "with", "this", "that", "from", "into", "handled", "happens",
+    "file",
+];
+
+/// Kata kunci pertanyaan: huruf kecil, minimal 3 karakter, tanpa stopword.
+pub fn keywords(question: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for w in question.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+        let w = w.to_lowercase();
+        if w.chars().count() >= 3 && !STOPWORDS.contains(&w.as_str()) && !out.contains(&w) {
+            out.push(w);
+        }
+    }
+    out.truncate(10);
+    out
+}
+
+/// Potongan baris di sekitar kemunculan kata kunci pertama (1-based, inklusif).
+fn excerpt(code: &str, terms: &[String]) -> (usize, usize, String) {
+    let lines: Vec<&str> = code.lines().collect();
+    if lines.is_empty() {
+        return (0, 0, String::new());
+    }
+    let hit = lines
+        .iter()
+        .position(|l| {
+            let l = l.to_lowercase();
+            terms.iter().any(|t| l.contains(t.as_str()))
+        })
+        .unwrap_or(0);
+    let start = hit.saturating_sub(EXCERPT_RADIUS);
+    let end = (hit + EXCERPT_RADIUS + 1).min(lines.len());
+    (start + 1, end, lines[start..end].join("\n"))
+}
+
+/// Baris `start..=end` (1-based) dari kode.
+fn lines_between(code: &str, start: usize, end: usize) -> String {
+    code.lines()
+        .skip(start.saturating_sub(1))
+        .take((end + 1).saturating_sub(start.max(1)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Sumber untuk pertanyaan: file teratas dari indeks pencarian (semantic.rs),
+/// dengan potongan kode yang paling cocok, dibatasi token.
+pub async fn retrieve(
+    pool: &MySqlPool,
+    app_id: i64,
+    question: &str,
+    lang: &str,
+    user: Option<&str>,
+) -> anyhow::Result<Vec<Source>> {
+    let terms = keywords(question);
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+    let budget = context_budget();
+    let mut used = 0;
+    let mut out = Vec::new();
+    let found = crate::semantic::search(pool, app_id, question, MAX_SOURCES * 2, user).await?;
+    for hit in found.files {
+        if out.len() >= MAX_SOURCES {
+            break;
+        }
+        let src = crate::services::load_file_source(pool, hit.file_id).await?;
+        let (line_start, line_end, text) = match hit.ranges.iter().find(|r| r.source == "code") {
+            Some(r) => (r.line_start, r.line_end, lines_between(&src.code, r.line_start, r.line_end)),
+            None => excerpt(&src.code, &terms),
+        };
+        let summary = crate::services::cached_analysis(pool, hit.file_id, "fungsi", lang)
+            .await?
+            .map(|s| s.chars().take(600).collect::<String>());
+        let tokens = estimate_tokens(&text) + summary.as_deref().map(estimate_tokens).unwrap_or(0);
+        if used + tokens > budget && !out.is_empty() {
+            continue;
+        }
+        used += tokens;
+        out.push(Source {
+            n: out.len() + 1,
+            file_id: hit.file_id,
+            path: src.path,
+            line_start,
+            line_end,
+            excerpt: text,
+            summary,
+        });
+    }
+    Ok(out)
+}
+
+pub fn sources_block(sources: &[Source]) -> String {
+    if sources.is_empty() {
+        return "(tidak ada file yang cocok dengan pertanyaan)".to_string();
+    }
+    let mut out = String::new();
+    for s in sources {
+        out.push_str(&format!("[{}] {} (baris {}-{})\n", s.n, s.path, s.line_start, s.line_end));
+        if let Some(summary) = &s.summary {
+            out.push_str(&format!("Ringkasan analisa: {summary}\n"));
+        }
+        out.push_str(&format!("```\n{}\n```\n\n", s.excerpt));
+    }
+    out
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Conversation {
+    pub id: i64,
+    pub app_id: i64,
+    pub title: String,
+    pub lang: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Message {
+    pub id: i64,
+    pub role: String,
+    pub content: String,
+    pub sources: Vec<Source>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Potongan teks jawaban; `cite` = sumber yang dikutip dengan [n].
+#[derive(Debug, Clone, Serialize)]
+pub struct Segment {
+    pub text: String,
+    pub cite: Option<usize>,
+    pub href: Option<String>,
+}
+
+impl Message {
+    /// Pecah jawaban menjadi teks dan sitasi [n] yang bisa ditautkan.
+    pub fn segments(&self, app_id: i64) -> Vec<Segment> {
+        static CITE: OnceLock<Regex> = OnceLock::new();
+        let re = CITE.get_or_init(|| Regex::new(r"\[(\d+)\]").expect("regex sitasi valid"));
+        let mut out = Vec::new();
+        let mut last = 0;
+        for caps in re.captures_iter(&self.content) {
+            let m = caps.get(0).expect("match");
+            let n: usize = caps[1].parse().unwrap_or(0);
+            let Some(src) = self.sources.iter().find(|s| s.n == n) else {
+                continue;
+            };
+            if m.start() > last {
+                out.push(Segment { text: self.content[last..m.start()].to_string(), cite: None, href: None });
+            }
+            out.push(Segment { text: m.as_str().to_string(), cite: Some(n), href: Some(src.href(app_id)) });
+            last = m.end();
+        }
+        if last < self.content.len() {
+            out.push(Segment { text: self.content[last..].to_string(), cite: None, href: None });
+        }
+        out
+    }
+}
+
+pub async fn conversations(pool: &MySqlPool, app_id: i64) -> anyhow::Result<Vec<Conversation>> {
+    Ok(sqlx::query_as(
+        "SELECT id, app_id, title, lang, created_at FROM chat_conversations WHERE app_id=? ORDER BY id DESC LIMIT 50",
+    )
+    .bind(app_id)
+    .fetch_all(pool)
+    .await?)
+}
+
+pub async fn conversation(pool: &MySqlPool, app_id: i64, id: i64) -> anyhow::Result<Option<Conversation>> {
+    Ok(sqlx::query_as(
+        "SELECT id, app_id, title, lang, created_at FROM chat_conversations WHERE id=? AND app_id=?",
+    )
+    .bind(id)
+    .bind(app_id)
+    .fetch_optional(pool)
+    .await?)
+}
+
+#[derive(sqlx::FromRow)]
+struct MessageRow {
+    id: i64,
+    role: String,
+    content: String,
+    sources: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+pub async fn messages(pool: &MySqlPool, conversation_id: i64) -> anyhow::Result<Vec<Message>> {
+    let rows: Vec<MessageRow> = sqlx::query_as(
+        "SELECT id, role, content, CAST(sources AS CHAR) AS sources, created_at FROM chat_messages
+         WHERE conversation_id=? ORDER BY id",
+    )
+    .bind(conversation_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|r| Message {
+            id: r.id,
+            role: r.role,
+            content: r.content,
+            sources: r.sources.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default(),
+            created_at: r.created_at,
+        })
+        .collect())
+}
+
+async fn history_text(pool: &MySqlPool, conversation_id: i64) -> anyhow::Result<String> {
+    let mut rows: Vec<(String, String)> = sqlx::query_as(
+        "SELECT role, content FROM chat_messages WHERE conversation_id=? ORDER BY id DESC LIMIT ?",
+    )
+    .bind(conversation_id)
+    .bind(HISTORY_MESSAGES)
+    .fetch_all(pool)
+    .await?;
+    rows.reverse();
+    if rows.is_empty() {
+        return Ok("(belum ada)".to_string());
+    }
+    Ok(rows
+        .into_iter()
+        .map(|(role, content)| {
+            let who = if role == "user" { "Pengguna" } else { "Asisten" };
+            format!("{who}: {}", content.chars().take(1500).collect::<String>())
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n"))
+}
+
+async fn insert_message(
+    conn: &mut sqlx::MySqlConnection,
+    conversation_id: i64,
+    role: &str,
+    content: &str,
+    sources: Option<&[Source]>,
+    prompt_version: Option<i32>,
+) -> anyhow::Result<i64> {
+    let sources = sources.map(serde_json::to_string).transpose()?;
+    let res = sqlx::query(
+        "INSERT INTO chat_messages (conversation_id, role, content, sources, prompt_version) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(conversation_id)
+    .bind(role)
+    .bind(content)
+    .bind(sources)
+    .bind(prompt_version)
+    .execute(conn)
+    .await?;
+    Ok(res.last_insert_id() as i64)
+}
+
+pub struct Question<'a> {
+    pub app_id: i64,
+    /// None = percakapan baru (judul dari pertanyaan).
+    pub conversation_id: Option<i64>,
+    pub question: &'a str,
+    pub lang: &'a str,
+    pub user: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Answer {
+    pub conversation_id: i64,
+    pub message: Message,
+}
+
+/// Pertanyaan yang ditolak `ask` sebelum memanggil LLM.
+#[derive(Debug)]
+pub enum InvalidQuestion {
+    Empty,
+    /// Percakapan tidak ada (atau milik aplikasi lain).
+    ConversationNotFound(i64),
+}
+
+impl InvalidQuestion {
+    pub fn message(&self, loc: Locale) -> String {
+        match self {
+            InvalidQuestion::Empty => loc.t("err.empty_question").to_string(),
+            InvalidQuestion::ConversationNotFound(id) => {
+                loc.tf("err.conversation_not_found", &[("id", &id.to_string())])
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for InvalidQuestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message(Locale::default()))
+    }
+}
+
+impl std::error::Error for InvalidQuestion {}
+
+/// Jawab pertanyaan: cari sumber, panggil LLM, simpan pertanyaan & jawaban.
+pub async fn ask(pool: &MySqlPool, q: Question<'_>) -> anyhow::Result<Answer> {
+    let question = q.question.trim();
+    if question.is_empty() {
+        return Err(InvalidQuestion::Empty.into());
+    }
+    let (app_name,): (String,) = sqlx::query_as("SELECT nama_aplikasi FROM applications WHERE id=?")
+        .bind(q.app_id)
+        .fetch_one(pool)
+        .await?;
+    let existing = match q.conversation_id {
+        Some(id) => match conversation(pool, q.app_id, id).await? {
+            Some(c) => Some(c.id),
+            None => return Err(InvalidQuestion::ConversationNotFound(id).into()),
+        },
+        None => None,
+    };
+
+    let riwayat = match existing {
+        Some(id) => history_text(pool, id).await?,
+        None => "(belum ada)".to_string(),
+    };
+    let sources = retrieve(pool, q.app_id, question, q.lang, q.user).await?;
+    let prompt = crate::prompts::active(pool, "chat").await?;
+    let mut text = crate::prompts::render(
+        &prompt.template,
+        &[
+            ("app", &app_name),
+            ("pertanyaan", question),
+            ("sumber", &sources_block(&sources)),
+            ("riwayat", &riwayat),
+            ("bahasa", crate::lang::prompt_name(q.lang)),
+        ],
+    );
+    text.push_str(&crate::lang::instruction(q.lang));
+
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or_default();
+    let ctx = LlmCtx { pool, app_id: Some(q.app_id), file_id: None, kind: "chat", user: q.user, lang: q.lang };
+    let answer = crate::openai::tanya(&api_key, &text, &ctx).await?;
+
+    // Percakapan & pesan baru disimpan setelah LLM menjawab, sekaligus dalam
+    // satu transaksi, supaya kegagalan tidak meninggalkan pertanyaan tanpa jawaban.
+    let mut tx = pool.begin().await?;
+    let conversation_id = match existing {
+        Some(id) => id,
+        None => {
+            let title: String = question.chars().take(120).collect();
+            sqlx::query("INSERT INTO chat_conversations (app_id, title, lang, created_by) VALUES (?, ?, ?, ?)")
+                .bind(q.app_id)
+                .bind(&title)
+                .bind(q.lang)
+                .bind(q.user)
+                .execute(&mut *tx)
+                .await?
+                .last_insert_id() as i64
+        }
+    };
+    insert_message(&mut tx, conversation_id, "user", question, None, None).await?;
+    let id =
+        insert_message(&mut tx, conversation_id, "assistant", &answer, Some(&sources), Some(prompt.version)).await?;
+    tx.commit().await?;
+
+    Ok(Answer {
+        conversation_id,
+        message: Message { id, role: "assistant".into(), content: answer, sources, created_at: Utc::now() },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(content: &str, cited: &[usize]) -> Message {
+        let sources = cited
+            .iter()
+            .map(|&n| Source {
+                n,
+                file_id: n as i64 * 10,
+                path: format!("src/f{n}.rs"),
+                line_start: 1,
+                line_end: 5,
+                excerpt: String::new(),
+                summary: None,
+            })
+            .collect();
+        Message { id: 1, role: "assistant".into(), content: content.into(), sources, created_at: Utc::now() }
+    }
+
+    fn parts(segments: &[Segment]) -> Vec<(&str, Option<usize>)> {
+        segments.iter().map(|s| (s.text.as_str(), s.cite)).collect()
+    }
+
+    #[test]
+    fn keywords_drop_stopwords_short_words_and_duplicates() {
+        assert_eq!(keywords("Di mana fungsi login_user dipanggil?"), ["fungsi", "login_user", "dipanggil"]);
+        assert_eq!(keywords("How is the Token TOKEN refreshed"), ["token", "refreshed"]);
+        assert!(keywords("apa itu di ke?").is_empty());
+        let many: String = (0..15).map(|i| format!("word{i} ")).collect();
+        assert_eq!(keywords(&many).len(), 10);
+    }
+
+    #[test]
+    fn segments_link_known_citations() {
+        let m = message("Login ada di [1], token di [2].", &[1, 2]);
+        let segs = m.segments(7);
+        assert_eq!(
+            parts(&segs),
+            [("Login ada di ", None), ("[1]", Some(1)), (", token di ", None), ("[2]", Some(2)), (".", None)]
+        );
+        assert_eq!(segs[1].href.as_deref(), Some("/apps/7?q=f1.rs#file-10"));
+        assert!(segs[0].href.is_none());
+    }
+
+    #[test]
+    fn segments_keep_unknown_citation_as_text() {
+        let m = message("Lihat [3] dan [1]", &[1]);
+        assert_eq!(parts(&m.segments(7)), [("Lihat [3] dan ", None), ("[1]", Some(1))]);
+        let m = message("Tanpa sumber [1]", &[]);
+        assert_eq!(parts(&m.segments(7)), [("Tanpa sumber [1]", None)]);
+        assert!(message("", &[]).segments(7).is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+// src/context.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Konteks file terkait untuk prompt analisa per file. Tetangga diambil dari
+// import yang sudah di-resolve (imports.rs): file yang diimpor lebih dulu,
+// lalu file yang mengimpor file ini. Tiap tetangga diwakili ringkasan
+// analisa "fungsi" yang sudah ada (bahasa yang sama), atau bila belum ada,
+// baris-baris signature dari kodenya. Total dibatasi ANALYSIS_CONTEXT_TOKENS.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use sqlx::MySqlPool;
+
+use crate::usage::estimate_tokens;
+
+/// Batas token konteks (env ANALYSIS_CONTEXT_TOKENS, default 1500; 0 = nonaktif).
+pub fn token_budget() -> i64 {
+    std::env::var("ANALYSIS_CONTEXT_TOKENS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1500)
+}
+
+const SUMMARY_CHARS: usize = 800;
+
+/// Teks pembingkai konteks di prompt.
+struct Framing {
+    heading: &'static str,
+    imported: &'static str,
+    importer: &'static str,
+    functions: &'static str,
+    skipped: &'static str,
+}
+
+const FRAMING_ID: Framing = Framing {
+    heading: "Konteks file terkait (ringkasan file yang diimpor dan yang mengimpor file ini; \
+              gunakan untuk menjelaskan hubungan antar file, jangan dianalisa ulang):",
+    imported: "diimpor oleh file ini",
+    importer: "mengimpor file ini",
+    functions: "Fungsi",
+    skipped: "file terkait lain tidak dimuat karena batas token.",
+};
+
+const FRAMING_EN: Framing = Framing {
+    heading: "Related files (summaries of the files imported by and importing this file; \
+              use them to explain how the files relate, do not analyze them again):",
+    imported: "imported by this file",
+    importer: "imports this file",
+    functions: "Functions",
+    skipped: "more related files omitted due to the token limit.",
+};
+
+/// Bahasa Indonesia untuk "id", selain itu bahasa Inggris; bahasa jawaban
+/// tetap diatur oleh `lang::instruction`.
+fn framing(lang: &str) -> &'static Framing {
+    if lang == "id" {
+        &FRAMING_ID
+    } else {
+        &FRAMING_EN
+    }
+}
+const MAX_SIGNATURES: usize = 25;
+
+fn cut(s: &str, max: usize) -> String {
+    if s.len() <= max {
+        return s.to_string();
+    }
+    let end = (0..=max).rev().find(|i| s.is_char_boundary(*i)).unwrap_or(0);
+    format!("{}…", &s[..end])
+}
+
+/// Baris deklarasi (fungsi, kelas, struct, ...) sebagai pengganti ringkasan.
+pub fn signatures(code: &str) -> Vec<String> {
+    static SIG: OnceLock<Regex> = OnceLock::new();
+    let re = SIG.get_or_init(|| {
+        Regex::new(
+            r"^\s*(?:(?:pub(?:\([^)]*\))?|export|default|public|private|protected|static|async|abstract|final|unsafe)\s+)*(?:fn|def|class|struct|enum|trait|impl|interface|type|function|func|const\s+\w+\s*=\s*(?:async\s*)?\(|module)\b",
+        )
+        .expect("regex signature valid")
+    });
+    code.lines()
+        .filter(|l| re.is_match(l))
+        .map(|l| cut(l.trim().trim_end_matches('{').trim_end(), 160))
+        .take(MAX_SIGNATURES)
+        .collect()
+}
+
+/// Wakil satu file tetangga: ringkasan analisa fungsi atau signature.
+async fn describe(pool: &MySqlPool, file_id: i64, lang: &str) -> anyhow::Result<Option<String>> {
+    if let Some(data) = crate::services::cached_structured(pool, file_id, "fungsi", lang).await? {
+        if let Some(s) = data.get("ringkasan").and_then(|v| v.as_str()) {
+            let names: Vec<&str> = data
+                .get("functions")
+                .and_then(|v| v.as_array())
+                .map(|a| a.iter().filter_map(|f| f.get("name").and_then(|n| n.as_str())).collect())
+                .unwrap_or_default();
+            let mut out = cut(s, SUMMARY_CHARS);
+            if !names.is_empty() {
+                out.push_str(&format!("\n{}: {}", framing(lang).functions, names.join(", ")));
+            }
+            return Ok(Some(out));
+        }
+    }
+    if let Some(text) = crate::services::cached_analysis(pool, file_id, "fungsi", lang).await? {
+        return Ok(Some(cut(text.trim(), SUMMARY_CHARS)));
+    }
+    let src = crate::services::load_file_source(pool, file_id).await?;
+    let sigs = signatures(&src.code);
+    Ok(if sigs.is_empty() { None } else { Some(sigs.join("\n")) })
+}
+
+/// Teks konteks untuk prompt analisa `file_id`; kosong bila tidak ada
+/// tetangga atau budget 0.
+pub async fn related(pool: &MySqlPool, file_id: i64, app_id: i64, lang: &str) -> anyhow::Result<String> {
+    let budget = token_budget();
+    if budget <= 0 {
+        return Ok(String::new());
+    }
+    crate::imports::ensure_indexed(pool, app_id).await?;
+    let imports = crate::imports::imports_of(pool, file_id).await?;
+    let importers = crate::imports::importers_of(pool, file_id).await?;
+
+    let mut seen = std::collections::HashSet::from([file_id]);
+    let neighbours = imports
+        .into_iter()
+        .map(|(id, p)| (id, p, framing(lang).imported))
+        .chain(importers.into_iter().map(|(id, p)| (id, p, framing(lang).importer)));
+
+    let mut out = String::new();
+    let mut used = 0;
+    let mut skipped = 0;
+    for (id, path, relation) in neighbours {
+        if !seen.insert(id) {
+            continue;
+        }
+        if used >= budget {
+            skipped += 1;
+            continue;
+        }
+        let Some(desc) = describe(pool, id, lang).await? else {
+            continue;
+        };
+        let block = format!("### {path} ({relation})\n{desc}\n\n");
+        let tokens = estimate_tokens(&block);
+        if used + tokens > budget {
+            skipped += 1;
+            continue;
+        }
+        used += tokens;
+        out.push_str(&block);
+    }
+    if skipped > 0 && !out.is_empty() {
+        out.push_str(&format!("({skipped} {})\n", framing(lang).skipped));
+    }
+    Ok(out.trim_end().to_string())
+}
+
+/// Tambahkan konteks ke prompt: di posisi `{{konteks}}` bila template
+/// memakainya, selain itu sebagai bagian terpisah di akhir.
+pub fn apply(template: &str, prompt: String, context: &str, lang: &str) -> String {
+    if context.is_empty() || template.contains("{{konteks}}") {
+        return prompt;
+    }
+    format!("{prompt}\n\n{}\n{context}", framing(lang).heading)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_frames_context_in_output_language() {
+        let id = apply("Review {{kode}}", "Review x".into(), "### a.rs", "id");
+        assert!(id.starts_with("Review x\n\nKonteks file terkait"), "{id}");
+        assert!(id.ends_with(":\n### a.rs"));
+        let en = apply("Review {{kode}}", "Review x".into(), "### a.rs", "ja");
+        assert!(en.starts_with("Review x\n\nRelated files"), "{en}");
+        assert!(!en.contains("Konteks"));
+    }
+
+    #[test]
+    fn apply_leaves_prompt_when_template_places_context() {
+        assert_eq!(apply("{{kode}}\n{{konteks}}", "x\nctx".into(), "ctx", "en"), "x\nctx");
+        assert_eq!(apply("{{kode}}", "x".into(), "", "en"), "x");
+    }
+}
@@ -0,0 +1,749 @@
+// src/dbschema.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Model skema database aplikasi tanpa LLM:
+// - statement CREATE TABLE / ALTER TABLE / DROP TABLE di file .sql diparse
+//   menjadi tabel, kolom, primary key, index dan foreign key;
+// - literal string SQL di kode (SELECT ... FROM/JOIN, INSERT INTO, UPDATE,
+//   DELETE FROM, REPLACE INTO) dicatat sebagai pemakaian tabel per file
+//   (baca / tulis). Tabel yang dipakai kode tetapi tidak dideklarasikan
+//   tetap masuk model dengan `declared = false`.
+// Hasilnya dipakai untuk diagram ER dan matriks tabel × file.
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+/// Nomor baris pemakaian yang disimpan per file per tabel.
+const MAX_LINES: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Column {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub primary: bool,
+    pub unique: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ForeignKey {
+    pub name: Option<String>,
+    pub columns: Vec<String>,
+    pub ref_table: String,
+    pub ref_columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Index {
+    pub name: Option<String>,
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceFile {
+    pub file_id: i64,
+    pub path: String,
+    pub href: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Table {
+    pub name: String,
+    /// false = hanya ditemukan di query kode, tidak ada CREATE TABLE-nya.
+    pub declared: bool,
+    pub columns: Vec<Column>,
+    pub primary_key: Vec<String>,
+    pub indexes: Vec<Index>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub defined_in: Vec<SourceFile>,
+}
+
+impl Table {
+    fn new(name: &str) -> Self {
+        Table {
+            name: name.to_string(),
+            declared: false,
+            columns: Vec::new(),
+            primary_key: Vec::new(),
+            indexes: Vec::new(),
+            foreign_keys: Vec::new(),
+            defined_in: Vec::new(),
+        }
+    }
+
+    fn column_mut(&mut self, name: &str) -> Option<&mut Column> {
+        self.columns.iter_mut().find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    fn set_primary(&mut self, cols: Vec<String>) {
+        for c in &mut self.columns {
+            c.primary = cols.iter().any(|p| p.eq_ignore_ascii_case(&c.name));
+            if c.primary {
+                c.nullable = false;
+            }
+        }
+        self.primary_key = cols;
+    }
+}
+
+/// Pemakaian satu tabel oleh satu file.
+#[derive(Debug, Clone, Serialize)]
+pub struct Usage {
+    pub table: String,
+    pub file_id: i64,
+    pub path: String,
+    pub href: String,
+    pub read: bool,
+    pub write: bool,
+    /// File .sql yang mendefinisikan / mengubah struktur tabel.
+    pub ddl: bool,
+    pub lines: Vec<usize>,
+}
+
+impl Usage {
+    /// Kode sel matriks: R, W, RW, plus D untuk DDL.
+    pub fn code(&self) -> String {
+        let mut s = String::new();
+        if self.read {
+            s.push('R');
+        }
+        if self.write {
+            s.push('W');
+        }
+        if self.ddl {
+            s.push('D');
+        }
+        s
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Schema {
+    pub tables: Vec<Table>,
+    pub usage: Vec<Usage>,
+    pub sql_files: usize,
+}
+
+impl Schema {
+    /// File yang memakai minimal satu tabel (urut path) untuk baris matriks.
+    pub fn files(&self) -> Vec<SourceFile> {
+        let mut files: BTreeMap<&str, SourceFile> = BTreeMap::new();
+        for u in &self.usage {
+            files.entry(u.path.as_str()).or_insert_with(|| SourceFile {
+                file_id: u.file_id,
+                path: u.path.clone(),
+                href: u.href.clone(),
+            });
+        }
+        files.into_values().collect()
+    }
+
+    pub fn usage_of(&self, file_id: i64, table: &str) -> Option<&Usage> {
+        self.usage.iter().find(|u| u.file_id == file_id && u.table.eq_ignore_ascii_case(table))
+    }
+}
+
+fn re(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("regex dbschema valid"))
+}
+
+const IDENT: &str = r#"(?:[`"\[]?[A-Za-z_][\w$]*[`"\]]?\.)?[`"\[]?([A-Za-z_][\w$]*)[`"\]]?"#;
+
+/// Nama identifier tanpa kutip / prefix schema (`db`.`users` → users).
+fn ident(raw: &str) -> String {
+    let last = raw.trim().rsplit('.').next().unwrap_or("");
+    last.trim_matches(|c| c == '`' || c == '"' || c == '[' || c == ']').to_string()
+}
+
+/// Daftar kolom dalam kurung: "(`a`, b(10) DESC)" → [a, b].
+fn column_list(s: &str) -> Vec<String> {
+    let inner = s.trim().trim_start_matches('(').trim_end_matches(')');
+    split_top_level(inner, ',')
+        .iter()
+        .filter_map(|c| c.split_whitespace().next())
+        .map(|c| ident(c.split('(').next().unwrap_or(c)))
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+/// Pecah teks pada `sep` di luar kurung dan kutip.
+fn split_top_level(s: &str, sep: char) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut cur = String::new();
+    for ch in s.chars() {
+        match quote {
+            Some(q) => {
+                if ch == q {
+                    quote = None;
+                }
+                cur.push(ch);
+                continue;
+            }
+            None if ch == '\'' || ch == '"' || ch == '`' => {
+                quote = Some(ch);
+                cur.push(ch);
+                continue;
+            }
+            None => {}
+        }
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c == sep && depth == 0 => {
+                out.push(std::mem::take(&mut cur));
+                continue;
+            }
+            _ => {}
+        }
+        cur.push(ch);
+    }
+    if !cur.trim().is_empty() {
+        out.push(cur);
+    }
+    out
+}
+
+/// Buang komentar SQL (`-- `, `#`, `/* */`) di luar kutip.
+fn strip_comments(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let chars: Vec<char> = sql.chars().collect();
+    let mut i = 0;
+    let mut quote: Option<char> = None;
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(q) = quote {
+            out.push(c);
+            if c == q {
+                quote = None;
+            }
+            i += 1;
+            continue;
+        }
+        if c == '\'' || c == '"' || c == '`' {
+            quote = Some(c);
+            out.push(c);
+            i += 1;
+        } else if c == '-' && chars.get(i + 1) == Some(&'-') || c == '#' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                if chars[i] == '\n' {
+                    out.push('\n');
+                }
+                i += 1;
+            }
+            i += 2;
+        } else {
+            out.push(c);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Isi kurung pertama setelah posisi `from` (seimbang), beserta sisanya.
+fn paren_body(s: &str) -> Option<(&str, &str)> {
+    let start = s.find('(')?;
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for (i, ch) in s[start..].char_indices() {
+        if let Some(q) = quote {
+            if ch == q {
+                quote = None;
+            }
+            continue;
+        }
+        match ch {
+            '\'' | '"' | '`' => quote = Some(ch),
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    let end = start + i;
+                    return Some((&s[start + 1..end], &s[end + 1..]));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_foreign_key(name: Option<String>, rest: &str) -> Option<ForeignKey> {
+    static FK: OnceLock<Regex> = OnceLock::new();
+    let fk = re(
+        &FK,
+        &format!(r"(?is)FOREIGN\s+KEY\s*(?:{IDENT}\s*)?(\([^)]*\))\s*REFERENCES\s+({IDENT})\s*(\([^)]*\))?"),
+    );
+    let c = fk.captures(rest)?;
+    Some(ForeignKey {
+        name,
+        columns: column_list(c.get(2)?.as_str()),
+        ref_table: ident(c.get(3)?.as_str()),
+        ref_columns: c.get(5).map(|m| column_list(m.as_str())).unwrap_or_default(),
+    })
+}
+
+/// Satu definisi kolom (`name TYPE ... [NOT NULL] [PRIMARY KEY] [REFERENCES t(c)]`).
+fn parse_column(def: &str, table: &mut Table) {
+    static REFS: OnceLock<Regex> = OnceLock::new();
+    let refs = re(&REFS, &format!(r"(?is)\bREFERENCES\s+({IDENT})\s*(\([^)]*\))?"));
+    let mut parts = def.trim().splitn(2, char::is_whitespace);
+    let name = ident(parts.next().unwrap_or(""));
+    if name.is_empty() {
+        return;
+    }
+    let rest = parts.next().unwrap_or("").trim();
+    let upper = rest.to_ascii_uppercase();
+    // Tipe dengan argumen: varchar(255), decimal(10, 2), enum('a','b')
+    let type_end = rest.find(|c: char| c.is_whitespace() || c == '(').unwrap_or(rest.len());
+    let base = &rest[..type_end];
+    let data_type = match rest[type_end..].starts_with('(').then(|| paren_body(&rest[type_end..])).flatten() {
+        Some((args, _)) => format!("{base}({})", args.trim()),
+        None => base.to_string(),
+    };
+    let primary = upper.contains("PRIMARY KEY");
+    let column = Column {
+        name: name.clone(),
+        data_type: data_type.to_ascii_lowercase(),
+        nullable: !upper.contains("NOT NULL") && !primary,
+        primary,
+        unique: upper.contains("UNIQUE"),
+    };
+    if let Some(existing) = table.column_mut(&name) {
+        *existing = column;
+    } else {
+        table.columns.push(column);
+    }
+    if primary {
+        table.primary_key = vec![name.clone()];
+    }
+    if let Some(c) = refs.captures(rest) {
+        table.foreign_keys.push(ForeignKey {
+            name: None,
+            columns: vec![name],
+            ref_table: ident(c.get(1).map(|m| m.as_str()).unwrap_or("")),
+            ref_columns: c.get(3).map(|m| column_list(m.as_str())).unwrap_or_default(),
+        });
+    }
+}
+
+/// Elemen di dalam CREATE TABLE (...) atau aksi ADD di ALTER TABLE.
+fn parse_element(item: &str, table: &mut Table) {
+    static CONSTRAINT: OnceLock<Regex> = OnceLock::new();
+    let constraint = re(&CONSTRAINT, &format!(r"(?is)^CONSTRAINT\s+{IDENT}\s+(.*)$"));
+    let item = item.trim();
+    if item.is_empty() {
+        return;
+    }
+    let (name, body) = match constraint.captures(item) {
+        Some(c) => (c.get(1).map(|m| m.as_str().to_string()), c.get(2).map(|m| m.as_str()).unwrap_or("")),
+        None => (None, item),
+    };
+    let upper = body.to_ascii_uppercase();
+    let cols = || paren_body(body).map(|(b, _)| column_list(b)).unwrap_or_default();
+    let index_name = || {
+        body.split_whitespace()
+            .nth(if upper.starts_with("UNIQUE KEY") || upper.starts_with("UNIQUE INDEX") { 2 } else { 1 })
+            .filter(|n| !n.starts_with('('))
+            .map(|n| ident(n.split('(').next().unwrap_or(n)))
+    };
+    if upper.starts_with("PRIMARY KEY") {
+        table.set_primary(cols());
+    } else if upper.starts_with("FOREIGN KEY") {
+        if let Some(fk) = parse_foreign_key(name, body) {
+            table.foreign_keys.push(fk);
+        }
+    } else if upper.starts_with("UNIQUE") {
+        table.indexes.push(Index { name: name.or_else(index_name), columns: cols(), unique: true });
+    } else if upper.starts_with("KEY ") || upper.starts_with("INDEX ") || upper.starts_with("FULLTEXT") || upper.starts_with("SPATIAL") {
+        table.indexes.push(Index { name: index_name(), columns: cols(), unique: false });
+    } else if upper.starts_with("CHECK") {
+        // constraint CHECK tidak dimodelkan
+    } else {
+        parse_column(body, table);
+    }
+}
+
+/// Terapkan satu aksi ALTER TABLE.
+fn parse_alter_action(action: &str, table: &mut Table) {
+    static WORDS: OnceLock<Regex> = OnceLock::new();
+    let words = re(&WORDS, r"(?is)^(ADD|DROP|MODIFY|CHANGE|ALTER)\s+(?:(COLUMN)\s+)?(.*)$");
+    let Some(c) = words.captures(action.trim()) else {
+        return;
+    };
+    let verb = c[1].to_ascii_uppercase();
+    let rest = c.get(3).map(|m| m.as_str().trim()).unwrap_or("");
+    let upper = rest.to_ascii_uppercase();
+    match verb.as_str() {
+        "ADD" => {
+            let inner = rest.trim_start_matches('(').trim_end_matches(')');
+            if rest.starts_with('(') {
+                for item in split_top_level(inner, ',') {
+                    parse_element(&item, table);
+                }
+            } else {
+                parse_element(rest, table);
+            }
+        }
+        "DROP" => {
+            if upper.starts_with("PRIMARY KEY") {
+                table.set_primary(Vec::new());
+            } else if upper.starts_with("FOREIGN KEY") {
+                let name = rest.split_whitespace().nth(2).map(ident);
+                table.foreign_keys.retain(|f| f.name != name);
+            } else if upper.starts_with("INDEX") || upper.starts_with("KEY") {
+                let name = rest.split_whitespace().nth(1).map(ident);
+                table.indexes.retain(|i| i.name != name);
+            } else if let Some(col) = rest.split_whitespace().next().map(ident) {
+                table.columns.retain(|c| !c.name.eq_ignore_ascii_case(&col));
+                table.primary_key.retain(|c| !c.eq_ignore_ascii_case(&col));
+            }
+        }
+        "MODIFY" => parse_column(rest, table),
+        "CHANGE" => {
+            let mut parts = rest.splitn(2, char::is_whitespace);
+            let old = ident(parts.next().unwrap_or(""));
+            let def = parts.next().unwrap_or("");
+            let new_name = def.split_whitespace().next().map(ident).unwrap_or_default();
+            if let Some(col) = table.column_mut(&old) {
+                col.name = new_name;
+            }
+            parse_column(def, table);
+        }
+        _ => {}
+    }
+}
+
+/// Parse isi file .sql ke dalam `tables`; kembalikan tabel yang disentuh DDL.
+fn parse_ddl(sql: &str, tables: &mut BTreeMap<String, Table>) -> Vec<String> {
+    static CREATE: OnceLock<Regex> = OnceLock::new();
+    static ALTER: OnceLock<Regex> = OnceLock::new();
+    static DROP: OnceLock<Regex> = OnceLock::new();
+    let create = re(
+        &CREATE,
+        &format!(r"(?is)^\s*CREATE\s+(?:OR\s+REPLACE\s+)?(?:TEMPORARY\s+)?TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?({IDENT})\s*\("),
+    );
+    let alter = re(&ALTER, &format!(r"(?is)^\s*ALTER\s+TABLE\s+(?:ONLY\s+)?(?:IF\s+EXISTS\s+)?({IDENT})\s+(.*)$"));
+    let drop = re(&DROP, &format!(r"(?is)^\s*DROP\s+TABLE\s+(?:IF\s+EXISTS\s+)?({IDENT})"));
+
+    let mut touched = Vec::new();
+    for stmt in split_top_level(&strip_comments(sql), ';') {
+        if let Some(c) = create.captures(&stmt) {
+            let name = ident(&c[1]);
+            let key = name.to_ascii_lowercase();
+            let mut table = Table::new(&name);
+            table.declared = true;
+            if let Some((body, _)) = paren_body(&stmt[c.get(0).map(|m| m.end() - 1).unwrap_or(0)..]) {
+                for item in split_top_level(body, ',') {
+                    parse_element(&item, &mut table);
+                }
+            }
+            if !table.primary_key.is_empty() {
+                let pk = table.primary_key.clone();
+                table.set_primary(pk);
+            }
+            tables.insert(key.clone(), table);
+            touched.push(key);
+        } else if let Some(c) = alter.captures(&stmt) {
+            let name = ident(&c[1]);
+            let key = name.to_ascii_lowercase();
+            let table = tables.entry(key.clone()).or_insert_with(|| Table::new(&name));
+            for action in split_top_level(c.get(3).map(|m| m.as_str()).unwrap_or(""), ',') {
+                parse_alter_action(&action, table);
+            }
+            touched.push(key);
+        } else if let Some(c) = drop.captures(&stmt) {
+            tables.remove(&ident(&c[1]).to_ascii_lowercase());
+        }
+    }
+    touched
+}
+
+/// Literal string di kode beserta baris awalnya ("...", '...', `...`, r#"..."#).
+fn string_literals(code: &str) -> Vec<(usize, &str)> {
+    static LIT: OnceLock<Regex> = OnceLock::new();
+    let lit = re(
+        &LIT,
+        r##"(?s)r#+"(.*?)"#+|"((?:[^"\\]|\\.)*)"|'((?:[^'\\\n]|\\.)*)'|`((?:[^`\\]|\\.)*)`"##,
+    );
+    lit.captures_iter(code)
+        .filter_map(|c| {
+            let m = (1..=4).find_map(|i| c.get(i))?;
+            let line = code[..m.start()].matches('\n').count() + 1;
+            Some((line, m.as_str()))
+        })
+        .collect()
+}
+
+/// Tabel yang dibaca / ditulis oleh satu teks SQL. Daftar tabel berkoma
+/// setelah FROM (`FROM a, b x`) ikut dihitung; nama CTE (`WITH c AS (...)`)
+/// bukan tabel sehingga dilewati.
+pub fn sql_usage(sql: &str) -> Vec<(String, bool)> {
+    static KEYWORD: OnceLock<Regex> = OnceLock::new();
+    static WRITE: OnceLock<Regex> = OnceLock::new();
+    static READ: OnceLock<Regex> = OnceLock::new();
+    static FROM_LIST: OnceLock<Regex> = OnceLock::new();
+    static CTE: OnceLock<Regex> = OnceLock::new();
+    static DELETE_BEFORE: OnceLock<Regex> = OnceLock::new();
+    let keyword = re(&KEYWORD, r"(?i)\b(SELECT|INSERT|UPDATE|DELETE|REPLACE)\b");
+    let write = re(
+        &WRITE,
+        &format!(
+            r"(?is)\b(?:INSERT\s+(?:IGNORE\s+)?INTO|REPLACE\s+INTO|UPDATE(?:\s+IGNORE)?|DELETE\s+(?:\w+\s+)?FROM)\s+{IDENT}"
+        ),
+    );
+    let read = re(&READ, &format!(r"(?is)\b(FROM|JOIN)\s+{IDENT}"));
+    // ", tabel" berikutnya setelah tabel FROM (dengan alias opsional)
+    let from_list = re(
+        &FROM_LIST,
+        &format!(r#"(?is)^(?:\s+(?:AS\s+)?[`"\[]?[A-Za-z_][\w$]*[`"\]]?)?\s*,\s*{IDENT}"#),
+    );
+    let cte = re(
+        &CTE,
+        &format!(r"(?is)(?:\bWITH\s+(?:RECURSIVE\s+)?|\)\s*,\s*){IDENT}\s*(?:\([^()]*\)\s*)?AS\s*\("),
+    );
+    let delete_before = re(&DELETE_BEFORE, r"(?is)\bDELETE(?:\s+\w+)?\s*$");
+
+    if !keyword.is_match(sql) {
+        return Vec::new();
+    }
+    let upper = sql.to_ascii_uppercase();
+    let ctes: Vec<String> = if upper.contains("WITH") {
+        cte.captures_iter(sql).map(|c| c[1].to_string()).collect()
+    } else {
+        Vec::new()
+    };
+    let is_cte = |name: &str| ctes.iter().any(|c| c.eq_ignore_ascii_case(name));
+    let mut out = Vec::new();
+    for c in write.captures_iter(sql) {
+        let m = c.get(0).map(|m| m.as_str().to_ascii_uppercase()).unwrap_or_default();
+        // UPDATE harus diikuti SET supaya kata "update" biasa tidak terhitung
+        if m.starts_with("UPDATE") && !upper[c.get(0).map(|m| m.end()).unwrap_or(0)..].trim_start().starts_with("SET") {
+            continue;
+        }
+        if !is_cte(&c[1]) {
+            out.push((c[1].to_string(), true));
+        }
+    }
+    if upper.contains("SELECT") {
+        for c in read.captures_iter(sql) {
+            let Some(m) = c.get(0) else { continue };
+            if delete_before.is_match(&sql[..m.start()]) {
+                continue;
+            }
+            let mut names = vec![c[2].to_string()];
+            if c[1].eq_ignore_ascii_case("FROM") {
+                let mut end = m.end();
+                while let Some(next) = from_list.captures(&sql[end..]) {
+                    names.push(next[1].to_string());
+                    end += next.get(0).map(|n| n.end()).unwrap_or(0);
+                }
+            }
+            for name in names {
+                if !name.eq_ignore_ascii_case("dual") && !is_cte(&name) {
+                    out.push((name, false));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// File yang tidak dipindai untuk query (dokumentasi / data).
+fn skip_usage_scan(path: &str) -> bool {
+    matches!(
+        crate::fulltext::language_of(path),
+        None | Some("Markdown") | Some("JSON") | Some("YAML") | Some("XML") | Some("CSS")
+    )
+}
+
+/// Bangun model skema dari seluruh file aplikasi.
+pub async fn build(pool: &MySqlPool, app_id: i64) -> anyhow::Result<Schema> {
+    let files: Vec<(i64, String, Option<String>)> =
+        sqlx::query_as("SELECT id, full_path, content_file FROM files WHERE app_id=? ORDER BY full_path")
+            .bind(app_id)
+            .fetch_all(pool)
+            .await?;
+
+    let mut tables: BTreeMap<String, Table> = BTreeMap::new();
+    // (file_id, table lowercase) → usage
+    let mut usage: BTreeMap<(i64, String), Usage> = BTreeMap::new();
+    let mut sql_files = 0;
+    let new_usage = |file_id: i64, path: &str, table: &str| Usage {
+        table: table.to_string(),
+        file_id,
+        path: path.to_string(),
+        href: crate::services::file_href(app_id, file_id, path),
+        read: false,
+        write: false,
+        ddl: false,
+        lines: Vec::new(),
+    };
+
+    // DDL dulu (urut path) supaya ALTER di migrasi berikutnya diterapkan berurutan
+    for (file_id, path, content) in &files {
+        if !path.to_ascii_lowercase().ends_with(".sql") {
+            continue;
+        }
+        let Some(code) = content.as_deref() else { continue };
+        sql_files += 1;
+        for key in parse_ddl(code, &mut tables) {
+            let name = tables.get(&key).map(|t| t.name.clone()).unwrap_or_else(|| key.clone());
+            if let Some(t) = tables.get_mut(&key) {
+                if !t.defined_in.iter().any(|f| f.file_id == *file_id) {
+                    t.defined_in.push(SourceFile {
+                        file_id: *file_id,
+                        path: path.clone(),
+                        href: crate::services::file_href(app_id, *file_id, path),
+                    });
+                }
+            }
+            usage
+                .entry((*file_id, key))
+                .or_insert_with(|| new_usage(*file_id, path, &name))
+                .ddl = true;
+        }
+    }
+
+    for (file_id, path, content) in &files {
+        if skip_usage_scan(path) {
+            continue;
+        }
+        let Some(code) = content.as_deref() else { continue };
+        let is_sql = path.to_ascii_lowercase().ends_with(".sql");
+        let pieces: Vec<(usize, String)> = if is_sql {
+            // Statement di file .sql (termasuk INSERT data awal) beserta baris awalnya
+            let mut line = 1;
+            split_top_level(&strip_comments(code), ';')
+                .into_iter()
+                .map(|stmt| {
+                    let leading = &stmt[..stmt.len() - stmt.trim_start().len()];
+                    let at = line + leading.matches('\n').count();
+                    line += stmt.matches('\n').count();
+                    (at, stmt)
+                })
+                .collect()
+        } else {
+            string_literals(code).into_iter().map(|(l, s)| (l, s.to_string())).collect()
+        };
+        for (line, text) in pieces {
+            for (name, is_write) in sql_usage(&text) {
+                let key = name.to_ascii_lowercase();
+                let display = tables.get(&key).map(|t| t.name.clone()).unwrap_or(name);
+                tables.entry(key.clone()).or_insert_with(|| Table::new(&display));
+                let u = usage
+                    .entry((*file_id, key))
+                    .or_insert_with(|| new_usage(*file_id, path, &display));
+                if is_write {
+                    u.write = true;
+                } else {
+                    u.read = true;
+                }
+                if u.lines.len() < MAX_LINES && !u.lines.contains(&line) {
+                    u.lines.push(line);
+                }
+            }
+        }
+    }
+
+    Ok(Schema { tables: tables.into_values().collect(), usage: usage.into_values().collect(), sql_files })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reads(sql: &str) -> Vec<String> {
+        sql_usage(sql).into_iter().filter(|(_, w)| !w).map(|(t, _)| t).collect()
+    }
+
+    #[test]
+    fn from_list_counts_every_table() {
+        assert_eq!(reads("SELECT * FROM t1, t2 WHERE t1.id = t2.id"), ["t1", "t2"]);
+        assert_eq!(reads("SELECT * FROM `db`.`t1` a, t2 AS b, t3 JOIN t4 ON 1=1"), ["t1", "t2", "t3", "t4"]);
+        assert_eq!(reads("SELECT a, b FROM t ORDER BY a, b"), ["t"]);
+        assert_eq!(reads("SELECT * FROM t LIMIT 10, 20"), ["t"]);
+    }
+
+    #[test]
+    fn cte_names_are_not_tables() {
+        assert_eq!(reads("WITH c AS (SELECT * FROM orders) SELECT * FROM c"), ["orders"]);
+        assert_eq!(
+            reads("WITH RECURSIVE a (n) AS (SELECT 1 FROM dual), b AS (SELECT * FROM users) SELECT * FROM a, b, items"),
+            ["users", "items"]
+        );
+        let usage = sql_usage("WITH c AS (SELECT id FROM src) INSERT INTO dst SELECT * FROM c");
+        assert_eq!(usage, [("dst".to_string(), true), ("src".to_string(), false)]);
+    }
+
+    #[test]
+    fn writes_and_reads() {
+        let usage = sql_usage("UPDATE users SET a=1 WHERE id IN (SELECT id FROM banned)");
+        assert_eq!(usage, [("users".to_string(), true), ("banned".to_string(), false)]);
+        assert!(sql_usage("please update the docs from github").is_empty());
+        assert_eq!(sql_usage("DELETE FROM logs WHERE id IN (SELECT 1)"), [("logs".to_string(), true)]);
+    }
+
+    #[test]
+    fn ddl_builds_tables_and_applies_alters() {
+        let sql = "-- skema
+CREATE TABLE IF NOT EXISTS `users` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `email` varchar(255) NOT NULL UNIQUE,
+  `note` text, /* bebas; boleh kosong */
+  PRIMARY KEY (`id`)
+);
+CREATE TABLE orders (id bigint PRIMARY KEY, user_id int, total decimal(10,2),
+  CONSTRAINT fk_user FOREIGN KEY (user_id) REFERENCES users (id));
+ALTER TABLE orders ADD COLUMN status varchar(16) NOT NULL, ADD KEY idx_status (status);
+CREATE TABLE tmp (x int);
+DROP TABLE tmp;";
+        let mut tables = BTreeMap::new();
+        let touched = parse_ddl(sql, &mut tables);
+        assert_eq!(touched, ["users", "orders", "orders", "tmp"]);
+        assert_eq!(tables.keys().collect::<Vec<_>>(), ["orders", "users"]);
+
+        let users = &tables["users"];
+        assert!(users.declared);
+        assert_eq!(users.primary_key, ["id"]);
+        let cols: Vec<(&str, bool, bool, bool)> =
+            users.columns.iter().map(|c| (c.name.as_str(), c.nullable, c.primary, c.unique)).collect();
+        assert_eq!(cols, [("id", false, true, false), ("email", false, false, true), ("note", true, false, false)]);
+
+        let orders = &tables["orders"];
+        assert_eq!(orders.primary_key, ["id"]);
+        let names: Vec<&str> = orders.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "user_id", "total", "status"]);
+        let fk = &orders.foreign_keys[0];
+        assert_eq!(fk.name.as_deref(), Some("fk_user"));
+        assert_eq!((fk.columns.as_slice(), fk.ref_table.as_str()), (&["user_id".to_string()][..], "users"));
+        assert_eq!(fk.ref_columns, ["id"]);
+        assert_eq!(orders.indexes.iter().map(|i| i.columns.clone()).collect::<Vec<_>>(), [vec!["status".to_string()]]);
+    }
+
+    #[test]
+    fn string_literals_keep_their_line() {
+        let code = "let a = \"SELECT 1\";\nlet b = r#\"UPDATE t\nSET x=1\"#;\n$q = 'it\\'s';";
+        let lits: Vec<(usize, &str)> = string_literals(code);
+        assert_eq!(lits, [(1, "SELECT 1"), (2, "UPDATE t\nSET x=1"), (4, "it\\'s")]);
+    }
+}
@@ -0,0 +1,374 @@
+// src/depgraph.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Graph dependensi modul seluruh aplikasi, dihitung dari import yang
+// ter-resolve (file_imports, lihat imports.rs) tanpa LLM. Node = file,
+// edge = file → file yang diimpor. Setiap kondisi isi aplikasi (snapshot,
+// ditandai signature jumlah/id file dan import) disimpan sekali di tabel
+// dependency_graphs; graph dihitung ulang hanya bila signature berubah.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+/// Jumlah snapshot yang disimpan per aplikasi (yang lebih lama dihapus).
+const KEEP_SNAPSHOTS: i64 = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleNode {
+    pub file_id: i64,
+    pub path: String,
+    /// Folder file ("" = akar aplikasi), dipakai untuk cluster dan filter.
+    pub folder: String,
+    pub language: Option<String>,
+    pub line_count: Option<i32>,
+    pub imports: usize,
+    pub imported_by: usize,
+    /// Tautan ke baris file di halaman aplikasi.
+    pub href: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleEdge {
+    pub from: i64,
+    pub to: i64,
+    /// Spesifier import yang menghasilkan edge ini (mis. `crate::db`, `./util`).
+    pub specs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderStat {
+    pub folder: String,
+    pub files: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DepGraph {
+    pub nodes: Vec<ModuleNode>,
+    pub edges: Vec<ModuleEdge>,
+    pub folders: Vec<FolderStat>,
+    /// Import yang tidak ter-resolve ke file aplikasi (library luar).
+    pub external_imports: usize,
+}
+
+fn in_folder(node_folder: &str, folder: &str) -> bool {
+    folder.is_empty() || node_folder == folder || node_folder.starts_with(&format!("{folder}/"))
+}
+
+impl DepGraph {
+    /// Hanya file di `folder` (termasuk subfolder). `neighbors` = ikutkan juga
+    /// file di luar folder yang langsung diimpor / mengimpor file tersebut.
+    pub fn filter_folder(&self, folder: &str, neighbors: bool) -> DepGraph {
+        let folder = folder.trim_matches('/');
+        if folder.is_empty() {
+            return self.clone();
+        }
+        let inside: HashSet<i64> = self
+            .nodes
+            .iter()
+            .filter(|n| in_folder(&n.folder, folder))
+            .map(|n| n.file_id)
+            .collect();
+        let mut keep = inside.clone();
+        if neighbors {
+            for e in &self.edges {
+                if inside.contains(&e.from) || inside.contains(&e.to) {
+                    keep.insert(e.from);
+                    keep.insert(e.to);
+                }
+            }
+        }
+        let nodes: Vec<ModuleNode> = self.nodes.iter().filter(|n| keep.contains(&n.file_id)).cloned().collect();
+        let edges = self
+            .edges
+            .iter()
+            .filter(|e| keep.contains(&e.from) && keep.contains(&e.to))
+            .filter(|e| inside.contains(&e.from) || inside.contains(&e.to))
+            .cloned()
+            .collect();
+        DepGraph {
+            folders: folder_stats(&nodes),
+            nodes,
+            edges,
+            external_imports: self.external_imports,
+        }
+    }
+}
+
+/// Jumlah file per folder, termasuk folder induk (dihitung kumulatif) agar
+/// filter bisa memilih `src` maupun `src/api`.
+fn folder_stats(nodes: &[ModuleNode]) -> Vec<FolderStat> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for n in nodes {
+        *counts.entry(n.folder.as_str()).or_default() += 1;
+        let mut f = n.folder.as_str();
+        while let Some((parent, _)) = f.rsplit_once('/') {
+            *counts.entry(parent).or_default() += 1;
+            f = parent;
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(folder, files)| FolderStat { folder: folder.to_string(), files })
+        .collect()
+}
+
+/// Graph satu snapshot aplikasi.
+#[derive(Debug, Clone, Serialize)]
+pub struct Snapshot {
+    pub id: i64,
+    pub app_id: i64,
+    pub created_at: DateTime<Utc>,
+    pub graph: DepGraph,
+}
+
+/// Ringkasan snapshot untuk pilihan di halaman graph.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SnapshotInfo {
+    pub id: i64,
+    pub file_count: i32,
+    pub edge_count: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Penanda isi aplikasi: berubah bila file atau hasil indeks import berubah.
+async fn signature(pool: &MySqlPool, app_id: i64) -> anyhow::Result<String> {
+    let (files, max_file): (i64, Option<i64>) =
+        sqlx::query_as("SELECT COUNT(*), MAX(id) FROM files WHERE app_id=?")
+            .bind(app_id)
+            .fetch_one(pool)
+            .await?;
+    let (imports, max_import): (i64, Option<i64>) = sqlx::query_as(
+        "SELECT COUNT(*), MAX(i.id) FROM file_imports i JOIN files f ON f.id = i.file_id WHERE f.app_id=?",
+    )
+    .bind(app_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(format!("{files}:{}:{imports}:{}", max_file.unwrap_or(0), max_import.unwrap_or(0)))
+}
+
+/// Hitung graph dependensi dari file_imports.
+pub async fn build(pool: &MySqlPool, app_id: i64) -> anyhow::Result<DepGraph> {
+    let files: Vec<(i64, String, Option<i32>)> = sqlx::query_as(
+        "SELECT f.id, f.full_path, m.line_count FROM files f
+         LEFT JOIN file_metadata m ON m.file_id = f.id
+         WHERE f.app_id=? ORDER BY f.full_path",
+    )
+    .bind(app_id)
+    .fetch_all(pool)
+    .await?;
+    let rows: Vec<(i64, Option<i64>, String)> = sqlx::query_as(
+        "SELECT i.file_id, i.target_file_id, i.spec FROM file_imports i
+         JOIN files f ON f.id = i.file_id WHERE f.app_id=? ORDER BY i.file_id, i.id",
+    )
+    .bind(app_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut external_imports = 0;
+    let mut edges: BTreeMap<(i64, i64), Vec<String>> = BTreeMap::new();
+    for (from, to, spec) in rows {
+        match to {
+            Some(to) if to != from => {
+                let specs = edges.entry((from, to)).or_default();
+                if !specs.contains(&spec) {
+                    specs.push(spec);
+                }
+            }
+            Some(_) => {}
+            None => external_imports += 1,
+        }
+    }
+
+    let mut out: HashMap<i64, usize> = HashMap::new();
+    let mut inc: HashMap<i64, usize> = HashMap::new();
+    for (from, to) in edges.keys() {
+        *out.entry(*from).or_default() += 1;
+        *inc.entry(*to).or_default() += 1;
+    }
+
+    let nodes: Vec<ModuleNode> = files
+        .into_iter()
+        .map(|(file_id, path, line_count)| ModuleNode {
+            folder: path.rsplit_once('/').map(|(f, _)| f.to_string()).unwrap_or_default(),
+            language: crate::fulltext::language_of(&path).map(str::to_string),
+            imports: out.get(&file_id).copied().unwrap_or(0),
+            imported_by: inc.get(&file_id).copied().unwrap_or(0),
+            href: crate::services::file_href(app_id, file_id, &path),
+            file_id,
+            path,
+            line_count,
+        })
+        .collect();
+    Ok(DepGraph {
+        folders: folder_stats(&nodes),
+        nodes,
+        edges: edges.into_iter().map(|((from, to), specs)| ModuleEdge { from, to, specs }).collect(),
+        external_imports,
+    })
+}
+
+/// Snapshot terbaru; dihitung dan disimpan bila isi aplikasi sudah berubah
+/// sejak snapshot terakhir.
+pub async fn current(pool: &MySqlPool, app_id: i64) -> anyhow::Result<Snapshot> {
+    crate::imports::ensure_indexed(pool, app_id).await?;
+    let sig = signature(pool, app_id).await?;
+    let latest: Option<(i64, String, String, DateTime<Utc>)> = sqlx::query_as(
+        "SELECT id, signature, CAST(graph AS CHAR), created_at FROM dependency_graphs
+         WHERE app_id=? ORDER BY id DESC LIMIT 1",
+    )
+    .bind(app_id)
+    .fetch_optional(pool)
+    .await?;
+    if let Some((id, stored_sig, json, created_at)) = latest {
+        if stored_sig == sig {
+            if let Ok(graph) = serde_json::from_str(&json) {
+                return Ok(Snapshot { id, app_id, created_at, graph });
+            }
+        }
+    }
+
+    let graph = build(pool, app_id).await?;
+    let res = sqlx::query(
+        "INSERT INTO dependency_graphs (app_id, signature, file_count, edge_count, graph) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(app_id)
+    .bind(&sig)
+    .bind(graph.nodes.len() as i32)
+    .bind(graph.edges.len() as i32)
+    .bind(serde_json::to_string(&graph)?)
+    .execute(pool)
+    .await?;
+    let id = res.last_insert_id() as i64;
+    sqlx::query(
+        "DELETE FROM dependency_graphs WHERE app_id=? AND id NOT IN (
+           SELECT id FROM (SELECT id FROM dependency_graphs WHERE app_id=? ORDER BY id DESC LIMIT ?) keep
+         )",
+    )
+    .bind(app_id)
+    .bind(app_id)
+    .bind(KEEP_SNAPSHOTS)
+    .execute(pool)
+    .await?;
+    Ok(Snapshot { id, app_id, created_at: Utc::now(), graph })
+}
+
+/// Snapshot tertentu milik aplikasi.
+pub async fn load(pool: &MySqlPool, app_id: i64, snapshot_id: i64) -> anyhow::Result<Option<Snapshot>> {
+    let row: Option<(String, DateTime<Utc>)> = sqlx::query_as(
+        "SELECT CAST(graph AS CHAR), created_at FROM dependency_graphs WHERE id=? AND app_id=?",
+    )
+    .bind(snapshot_id)
+    .bind(app_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some((json, created_at)) = row else {
+        return Ok(None);
+    };
+    Ok(Some(Snapshot { id: snapshot_id, app_id, created_at, graph: serde_json::from_str(&json)? }))
+}
+
+/// Daftar snapshot aplikasi, terbaru dulu.
+pub async fn snapshots(pool: &MySqlPool, app_id: i64) -> anyhow::Result<Vec<SnapshotInfo>> {
+    Ok(sqlx::query_as(
+        "SELECT id, file_count, edge_count, created_at FROM dependency_graphs WHERE app_id=? ORDER BY id DESC",
+    )
+    .bind(app_id)
+    .fetch_all(pool)
+    .await?)
+}
+
+/// Snapshot dari query string (`snapshot=<id>`), atau snapshot terbaru.
+pub async fn resolve(pool: &MySqlPool, app_id: i64, snapshot: Option<i64>) -> anyhow::Result<Option<Snapshot>> {
+    match snapshot {
+        Some(id) => load(pool, app_id, id).await,
+        None => current(pool, app_id).await.map(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(file_id: i64, path: &str) -> ModuleNode {
+        let folder = path.rsplit_once('/').map(|(f, _)| f).unwrap_or("").to_string();
+        ModuleNode {
+            file_id,
+            path: path.into(),
+            folder,
+            language: None,
+            line_count: None,
+            imports: 0,
+            imported_by: 0,
+            href: String::new(),
+        }
+    }
+
+    fn edge(from: i64, to: i64) -> ModuleEdge {
+        ModuleEdge { from, to, specs: Vec::new() }
+    }
+
+    fn graph() -> DepGraph {
+        let nodes = vec![
+            node(1, "src/main.rs"),
+            node(2, "src/api/users.rs"),
+            node(3, "src/api/auth.rs"),
+            node(4, "lib/db.rs"),
+            node(5, "tests/it.rs"),
+            node(6, "src/apiary/hive.rs"),
+            node(7, "build.rs"),
+        ];
+        let edges = vec![edge(1, 2), edge(2, 3), edge(2, 4), edge(5, 1), edge(1, 4), edge(6, 1)];
+        DepGraph { folders: folder_stats(&nodes), nodes, edges, external_imports: 3 }
+    }
+
+    fn ids(g: &DepGraph) -> Vec<i64> {
+        g.nodes.iter().map(|n| n.file_id).collect()
+    }
+
+    fn pairs(g: &DepGraph) -> Vec<(i64, i64)> {
+        g.edges.iter().map(|e| (e.from, e.to)).collect()
+    }
+
+    #[test]
+    fn filter_keeps_folder_and_subfolders_only() {
+        let g = graph().filter_folder("src/api", false);
+        assert_eq!(ids(&g), [2, 3]);
+        assert_eq!(pairs(&g), [(2, 3)]);
+        assert_eq!(g.external_imports, 3);
+
+        // "src" mencakup src/api dan src/apiary, tapi "src/api" tidak mencakup src/apiary
+        assert_eq!(ids(&graph().filter_folder("/src/", false)), [1, 2, 3, 6]);
+        assert_eq!(ids(&graph().filter_folder("", false)).len(), 7);
+    }
+
+    #[test]
+    fn filter_with_neighbors_drops_edges_between_neighbors() {
+        let g = graph().filter_folder("src/api", true);
+        assert_eq!(ids(&g), [1, 2, 3, 4]);
+        // 1→4 menghubungkan dua tetangga di luar folder, jadi tidak ikut
+        assert_eq!(pairs(&g), [(1, 2), (2, 3), (2, 4)]);
+    }
+
+    #[test]
+    fn folder_stats_count_parents_cumulatively() {
+        let stats: Vec<(String, usize)> =
+            folder_stats(&graph().nodes).into_iter().map(|f| (f.folder, f.files)).collect();
+        let expected = [("", 1), ("lib", 1), ("src", 4), ("src/api", 2), ("src/apiary", 1), ("tests", 1)];
+        assert_eq!(stats, expected.map(|(f, n)| (f.to_string(), n)));
+
+        let filtered = graph().filter_folder("src/api", true);
+        let folders: Vec<(&str, usize)> = filtered.folders.iter().map(|f| (f.folder.as_str(), f.files)).collect();
+        assert_eq!(folders, [("lib", 1), ("src", 3), ("src/api", 2)]);
+    }
+}
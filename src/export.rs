@@ -0,0 +1,571 @@
+// src/export.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Ekspor graph (graph per file, graph dependensi, diagram ER) ke format
+// yang dipakai tooling luar: Graphviz DOT, Mermaid, GraphML dan JSON polos,
+// serta gambar SVG / PNG yang dirender di server (lihat render.rs).
+// Ketiga sumber dipetakan dulu ke `ExportGraph` netral, lalu dirender.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Dot,
+    Mermaid,
+    GraphMl,
+    Json,
+    Svg,
+    Png,
+}
+
+impl Format {
+    /// Nilai query string `format=` (dot, mermaid, graphml, json, svg, png).
+    pub fn parse(s: &str) -> Option<Format> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "dot" | "gv" | "graphviz" => Some(Format::Dot),
+            "mermaid" | "mmd" => Some(Format::Mermaid),
+            "graphml" => Some(Format::GraphMl),
+            "json" => Some(Format::Json),
+            "svg" => Some(Format::Svg),
+            "png" => Some(Format::Png),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Dot => "dot",
+            Format::Mermaid => "mmd",
+            Format::GraphMl => "graphml",
+            Format::Json => "json",
+            Format::Svg => "svg",
+            Format::Png => "png",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Format::Dot => "text/vnd.graphviz; charset=utf-8",
+            Format::Mermaid => "text/plain; charset=utf-8",
+            Format::GraphMl => "application/graphml+xml; charset=utf-8",
+            Format::Json => "application/json; charset=utf-8",
+            Format::Svg => "image/svg+xml; charset=utf-8",
+            Format::Png => "image/png",
+        }
+    }
+}
+
+/// Jenis graph: menentukan bentuk Mermaid (flowchart vs erDiagram) dan DOT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Flow,
+    Er,
+}
+
+/// Kolom tabel pada diagram ER.
+#[derive(Debug, Clone, Serialize)]
+pub struct Field {
+    pub name: String,
+    pub data_type: String,
+    /// "PK", "FK" atau "PK,FK".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportNode {
+    pub id: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportEdge {
+    pub from: String,
+    pub to: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Arah panah: to (default), from, both, none.
+    pub arrows: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportGraph {
+    pub title: String,
+    pub kind: Kind,
+    pub nodes: Vec<ExportNode>,
+    pub edges: Vec<ExportEdge>,
+}
+
+/// Graph per file (dokumen nodes/edges dari LLM).
+pub fn from_file_graph(title: &str, g: &crate::graph::Graph) -> ExportGraph {
+    ExportGraph {
+        title: g.title.clone().unwrap_or_else(|| title.to_string()),
+        kind: Kind::Flow,
+        nodes: g
+            .nodes
+            .iter()
+            .map(|n| ExportNode { id: n.id.clone(), label: n.label.clone(), group: n.group.clone(), fields: Vec::new() })
+            .collect(),
+        edges: g
+            .edges
+            .iter()
+            .map(|e| ExportEdge {
+                from: e.from.clone(),
+                to: e.to.clone(),
+                label: e.label.clone(),
+                arrows: e.arrows.clone().unwrap_or_else(|| "to".to_string()),
+            })
+            .collect(),
+    }
+}
+
+/// Graph dependensi modul: node = file (dikelompokkan per folder).
+pub fn from_dep_graph(title: &str, g: &crate::depgraph::DepGraph) -> ExportGraph {
+    ExportGraph {
+        title: title.to_string(),
+        kind: Kind::Flow,
+        nodes: g
+            .nodes
+            .iter()
+            .map(|n| ExportNode {
+                id: n.file_id.to_string(),
+                label: n.path.clone(),
+                group: Some(n.folder.clone()).filter(|f| !f.is_empty()),
+                fields: Vec::new(),
+            })
+            .collect(),
+        edges: g
+            .edges
+            .iter()
+            .map(|e| ExportEdge {
+                from: e.from.to_string(),
+                to: e.to.to_string(),
+                label: None,
+                arrows: "to".to_string(),
+            })
+            .collect(),
+    }
+}
+
+/// Call graph berakar di satu fungsi: node = fungsi/method (group = file),
+/// edge = pemanggilan (label = jumlah titik panggil bila lebih dari satu).
+pub fn from_call_graph(title: &str, g: &crate::callgraph::CallGraph) -> ExportGraph {
+    ExportGraph {
+        title: title.to_string(),
+        kind: Kind::Flow,
+        nodes: g
+            .nodes
+            .iter()
+            .map(|n| ExportNode {
+                id: n.id.to_string(),
+                label: n.label.clone(),
+                group: Some(n.path.clone()),
+                fields: Vec::new(),
+            })
+            .collect(),
+        edges: g
+            .edges
+            .iter()
+            .map(|e| ExportEdge {
+                from: e.from.to_string(),
+                to: e.to.to_string(),
+                label: (e.calls > 1).then(|| format!("{}×", e.calls)),
+                arrows: "to".to_string(),
+            })
+            .collect(),
+    }
+}
+
+/// Diagram ER: node = tabel beserta kolomnya, edge = foreign key.
+pub fn from_schema(title: &str, s: &crate::dbschema::Schema) -> ExportGraph {
+    let id = |name: &str| name.to_ascii_lowercase();
+    let known: std::collections::HashSet<String> = s.tables.iter().map(|t| id(&t.name)).collect();
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    for t in &s.tables {
+        let fk_cols: Vec<&String> = t.foreign_keys.iter().flat_map(|f| &f.columns).collect();
+        nodes.push(ExportNode {
+            id: id(&t.name),
+            label: t.name.clone(),
+            group: None,
+            fields: t
+                .columns
+                .iter()
+                .map(|c| {
+                    let fk = fk_cols.iter().any(|f| f.eq_ignore_ascii_case(&c.name));
+                    let key = match (c.primary, fk) {
+                        (true, true) => Some("PK,FK"),
+                        (true, false) => Some("PK"),
+                        (false, true) => Some("FK"),
+                        (false, false) => None,
+                    };
+                    Field { name: c.name.clone(), data_type: c.data_type.clone(), key: key.map(str::to_string) }
+                })
+                .collect(),
+        });
+        for fk in &t.foreign_keys {
+            if !known.contains(&id(&fk.ref_table)) {
+                continue;
+            }
+            edges.push(ExportEdge {
+                from: id(&t.name),
+                to: id(&fk.ref_table),
+                label: Some(fk.columns.join(", ")),
+                arrows: "to".to_string(),
+            });
+        }
+    }
+    ExportGraph { title: title.to_string(), kind: Kind::Er, nodes, edges }
+}
+
+/// Format yang ditawarkan di halaman graph: (nilai `format=`, nama tampilan).
+pub const FORMATS: [(&str, &str); 6] = [
+    ("svg", "SVG"),
+    ("png", "PNG"),
+    ("dot", "Graphviz DOT"),
+    ("mermaid", "Mermaid"),
+    ("graphml", "GraphML"),
+    ("json", "JSON"),
+];
+
+/// URL API dengan `format=` (URL boleh sudah ber-query).
+pub fn url(api_url: &str, format: &str) -> String {
+    let sep = if api_url.contains('?') { '&' } else { '?' };
+    format!("{api_url}{sep}format={format}")
+}
+
+/// Tautan unduhan untuk setiap format dari URL API.
+pub fn links(api_url: &str) -> Vec<(&'static str, String)> {
+    FORMATS.iter().map(|(value, name)| (*name, url(api_url, value))).collect()
+}
+
+/// Nama file unduhan: judul yang aman untuk nama file + ekstensi format.
+pub fn file_name(base: &str, format: Format) -> String {
+    let safe: String = base
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect();
+    format!("{}.{}", safe.trim_matches('_'), format.extension())
+}
+
+pub fn render(g: &ExportGraph, format: Format) -> Result<Vec<u8>, crate::render::TooLarge> {
+    Ok(match format {
+        Format::Dot => to_dot(g).into_bytes(),
+        Format::Mermaid => to_mermaid(g).into_bytes(),
+        Format::GraphMl => to_graphml(g).into_bytes(),
+        Format::Json => serde_json::to_vec_pretty(g).unwrap_or_default(),
+        Format::Svg => crate::render::svg(g).into_bytes(),
+        Format::Png => crate::render::png(g)?,
+    })
+}
+
+fn dot_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+/// Label record DOT: karakter struktur record di-escape.
+fn dot_record(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>' | '"' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn to_dot(g: &ExportGraph) -> String {
+    let mut out = format!("digraph {} {{\n  rankdir=LR;\n", dot_quote(&g.title));
+    match g.kind {
+        Kind::Er => out.push_str("  node [shape=record, fontname=\"monospace\", fontsize=10];\n"),
+        Kind::Flow => out.push_str("  node [shape=box, fontsize=10];\n"),
+    }
+    let node_line = |n: &ExportNode| -> String {
+        if n.fields.is_empty() {
+            format!("{} [label={}];\n", dot_quote(&n.id), dot_quote(&n.label))
+        } else {
+            let fields: Vec<String> = n
+                .fields
+                .iter()
+                .map(|f| {
+                    let key = f.key.as_deref().map(|k| format!(" [{k}]")).unwrap_or_default();
+                    format!("{} : {}{}\\l", dot_record(&f.name), dot_record(&f.data_type), dot_record(&key))
+                })
+                .collect();
+            format!("{} [label=\"{{{}|{}}}\"];\n", dot_quote(&n.id), dot_record(&n.label), fields.concat())
+        }
+    };
+    // Node dengan group yang sama masuk satu subgraph cluster
+    let mut groups: BTreeMap<&str, Vec<&ExportNode>> = BTreeMap::new();
+    for n in &g.nodes {
+        match n.group.as_deref() {
+            Some(group) => groups.entry(group).or_default().push(n),
+            None => out.push_str(&format!("  {}", node_line(n))),
+        }
+    }
+    for (i, (group, nodes)) in groups.iter().enumerate() {
+        out.push_str(&format!("  subgraph cluster_{i} {{\n    label={};\n", dot_quote(group)));
+        for n in nodes {
+            out.push_str(&format!("    {}", node_line(n)));
+        }
+        out.push_str("  }\n");
+    }
+    for e in &g.edges {
+        let mut attrs = Vec::new();
+        if let Some(l) = &e.label {
+            attrs.push(format!("label={}", dot_quote(l)));
+        }
+        match e.arrows.as_str() {
+            "from" => attrs.push("dir=back".to_string()),
+            "both" => attrs.push("dir=both".to_string()),
+            "none" => attrs.push("dir=none".to_string()),
+            _ => {}
+        }
+        let attrs = if attrs.is_empty() { String::new() } else { format!(" [{}]", attrs.join(", ")) };
+        out.push_str(&format!("  {} -> {}{};\n", dot_quote(&e.from), dot_quote(&e.to), attrs));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Teks label Mermaid di dalam tanda kutip.
+fn mermaid_text(s: &str) -> String {
+    s.replace('"', "#quot;").replace('<', "#lt;").replace('>', "#gt;").replace('\n', "<br/>")
+}
+
+/// Identifier Mermaid (entity erDiagram / tipe atribut): hanya huruf, angka, _ dan -.
+fn mermaid_word(s: &str) -> String {
+    let w: String = s
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect();
+    if w.is_empty() { "_".to_string() } else { w }
+}
+
+fn to_mermaid(g: &ExportGraph) -> String {
+    let mut out = format!("---\ntitle: \"{}\"\n---\n", mermaid_text(&g.title));
+    if g.kind == Kind::Er {
+        out.push_str("erDiagram\n");
+        // Nama entity dari label; label berbeda bisa jadi kata yang sama
+        // (mis. "order.items" dan "order items"), jadi diberi akhiran _2, _3, ...
+        let mut used: HashSet<String> = HashSet::new();
+        let mut entity: BTreeMap<&str, String> = BTreeMap::new();
+        for n in &g.nodes {
+            let base = mermaid_word(&n.label);
+            let mut word = base.clone();
+            let mut i = 1;
+            while !used.insert(word.clone()) {
+                i += 1;
+                word = format!("{base}_{i}");
+            }
+            entity.insert(n.id.as_str(), word);
+        }
+        for n in &g.nodes {
+            out.push_str(&format!("  {} {{\n", entity[n.id.as_str()]));
+            for f in &n.fields {
+                let key = f.key.as_deref().map(|k| format!(" {k}")).unwrap_or_default();
+                out.push_str(&format!("    {} {}{}\n", mermaid_word(&f.data_type), mermaid_word(&f.name), key));
+            }
+            out.push_str("  }\n");
+        }
+        let name = |id: &str| entity.get(id).cloned().unwrap_or_else(|| mermaid_word(id));
+        for e in &g.edges {
+            out.push_str(&format!(
+                "  {} }}o--|| {} : \"{}\"\n",
+                name(&e.from),
+                name(&e.to),
+                mermaid_text(e.label.as_deref().unwrap_or(""))
+            ));
+        }
+        return out;
+    }
+
+    // Flowchart: id Mermaid dibuat urut (n0, n1, ...) agar aman dari karakter khusus
+    out.push_str("flowchart LR\n");
+    let ids: BTreeMap<&str, String> = g.nodes.iter().enumerate().map(|(i, n)| (n.id.as_str(), format!("n{i}"))).collect();
+    let node_line = |n: &ExportNode| format!("{}[\"{}\"]\n", ids[n.id.as_str()], mermaid_text(&n.label));
+    let mut groups: BTreeMap<&str, Vec<&ExportNode>> = BTreeMap::new();
+    for n in &g.nodes {
+        match n.group.as_deref() {
+            Some(group) => groups.entry(group).or_default().push(n),
+            None => out.push_str(&format!("  {}", node_line(n))),
+        }
+    }
+    for (i, (group, nodes)) in groups.iter().enumerate() {
+        out.push_str(&format!("  subgraph g{i}[\"{}\"]\n", mermaid_text(group)));
+        for n in nodes {
+            out.push_str(&format!("    {}", node_line(n)));
+        }
+        out.push_str("  end\n");
+    }
+    for e in &g.edges {
+        let (Some(from), Some(to)) = (ids.get(e.from.as_str()), ids.get(e.to.as_str())) else {
+            continue;
+        };
+        let (from, to, arrow) = match e.arrows.as_str() {
+            "from" => (to, from, "-->"),
+            "both" => (from, to, "<-->"),
+            "none" => (from, to, "---"),
+            _ => (from, to, "-->"),
+        };
+        match &e.label {
+            Some(l) => out.push_str(&format!("  {from} {arrow}|\"{}\"| {to}\n", mermaid_text(l))),
+            None => out.push_str(&format!("  {from} {arrow} {to}\n")),
+        }
+    }
+    out
+}
+
+pub fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn to_graphml(g: &ExportGraph) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n\
+         \x20 <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n\
+         \x20 <key id=\"group\" for=\"node\" attr.name=\"group\" attr.type=\"string\"/>\n\
+         \x20 <key id=\"fields\" for=\"node\" attr.name=\"fields\" attr.type=\"string\"/>\n\
+         \x20 <key id=\"elabel\" for=\"edge\" attr.name=\"label\" attr.type=\"string\"/>\n\
+         \x20 <key id=\"arrows\" for=\"edge\" attr.name=\"arrows\" attr.type=\"string\"/>\n",
+    );
+    out.push_str(&format!("  <graph id=\"{}\" edgedefault=\"directed\">\n", xml_escape(&g.title)));
+    for n in &g.nodes {
+        out.push_str(&format!("    <node id=\"{}\">\n", xml_escape(&n.id)));
+        out.push_str(&format!("      <data key=\"label\">{}</data>\n", xml_escape(&n.label)));
+        if let Some(group) = &n.group {
+            out.push_str(&format!("      <data key=\"group\">{}</data>\n", xml_escape(group)));
+        }
+        if !n.fields.is_empty() {
+            let fields: Vec<String> = n
+                .fields
+                .iter()
+                .map(|f| match &f.key {
+                    Some(k) => format!("{} {} {k}", f.name, f.data_type),
+                    None => format!("{} {}", f.name, f.data_type),
+                })
+                .collect();
+            out.push_str(&format!("      <data key=\"fields\">{}</data>\n", xml_escape(&fields.join("\n"))));
+        }
+        out.push_str("    </node>\n");
+    }
+    for (i, e) in g.edges.iter().enumerate() {
+        out.push_str(&format!(
+            "    <edge id=\"e{i}\" source=\"{}\" target=\"{}\">\n",
+            xml_escape(&e.from),
+            xml_escape(&e.to)
+        ));
+        if let Some(l) = &e.label {
+            out.push_str(&format!("      <data key=\"elabel\">{}</data>\n", xml_escape(l)));
+        }
+        out.push_str(&format!("      <data key=\"arrows\">{}</data>\n", xml_escape(&e.arrows)));
+        out.push_str("    </edge>\n");
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, label: &str, group: Option<&str>) -> ExportNode {
+        ExportNode { id: id.into(), label: label.into(), group: group.map(str::to_string), fields: Vec::new() }
+    }
+
+    fn edge(from: &str, to: &str, label: Option<&str>, arrows: &str) -> ExportEdge {
+        ExportEdge { from: from.into(), to: to.into(), label: label.map(str::to_string), arrows: arrows.into() }
+    }
+
+    fn flow() -> ExportGraph {
+        ExportGraph {
+            title: "a \"b\" <c>".into(),
+            kind: Kind::Flow,
+            nodes: vec![node("src/a.rs", "a<T> & \"b\"", None), node("x\"y", "x\ny", Some("grp \"1\""))],
+            edges: vec![edge("src/a.rs", "x\"y", Some("uses \"it\""), "both")],
+        }
+    }
+
+    #[test]
+    fn dot_escapes_quotes_and_records() {
+        assert_eq!(dot_quote("a \"b\"\\\nc"), "\"a \\\"b\\\"\\\\\\nc\"");
+        assert_eq!(dot_record("{a|b} <p> \"q\""), "\\{a\\|b\\} \\<p\\> \\\"q\\\"");
+        let dot = to_dot(&flow());
+        assert!(dot.starts_with("digraph \"a \\\"b\\\" <c>\" {"));
+        assert!(dot.contains("subgraph cluster_0 {\n    label=\"grp \\\"1\\\"\";"));
+        assert!(dot.contains("\"x\\\"y\" [label=\"x\\ny\"];"));
+        assert!(dot.contains("\"src/a.rs\" -> \"x\\\"y\" [label=\"uses \\\"it\\\"\", dir=both];"));
+    }
+
+    #[test]
+    fn mermaid_uses_safe_ids_and_entities() {
+        assert_eq!(mermaid_text("\"a\" <b>\nc"), "#quot;a#quot; #lt;b#gt;<br/>c");
+        assert_eq!(mermaid_word("order items!"), "order_items_");
+        assert_eq!(mermaid_word(""), "_");
+        let mmd = to_mermaid(&flow());
+        assert!(mmd.contains("title: \"a #quot;b#quot; #lt;c#gt;\""));
+        assert!(mmd.contains("  n0[\"a#lt;T#gt; & #quot;b#quot;\"]\n"));
+        assert!(mmd.contains("  subgraph g0[\"grp #quot;1#quot;\"]\n    n1[\"x<br/>y\"]\n  end\n"));
+        assert!(mmd.contains("  n0 <-->|\"uses #quot;it#quot;\"| n1\n"));
+    }
+
+    #[test]
+    fn mermaid_er_entities_stay_distinct() {
+        let g = ExportGraph {
+            title: "db".into(),
+            kind: Kind::Er,
+            nodes: vec![
+                node("t1", "order items", None),
+                node("t2", "order.items", None),
+                node("t3", "order_items", None),
+                node("t4", "order_items_2", None),
+            ],
+            edges: vec![edge("t2", "t1", Some("fk"), "to"), edge("t3", "t4", None, "to")],
+        };
+        let mmd = to_mermaid(&g);
+        for entity in ["order_items", "order_items_2", "order_items_3", "order_items_2_2"] {
+            assert_eq!(mmd.matches(&format!("  {entity} {{\n")).count(), 1, "{entity}\n{mmd}");
+        }
+        assert!(mmd.contains("  order_items_2 }o--|| order_items : \"fk\"\n"));
+        assert!(mmd.contains("  order_items_3 }o--|| order_items_2_2 : \"\"\n"));
+    }
+
+    #[test]
+    fn graphml_escapes_xml() {
+        assert_eq!(xml_escape("<a & 'b' \"c\">"), "&lt;a &amp; &apos;b&apos; &quot;c&quot;&gt;");
+        let xml = to_graphml(&flow());
+        assert!(xml.contains("<node id=\"x&quot;y\">"));
+        assert!(xml.contains("<data key=\"label\">a&lt;T&gt; &amp; &quot;b&quot;</data>"));
+        assert!(xml.contains("source=\"src/a.rs\" target=\"x&quot;y\""));
+    }
+
+    #[test]
+    fn names_and_links() {
+        assert_eq!(Format::parse(" GV "), Some(Format::Dot));
+        assert_eq!(Format::parse("pdf"), None);
+        assert_eq!(file_name("graph: src/a.rs", Format::Svg), "graph__src_a.rs.svg");
+        assert_eq!(url("/api/x", "dot"), "/api/x?format=dot");
+        assert_eq!(url("/api/x?snapshot=2", "png"), "/api/x?snapshot=2&format=png");
+    }
+}
@@ -0,0 +1,847 @@
+// src/fulltext.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Pencarian teks penuh di isi file (content_file), semua hasil analisa dan
+// tabel yang diekstrak analisa relasi_db, untuk satu aplikasi atau lintas
+// semua aplikasi. Kandidat disaring dulu dengan indeks trigram per aplikasi
+// yang disimpan di memori (dibangun ulang bila isi aplikasi berubah, juga
+// dipakai di mode regex lewat potongan literal polanya), lalu hanya isi
+// kandidat yang diambil dari MySQL dan dicocokkan baris per baris dengan
+// crate regex sehingga hasilnya punya nomor baris dan potongan teks yang bisa
+// di-highlight.
+// Filter: bahasa pemrograman (dari ekstensi), folder, dan sumber (kode,
+// analisa, tabel SQL).
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+const MAX_FILES: usize = 200;
+const MAX_LINES_PER_FILE: usize = 20;
+const MAX_ANALYSIS_LINES: usize = 3;
+/// Panjang maksimum potongan baris yang ditampilkan (karakter).
+const SNIPPET_CHARS: usize = 240;
+
+/// Bahasa pemrograman menurut ekstensi file (label dipakai sebagai nilai filter).
+pub fn language_of(path: &str) -> Option<&'static str> {
+    let ext = path.rsplit_once('.')?.1.to_ascii_lowercase();
+    Some(match ext.as_str() {
+        "rs" => "Rust",
+        "py" => "Python",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "ts" | "tsx" => "TypeScript",
+        "php" => "PHP",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" => "C++",
+        "cs" => "C#",
+        "rb" => "Ruby",
+        "swift" => "Swift",
+        "sql" => "SQL",
+        "html" | "htm" => "HTML",
+        "css" | "scss" | "sass" | "less" => "CSS",
+        "vue" => "Vue",
+        "json" => "JSON",
+        "yml" | "yaml" => "YAML",
+        "xml" => "XML",
+        "md" => "Markdown",
+        "sh" | "bash" => "Shell",
+        _ => return None,
+    })
+}
+
+/// Parameter pencarian (query string: q, regex, case, language, folder, scope).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Options {
+    pub query: String,
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub language: Option<String>,
+    pub folder: Option<String>,
+    /// "all" | "code" | "analysis" | "sql" (tabel dari analisa relasi_db)
+    pub scope: String,
+}
+
+impl Options {
+    pub fn from_query(qs: &HashMap<String, String>) -> Self {
+        let flag = |k: &str| matches!(qs.get(k).map(String::as_str), Some("1" | "on" | "true"));
+        let text = |k: &str| qs.get(k).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let scope = match qs.get("scope").map(String::as_str) {
+            Some("code") => "code",
+            Some("analysis") => "analysis",
+            Some("sql") => "sql",
+            _ => "all",
+        };
+        Options {
+            query: qs.get("q").map(|q| q.trim().to_string()).unwrap_or_default(),
+            regex: flag("regex"),
+            case_sensitive: flag("case"),
+            language: text("language"),
+            folder: text("folder").map(|f| f.trim_matches('/').to_string()),
+            scope: scope.to_string(),
+        }
+    }
+
+    fn wants_code(&self) -> bool {
+        matches!(self.scope.as_str(), "all" | "code")
+    }
+
+    fn wants_analysis(&self) -> bool {
+        matches!(self.scope.as_str(), "all" | "analysis")
+    }
+
+    fn wants_tables(&self) -> bool {
+        matches!(self.scope.as_str(), "all" | "sql")
+    }
+
+    /// Regex pencocok; pesan error bila pola regex tidak valid.
+    pub fn matcher(&self) -> Result<Regex, String> {
+        let pattern = if self.regex { self.query.clone() } else { regex::escape(&self.query) };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .size_limit(1 << 20)
+            .build()
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Bagian teks; `hit` = bagian yang cocok dengan pencarian.
+#[derive(Debug, Clone, Serialize)]
+pub struct Piece {
+    pub text: String,
+    pub hit: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LineMatch {
+    pub line: usize,
+    pub pieces: Vec<Piece>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisMatch {
+    pub kind: String,
+    pub lang: String,
+    pub lines: Vec<LineMatch>,
+}
+
+/// Tabel database (hasil analisa relasi_db) yang namanya cocok.
+#[derive(Debug, Clone, Serialize)]
+pub struct TableMatch {
+    pub table: String,
+    pub pieces: Vec<Piece>,
+    pub operations: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileMatch {
+    pub file_id: i64,
+    pub app_id: i64,
+    pub path: String,
+    pub language: Option<&'static str>,
+    pub lines: Vec<LineMatch>,
+    /// Jumlah baris cocok yang tidak ditampilkan (di atas MAX_LINES_PER_FILE).
+    pub more_lines: usize,
+    pub analyses: Vec<AnalysisMatch>,
+    pub tables: Vec<TableMatch>,
+}
+
+impl FileMatch {
+    pub fn href(&self) -> String {
+        crate::services::file_href(self.app_id, self.file_id, &self.path)
+    }
+
+    pub fn match_count(&self) -> usize {
+        self.lines.len()
+            + self.more_lines
+            + self.analyses.iter().map(|a| a.lines.len()).sum::<usize>()
+            + self.tables.len()
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub files: Vec<FileMatch>,
+    pub total_matches: usize,
+    /// true bila hasil dipotong di MAX_FILES file.
+    pub truncated: bool,
+}
+
+/// Pecah satu baris menjadi potongan biasa / cocok, dipendekkan di sekitar
+/// kecocokan pertama bila baris terlalu panjang.
+pub fn highlight(line: &str, re: &Regex) -> Option<Vec<Piece>> {
+    let first = re.find_iter(line).find(|m| !m.as_str().is_empty())?;
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    let (mut from, mut to) = (0, line.len());
+    if chars.len() > SNIPPET_CHARS {
+        let hit_char = chars.iter().position(|(i, _)| *i >= first.start()).unwrap_or(0);
+        let start = hit_char.saturating_sub(SNIPPET_CHARS / 3);
+        let end = (start + SNIPPET_CHARS).min(chars.len());
+        from = chars[start].0;
+        to = chars.get(end).map(|c| c.0).unwrap_or(line.len());
+    }
+    let mut pieces = Vec::new();
+    if from > 0 {
+        pieces.push(Piece { text: "…".into(), hit: false });
+    }
+    let mut last = from;
+    for m in re.find_iter(&line[from..to]) {
+        if m.as_str().is_empty() {
+            continue;
+        }
+        let (s, e) = (from + m.start(), from + m.end());
+        if s > last {
+            pieces.push(Piece { text: line[last..s].to_string(), hit: false });
+        }
+        pieces.push(Piece { text: line[s..e].to_string(), hit: true });
+        last = e;
+    }
+    if last < to {
+        pieces.push(Piece { text: line[last..to].to_string(), hit: false });
+    }
+    if to < line.len() {
+        pieces.push(Piece { text: "…".into(), hit: false });
+    }
+    Some(pieces)
+}
+
+/// Baris-baris yang cocok (1-based) beserta jumlah total kecocokan.
+pub fn match_lines(text: &str, re: &Regex, max: usize) -> (Vec<LineMatch>, usize) {
+    let mut out = Vec::new();
+    let mut total = 0;
+    for (i, line) in text.lines().enumerate() {
+        if let Some(pieces) = highlight(line, re) {
+            total += 1;
+            if out.len() < max {
+                out.push(LineMatch { line: i + 1, pieces });
+            }
+        }
+    }
+    (out, total)
+}
+
+/// Pola `LIKE` "memuat `term`" dengan `%`, `_` dan `\` di-escape.
+pub fn like(term: &str) -> String {
+    format!("%{}%", term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"))
+}
+
+fn folder_ok(path: &str, folder: Option<&str>) -> bool {
+    match folder {
+        Some(f) => path.starts_with(&format!("{f}/")) || path.contains(&format!("/{f}/")),
+        None => true,
+    }
+}
+
+/// Daftar bahasa yang ada di aplikasi, atau di semua aplikasi bila `app_id`
+/// None (untuk pilihan filter).
+pub async fn languages(pool: &MySqlPool, app_id: Option<i64>) -> anyhow::Result<Vec<&'static str>> {
+    let paths: Vec<(String,)> = match app_id {
+        Some(a) => sqlx::query_as("SELECT full_path FROM files WHERE app_id=?").bind(a).fetch_all(pool).await?,
+        None => sqlx::query_as("SELECT DISTINCT full_path FROM files").fetch_all(pool).await?,
+    };
+    let set: BTreeSet<&'static str> = paths.iter().filter_map(|(p,)| language_of(p)).collect();
+    Ok(set.into_iter().collect())
+}
+
+/// Entri hasil untuk file, dibuat bila belum ada.
+fn slot<'a>(
+    files: &'a mut Vec<FileMatch>,
+    by_id: &mut HashMap<i64, usize>,
+    file_id: i64,
+    app_id: i64,
+    path: String,
+) -> &'a mut FileMatch {
+    let i = *by_id.entry(file_id).or_insert_with(|| {
+        files.push(FileMatch {
+            file_id,
+            app_id,
+            language: language_of(&path),
+            path,
+            lines: Vec::new(),
+            more_lines: 0,
+            analyses: Vec::new(),
+            tables: Vec::new(),
+        });
+        files.len() - 1
+    });
+    &mut files[i]
+}
+
+/// Tabel dari data terstruktur analisa relasi_db yang namanya cocok.
+fn match_tables(structured: &str, re: &Regex) -> Vec<TableMatch> {
+    let Ok(data) = serde_json::from_str::<serde_json::Value>(structured) else {
+        return Vec::new();
+    };
+    data.get("tables")
+        .and_then(|t| t.as_array())
+        .map(|tables| {
+            tables
+                .iter()
+                .filter_map(|t| {
+                    let name = t.get("table")?.as_str()?;
+                    let pieces = highlight(name, re)?;
+                    let operations = t
+                        .get("operations")
+                        .and_then(|o| o.as_array())
+                        .map(|o| o.iter().filter_map(|x| x.as_str().map(str::to_string)).collect())
+                        .unwrap_or_default();
+                    Some(TableMatch { table: name.to_string(), pieces, operations })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Sumber teks yang diindeks per file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Source {
+    Code,
+    Analysis,
+    Tables,
+}
+
+/// Satu teks di indeks: isi file, satu hasil analisa, atau data terstruktur
+/// relasi_db. Isinya tidak disimpan; diambil dari MySQL hanya bila lolos
+/// prefilter.
+struct Doc {
+    source: Source,
+    file_id: i64,
+    path: String,
+    kind: String,
+    lang: String,
+}
+
+/// Indeks trigram satu aplikasi di memori: trigram -> dokumen yang memuatnya.
+struct TextIndex {
+    signature: String,
+    docs: Vec<Doc>,
+    grams: HashMap<u32, Vec<u32>>,
+}
+
+impl TextIndex {
+    fn add(&mut self, doc: Doc, text: &str) {
+        let id = self.docs.len() as u32;
+        let mut seen = HashSet::new();
+        for g in trigrams(&fold(text)) {
+            if seen.insert(g) {
+                self.grams.entry(g).or_default().push(id);
+            }
+        }
+        self.docs.push(doc);
+    }
+
+    /// Dokumen yang memuat semua trigram `required` (semua dokumen bila kosong).
+    fn candidates(&self, required: &HashSet<u32>) -> Vec<&Doc> {
+        if required.is_empty() {
+            return self.docs.iter().collect();
+        }
+        let mut lists = Vec::new();
+        for g in required {
+            match self.grams.get(g) {
+                Some(l) => lists.push(l),
+                None => return Vec::new(),
+            }
+        }
+        lists.sort_by_key(|l| l.len());
+        let mut ids: Vec<u32> = lists[0].clone();
+        for l in &lists[1..] {
+            ids.retain(|id| l.binary_search(id).is_ok());
+        }
+        ids.into_iter().map(|id| &self.docs[id as usize]).collect()
+    }
+}
+
+fn cache() -> &'static Mutex<HashMap<i64, Arc<TextIndex>>> {
+    static CACHE: OnceLock<Mutex<HashMap<i64, Arc<TextIndex>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Byte teks untuk trigram: ASCII dikecilkan, huruf non-ASCII yang
+/// case-fold-nya huruf ASCII (K kelvin, ſ) ikut dipetakan, karakter non-ASCII
+/// lain menjadi pemisah (0).
+fn fold(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '\0' => 0,
+            c if c.is_ascii() => (c as u8).to_ascii_lowercase(),
+            '\u{212A}' => b'k',
+            '\u{17F}' => b's',
+            _ => 0,
+        })
+        .collect()
+}
+
+fn trigrams(bytes: &[u8]) -> impl Iterator<Item = u32> + '_ {
+    bytes
+        .windows(3)
+        .filter(|w| !w.contains(&0))
+        .map(|w| (w[0] as u32) << 16 | (w[1] as u32) << 8 | w[2] as u32)
+}
+
+/// Potongan teks yang pasti muncul utuh di setiap kecocokan pola regex.
+/// Perkiraan konservatif: hanya urutan karakter biasa di tingkat atas; grup,
+/// kelas karakter dan escape seperti `\d` memutus potongan, karakter sebelum
+/// `?`, `*` atau `{0` dibuang. Pola dengan `|` di tingkat atas atau mode `x`
+/// tidak menghasilkan potongan apa pun.
+fn regex_literals(pattern: &str) -> Vec<String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut runs = Vec::new();
+    let mut cur = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match c {
+            '\\' => {
+                let Some(&e) = chars.get(i) else { break };
+                i += 1;
+                if e.is_ascii_alphanumeric() {
+                    // kelas (\d, \w, \p{..}), jangkar (\b) atau kode (\x{..}, \n)
+                    if chars.get(i) == Some(&'{') {
+                        while i < chars.len() && chars[i] != '}' {
+                            i += 1;
+                        }
+                        i += 1;
+                    }
+                    runs.push(std::mem::take(&mut cur));
+                } else {
+                    cur.push(e);
+                }
+            }
+            '(' => {
+                if chars.get(i) == Some(&'?') {
+                    let flags: String =
+                        chars[i + 1..].iter().take_while(|c| c.is_ascii_alphabetic() || **c == '-').collect();
+                    if flags.split('-').next().unwrap_or("").contains('x') {
+                        return Vec::new();
+                    }
+                }
+                runs.push(std::mem::take(&mut cur));
+                i = skip_group(&chars, i);
+            }
+            '[' => {
+                runs.push(std::mem::take(&mut cur));
+                i = skip_class(&chars, i);
+            }
+            '|' => return Vec::new(),
+            '.' | '^' | '$' => runs.push(std::mem::take(&mut cur)),
+            '*' | '?' | '+' | '{' => {
+                let optional = match c {
+                    '*' | '?' => true,
+                    '+' => false,
+                    _ if chars.get(i).is_some_and(char::is_ascii_digit) => chars[i] == '0',
+                    _ => {
+                        cur.push(c);
+                        continue;
+                    }
+                };
+                if c == '{' {
+                    while i < chars.len() && chars[i] != '}' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                if chars.get(i) == Some(&'?') {
+                    i += 1;
+                }
+                if optional {
+                    cur.pop();
+                }
+                runs.push(std::mem::take(&mut cur));
+            }
+            c => cur.push(c),
+        }
+    }
+    runs.push(cur);
+    runs.retain(|r| !r.is_empty());
+    runs
+}
+
+/// Posisi setelah `)` penutup grup yang dibuka tepat sebelum `i`.
+fn skip_group(chars: &[char], mut i: usize) -> usize {
+    let mut depth = 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '[' => {
+                i = skip_class(chars, i + 1);
+                continue;
+            }
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+/// Posisi setelah `]` penutup kelas karakter yang dibuka tepat sebelum `i`
+/// (termasuk kelas bersarang dan `]` di awal kelas).
+fn skip_class(chars: &[char], mut i: usize) -> usize {
+    if chars.get(i) == Some(&'^') {
+        i += 1;
+    }
+    if chars.get(i) == Some(&']') {
+        i += 1;
+    }
+    let mut depth = 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+/// Trigram yang wajib ada di teks yang cocok dengan pencarian.
+fn required_trigrams(opts: &Options) -> HashSet<u32> {
+    let literals = if opts.regex { regex_literals(&opts.query) } else { vec![opts.query.clone()] };
+    literals.iter().flat_map(|l| trigrams(&fold(l)).collect::<Vec<_>>()).collect()
+}
+
+/// Indeks terbaru aplikasi: dibangun ulang bila isi aplikasi berubah.
+async fn load(pool: &MySqlPool, app_id: i64) -> anyhow::Result<Arc<TextIndex>> {
+    let signature = crate::semantic::source_signature(pool, app_id).await?;
+    if let Some(idx) = cache().lock().expect("cache indeks teks").get(&app_id) {
+        if idx.signature == signature {
+            return Ok(idx.clone());
+        }
+    }
+
+    let mut idx = TextIndex { signature, docs: Vec::new(), grams: HashMap::new() };
+    let files: Vec<(i64, String, String)> = sqlx::query_as(
+        "SELECT id, full_path, content_file FROM files
+         WHERE app_id=? AND content_file IS NOT NULL ORDER BY id",
+    )
+    .bind(app_id)
+    .fetch_all(pool)
+    .await?;
+    for (file_id, path, content) in files {
+        let doc = Doc { source: Source::Code, file_id, path, kind: String::new(), lang: String::new() };
+        idx.add(doc, &content);
+    }
+    let results: Vec<(i64, String, String, String, String, Option<String>)> = sqlx::query_as(
+        "SELECT r.file_id, f.full_path, r.kind, r.lang, r.content,
+                CASE WHEN r.kind = 'relasi_db' THEN CAST(r.structured AS CHAR) END
+         FROM analysis_results r JOIN files f ON f.id = r.file_id
+         WHERE f.app_id=? ORDER BY r.file_id, r.kind, r.lang",
+    )
+    .bind(app_id)
+    .fetch_all(pool)
+    .await?;
+    for (file_id, path, kind, lang, content, structured) in results {
+        if let Some(s) = structured {
+            let (path, kind, lang) = (path.clone(), kind.clone(), lang.clone());
+            idx.add(Doc { source: Source::Tables, file_id, path, kind, lang }, &s);
+        }
+        idx.add(Doc { source: Source::Analysis, file_id, path, kind, lang }, &content);
+    }
+
+    let idx = Arc::new(idx);
+    cache().lock().expect("cache indeks teks").insert(app_id, idx.clone());
+    Ok(idx)
+}
+
+/// Kunci isi dokumen: sumber, file, jenis analisa, bahasa.
+type TextKey = (Source, i64, String, String);
+
+/// Isi dokumen-dokumen kandidat, diambil sekaligus per batch.
+async fn fetch_texts(pool: &MySqlPool, docs: &[(i64, &Doc)]) -> anyhow::Result<HashMap<TextKey, String>> {
+    let mut out = HashMap::new();
+    let of = |s: Source| docs.iter().map(|(_, d)| *d).filter(move |d| d.source == s).collect::<Vec<_>>();
+
+    let code = of(Source::Code);
+    if !code.is_empty() {
+        let mut qb = sqlx::QueryBuilder::new("SELECT id, content_file FROM files WHERE id IN (");
+        let mut sep = qb.separated(", ");
+        for d in &code {
+            sep.push_bind(d.file_id);
+        }
+        qb.push(")");
+        let rows: Vec<(i64, Option<String>)> = qb.build_query_as().fetch_all(pool).await?;
+        for (file_id, content) in rows {
+            out.insert((Source::Code, file_id, String::new(), String::new()), content.unwrap_or_default());
+        }
+    }
+
+    for source in [Source::Analysis, Source::Tables] {
+        let list = of(source);
+        if list.is_empty() {
+            continue;
+        }
+        let column = match source {
+            Source::Tables => "CAST(structured AS CHAR)",
+            _ => "content",
+        };
+        let mut qb = sqlx::QueryBuilder::new(format!(
+            "SELECT file_id, kind, lang, {column} FROM analysis_results WHERE (file_id, kind, lang) IN "
+        ));
+        qb.push_tuples(&list, |mut t, d| {
+            t.push_bind(d.file_id).push_bind(&d.kind).push_bind(&d.lang);
+        });
+        let rows: Vec<(i64, String, String, Option<String>)> = qb.build_query_as().fetch_all(pool).await?;
+        for (file_id, kind, lang, text) in rows {
+            out.insert((source, file_id, kind, lang), text.unwrap_or_default());
+        }
+    }
+    Ok(out)
+}
+
+/// Jumlah file kandidat yang isinya diambil dari MySQL per query.
+const FETCH_BATCH: usize = 50;
+
+/// Cari di satu aplikasi, atau di semua aplikasi bila `app_id` None.
+pub async fn search(pool: &MySqlPool, app_id: Option<i64>, opts: &Options) -> anyhow::Result<Report> {
+    let re = opts.matcher().map_err(|e| anyhow::anyhow!(e))?;
+    let apps: Vec<i64> = match app_id {
+        Some(a) => vec![a],
+        None => sqlx::query_scalar("SELECT id FROM applications ORDER BY id").fetch_all(pool).await?,
+    };
+    let mut indexes = Vec::new();
+    for app in apps {
+        indexes.push((app, load(pool, app).await?));
+    }
+
+    // Prefilter: trigram wajib dari kueri (juga mode regex), lalu filter
+    // folder / bahasa / sumber, semuanya tanpa membaca isi dari MySQL
+    let required = required_trigrams(opts);
+    let wanted = |s: Source| match s {
+        Source::Code => opts.wants_code(),
+        Source::Analysis => opts.wants_analysis(),
+        Source::Tables => opts.wants_tables(),
+    };
+    let accept = |path: &str| {
+        folder_ok(path, opts.folder.as_deref())
+            && opts.language.as_deref().map(|l| language_of(path) == Some(l)).unwrap_or(true)
+    };
+    let mut docs: Vec<(i64, &Doc)> = indexes
+        .iter()
+        .flat_map(|(app, idx)| idx.candidates(&required).into_iter().map(move |d| (*app, d)))
+        .filter(|(_, d)| wanted(d.source) && accept(&d.path))
+        .collect();
+    docs.sort_by(|(a, x), (b, y)| {
+        a.cmp(b)
+            .then_with(|| x.path.cmp(&y.path))
+            .then(x.file_id.cmp(&y.file_id))
+            .then(x.source.cmp(&y.source))
+            .then_with(|| (&x.kind, &x.lang).cmp(&(&y.kind, &y.lang)))
+    });
+
+    // Isi diambil per batch file, urut hasil akhir; berhenti begitu lewat
+    // MAX_FILES file yang cocok
+    let mut files: Vec<FileMatch> = Vec::new();
+    let mut by_id: HashMap<i64, usize> = HashMap::new();
+    let mut rest = &docs[..];
+    while !rest.is_empty() && files.len() <= MAX_FILES {
+        let mut end = 0;
+        let mut seen = 0;
+        while end < rest.len() {
+            if end == 0 || rest[end].1.file_id != rest[end - 1].1.file_id {
+                if seen == FETCH_BATCH {
+                    break;
+                }
+                seen += 1;
+            }
+            end += 1;
+        }
+        let (batch, tail) = rest.split_at(end);
+        rest = tail;
+        let mut texts = fetch_texts(pool, batch).await?;
+        for (app, d) in batch {
+            let Some(text) = texts.remove(&(d.source, d.file_id, d.kind.clone(), d.lang.clone())) else {
+                continue;
+            };
+            if files.len() == MAX_FILES && !by_id.contains_key(&d.file_id) {
+                // cukup tahu masih ada file lain yang cocok
+                let hit = match d.source {
+                    Source::Tables => !match_tables(&text, &re).is_empty(),
+                    _ => text.lines().any(|l| highlight(l, &re).is_some()),
+                };
+                if hit {
+                    slot(&mut files, &mut by_id, d.file_id, *app, d.path.clone());
+                    break;
+                }
+                continue;
+            }
+            match d.source {
+                Source::Code => {
+                    let (lines, total) = match_lines(&text, &re, MAX_LINES_PER_FILE);
+                    if lines.is_empty() {
+                        continue;
+                    }
+                    let f = slot(&mut files, &mut by_id, d.file_id, *app, d.path.clone());
+                    f.more_lines = total - lines.len();
+                    f.lines = lines;
+                }
+                Source::Analysis => {
+                    let (lines, _) = match_lines(&text, &re, MAX_ANALYSIS_LINES);
+                    if lines.is_empty() {
+                        continue;
+                    }
+                    slot(&mut files, &mut by_id, d.file_id, *app, d.path.clone()).analyses.push(AnalysisMatch {
+                        kind: d.kind.clone(),
+                        lang: d.lang.clone(),
+                        lines,
+                    });
+                }
+                Source::Tables => {
+                    // Tabel yang diekstrak analisa relasi_db (structured.tables[].table)
+                    let found = match_tables(&text, &re);
+                    if found.is_empty() {
+                        continue;
+                    }
+                    let f = slot(&mut files, &mut by_id, d.file_id, *app, d.path.clone());
+                    for t in found {
+                        // satu baris per tabel walau ada hasil di beberapa bahasa
+                        if !f.tables.iter().any(|x| x.table == t.table) {
+                            f.tables.push(t);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let truncated = files.len() > MAX_FILES;
+    files.truncate(MAX_FILES);
+    let total_matches = files.iter().map(FileMatch::match_count).sum();
+    Ok(Report { files, total_matches, truncated })
+}
+
+/// Hasil per aplikasi, urut nama aplikasi (untuk pencarian lintas aplikasi).
+#[derive(Debug, Serialize)]
+pub struct AppGroup {
+    pub app_id: i64,
+    pub app_name: String,
+    pub files: Vec<FileMatch>,
+}
+
+impl AppGroup {
+    pub fn match_count(&self) -> usize {
+        self.files.iter().map(FileMatch::match_count).sum()
+    }
+}
+
+pub async fn group_by_app(pool: &MySqlPool, files: Vec<FileMatch>) -> anyhow::Result<Vec<AppGroup>> {
+    let names: HashMap<i64, String> = sqlx::query_as::<_, (i64, String)>("SELECT id, nama_aplikasi FROM applications")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+    let mut groups: Vec<AppGroup> = Vec::new();
+    for f in files {
+        match groups.last_mut() {
+            Some(g) if g.app_id == f.app_id => g.files.push(f),
+            _ => groups.push(AppGroup {
+                app_id: f.app_id,
+                app_name: names.get(&f.app_id).cloned().unwrap_or_else(|| format!("#{}", f.app_id)),
+                files: vec![f],
+            }),
+        }
+    }
+    groups.sort_by(|a, b| a.app_name.to_lowercase().cmp(&b.app_name.to_lowercase()).then(a.app_id.cmp(&b.app_id)));
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(texts: &[&str]) -> TextIndex {
+        let mut idx = TextIndex { signature: String::new(), docs: Vec::new(), grams: HashMap::new() };
+        for (i, t) in texts.iter().enumerate() {
+            let path = format!("f{i}.rs");
+            idx.add(Doc { source: Source::Code, file_id: i as i64, path, kind: String::new(), lang: String::new() }, t);
+        }
+        idx
+    }
+
+    fn found(idx: &TextIndex, query: &str, regex: bool) -> Vec<i64> {
+        let opts = Options { query: query.into(), regex, ..Default::default() };
+        idx.candidates(&required_trigrams(&opts)).iter().map(|d| d.file_id).collect()
+    }
+
+    #[test]
+    fn regex_literals_stop_at_metacharacters() {
+        assert_eq!(regex_literals(r"foo\.bar"), ["foo.bar"]);
+        assert_eq!(regex_literals(r"fn\s+(\w+)_handler"), ["fn", "_handler"]);
+        assert_eq!(regex_literals("colou?r"), ["colo", "r"]);
+        assert_eq!(regex_literals("ab+c{0,2}d"), ["ab", "d"]);
+        assert_eq!(regex_literals("[]abc]def.*xyz"), ["def", "xyz"]);
+        assert_eq!(regex_literals(r"\p{Lu}pper"), ["pper"]);
+        assert!(regex_literals("alpha|beta").is_empty());
+        assert!(regex_literals("(?x) alpha beta").is_empty());
+    }
+
+    #[test]
+    fn prefilter_keeps_every_possible_match() {
+        let idx = index(&["fn parse_user() {}", "fn render() {}", "let \u{212A}ey = 1;"]);
+        assert_eq!(found(&idx, "PARSE", false), [0]);
+        assert_eq!(found(&idx, "re.der", true), [1]);
+        assert_eq!(found(&idx, "fn", false), [0, 1, 2]);
+        assert_eq!(found(&idx, "parse|render", true), [0, 1, 2]);
+        assert!(found(&idx, "missing", false).is_empty());
+        // huruf K kelvin cocok dengan "key" tanpa membedakan besar-kecil
+        assert_eq!(found(&idx, "key", false), [2]);
+        let re = Options { query: "key".into(), ..Default::default() }.matcher().unwrap();
+        assert!(re.is_match("let \u{212A}ey = 1;"));
+    }
+
+    #[test]
+    fn highlight_splits_hits_and_trims_long_lines() {
+        let re = Regex::new("ab").unwrap();
+        let pieces = highlight("xabyab", &re).unwrap();
+        let parts: Vec<(&str, bool)> = pieces.iter().map(|p| (p.text.as_str(), p.hit)).collect();
+        assert_eq!(parts, [("x", false), ("ab", true), ("y", false), ("ab", true)]);
+        assert!(highlight("nothing", &re).is_none());
+
+        let line = format!("{}ab{}", "-".repeat(500), "-".repeat(500));
+        let pieces = highlight(&line, &re).unwrap();
+        assert_eq!(pieces.first().unwrap().text, "…");
+        assert_eq!(pieces.last().unwrap().text, "…");
+        assert!(pieces.iter().any(|p| p.hit));
+        let shown: usize = pieces.iter().map(|p| p.text.chars().count()).sum();
+        assert_eq!(shown, SNIPPET_CHARS + 2);
+    }
+
+    #[test]
+    fn match_lines_counts_beyond_limit() {
+        let re = Regex::new("x").unwrap();
+        let (lines, total) = match_lines("x\ny\nx\nx", &re, 2);
+        assert_eq!(lines.iter().map(|l| l.line).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(total, 3);
+    }
+
+    #[test]
+    fn like_escapes_wildcards() {
+        assert_eq!(like("a_b%c\\d"), "%a\\_b\\%c\\\\d%");
+    }
+}
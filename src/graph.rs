@@ -0,0 +1,269 @@
+// src/graph.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Graph per file dalam format data saja: LLM menjawab dokumen JSON
+// nodes/edges yang divalidasi terhadap SCHEMA plus aturan tambahan (id unik,
+// edge menunjuk node yang ada, batas ukuran). Jawaban yang ditolak diminta
+// ulang dengan alasan penolakannya (LLM_JSON_RETRIES kali). Hasil disimpan
+// sebagai JSON di files.json_graph dan digambar oleh kode tetap di
+// graph.html, sehingga tidak ada lagi JavaScript dari LLM yang dijalankan.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+use crate::usage::LlmCtx;
+
+const MAX_NODES: usize = 200;
+const MAX_EDGES: usize = 500;
+const MAX_LABEL_CHARS: usize = 200;
+
+/// JSON Schema dokumen graph (subset yang didukung schema.rs).
+pub const SCHEMA: &str = r#"{
+  "type": "object",
+  "required": ["nodes", "edges"],
+  "properties": {
+    "title": {"type": "string"},
+    "nodes": {
+      "type": "array",
+      "items": {
+        "type": "object",
+        "required": ["id", "label"],
+        "properties": {
+          "id": {"type": "string"},
+          "label": {"type": "string"},
+          "group": {"type": "string"},
+          "description": {"type": "string"}
+        },
+        "additionalProperties": false
+      }
+    },
+    "edges": {
+      "type": "array",
+      "items": {
+        "type": "object",
+        "required": ["from", "to"],
+        "properties": {
+          "from": {"type": "string"},
+          "to": {"type": "string"},
+          "label": {"type": "string"},
+          "arrows": {"type": "string", "enum": ["to", "from", "both", "none"]}
+        },
+        "additionalProperties": false
+      }
+    }
+  },
+  "additionalProperties": false
+}"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Node {
+    pub id: String,
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arrows: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Graph {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+fn schema() -> serde_json::Value {
+    serde_json::from_str(SCHEMA).expect("schema graph valid")
+}
+
+/// Validasi dokumen graph: schema, lalu aturan yang tidak bisa dinyatakan di schema.
+pub fn validate(value: &serde_json::Value) -> Result<Graph, String> {
+    crate::schema::validate(&schema(), value)?;
+    let graph: Graph = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+    if graph.nodes.is_empty() {
+        return Err("graph tidak punya node".into());
+    }
+    if graph.nodes.len() > MAX_NODES {
+        return Err(format!("terlalu banyak node ({} > {MAX_NODES})", graph.nodes.len()));
+    }
+    if graph.edges.len() > MAX_EDGES {
+        return Err(format!("terlalu banyak edge ({} > {MAX_EDGES})", graph.edges.len()));
+    }
+    let mut ids = HashSet::new();
+    for n in &graph.nodes {
+        if n.id.trim().is_empty() {
+            return Err("id node tidak boleh kosong".into());
+        }
+        if !ids.insert(n.id.as_str()) {
+            return Err(format!("id node duplikat: {}", n.id));
+        }
+        if n.label.chars().count() > MAX_LABEL_CHARS {
+            return Err(format!("label node {} lebih dari {MAX_LABEL_CHARS} karakter", n.id));
+        }
+    }
+    for (i, e) in graph.edges.iter().enumerate() {
+        for end in [&e.from, &e.to] {
+            if !ids.contains(end.as_str()) {
+                return Err(format!("edge #{i} menunjuk node yang tidak ada: {end}"));
+            }
+        }
+        if e.label.as_deref().map(|l| l.chars().count() > MAX_LABEL_CHARS).unwrap_or(false) {
+            return Err(format!("label edge #{i} lebih dari {MAX_LABEL_CHARS} karakter"));
+        }
+    }
+    Ok(graph)
+}
+
+/// Ambil JSON dari jawaban LLM lalu validasi.
+pub fn parse(raw: &str) -> Result<Graph, String> {
+    validate(&crate::schema::extract(raw)?)
+}
+
+/// Graph tersimpan di files.json_graph (None bila belum ada / tidak valid).
+pub fn from_stored(stored: Option<&str>) -> Option<Graph> {
+    let v: serde_json::Value = serde_json::from_str(stored?).ok()?;
+    validate(&v).ok()
+}
+
+/// Minta graph ke LLM untuk satu file, validasi (dengan perbaikan ulang),
+/// lalu simpan ke files.json_graph.
+pub async fn generate(pool: &MySqlPool, file_id: i64, user: Option<&str>) -> anyhow::Result<Graph> {
+    let src = crate::services::load_file_source(pool, file_id).await?;
+    let prompt = crate::prompts::active(pool, "graph").await?;
+    let mut text = crate::prompts::render(&prompt.template, &[("kode", &src.code), ("path", &src.path)]);
+    text.push_str(&format!(
+        "\n\nJawab hanya dengan satu objek JSON yang valid sesuai JSON Schema berikut, tanpa teks lain:\n{SCHEMA}"
+    ));
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or_default();
+    let lang = crate::lang::resolve(pool, src.app_id, None).await?;
+    let ctx = LlmCtx { pool, app_id: Some(src.app_id), file_id: Some(file_id), kind: "graph", user, lang };
+
+    let mut prompt_text = text.clone();
+    let mut attempt = 0;
+    let graph = loop {
+        let raw = crate::openai::generate_graph_json(&api_key, &prompt_text, &ctx).await?;
+        match parse(&raw) {
+            Ok(g) => break g,
+            Err(reason) if attempt < crate::schema::max_retries() => {
+                attempt += 1;
+                prompt_text = format!(
+                    "{text}\n\nJawaban sebelumnya:\n{raw}\n\nJawaban tersebut ditolak ({reason}). \
+                     Perbaiki dan jawab ulang hanya dengan JSON graph yang valid sesuai schema."
+                );
+            }
+            Err(reason) => {
+                return Err(InvalidGraph(format!(
+                    "Graph dari LLM tidak valid setelah {} percobaan: {reason}",
+                    attempt + 1
+                ))
+                .into());
+            }
+        }
+    };
+
+    sqlx::query("UPDATE files SET json_graph=?, json_graph_prompt=? WHERE id=?")
+        .bind(serde_json::to_string(&graph)?)
+        .bind(prompt.version)
+        .bind(file_id)
+        .execute(pool)
+        .await?;
+    Ok(graph)
+}
+
+/// Graph dari LLM tetap tidak valid setelah semua percobaan perbaikan.
+#[derive(Debug)]
+pub struct InvalidGraph(pub String);
+
+impl std::fmt::Display for InvalidGraph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for InvalidGraph {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rejected(value: serde_json::Value) -> String {
+        validate(&value).expect_err("graph harus ditolak")
+    }
+
+    #[test]
+    fn accepts_valid_graph() {
+        let g = validate(&json!({
+            "title": "auth",
+            "nodes": [{"id": "a", "label": "login"}, {"id": "b", "label": "db", "group": "infra"}],
+            "edges": [{"from": "a", "to": "b", "arrows": "to"}]
+        }))
+        .unwrap();
+        assert_eq!(g.nodes.len(), 2);
+        assert_eq!(g.edges[0].to, "b");
+    }
+
+    #[test]
+    fn rejects_rule_violations() {
+        assert!(rejected(json!({"nodes": [], "edges": []})).contains("tidak punya node"));
+        let dup = rejected(json!({"nodes": [{"id": "a", "label": "x"}, {"id": "a", "label": "y"}], "edges": []}));
+        assert!(dup.contains("duplikat: a"), "{dup}");
+        let dangling = rejected(json!({
+            "nodes": [{"id": "a", "label": "x"}],
+            "edges": [{"from": "a", "to": "a"}, {"from": "a", "to": "zz"}]
+        }));
+        assert!(dangling.contains("edge #1") && dangling.contains("zz"), "{dangling}");
+        let long = "x".repeat(MAX_LABEL_CHARS + 1);
+        assert!(rejected(json!({"nodes": [{"id": "a", "label": long}], "edges": []})).contains("label node a"));
+        let edge_label = rejected(json!({
+            "nodes": [{"id": "a", "label": "x"}],
+            "edges": [{"from": "a", "to": "a", "label": long}]
+        }));
+        assert!(edge_label.contains("label edge #0"), "{edge_label}");
+        // batas panjang dihitung per karakter, bukan per byte
+        let multibyte = "é".repeat(MAX_LABEL_CHARS);
+        assert!(validate(&json!({"nodes": [{"id": "a", "label": multibyte}], "edges": []})).is_ok());
+    }
+
+    #[test]
+    fn rejects_schema_violations() {
+        assert!(validate(&json!({"nodes": [{"id": "a", "label": "x"}]})).is_err());
+        assert!(validate(&json!({"nodes": [{"id": "a"}], "edges": []})).is_err());
+        assert!(validate(&json!({
+            "nodes": [{"id": "a", "label": "x"}],
+            "edges": [{"from": "a", "to": "a", "arrows": "sideways"}]
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn from_stored_ignores_legacy_and_missing_graphs() {
+        assert!(from_stored(None).is_none());
+        let legacy = "var nodes = new vis.DataSet([{id: 1, label: 'main'}]); new vis.Network(el, {nodes}, {});";
+        assert!(from_stored(Some(legacy)).is_none());
+        assert!(from_stored(Some(r#"{"nodes": [], "edges": []}"#)).is_none());
+        let stored = r#"{"nodes": [{"id": "a", "label": "main"}], "edges": []}"#;
+        assert_eq!(from_stored(Some(stored)).unwrap().nodes[0].label, "main");
+    }
+}
@@ -1,5 +1,5 @@
 // src/handlers.rs
-use futures_util::TryStreamExt;
+use futures_util::{StreamExt, TryStreamExt};
 use warp::Reply;
 use warp::multipart::FormData;
 use warp::Buf;
@@ -10,10 +10,15 @@ use serde_json::json;
 use chrono::Utc;
 use askama::Template; // for render()
 use serde::Deserialize;   
+use std::collections::HashMap;
+
+use crate::i18n::{self, Locale};
+use crate::jobs::Jobs;
 
 use crate::models::{
-    IndexPage, UploadPage, DetailPage, AnalysisPage, AnalysisAllPage,
-    AppRow, AnalysisJoinRow, FileWithAnalyses,
+    IndexPage, UploadPage, DetailPage, AnalysisPage, AnalysisAllPage, UsagePage, EstimatePage,
+    PromptsPage, PromptEditPage, PromptListRow, AnalysisPreview, KindsPage,
+    AppRow, AnalysisJoinRow, FileWithAnalyses, ChatPage, ChatMessageView, SearchPage,
 };
 
 // Semua handler konsisten mengembalikan Response<Body>
@@ -26,6 +31,7 @@ const PER_PAGE: i64 = 25;
 pub struct PageQ {
     pub page: Option<usize>,
     pub q: Option<String>,   // ⟵ TAMBAH INI
+    pub lang: Option<String>, // bahasa hasil analisa yang ditampilkan
 }
 
 fn truncate_words(s: &str, max_words: usize) -> String {
@@ -43,20 +49,25 @@ fn truncate_words(s: &str, max_words: usize) -> String {
     out
 }
 
-pub async fn api_get_app_summary_full(app_id: i32, pool: MySqlPool) -> HandlerResult {
-    match sqlx::query!("SELECT summary FROM app_summary WHERE app_id=?", app_id)
-        .fetch_optional(&pool).await
-    {
-        Ok(Some(row)) => {
-            let full = row.summary.unwrap_or_default();
-            let body = json!({ "title": "Ringkasan Aplikasi", "content": full });
+/// Summary aplikasi dalam bahasa `?lang=` (default: setelan aplikasi).
+async fn summary_for_request(pool: &MySqlPool, app_id: i32, qs: &HashMap<String, String>) -> anyhow::Result<Option<String>> {
+    let lang = crate::lang::resolve(pool, app_id as i64, qs.get("lang").map(String::as_str)).await?;
+    crate::services::cached_summary(pool, app_id as i64, lang).await
+}
+
+pub async fn api_get_app_summary_full(
+    app_id: i32,
+    qs: HashMap<String, String>,
+    pool: MySqlPool,
+    loc: Locale,
+) -> HandlerResult {
+    match summary_for_request(&pool, app_id, &qs).await {
+        Ok(Some(full)) => {
+            let body = json!({ "title": loc.t("msg.summary_title"), "content": full });
             Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
         }
         Ok(None) => {
-            let body = json!({
-                "error": "no_summary",
-                "message": "Belum ada summary. Klik 'Summary' atau 'Summary Ulang' untuk membuatnya."
-            });
+            let body = json!({ "error": "no_summary", "message": loc.t("msg.no_summary") });
             Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response())
         }
         Err(e) => {
@@ -66,21 +77,20 @@ pub async fn api_get_app_summary_full(app_id: i32, pool: MySqlPool) -> HandlerRe
     }
 }
 
-pub async fn api_get_app_summary_preview(app_id: i32, pool: MySqlPool) -> HandlerResult {
-    match sqlx::query!("SELECT summary FROM app_summary WHERE app_id=?", app_id)
-        .fetch_optional(&pool).await
-    {
-        Ok(Some(row)) => {
-            let full = row.summary.unwrap_or_default();
+pub async fn api_get_app_summary_preview(
+    app_id: i32,
+    qs: HashMap<String, String>,
+    pool: MySqlPool,
+    loc: Locale,
+) -> HandlerResult {
+    match summary_for_request(&pool, app_id, &qs).await {
+        Ok(Some(full)) => {
             let short = truncate_words(&full, 50);
-            let body = json!({ "title": "Ringkasan Aplikasi", "content": short });
+            let body = json!({ "title": loc.t("msg.summary_title"), "content": short });
             Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
         }
         Ok(None) => {
-            let body = json!({
-                "error": "no_summary",
-                "message": "Belum ada summary. Klik 'Summary' atau 'Summary Ulang' untuk menghasilkan ringkasan."
-            });
+            let body = json!({ "error": "no_summary", "message": loc.t("msg.no_summary") });
             Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response())
         }
         Err(e) => {
@@ -90,22 +100,22 @@ pub async fn api_get_app_summary_preview(app_id: i32, pool: MySqlPool) -> Handle
     }
 }
 
-pub async fn upload_page() -> HandlerResult {
-    let page = UploadPage;
+pub async fn upload_page(loc: Locale) -> HandlerResult {
+    let page = UploadPage { loc };
     Ok(askama_warp::reply(&page, "html"))
 }
 
-pub async fn upload_zip(form: FormData, pool: MySqlPool) -> HandlerResult {
+pub async fn upload_zip(form: FormData, pool: MySqlPool, loc: Locale) -> HandlerResult {
     let mut app_name = "MyApp".to_string();
     let mut zip_path: Option<String> = None;
 
     let mut parts = form;
-    while let Some(part) = parts.try_next().await.map_err(|_| warp::reject())? {
+    while let Some(part) = parts.try_next().await.map_err(|_| i18n::reject(loc))? {
         match part.name() {
             "app_name" => {
                 let mut data = Vec::new();
                 let mut s = part.stream();
-                while let Some(mut chunk) = s.try_next().await.map_err(|_| warp::reject())? {
+                while let Some(mut chunk) = s.try_next().await.map_err(|_| i18n::reject(loc))? {
                     data.extend_from_slice(chunk.chunk());
                     chunk.advance(chunk.remaining());
                 }
@@ -114,11 +124,11 @@ pub async fn upload_zip(form: FormData, pool: MySqlPool) -> HandlerResult {
             "file" => {
                 let mut fname = std::env::temp_dir();
                 fname.push(format!("{}.zip", uuid::Uuid::new_v4()));
-                let mut f = std::fs::File::create(&fname).map_err(|_| warp::reject())?;
+                let mut f = std::fs::File::create(&fname).map_err(|_| i18n::reject(loc))?;
 
                 let mut s = part.stream();
-                while let Some(mut chunk) = s.try_next().await.map_err(|_| warp::reject())? {
-                    f.write_all(chunk.chunk()).map_err(|_| warp::reject())?;
+                while let Some(mut chunk) = s.try_next().await.map_err(|_| i18n::reject(loc))? {
+                    f.write_all(chunk.chunk()).map_err(|_| i18n::reject(loc))?;
                     chunk.advance(chunk.remaining());
                 }
                 zip_path = Some(fname.to_string_lossy().to_string());
@@ -127,10 +137,10 @@ pub async fn upload_zip(form: FormData, pool: MySqlPool) -> HandlerResult {
         }
     }
 
-    let zip_path = zip_path.ok_or_else(warp::reject)?;
+    let zip_path = zip_path.ok_or_else(|| i18n::reject(loc))?;
     let app_id = crate::services::extract_and_store(&pool, &app_name, &zip_path)
         .await
-        .map_err(|_| warp::reject())?;
+        .map_err(|_| i18n::reject(loc))?;
 
     // Bangun Response<Body> untuk redirect
     let res = warp::http::Response::builder()
@@ -142,7 +152,7 @@ pub async fn upload_zip(form: FormData, pool: MySqlPool) -> HandlerResult {
     Ok(res)
 }
 
-pub async fn list_apps(pool: MySqlPool) -> HandlerResult {
+pub async fn list_apps(pool: MySqlPool, loc: Locale) -> HandlerResult {
     match sqlx::query_as::<_, AppRow>(
         "SELECT id, nama_aplikasi, created_at FROM applications ORDER BY id DESC"
     ).fetch_all(&pool).await {
@@ -155,12 +165,14 @@ pub async fn list_apps(pool: MySqlPool) -> HandlerResult {
             let page = IndexPage {
                 apps: &rows,
                 json: json_data,
+                loc,
             };
             Ok(askama_warp::reply(&page, "html"))
         }
         Err(e) => {
             let html = format!(
-                "<h3>DB error</h3><pre>{e}</pre><p>Cek DATABASE_URL / DB & tabel.</p>"
+                "<h3>DB error</h3><pre>{e}</pre><p>{}</p>",
+                loc.t("err.db_list")
             );
             Ok(warp::reply::html(html).into_response())
         }
@@ -177,23 +189,20 @@ struct FileJoinRow {
     full_path: String,
     json_graph: Option<String>,
     line_count: Option<i32>,
-    analisa_fungsi: Option<String>,
-    analisa_relasi_file: Option<String>,
-    analisa_relasi_db: Option<String>,
 }
 
 // UBAH tanda tangan: terima query page
-pub async fn app_detail(app_id: i32, q: PageQ, pool: MySqlPool) -> HandlerResult {
+pub async fn app_detail(app_id: i32, q: PageQ, pool: MySqlPool, loc: Locale) -> HandlerResult {
     let app: Option<AppRow> = sqlx::query_as(
         "SELECT id, nama_aplikasi, created_at FROM applications WHERE id=?",
     )
     .bind(app_id)
     .fetch_optional(&pool)
     .await
-    .map_err(|_| warp::reject())?;
+    .map_err(|_| i18n::reject(loc))?;
 
     let Some(app) = app else {
-        let html = format!("Aplikasi dengan id {} tidak ditemukan.", app_id);
+        let html = loc.tf("err.app_not_found", &[("id", &app_id.to_string())]);
         return Ok(
             warp::reply::with_status(warp::reply::html(html), StatusCode::NOT_FOUND)
                 .into_response()
@@ -214,13 +223,13 @@ pub async fn app_detail(app_id: i32, q: PageQ, pool: MySqlPool) -> HandlerResult
         .bind(&like)
         .bind(&like)
         .fetch_one(&pool).await
-        .map_err(|_| warp::reject())?;
+        .map_err(|_| i18n::reject(loc))?;
         (cnt, Some(like))
     } else {
         let cnt: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM files WHERE app_id=?")
             .bind(app_id)
             .fetch_one(&pool).await
-            .map_err(|_| warp::reject())?;
+            .map_err(|_| i18n::reject(loc))?;
         (cnt, None)
     };
 
@@ -236,12 +245,10 @@ pub async fn app_detail(app_id: i32, q: PageQ, pool: MySqlPool) -> HandlerResult
         sqlx::query_as::<_, FileJoinRow>(
             r#"
             SELECT
-                f.id, f.app_id, f.nama_file, f.nama_folder, f.full_path, f.json_graph,
-                m.line_count,
-                a.analisa_fungsi, a.analisa_relasi_file, a.analisa_relasi_db
+                f.id, f.app_id, f.nama_file, f.nama_folder, f.full_path, CAST(f.json_graph AS CHAR) AS json_graph,
+                m.line_count
             FROM files f
             LEFT JOIN file_metadata m ON m.file_id = f.id
-            LEFT JOIN analysis a ON a.file_id = f.id
             WHERE f.app_id = ?
               AND (f.nama_file LIKE ? OR COALESCE(f.nama_folder,'') LIKE ? OR f.full_path LIKE ?)
             ORDER BY f.id
@@ -251,17 +258,15 @@ pub async fn app_detail(app_id: i32, q: PageQ, pool: MySqlPool) -> HandlerResult
         .bind(app_id)
         .bind(like).bind(like).bind(like)
         .bind(PER_PAGE).bind(offset)
-        .fetch_all(&pool).await.map_err(|_| warp::reject())?
+        .fetch_all(&pool).await.map_err(|_| i18n::reject(loc))?
     } else {
         sqlx::query_as::<_, FileJoinRow>(
             r#"
             SELECT
-                f.id, f.app_id, f.nama_file, f.nama_folder, f.full_path, f.json_graph,
-                m.line_count,
-                a.analisa_fungsi, a.analisa_relasi_file, a.analisa_relasi_db
+                f.id, f.app_id, f.nama_file, f.nama_folder, f.full_path, CAST(f.json_graph AS CHAR) AS json_graph,
+                m.line_count
             FROM files f
             LEFT JOIN file_metadata m ON m.file_id = f.id
-            LEFT JOIN analysis a ON a.file_id = f.id
             WHERE f.app_id = ?
             ORDER BY f.id
             LIMIT ? OFFSET ?
@@ -269,15 +274,33 @@ pub async fn app_detail(app_id: i32, q: PageQ, pool: MySqlPool) -> HandlerResult
         )
         .bind(app_id)
         .bind(PER_PAGE).bind(offset)
-        .fetch_all(&pool).await.map_err(|_| warp::reject())?
+        .fetch_all(&pool).await.map_err(|_| i18n::reject(loc))?
     }; // ⟵ TUTUP ekspresi if/else dengan `};`
 
+    // Hasil analisa semua jenis (registry analysis_kinds) untuk file di halaman ini
+    let kinds = crate::kinds::list(&pool, true).await.map_err(|_| i18n::reject(loc))?;
+    let app_lang = crate::lang::app_setting(&pool, app.id).await.map_err(|_| i18n::reject(loc))?;
+    let lang = crate::lang::normalize(q.lang.as_deref())
+        .or(app_lang)
+        .unwrap_or_else(crate::lang::default_lang);
+    let ids: Vec<i64> = rows.iter().map(|r| r.id).collect();
+    let mut results = crate::services::results_for_files(&pool, &ids, lang)
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+
     // Susun vec + nomor urut global
     let mut files: Vec<FileWithAnalyses> = Vec::with_capacity(rows.len());
     for (i, r) in rows.into_iter().enumerate() {
-        let fungsi_preview = r.analisa_fungsi.as_deref().map(|s| truncate_words(s, 50));
-        let relasi_file_preview = r.analisa_relasi_file.as_deref().map(|s| truncate_words(s, 50));
-        let relasi_db_preview = r.analisa_relasi_db.as_deref().map(|s| truncate_words(s, 50));
+        let mut done = results.remove(&r.id).unwrap_or_default();
+        let analyses = kinds
+            .iter()
+            .map(|k| AnalysisPreview {
+                kind: k.name.clone(),
+                label: k.label.clone(),
+                badge: k.badge.clone(),
+                preview: done.remove(&k.name).map(|s| truncate_words(&s, 50)),
+            })
+            .collect();
 
         files.push(FileWithAnalyses {
             id: r.id as i64,
@@ -286,9 +309,7 @@ pub async fn app_detail(app_id: i32, q: PageQ, pool: MySqlPool) -> HandlerResult
             nama_folder: r.nama_folder,
             full_path: r.full_path,
             line_count: r.line_count,
-            fungsi_preview,
-            relasi_file_preview,
-            relasi_db_preview,
+            analyses,
             has_graph: r.json_graph.as_deref().map(|s| !s.trim().is_empty()).unwrap_or(false),
             row_no: Some(offset + i as i64 + 1),
         });
@@ -324,120 +345,117 @@ pub async fn app_detail(app_id: i32, q: PageQ, pool: MySqlPool) -> HandlerResult
         to,
     };
 
+    let jobs = crate::jobs::recent_jobs(&pool, app.id, 5)
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+
     let page_tmpl = DetailPage {
         app: &app,
         files: &files,
         pagination,
         search: q.q.clone(),
+        jobs: &jobs,
+        kinds: &kinds,
+        lang,
+        app_lang: app_lang.unwrap_or(""),
+        languages: &crate::lang::LANGUAGES,
+        loc,
     };
     Ok(askama_warp::reply(&page_tmpl, "html"))
 }
 
 
-pub async fn app_summary(app_id: i32, pool: MySqlPool, force: bool) -> HandlerResult {
+pub async fn app_summary(
+    app_id: i32,
+    qs: HashMap<String, String>,
+    pool: MySqlPool,
+    jobs: Jobs,
+    user: Option<String>,
+    loc: Locale,
+    force: bool,
+) -> HandlerResult {
+    let lang = crate::lang::resolve(&pool, app_id as i64, qs.get("lang").map(String::as_str))
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+    let force_href = format!("/apps/{}/summary/force?lang={}", app_id, lang);
     if !force {
-        if let Some(row) = sqlx::query!("SELECT summary FROM app_summary WHERE app_id=?", app_id)
-            .fetch_optional(&pool).await.map_err(|_| warp::reject())?
+        if let Some(s) = crate::services::cached_summary(&pool, app_id as i64, lang)
+            .await
+            .map_err(|_| i18n::reject(loc))?
         {
-            if let Some(s) = row.summary {
-                let page = AnalysisPage {
-                    title: "Summary Aplikasi",
-                    content: &s,
-                    back_href: "/apps",
-                    force_href: Some(&format!("/apps/{}/summary/force", app_id)),
-                };
-                return Ok(askama_warp::reply(&page, "html"));
-            }
+            let page = AnalysisPage {
+                title: loc.t("msg.app_summary"),
+                content: &s,
+                back_href: "/apps",
+                force_href: Some(&force_href),
+                job_id: None,
+                loc,
+            };
+            return Ok(askama_warp::reply(&page, "html"));
         }
     }
 
-    let rows = sqlx::query!(
-        r#"SELECT f.full_path, f.content_file, m.line_count, m.imports, m.sql_queries
-           FROM files f LEFT JOIN file_metadata m ON m.file_id=f.id
-           WHERE f.app_id=? ORDER BY f.id"#,
-        app_id
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(|_| warp::reject())?;
-
-    let mut payload = String::new();
-    const SNIPPET_CHARS: usize = 2000;
-    for r in rows {
-        let snippet = r
-            .content_file
-            .as_deref()
-            .map(|s| {
-                if s.len() > SNIPPET_CHARS {
-                    format!("{}...\n[truncated]", &s[..SNIPPET_CHARS])
-                } else {
-                    s.to_string()
-                }
-            })
-            .unwrap_or_default();
-
-        payload.push_str(&format!(
-            "- {} | lines={:?}\nimports:\n{}\nsql:\n{}\ncontent:\n{}\n\n",
-            r.full_path,
-            r.line_count,
-            r.imports.unwrap_or_default(),
-            r.sql_queries.unwrap_or_default(),
-            snippet
-        ));
+    // Belum ada / dipaksa ulang: tampilkan perkiraan token dulu, job baru
+    // dijalankan setelah user konfirmasi (?confirm=1)
+    if qs.get("confirm").map(String::as_str) != Some("1") {
+        let estimate = crate::budget::estimate_summary(&pool, app_id as i64, loc)
+            .await
+            .map_err(|_| i18n::reject(loc))?;
+        let confirm_href = format!("{force_href}&confirm=1");
+        let page = EstimatePage {
+            title: loc.t("msg.app_summary"),
+            estimate: &estimate,
+            confirm_href: &confirm_href,
+            back_href: "/apps",
+            loc,
+        };
+        return Ok(askama_warp::reply(&page, "html"));
     }
 
-    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or_default();
-    let summary = crate::openai::summary_app(&api_key, &payload)
+    // Jalankan sebagai job, hasil di-stream via SSE
+    let job_id = crate::jobs::spawn_summary(pool, jobs, app_id as i64, lang, user)
         .await
-        .map_err(|_| warp::reject())?;
-
-    sqlx::query(
-        "INSERT INTO app_summary (app_id, summary) VALUES (?, ?)
-         ON DUPLICATE KEY UPDATE summary=VALUES(summary), created_at=CURRENT_TIMESTAMP",
-    )
-    .bind(app_id)
-    .bind(&summary)
-    .execute(&pool)
-    .await
-    .map_err(|_| warp::reject())?;
-
+        .map_err(|_| i18n::reject(loc))?;
     let page = AnalysisPage {
-        title: "Summary Aplikasi",
-        content: &summary,
+        title: loc.t("msg.app_summary"),
+        content: "",
         back_href: "/apps",
-        force_href: Some(&format!("/apps/{}/summary/force", app_id)),
+        force_href: Some(&force_href),
+        job_id: Some(job_id),
+        loc,
     };
     Ok(askama_warp::reply(&page, "html"))
 }
 
-pub async fn app_analysis_all(app_id: i32, pool: MySqlPool) -> HandlerResult {
+pub async fn app_analysis_all(
+    app_id: i32,
+    qs: HashMap<String, String>,
+    pool: MySqlPool,
+    loc: Locale,
+) -> HandlerResult {
     let app: Option<AppRow> = sqlx::query_as(
         "SELECT id, nama_aplikasi, created_at FROM applications WHERE id=?",
     )
     .bind(app_id)
     .fetch_optional(&pool)
     .await
-    .map_err(|_| warp::reject())?;
+    .map_err(|_| i18n::reject(loc))?;
 
     let Some(app) = app else {
-        let html = format!("Aplikasi dengan id {} tidak ditemukan.", app_id);
+        let html = loc.tf("err.app_not_found", &[("id", &app_id.to_string())]);
         return Ok(
             warp::reply::with_status(warp::reply::html(html), StatusCode::NOT_FOUND)
                 .into_response()
         );
     };
 
-    let rows: Vec<AnalysisJoinRow> = sqlx::query_as(
+    let mut rows: Vec<AnalysisJoinRow> = sqlx::query_as(
         r#"
         SELECT
             f.id AS file_id,
             f.nama_file,
-            f.full_path,
-            a.analisa_fungsi,
-            a.analisa_relasi_file,
-            a.analisa_relasi_db
+            f.full_path
         FROM files f
-        LEFT JOIN analysis a ON a.file_id = f.id
         WHERE f.app_id = ?
         ORDER BY f.id
         "#
@@ -445,19 +463,60 @@ pub async fn app_analysis_all(app_id: i32, pool: MySqlPool) -> HandlerResult {
     .bind(app_id)
     .fetch_all(&pool)
     .await
-    .map_err(|_| warp::reject())?;
+    .map_err(|_| i18n::reject(loc))?;
+
+    // Satu kolom per jenis analisa di registry
+    let kinds = crate::kinds::list(&pool, true).await.map_err(|_| i18n::reject(loc))?;
+    let lang = crate::lang::resolve(&pool, app.id, qs.get("lang").map(String::as_str))
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+    let ids: Vec<i64> = rows.iter().map(|r| r.file_id).collect();
+    let mut results = crate::services::results_for_files(&pool, &ids, lang)
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+    for r in rows.iter_mut() {
+        let mut done = results.remove(&r.file_id).unwrap_or_default();
+        r.results = kinds.iter().map(|k| done.remove(&k.name)).collect();
+    }
 
-    let page = AnalysisAllPage { app: &app, rows: &rows };
+    let page = AnalysisAllPage {
+        app: &app,
+        kinds: &kinds,
+        rows: &rows,
+        lang,
+        languages: &crate::lang::LANGUAGES,
+        loc,
+    };
     Ok(askama_warp::reply(&page, "html"))
 }
 
 // ====== API untuk modal: ambil full konten analisa ======
-pub async fn api_get_analysis(file_id: i32, kind: String, pool: MySqlPool) -> HandlerResult {
-    let row = match sqlx::query!(
-        "SELECT analisa_fungsi, analisa_relasi_file, analisa_relasi_db FROM analysis WHERE file_id=?",
-        file_id
-    ).fetch_optional(&pool).await {
-        Ok(r) => r,
+pub async fn api_get_analysis(
+    file_id: i32,
+    kind: String,
+    qs: HashMap<String, String>,
+    pool: MySqlPool,
+    loc: Locale,
+) -> HandlerResult {
+    let found = async {
+        let Some(k) = crate::kinds::get(&pool, &kind).await? else {
+            return Ok::<_, anyhow::Error>(None);
+        };
+        let (app_id,): (i64,) = sqlx::query_as("SELECT app_id FROM files WHERE id=?")
+            .bind(file_id)
+            .fetch_one(&pool)
+            .await?;
+        let lang = crate::lang::resolve(&pool, app_id, qs.get("lang").map(String::as_str)).await?;
+        let content = crate::services::cached_analysis(&pool, file_id as i64, &kind, lang).await?;
+        let structured = crate::services::cached_structured(&pool, file_id as i64, &kind, lang).await?;
+        Ok(Some((k, content, structured)))
+    }
+    .await;
+
+    let (title, content, format, structured) = match found {
+        Ok(Some((k, Some(content), structured))) => (k.label, content, k.output_format, structured),
+        Ok(Some((k, None, _))) => (k.label, loc.t("msg.no_result").into(), k.output_format, None),
+        Ok(None) => (loc.t("err.unknown").into(), loc.t("err.unknown_kind").into(), "text".into(), None),
         Err(e) => {
             let body = json!({"error":"db_error", "message": e.to_string()});
             return Ok(
@@ -467,182 +526,1631 @@ pub async fn api_get_analysis(file_id: i32, kind: String, pool: MySqlPool) -> Ha
         }
     };
 
-    let (title, content) = if let Some(r) = row {
-        match kind.as_str() {
-            "fungsi"      => ("Analisa Fungsi",     r.analisa_fungsi.unwrap_or("Belum ada hasil.".into())),
-            "relasi_file" => ("Relasi File",        r.analisa_relasi_file.unwrap_or("Belum ada hasil.".into())),
-            "relasi_db"   => ("Relasi DB",          r.analisa_relasi_db.unwrap_or("Belum ada hasil.".into())),
-            _             => ("Tidak dikenal",      "Jenis analisa tidak dikenal".into()),
+    let body = json!({ "title": title, "content": content, "format": format, "structured": structured });
+    Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+}
+
+// GET /api/apps/:id/structured/:kind?lang=&contains=  ← data JSON hasil analisa semua file
+pub async fn api_structured(
+    app_id: i32,
+    kind: String,
+    qs: HashMap<String, String>,
+    pool: MySqlPool,
+    loc: Locale,
+) -> HandlerResult {
+    let def = match crate::kinds::get(&pool, &kind).await {
+        Ok(Some(k)) => k,
+        Ok(None) => {
+            let body = json!({ "error": "not_found", "message": loc.t("err.unknown_kind") });
+            return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response());
+        }
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response());
+        }
+    };
+    let rows = async {
+        let lang = crate::lang::resolve(&pool, app_id as i64, qs.get("lang").map(String::as_str)).await?;
+        let contains = qs.get("contains").map(String::as_str);
+        let rows = crate::services::structured_results(&pool, app_id as i64, &kind, lang, contains).await?;
+        Ok::<_, anyhow::Error>((lang, rows))
+    }
+    .await;
+    match rows {
+        Ok((lang, rows)) => {
+            let body = json!({
+                "kind": def.name,
+                "format": def.output_format,
+                "schema": def.json_schema.as_deref().and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok()),
+                "lang": lang,
+                "files": rows,
+            });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+        }
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+// ====== Generate graph (nodes/edges JSON) via GPT dan simpan ke files.json_graph ======
+pub async fn generate_graph(file_id: i32, pool: MySqlPool, user: Option<String>, loc: Locale) -> HandlerResult {
+    let exists: Option<(i64,)> = sqlx::query_as("SELECT id FROM files WHERE id=?")
+        .bind(file_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+    if exists.is_none() {
+        return Err(i18n::reject(loc));
+    }
+
+    let graph = match crate::graph::generate(&pool, file_id as i64, user.as_deref()).await {
+        Ok(g) => g,
+        Err(e) => {
+            if let Some(message) = crate::budget::exceeded_message(&e, loc) {
+                let body = json!({ "error": "budget_exceeded", "message": message });
+                return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::TOO_MANY_REQUESTS).into_response());
+            }
+            if let Some(invalid) = e.downcast_ref::<crate::graph::InvalidGraph>() {
+                let body = json!({ "error": "invalid_graph", "message": loc.tf("err.invalid_graph", &[("reason", &invalid.0)]) });
+                return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::UNPROCESSABLE_ENTITY).into_response());
+            }
+            let body = json!({ "error": "llm_error", "message": e.to_string() });
+            return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_GATEWAY).into_response());
         }
-    } else {
-        ("Belum dianalisis", "Belum ada hasil. Jalankan analisa terlebih dahulu.".into())
     };
 
-    let body = json!({ "title": title, "content": content });
+    let body = json!({ "ok": true, "nodes": graph.nodes.len(), "edges": graph.edges.len() });
     Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
 }
 
-// ====== Generate graph JS via GPT dan simpan ke files.json_graph ======
-pub async fn generate_graph(file_id: i32, pool: MySqlPool) -> HandlerResult {
-    let row = sqlx::query!(
-        "SELECT full_path, content_file FROM files WHERE id=?",
-        file_id
-    ).fetch_one(&pool).await.map_err(|_| warp::reject())?;
-
-    let code = if let Some(c) = row.content_file {
-        c
-    } else {
-        std::fs::read_to_string(&row.full_path).unwrap_or_default()
+// ====== GET /api/files/:id/graph?format= → dokumen graph tersimpan (atau unduhan) ======
+pub async fn api_file_graph(file_id: i32, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let format = match export_format(&qs) {
+        Ok(f) => f,
+        Err(raw) => return Ok(invalid_format(&raw, loc)),
     };
+    let row: Option<(String, Option<String>)> =
+        sqlx::query_as("SELECT nama_file, CAST(json_graph AS CHAR) FROM files WHERE id=?")
+            .bind(file_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|_| i18n::reject(loc))?;
+    let Some((nama_file, stored)) = row else {
+        let body = json!({ "error": "not_found", "message": loc.t("err.file_not_found") });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response());
+    };
+    match (crate::graph::from_stored(stored.as_deref()), format) {
+        (Some(graph), Some(format)) => {
+            let export = crate::export::from_file_graph(&nama_file, &graph);
+            Ok(export_reply(&export, format, &format!("{nama_file}-graph"), loc))
+        }
+        (Some(graph), None) => Ok(warp::reply::with_status(warp::reply::json(&graph), StatusCode::OK).into_response()),
+        (None, _) => {
+            let body = json!({ "error": "no_graph", "message": loc.t("msg.no_graph_hint") });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response())
+        }
+    }
+}
 
-    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or_default();
-    let js = crate::openai::generate_graph_js(&api_key, &code).await.map_err(|_| warp::reject())?;
+/// `format=` di query string: None = respons JSON biasa; Err = nilai yang tidak dikenal.
+fn export_format(qs: &HashMap<String, String>) -> Result<Option<crate::export::Format>, String> {
+    match qs.get("format").map(|f| f.trim()).filter(|f| !f.is_empty()) {
+        None => Ok(None),
+        Some(raw) => crate::export::Format::parse(raw).map(Some).ok_or_else(|| raw.to_string()),
+    }
+}
 
-    sqlx::query!("UPDATE files SET json_graph=? WHERE id=?", js, file_id)
-        .execute(&pool)
-        .await
-        .map_err(|_| warp::reject())?;
+fn invalid_format(raw: &str, loc: Locale) -> warp::reply::Response {
+    let body = json!({ "error": "invalid_format", "message": loc.tf("err.invalid_format", &[("format", raw)]) });
+    warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_REQUEST).into_response()
+}
 
-    let body = json!({ "ok": true });
-    Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+/// Graph hasil ekspor sebagai file unduhan (SVG / PNG ditampilkan inline).
+fn export_reply(graph: &crate::export::ExportGraph, format: crate::export::Format, base: &str, loc: Locale) -> warp::reply::Response {
+    let body = match crate::export::render(graph, format) {
+        Ok(body) => body,
+        Err(e) => {
+            let body = json!({
+                "error": "too_large",
+                "message": loc.tf("err.render_too_large", &[("width", &e.width.to_string()), ("height", &e.height.to_string())]),
+            });
+            return warp::reply::with_status(warp::reply::json(&body), StatusCode::UNPROCESSABLE_ENTITY).into_response();
+        }
+    };
+    let name = crate::export::file_name(base, format);
+    let disposition = match format {
+        crate::export::Format::Svg | crate::export::Format::Png => "inline",
+        _ => "attachment",
+    };
+    warp::http::Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", format.content_type())
+        .header("Content-Disposition", format!("{disposition}; filename=\"{name}\""))
+        .body(warp::hyper::Body::from(body))
+        .unwrap()
+}
+
+#[derive(sqlx::FromRow)]
+struct GraphRow {
+    nama_file: String,
+    json_graph: Option<String>,
+    app_id: i64,
+    nama_aplikasi: String,
+    created_at: Option<chrono::DateTime<Utc>>,
 }
 
 // ====== Render graph ======
-pub async fn view_graph(file_id: i32, pool: MySqlPool) -> HandlerResult {
-    let row = sqlx::query!(
+pub async fn view_graph(file_id: i32, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let row: Option<GraphRow> = sqlx::query_as(
         r#"
-        SELECT f.nama_file, f.json_graph, a.id as app_id, a.nama_aplikasi, a.created_at
+        SELECT f.nama_file, CAST(f.json_graph AS CHAR) AS json_graph, a.id AS app_id, a.nama_aplikasi, a.created_at
         FROM files f
         JOIN applications a ON a.id = f.app_id
         WHERE f.id=?
         "#,
-        file_id
     )
+    .bind(file_id)
     .fetch_optional(&pool)
     .await
-    .map_err(|_| warp::reject())?;
+    .map_err(|_| i18n::reject(loc))?;
 
     let Some(r) = row else {
         return Ok(
             warp::reply::with_status(
-                warp::reply::html("File tidak ditemukan".to_string()),
+                warp::reply::html(loc.t("err.file_not_found").to_string()),
                 StatusCode::NOT_FOUND,
             ).into_response()
         );
     };
 
     let app = crate::models::AppRow {
-        id: r.app_id as i64,
+        id: r.app_id,
         nama_aplikasi: r.nama_aplikasi,
         created_at: r.created_at.unwrap_or(Utc::now()),
     };
 
-    let js = r.json_graph.unwrap_or_default();
-    if js.trim().is_empty() {
-        let html = "<div class='container p-3'><a href='javascript:history.back()'>&larr; Kembali</a><h4>Belum ada graph</h4><p>Silakan klik <em>Generate JSON</em> terlebih dahulu.</p></div>";
+    // Graph lama/rusak tidak ditampilkan; minta generate ulang
+    let Some(graph) = crate::graph::from_stored(r.json_graph.as_deref()) else {
+        let html = format!(
+            "<div class='container p-3'><a href='javascript:history.back()'>{}</a><h4>{}</h4><p>{}</p></div>",
+            loc.t("common.back"),
+            loc.t("msg.no_graph"),
+            loc.t("msg.no_graph_hint")
+        );
         return Ok(
-            warp::reply::with_status(warp::reply::html(html.to_string()), StatusCode::OK)
+            warp::reply::with_status(warp::reply::html(html), StatusCode::OK)
                 .into_response()
         );
-    }
+    };
 
     let page = crate::models::GraphPage {
         app: &app,
+        file_id,
         file_name: &r.nama_file,
-        graph_js: &js,
+        graph_json: serde_json::to_string(&graph).unwrap_or_default(),
+        loc,
     };
 
     Ok(askama_warp::reply(&page, "html"))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn analyze_file(
     file_id: i32,
     kind: String,
+    qs: HashMap<String, String>,
     pool: MySqlPool,
+    jobs: Jobs,
+    user: Option<String>,
+    loc: Locale,
     force: bool,
 ) -> HandlerResult {
     let (app_id,): (i64,) = sqlx::query_as("SELECT app_id FROM files WHERE id=?")
         .bind(file_id)
         .fetch_one(&pool)
         .await
-        .map_err(|_| warp::reject())?;
+        .map_err(|_| i18n::reject(loc))?;
     let back_link = format!("/apps/{app_id}");
+    let file_id = file_id as i64;
+
+    let Some(def) = crate::kinds::get(&pool, &kind).await.map_err(|_| i18n::reject(loc))? else {
+        let page = AnalysisPage {
+            title: loc.t("err.unknown"),
+            content: loc.t("err.unknown_kind"),
+            back_href: &back_link,
+            force_href: None,
+            job_id: None,
+            loc,
+        };
+        return Ok(warp::reply::html(page.render().unwrap()).into_response());
+    };
 
-    if !force {
-        if let Some(row) = sqlx::query!(
-            "SELECT analisa_fungsi, analisa_relasi_file, analisa_relasi_db FROM analysis WHERE file_id=?",
-            file_id
-        )
-        .fetch_optional(&pool)
+    let lang = crate::lang::resolve(&pool, app_id, qs.get("lang").map(String::as_str))
         .await
-        .map_err(|_| warp::reject())?
-        {
-            let cached = match kind.as_str() {
-                "fungsi" => row.analisa_fungsi,
-                "relasi_file" => row.analisa_relasi_file,
-                "relasi_db" => row.analisa_relasi_db,
-                _ => None,
-            };
-            if let Some(content) = cached {
-                let page = AnalysisPage {
-                    title: &format!("Hasil Analisa {}", kind),
-                    content: &content,
-                    back_href: &back_link,
-                    force_href: Some(&format!("/analyze/{}/{}/force", file_id, kind)),
-                };
-                return Ok(warp::reply::html(page.render().unwrap()).into_response());
+        .map_err(|_| i18n::reject(loc))?;
+    let force_href = format!("/analyze/{}/{}/force?lang={}", file_id, kind, lang);
+    let cached = if force {
+        None
+    } else {
+        crate::services::cached_analysis(&pool, file_id, &kind, lang)
+            .await
+            .map_err(|_| i18n::reject(loc))?
+    };
+
+    // Tanpa cache: jalankan sebagai job (atau sambung ke job yang sedang
+    // menganalisa file + kind + bahasa yang sama), jawaban LLM di-stream
+    // ke halaman via SSE
+    let (content, job_id) = match cached {
+        Some(content) => (content, None),
+        None => {
+            let id = crate::jobs::spawn_file(pool, jobs, app_id, file_id, kind.clone(), lang, user)
+                .await
+                .map_err(|_| i18n::reject(loc))?;
+            (String::new(), Some(id))
+        }
+    };
+
+    let page = AnalysisPage {
+        title: &loc.tf("msg.analysis_title", &[("label", &def.label)]),
+        content: &content,
+        back_href: &back_link,
+        force_href: Some(&force_href),
+        job_id,
+        loc,
+    };
+    Ok(warp::reply::html(page.render().unwrap()).into_response())
+}
+
+// ====== Analisa seluruh aplikasi (bulk) ======
+// Form: scope = all | filtered | pending, q = filter path (untuk scope filtered),
+// force = "1" untuk mengabaikan cache.
+pub async fn analyze_all(
+    app_id: i32,
+    form: HashMap<String, String>,
+    pool: MySqlPool,
+    jobs: Jobs,
+    user: Option<String>,
+    loc: Locale,
+) -> HandlerResult {
+    let scope = form.get("scope").map(String::as_str).unwrap_or("all");
+    let force = form.get("force").map(|v| v == "1").unwrap_or(false);
+
+    let q = form.get("q").map(String::as_str).unwrap_or("");
+    let lang = match crate::lang::resolve(&pool, app_id as i64, form.get("lang").map(String::as_str)).await {
+        Ok(l) => l,
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response());
+        }
+    };
+
+    // Hanya hasil dengan versi prompt lama: item (file, jenis) spesifik
+    if scope == "outdated" {
+        let items = match crate::prompts::outdated_items(&pool, app_id as i64, lang).await {
+            Ok(items) => items,
+            Err(e) => {
+                let body = json!({ "error": "db_error", "message": e.to_string() });
+                return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response());
+            }
+        };
+        match crate::budget::estimate_items(&pool, app_id as i64, &items, loc).await {
+            Ok(est) if !est.allowed => {
+                let body = json!({ "error": "budget_exceeded", "message": est.message });
+                return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::TOO_MANY_REQUESTS).into_response());
+            }
+            Ok(_) => {}
+            Err(e) => {
+                let body = json!({ "error": "db_error", "message": e.to_string() });
+                return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response());
             }
         }
+        return match crate::jobs::spawn_items(pool, jobs, app_id as i64, items, lang, user).await {
+            Ok(job_id) => {
+                let body = json!({ "job_id": job_id });
+                Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::ACCEPTED).into_response())
+            }
+            Err(e) => {
+                let body = json!({ "error": "db_error", "message": e.to_string() });
+                Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+            }
+        };
     }
 
-    // Ambil konten file dari DB; jika kosong, fallback ke filesystem
-    let row = sqlx::query!(
-        "SELECT full_path, content_file FROM files WHERE id=?",
-        file_id
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|_| warp::reject())?;
-
-    let code = if let Some(c) = row.content_file {
-        c
-    } else {
-        std::fs::read_to_string(&row.full_path).unwrap_or_default()
+    let file_ids = match crate::services::select_bulk_files(&pool, app_id as i64, scope, q, lang).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response());
+        }
     };
 
-    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or_default();
+    match crate::budget::estimate_bulk(&pool, app_id as i64, &file_ids, lang, force, loc).await {
+        Ok(est) if !est.allowed => {
+            let body = json!({ "error": "budget_exceeded", "message": est.message });
+            return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::TOO_MANY_REQUESTS).into_response());
+        }
+        Ok(_) => {}
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response());
+        }
+    }
+
+    match crate::jobs::spawn_bulk(pool, jobs, app_id as i64, file_ids, lang, force, user).await {
+        Ok(job_id) => {
+            let body = json!({ "job_id": job_id });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::ACCEPTED).into_response())
+        }
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+// GET /api/jobs/:id  ← progress job
+pub async fn api_job_status(job_id: i64, pool: MySqlPool, jobs: Jobs, loc: Locale) -> HandlerResult {
+    match crate::jobs::load_progress(&pool, &jobs, job_id).await {
+        Ok(Some(p)) => Ok(warp::reply::with_status(warp::reply::json(&p), StatusCode::OK).into_response()),
+        Ok(None) => {
+            let body = json!({ "error": "not_found", "message": loc.t("err.job_not_found") });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response())
+        }
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+// POST /api/jobs/:id/cancel
+pub async fn api_job_cancel(job_id: i64, pool: MySqlPool, jobs: Jobs, loc: Locale) -> HandlerResult {
+    match crate::jobs::cancel(&pool, &jobs, job_id).await {
+        Ok(true) => {
+            let body = json!({ "ok": true, "status": "cancelled" });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+        }
+        Ok(false) => {
+            let body = json!({ "error": "not_active", "message": loc.t("err.job_not_active") });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::CONFLICT).into_response())
+        }
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
 
-    let result = match kind.as_str() {
-        "fungsi" => crate::openai::analisa_fungsi(&api_key, &code).await,
-        "relasi_file" => crate::openai::analisa_relasi_file(&api_key, &code).await,
-        "relasi_db" => crate::openai::analisa_relasi_db(&api_key, &code).await,
-        _ => Ok("Jenis analisa tidak dikenal".to_string()),
+// POST /api/jobs/:id/resume
+pub async fn api_job_resume(job_id: i64, pool: MySqlPool, jobs: Jobs, loc: Locale) -> HandlerResult {
+    match crate::jobs::resume(&pool, &jobs, job_id).await {
+        Ok(true) => {
+            let body = json!({ "ok": true, "job_id": job_id });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+        }
+        Ok(false) => {
+            let body = json!({ "error": "not_resumable", "message": loc.t("err.job_not_resumable") });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::CONFLICT).into_response())
+        }
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
     }
-    .map_err(|_| warp::reject())?;
+}
 
-    // Simpan/update hasil analisa
-    let col = match kind.as_str() {
-        "fungsi" => "analisa_fungsi",
-        "relasi_file" => "analisa_relasi_file",
-        "relasi_db" => "analisa_relasi_db",
-        _ => "analisa_fungsi",
+// GET /jobs/:id/events  ← stream SSE: progress, file selesai, token LLM
+pub async fn job_events(job_id: i64, pool: MySqlPool, jobs: Jobs, loc: Locale) -> HandlerResult {
+    let (progress, rx) = match jobs.subscribe(job_id) {
+        Some(sub) => sub,
+        None => {
+            // Job tidak aktif di proses ini: kirim snapshot dari DB saja
+            let Some(p) = crate::jobs::load_progress(&pool, &jobs, job_id)
+                .await
+                .map_err(|_| i18n::reject(loc))?
+            else {
+                let body = json!({ "error": "not_found", "message": loc.t("err.job_not_found") });
+                return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response());
+            };
+            (p, tokio::sync::broadcast::channel(1).1)
+        }
     };
-    let q = format!(
-        "INSERT INTO analysis (file_id, {col}) VALUES (?, ?)
-         ON DUPLICATE KEY UPDATE {col}=VALUES({col}), created_at=CURRENT_TIMESTAMP"
-    );
-    sqlx::query(&q)
-        .bind(file_id)
-        .bind(&result)
-        .execute(&pool)
+
+    let events = crate::jobs::event_stream(progress, rx).map(|ev| {
+        warp::sse::Event::default()
+            .event(ev.name())
+            .json_data(&ev)
+    });
+    Ok(warp::sse::reply(warp::sse::keep_alive().stream(events)).into_response())
+}
+
+// ====== Pemakaian & biaya LLM ======
+// Query: days (default 30), app_id (opsional)
+fn usage_params(qs: &HashMap<String, String>) -> (i64, Option<i64>) {
+    let days = qs
+        .get("days")
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|d| *d > 0)
+        .unwrap_or(30);
+    let app_id = qs.get("app_id").and_then(|v| v.parse::<i64>().ok());
+    (days, app_id)
+}
+
+// GET /usage  ← dashboard
+pub async fn usage_page(qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let (days, app_id) = usage_params(&qs);
+    let report = match crate::usage::report(&pool, days, app_id).await {
+        Ok(r) => r,
+        Err(e) => {
+            let html = format!("<h3>DB error</h3><pre>{e}</pre><p>{}</p>", loc.t("err.db_usage"));
+            return Ok(warp::reply::html(html).into_response());
+        }
+    };
+    let budgets = crate::budget::list(&pool).await.map_err(|_| i18n::reject(loc))?;
+    let apps: Vec<AppRow> =
+        sqlx::query_as("SELECT id, nama_aplikasi, created_at FROM applications ORDER BY nama_aplikasi")
+            .fetch_all(&pool)
+            .await
+            .map_err(|_| i18n::reject(loc))?;
+    let page = UsagePage { report: &report, budgets: &budgets, apps: &apps, loc };
+    Ok(askama_warp::reply(&page, "html"))
+}
+
+// POST /usage/budgets  ← simpan / hapus budget (form: app_id, period, token_limit, cost_limit_usd)
+pub async fn save_budget(form: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let field = |k: &str| form.get(k).map(|v| v.trim()).filter(|v| !v.is_empty());
+    let app_id = field("app_id").and_then(|v| v.parse::<i64>().ok());
+    let period = field("period").unwrap_or("monthly");
+    let token_limit = field("token_limit").and_then(|v| v.parse::<i64>().ok());
+    let cost_limit_usd = field("cost_limit_usd").and_then(|v| v.parse::<f64>().ok());
+
+    crate::budget::save(&pool, app_id, period, token_limit, cost_limit_usd)
         .await
-        .map_err(|_| warp::reject())?;
+        .map_err(|_| i18n::reject(loc))?;
+    let res = warp::http::Response::builder()
+        .status(StatusCode::SEE_OTHER)
+        .header("Location", "/usage")
+        .body(warp::hyper::Body::empty())
+        .unwrap();
+    Ok(res)
+}
 
-    let page = AnalysisPage {
-        title: &format!("Hasil Analisa {}", kind),
-        content: &result,
-        back_href: &back_link,
-        force_href: Some(&format!("/analyze/{}/{}/force", file_id, kind)),
+// GET /api/apps/:id/estimate?kind=bulk|summary&scope=&q=&force=&lang=  ← perkiraan token & biaya
+pub async fn api_estimate(app_id: i32, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let lang = match crate::lang::resolve(&pool, app_id as i64, qs.get("lang").map(String::as_str)).await {
+        Ok(l) => l,
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response());
+        }
     };
-    Ok(warp::reply::html(page.render().unwrap()).into_response())
+    let estimate = match qs.get("kind").map(String::as_str) {
+        Some("summary") => crate::budget::estimate_summary(&pool, app_id as i64, loc).await,
+        _ if qs.get("scope").map(String::as_str) == Some("outdated") => {
+            match crate::prompts::outdated_items(&pool, app_id as i64, lang).await {
+                Ok(items) => crate::budget::estimate_items(&pool, app_id as i64, &items, loc).await,
+                Err(e) => Err(e),
+            }
+        }
+        _ => {
+            let scope = qs.get("scope").map(String::as_str).unwrap_or("all");
+            let q = qs.get("q").map(String::as_str).unwrap_or("");
+            let force = qs.get("force").map(|v| v == "1").unwrap_or(false);
+            match crate::services::select_bulk_files(&pool, app_id as i64, scope, q, lang).await {
+                Ok(ids) => crate::budget::estimate_bulk(&pool, app_id as i64, &ids, lang, force, loc).await,
+                Err(e) => Err(e),
+            }
+        }
+    };
+    match estimate {
+        Ok(e) => Ok(warp::reply::with_status(warp::reply::json(&e), StatusCode::OK).into_response()),
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+// GET /api/usage  ← JSON total per hari / aplikasi / jenis / model / user
+pub async fn api_usage(qs: HashMap<String, String>, pool: MySqlPool) -> HandlerResult {
+    let (days, app_id) = usage_params(&qs);
+    match crate::usage::report(&pool, days, app_id).await {
+        Ok(r) => Ok(warp::reply::with_status(warp::reply::json(&r), StatusCode::OK).into_response()),
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+// ===== Admin template prompt =====
+
+// GET /admin/prompts
+pub async fn admin_prompts(pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let prompts = crate::prompts::all(&pool).await.map_err(|_| i18n::reject(loc))?;
+    let mut rows = Vec::new();
+    for p in prompts {
+        let active = crate::prompts::active(&pool, &p.name).await.map_err(|_| i18n::reject(loc))?;
+        let outdated = crate::prompts::outdated_count(&pool, &p.name, active.version)
+            .await
+            .map_err(|_| i18n::reject(loc))?;
+        rows.push(PromptListRow {
+            name: p.name,
+            label: p.label,
+            version_label: active.label(),
+            outdated,
+        });
+    }
+    let page = PromptsPage { rows: &rows, loc };
+    Ok(askama_warp::reply(&page, "html"))
+}
+
+// GET /admin/prompts/:name
+pub async fn admin_prompt_edit(name: String, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let Some(info) = crate::prompts::info(&pool, &name).await.map_err(|_| i18n::reject(loc))? else {
+        return Ok(warp::reply::with_status(
+            warp::reply::html(loc.tf("err.prompt_unknown", &[("name", &name)])),
+            StatusCode::NOT_FOUND,
+        )
+        .into_response());
+    };
+    let active = crate::prompts::active(&pool, &info.name).await.map_err(|_| i18n::reject(loc))?;
+    let history = crate::prompts::history(&pool, &info.name).await.map_err(|_| i18n::reject(loc))?;
+    let page = PromptEditPage {
+        name: &info.name,
+        label: &info.label,
+        vars: info.vars,
+        active: &active,
+        default_template: &info.default,
+        history: &history,
+        loc,
+    };
+    Ok(askama_warp::reply(&page, "html"))
+}
+
+// POST /admin/prompts/:name  ← simpan sebagai versi baru (form: template, note)
+pub async fn admin_prompt_save(
+    name: String,
+    form: HashMap<String, String>,
+    pool: MySqlPool,
+    user: Option<String>,
+    loc: Locale,
+) -> HandlerResult {
+    let template = form.get("template").map(|t| t.replace("\r\n", "\n")).unwrap_or_default();
+    if template.trim().is_empty() {
+        let body = json!({ "error": "bad_request", "message": loc.t("err.template_empty") });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_REQUEST).into_response());
+    }
+    let note = form.get("note").map(|n| n.trim()).filter(|n| !n.is_empty());
+    if let Err(e) = crate::prompts::save(&pool, &name, &template, note, user.as_deref()).await {
+        let body = json!({ "error": "bad_request", "message": e.to_string() });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_REQUEST).into_response());
+    }
+    let res = warp::http::Response::builder()
+        .status(StatusCode::SEE_OTHER)
+        .header("Location", format!("/admin/prompts/{name}"))
+        .body(warp::hyper::Body::empty())
+        .unwrap();
+    Ok(res)
+}
+
+// POST /admin/prompts/:name/preview  ← render template dengan contoh data (form: template, file_id | app_id)
+pub async fn admin_prompt_preview(
+    name: String,
+    form: HashMap<String, String>,
+    pool: MySqlPool,
+    loc: Locale,
+) -> HandlerResult {
+    if !matches!(crate::prompts::info(&pool, &name).await, Ok(Some(_))) {
+        let body = json!({ "error": "not_found", "message": loc.tf("err.prompt_unknown", &[("name", &name)]) });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response());
+    }
+    let template = form.get("template").cloned().unwrap_or_default();
+    let id = |k: &str| form.get(k).and_then(|v| v.trim().parse::<i64>().ok());
+
+    let vars: Vec<(&str, String)> = if name == "chat" {
+        let question = form
+            .get("question")
+            .map(|q| q.trim().to_string())
+            .filter(|q| !q.is_empty())
+            .unwrap_or_else(|| loc.t("chat.sample_question").to_string());
+        let sumber = match id("app_id") {
+            Some(app_id) => match crate::chat::retrieve(&pool, app_id, &question, crate::lang::default_lang(), None).await {
+                Ok(sources) => crate::chat::sources_block(&sources),
+                Err(e) => {
+                    let body = json!({ "error": "db_error", "message": e.to_string() });
+                    return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response());
+                }
+            },
+            None => "[1] src/auth.rs (baris 1-3)\n```\npub fn login(user: &str) -> bool {\n```\n".to_string(),
+        };
+        vec![
+            ("pertanyaan", question),
+            ("sumber", sumber),
+            ("riwayat", "(belum ada)".to_string()),
+            ("app", "contoh-aplikasi".to_string()),
+        ]
+    } else if name == "summary" {
+        match id("app_id") {
+            Some(app_id) => {
+                let payload = match crate::services::summary_payload(&pool, app_id).await {
+                    Ok(p) => p,
+                    Err(e) => {
+                        let body = json!({ "error": "db_error", "message": e.to_string() });
+                        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response());
+                    }
+                };
+                let app: Option<(String,)> = sqlx::query_as("SELECT nama_aplikasi FROM applications WHERE id=?")
+                    .bind(app_id)
+                    .fetch_optional(&pool)
+                    .await
+                    .map_err(|_| i18n::reject(loc))?;
+                vec![("payload", payload), ("app", app.map(|(n,)| n).unwrap_or_default())]
+            }
+            None => vec![
+                ("payload", "- src/main.rs | lines=None\ncontent:\nfn main() {}\n".to_string()),
+                ("app", "contoh-aplikasi".to_string()),
+            ],
+        }
+    } else {
+        match id("file_id") {
+            Some(file_id) => match crate::services::load_file_source(&pool, file_id).await {
+                Ok(src) => {
+                    let lang = crate::lang::default_lang();
+                    let konteks = crate::context::related(&pool, file_id, src.app_id, lang).await.unwrap_or_default();
+                    vec![("kode", src.code), ("path", src.path), ("konteks", konteks)]
+                }
+                Err(_) => {
+                    let body = json!({
+                        "error": "not_found",
+                        "message": loc.tf("err.file_id_not_found", &[("id", &file_id.to_string())])
+                    });
+                    return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response());
+                }
+            },
+            None => vec![
+                ("kode", "fn main() {\n    println!(\"halo\");\n}\n".to_string()),
+                ("path", "src/main.rs".to_string()),
+                ("konteks", "### src/db.rs (diimpor oleh file ini)\npub async fn init_pool() -> MySqlPool".to_string()),
+            ],
+        }
+    };
+
+    let mut vars: Vec<(&str, &str)> = vars.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    vars.push(("bahasa", crate::lang::prompt_name(crate::lang::default_lang())));
+    let prompt = crate::prompts::render(&template, &vars);
+    let konteks = vars.iter().find(|(k, _)| *k == "konteks").map(|(_, v)| *v).unwrap_or("");
+    let prompt = crate::context::apply(&template, prompt, konteks, crate::lang::default_lang());
+    let body = json!({ "prompt": prompt, "tokens": crate::usage::estimate_tokens(&prompt) });
+    Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+}
+
+// ===== Admin jenis analisa =====
+
+async fn render_kinds(pool: &MySqlPool, error: Option<&str>, status: StatusCode, loc: Locale) -> HandlerResult {
+    let kinds = crate::kinds::list(pool, false).await.map_err(|_| i18n::reject(loc))?;
+    let page = KindsPage {
+        kinds: &kinds,
+        formats: &crate::kinds::OUTPUT_FORMATS,
+        badges: &crate::kinds::BADGES,
+        error,
+        loc,
+    };
+    let html = page.render().map_err(|_| i18n::reject(loc))?;
+    Ok(warp::reply::with_status(warp::reply::html(html), status).into_response())
+}
+
+// GET /admin/kinds
+pub async fn admin_kinds(pool: MySqlPool, loc: Locale) -> HandlerResult {
+    render_kinds(&pool, None, StatusCode::OK, loc).await
+}
+
+// POST /admin/kinds  ← tambah / ubah jenis (form: name, label, prompt, output_format, json_schema, badge, sort_order, enabled)
+pub async fn admin_kind_save(form: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let field = |k: &str| form.get(k).map(|v| v.trim()).unwrap_or("");
+    let f = crate::kinds::KindForm {
+        name: field("name"),
+        label: field("label"),
+        prompt: form.get("prompt").map(String::as_str),
+        output_format: field("output_format"),
+        json_schema: form.get("json_schema").map(String::as_str),
+        badge: field("badge"),
+        sort_order: field("sort_order").parse().unwrap_or(100),
+        enabled: form.get("enabled").map(|v| v == "1").unwrap_or(false),
+    };
+    if let Err(e) = crate::kinds::save(&pool, f).await {
+        let message = match e.downcast_ref::<crate::kinds::InvalidKind>() {
+            Some(invalid) => invalid.message(loc),
+            None => e.to_string(),
+        };
+        return render_kinds(&pool, Some(&message), StatusCode::BAD_REQUEST, loc).await;
+    }
+    let res = warp::http::Response::builder()
+        .status(StatusCode::SEE_OTHER)
+        .header("Location", "/admin/kinds")
+        .body(warp::hyper::Body::empty())
+        .unwrap();
+    Ok(res)
+}
+
+fn chat_view(app_id: i64, message: crate::chat::Message) -> ChatMessageView {
+    let segments = message.segments(app_id);
+    let sources = message.sources.iter().map(|s| (s.clone(), s.href(app_id))).collect();
+    ChatMessageView { message, segments, sources }
+}
+
+// GET /apps/:id/chat?c=&lang=  ← halaman chat (c = id percakapan)
+pub async fn chat_page(app_id: i32, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let app: Option<AppRow> = sqlx::query_as(
+        "SELECT id, nama_aplikasi, created_at FROM applications WHERE id=?",
+    )
+    .bind(app_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| i18n::reject(loc))?;
+    let Some(app) = app else {
+        let html = loc.tf("err.app_not_found", &[("id", &app_id.to_string())]);
+        return Ok(warp::reply::with_status(warp::reply::html(html), StatusCode::NOT_FOUND).into_response());
+    };
+
+    let conversations = crate::chat::conversations(&pool, app.id).await.map_err(|_| i18n::reject(loc))?;
+    let current = match qs.get("c").and_then(|v| v.parse::<i64>().ok()) {
+        Some(id) => crate::chat::conversation(&pool, app.id, id).await.map_err(|_| i18n::reject(loc))?,
+        None => None,
+    };
+    let lang = match (&current, crate::lang::normalize(qs.get("lang").map(String::as_str))) {
+        (_, Some(l)) => l,
+        (Some(c), None) => crate::lang::normalize(Some(&c.lang)).unwrap_or_else(crate::lang::default_lang),
+        (None, None) => crate::lang::resolve(&pool, app.id, None).await.map_err(|_| i18n::reject(loc))?,
+    };
+    let messages: Vec<ChatMessageView> = match &current {
+        Some(c) => crate::chat::messages(&pool, c.id)
+            .await
+            .map_err(|_| i18n::reject(loc))?
+            .into_iter()
+            .map(|m| chat_view(app.id, m))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let page = ChatPage {
+        app: &app,
+        conversations: &conversations,
+        current: current.map(|c| c.id),
+        messages: &messages,
+        lang,
+        languages: &crate::lang::LANGUAGES,
+        loc,
+    };
+    Ok(askama_warp::reply(&page, "html"))
+}
+
+// POST /api/apps/:id/chat  ← form: question, conversation_id (opsional), lang
+pub async fn api_chat_ask(
+    app_id: i32,
+    form: HashMap<String, String>,
+    pool: MySqlPool,
+    user: Option<String>,
+    loc: Locale,
+) -> HandlerResult {
+    let exists: Option<(i64,)> = sqlx::query_as("SELECT id FROM applications WHERE id=?")
+        .bind(app_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+    if exists.is_none() {
+        let body = json!({ "error": "not_found", "message": loc.tf("err.app_not_found", &[("id", &app_id.to_string())]) });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response());
+    }
+    let question = form.get("question").map(String::as_str).unwrap_or("");
+    let conversation_id = form.get("conversation_id").and_then(|v| v.parse::<i64>().ok());
+    let lang = match crate::lang::resolve(&pool, app_id as i64, form.get("lang").map(String::as_str)).await {
+        Ok(l) => l,
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response());
+        }
+    };
+    let q = crate::chat::Question {
+        app_id: app_id as i64,
+        conversation_id,
+        question,
+        lang,
+        user: user.as_deref(),
+    };
+    match crate::chat::ask(&pool, q).await {
+        Ok(answer) => {
+            let view = chat_view(app_id as i64, answer.message);
+            let sources: Vec<_> = view
+                .sources
+                .iter()
+                .map(|(s, href)| json!({ "n": s.n, "file_id": s.file_id, "path": s.path, "line_start": s.line_start, "line_end": s.line_end, "href": href }))
+                .collect();
+            let body = json!({
+                "conversation_id": answer.conversation_id,
+                "message": view.message,
+                "segments": view.segments,
+                "sources": sources,
+            });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+        }
+        Err(e) => {
+            if let Some(invalid) = e.downcast_ref::<crate::chat::InvalidQuestion>() {
+                let (error, status) = match invalid {
+                    crate::chat::InvalidQuestion::Empty => ("bad_request", StatusCode::BAD_REQUEST),
+                    crate::chat::InvalidQuestion::ConversationNotFound(_) => ("not_found", StatusCode::NOT_FOUND),
+                };
+                let body = json!({ "error": error, "message": invalid.message(loc) });
+                return Ok(warp::reply::with_status(warp::reply::json(&body), status).into_response());
+            }
+            if let Some(message) = crate::budget::exceeded_message(&e, loc) {
+                let body = json!({ "error": "budget_exceeded", "message": message });
+                return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::TOO_MANY_REQUESTS).into_response());
+            }
+            let body = json!({ "error": "llm_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_GATEWAY).into_response())
+        }
+    }
+}
+
+// GET /api/apps/:id/chat/:conversation_id  ← riwayat percakapan (JSON)
+pub async fn api_chat_history(app_id: i32, conversation_id: i64, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let res = async {
+        let Some(c) = crate::chat::conversation(&pool, app_id as i64, conversation_id).await? else {
+            return Ok(None);
+        };
+        let messages = crate::chat::messages(&pool, c.id).await?;
+        Ok::<_, anyhow::Error>(Some((c, messages)))
+    }
+    .await;
+    match res {
+        Ok(Some((conversation, messages))) => {
+            let body = json!({ "conversation": conversation, "messages": messages });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+        }
+        Ok(None) => {
+            let body = json!({ "error": "not_found", "message": loc.tf("err.conversation_not_found", &[("id", &conversation_id.to_string())]) });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response())
+        }
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+// GET /apps/:id/search?q=&regex=1&case=1&language=&folder=&scope=  ← pencarian teks penuh
+pub async fn app_search(app_id: i32, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let app: Option<AppRow> = sqlx::query_as(
+        "SELECT id, nama_aplikasi, created_at FROM applications WHERE id=?",
+    )
+    .bind(app_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| i18n::reject(loc))?;
+    let Some(app) = app else {
+        let html = loc.tf("err.app_not_found", &[("id", &app_id.to_string())]);
+        return Ok(warp::reply::with_status(warp::reply::html(html), StatusCode::NOT_FOUND).into_response());
+    };
+    search_page(Some(&app), qs, pool, loc).await
+}
+
+// GET /search?q=...  ← pencarian lintas semua aplikasi, dikelompokkan per aplikasi
+pub async fn global_search(qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    search_page(None, qs, pool, loc).await
+}
+
+async fn search_page(app: Option<&AppRow>, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let app_id = app.map(|a| a.id);
+    let opts = crate::fulltext::Options::from_query(&qs);
+    let languages = crate::fulltext::languages(&pool, app_id).await.map_err(|_| i18n::reject(loc))?;
+    let mut error = None;
+    let mut report = None;
+    let mut groups = Vec::new();
+    if !opts.query.is_empty() {
+        match opts.matcher() {
+            Ok(_) => {
+                let mut r = crate::fulltext::search(&pool, app_id, &opts)
+                    .await
+                    .map_err(|_| i18n::reject(loc))?;
+                if app.is_none() {
+                    groups = crate::fulltext::group_by_app(&pool, std::mem::take(&mut r.files))
+                        .await
+                        .map_err(|_| i18n::reject(loc))?;
+                }
+                report = Some(r);
+            }
+            Err(e) => error = Some(loc.tf("search.bad_regex", &[("error", &e)])),
+        }
+    }
+    let page = SearchPage {
+        app,
+        opts: &opts,
+        report: report.as_ref(),
+        groups: &groups,
+        error: error.as_deref(),
+        languages: &languages,
+        loc,
+    };
+    Ok(askama_warp::reply(&page, "html"))
+}
+
+// GET /api/apps/:id/search?...  ← sama seperti /apps/:id/search, dalam JSON
+pub async fn api_app_search(app_id: i32, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    search_json(Some(app_id as i64), qs, pool, loc).await
+}
+
+// GET /api/search?...  ← sama seperti /search, dalam JSON (dikelompokkan per aplikasi)
+pub async fn api_global_search(qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    search_json(None, qs, pool, loc).await
+}
+
+async fn search_json(app_id: Option<i64>, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let opts = crate::fulltext::Options::from_query(&qs);
+    if opts.query.is_empty() {
+        let body = json!({ "error": "bad_request", "message": loc.t("err.empty_query") });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_REQUEST).into_response());
+    }
+    if let Err(e) = opts.matcher() {
+        let body = json!({ "error": "bad_request", "message": loc.tf("search.bad_regex", &[("error", &e)]) });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_REQUEST).into_response());
+    }
+    let res = async {
+        let mut report = crate::fulltext::search(&pool, app_id, &opts).await?;
+        let body = match app_id {
+            Some(_) => json!({ "options": opts, "result": report }),
+            None => {
+                let groups = crate::fulltext::group_by_app(&pool, std::mem::take(&mut report.files)).await?;
+                json!({
+                    "options": opts,
+                    "total_matches": report.total_matches,
+                    "truncated": report.truncated,
+                    "apps": groups,
+                })
+            }
+        };
+        Ok::<_, anyhow::Error>(body)
+    }
+    .await;
+    match res {
+        Ok(body) => Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response()),
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+// GET /apps/:id/graph?folder=&neighbors=1&snapshot=  ← graph dependensi modul aplikasi
+pub async fn app_graph(app_id: i32, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let app: Option<AppRow> = sqlx::query_as(
+        "SELECT id, nama_aplikasi, created_at FROM applications WHERE id=?",
+    )
+    .bind(app_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| i18n::reject(loc))?;
+    let Some(app) = app else {
+        let html = loc.tf("err.app_not_found", &[("id", &app_id.to_string())]);
+        return Ok(warp::reply::with_status(warp::reply::html(html), StatusCode::NOT_FOUND).into_response());
+    };
+
+    let snapshot_id = qs.get("snapshot").and_then(|v| v.parse::<i64>().ok());
+    let Some(snapshot) = crate::depgraph::resolve(&pool, app.id, snapshot_id)
+        .await
+        .map_err(|_| i18n::reject(loc))?
+    else {
+        let html = loc.tf("err.snapshot_not_found", &[("id", &snapshot_id.unwrap_or_default().to_string())]);
+        return Ok(warp::reply::with_status(warp::reply::html(html), StatusCode::NOT_FOUND).into_response());
+    };
+    let snapshots = crate::depgraph::snapshots(&pool, app.id).await.map_err(|_| i18n::reject(loc))?;
+
+    let folder = qs.get("folder").map(|f| f.trim_matches('/')).unwrap_or("");
+    let neighbors = qs.get("neighbors").map(|v| v == "1").unwrap_or(false);
+    let graph = snapshot.graph.filter_folder(folder, neighbors);
+
+    let page = crate::models::AppGraphPage {
+        app: &app,
+        snapshot: &snapshot,
+        snapshots: &snapshots,
+        graph: &graph,
+        graph_json: serde_json::to_string(&graph).unwrap_or_default(),
+        folder,
+        neighbors,
+        loc,
+    };
+    Ok(askama_warp::reply(&page, "html"))
+}
+
+// GET /api/apps/:id/graph?folder=&neighbors=1&snapshot=&format=  ← sama seperti /apps/:id/graph, dalam JSON / unduhan
+pub async fn api_app_graph(app_id: i32, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let format = match export_format(&qs) {
+        Ok(f) => f,
+        Err(raw) => return Ok(invalid_format(&raw, loc)),
+    };
+    let snapshot_id = qs.get("snapshot").and_then(|v| v.parse::<i64>().ok());
+    let res = async {
+        let app: Option<(String,)> = sqlx::query_as("SELECT nama_aplikasi FROM applications WHERE id=?")
+            .bind(app_id)
+            .fetch_optional(&pool)
+            .await?;
+        let Some((nama_aplikasi,)) = app else {
+            return Ok(None);
+        };
+        let snapshot = crate::depgraph::resolve(&pool, app_id as i64, snapshot_id).await?;
+        Ok::<_, anyhow::Error>(snapshot.map(|s| (nama_aplikasi, s)))
+    }
+    .await;
+    match res {
+        Ok(Some((nama_aplikasi, snapshot))) => {
+            let folder = qs.get("folder").map(|f| f.trim_matches('/')).unwrap_or("");
+            let neighbors = qs.get("neighbors").map(|v| v == "1").unwrap_or(false);
+            if let Some(format) = format {
+                let graph = snapshot.graph.filter_folder(folder, neighbors);
+                let export = crate::export::from_dep_graph(&nama_aplikasi, &graph);
+                return Ok(export_reply(&export, format, &format!("{nama_aplikasi}-dependencies"), loc));
+            }
+            let body = json!({
+                "snapshot_id": snapshot.id,
+                "created_at": snapshot.created_at,
+                "graph": snapshot.graph.filter_folder(folder, neighbors),
+            });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+        }
+        Ok(None) => {
+            let message = match snapshot_id {
+                Some(id) => loc.tf("err.snapshot_not_found", &[("id", &id.to_string())]),
+                None => loc.tf("err.app_not_found", &[("id", &app_id.to_string())]),
+            };
+            let body = json!({ "error": "not_found", "message": message });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response())
+        }
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+async fn render_architecture(pool: &MySqlPool, app_id: i32, error: Option<&str>, status: StatusCode, loc: Locale) -> HandlerResult {
+    let app: Option<AppRow> = sqlx::query_as(
+        "SELECT id, nama_aplikasi, created_at FROM applications WHERE id=?",
+    )
+    .bind(app_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| i18n::reject(loc))?;
+    let Some(app) = app else {
+        let html = loc.tf("err.app_not_found", &[("id", &app_id.to_string())]);
+        return Ok(warp::reply::with_status(warp::reply::html(html), StatusCode::NOT_FOUND).into_response());
+    };
+    let report = crate::architecture::report(pool, app.id).await.map_err(|_| i18n::reject(loc))?;
+    let page = crate::models::ArchitecturePage { app: &app, report: &report, error, loc };
+    let html = page.render().map_err(|_| i18n::reject(loc))?;
+    Ok(warp::reply::with_status(warp::reply::html(html), status).into_response())
+}
+
+// GET /apps/:id/architecture  ← siklus import + pelanggaran aturan layering
+pub async fn architecture_page(app_id: i32, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    render_architecture(&pool, app_id, None, StatusCode::OK, loc).await
+}
+
+// POST /apps/:id/layer_rules  ← tambah aturan (form: source_pattern, target_pattern, kind=deny|allow, note)
+pub async fn layer_rule_add(app_id: i32, form: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let field = |k: &str| form.get(k).map(|v| v.trim()).unwrap_or("");
+    let res = crate::architecture::add_rule(
+        &pool,
+        app_id as i64,
+        field("source_pattern"),
+        field("target_pattern"),
+        field("kind"),
+        form.get("note").map(String::as_str),
+    )
+    .await;
+    if let Err(e) = res {
+        return render_architecture(&pool, app_id, Some(&e.to_string()), StatusCode::BAD_REQUEST, loc).await;
+    }
+    let res = warp::http::Response::builder()
+        .status(StatusCode::SEE_OTHER)
+        .header("Location", format!("/apps/{app_id}/architecture"))
+        .body(warp::hyper::Body::empty())
+        .unwrap();
+    Ok(res)
+}
+
+// POST /apps/:id/layer_rules/:rule_id/delete
+pub async fn layer_rule_delete(app_id: i32, rule_id: i64, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    crate::architecture::delete_rule(&pool, app_id as i64, rule_id)
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+    let res = warp::http::Response::builder()
+        .status(StatusCode::SEE_OTHER)
+        .header("Location", format!("/apps/{app_id}/architecture"))
+        .body(warp::hyper::Body::empty())
+        .unwrap();
+    Ok(res)
+}
+
+// GET /api/apps/:id/architecture  ← aturan, siklus, pelanggaran dan daftar temuan (JSON)
+pub async fn api_architecture(app_id: i32, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let exists: Option<(i64,)> = sqlx::query_as("SELECT id FROM applications WHERE id=?")
+        .bind(app_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+    if exists.is_none() {
+        let body = json!({ "error": "not_found", "message": loc.tf("err.app_not_found", &[("id", &app_id.to_string())]) });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response());
+    }
+    match crate::architecture::report(&pool, app_id as i64).await {
+        Ok(report) => {
+            let body = json!({
+                "snapshot_id": report.snapshot_id,
+                "rules": report.rules,
+                "cycles": report.cycles,
+                "violations": report.violations,
+                "findings": report.findings(),
+            });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+        }
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+// GET /apps/:id/schema  ← skema database (diagram ER) + matriks tabel × file
+pub async fn schema_page(app_id: i32, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let app: Option<AppRow> = sqlx::query_as(
+        "SELECT id, nama_aplikasi, created_at FROM applications WHERE id=?",
+    )
+    .bind(app_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| i18n::reject(loc))?;
+    let Some(app) = app else {
+        let html = loc.tf("err.app_not_found", &[("id", &app_id.to_string())]);
+        return Ok(warp::reply::with_status(warp::reply::html(html), StatusCode::NOT_FOUND).into_response());
+    };
+    let schema = crate::dbschema::build(&pool, app.id).await.map_err(|_| i18n::reject(loc))?;
+    let schema_json = serde_json::to_string(&schema).map_err(|_| i18n::reject(loc))?;
+    let page = crate::models::SchemaPage { app: &app, schema: &schema, schema_json, loc };
+    let html = page.render().map_err(|_| i18n::reject(loc))?;
+    Ok(warp::reply::html(html).into_response())
+}
+
+// GET /api/apps/:id/schema?format=  ← tabel, kolom, key, foreign key dan pemakaian per file (JSON / unduhan)
+pub async fn api_app_schema(app_id: i32, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let format = match export_format(&qs) {
+        Ok(f) => f,
+        Err(raw) => return Ok(invalid_format(&raw, loc)),
+    };
+    let app: Option<(String,)> = sqlx::query_as("SELECT nama_aplikasi FROM applications WHERE id=?")
+        .bind(app_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+    let Some((nama_aplikasi,)) = app else {
+        let body = json!({ "error": "not_found", "message": loc.tf("err.app_not_found", &[("id", &app_id.to_string())]) });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response());
+    };
+    match crate::dbschema::build(&pool, app_id as i64).await {
+        Ok(schema) => match format {
+            Some(format) => {
+                let export = crate::export::from_schema(&nama_aplikasi, &schema);
+                Ok(export_reply(&export, format, &format!("{nama_aplikasi}-schema"), loc))
+            }
+            None => Ok(warp::reply::with_status(warp::reply::json(&schema), StatusCode::OK).into_response()),
+        },
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+/// Parameter call graph: `symbol`, `depth` (1..=MAX_DEPTH), `dir` (both|callees|callers).
+fn call_params(qs: &HashMap<String, String>) -> (Option<i64>, usize, crate::callgraph::Direction) {
+    let symbol = qs.get("symbol").and_then(|v| v.parse::<i64>().ok());
+    let depth = qs
+        .get("depth")
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(crate::callgraph::DEFAULT_DEPTH)
+        .clamp(1, crate::callgraph::MAX_DEPTH);
+    let dir = crate::callgraph::Direction::parse(qs.get("dir").map(String::as_str).unwrap_or(""));
+    (symbol, depth, dir)
+}
+
+// GET /apps/:id/calls?q=&symbol=&depth=&dir=  ← caller/callee satu fungsi + call graph berakar di fungsi itu
+pub async fn calls_page(app_id: i32, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let app: Option<AppRow> = sqlx::query_as(
+        "SELECT id, nama_aplikasi, created_at FROM applications WHERE id=?",
+    )
+    .bind(app_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| i18n::reject(loc))?;
+    let Some(app) = app else {
+        let html = loc.tf("err.app_not_found", &[("id", &app_id.to_string())]);
+        return Ok(warp::reply::with_status(warp::reply::html(html), StatusCode::NOT_FOUND).into_response());
+    };
+    let index = crate::callgraph::load(&pool, app.id).await.map_err(|_| i18n::reject(loc))?;
+    let (symbol, depth, dir) = call_params(&qs);
+    let focus = match symbol {
+        Some(id) => match index.focus(id).filter(|f| f.symbol.callable()) {
+            Some(f) => Some(f),
+            None => {
+                let html = loc.tf("err.symbol_not_found", &[("id", &id.to_string())]);
+                return Ok(warp::reply::with_status(warp::reply::html(html), StatusCode::NOT_FOUND).into_response());
+            }
+        },
+        None => None,
+    };
+    let graph = focus.as_ref().map(|f| index.graph(f.symbol.id, depth, dir));
+    let q = qs.get("q").map(|v| v.trim()).unwrap_or("");
+
+    let page = crate::models::CallsPage {
+        app: &app,
+        index: &index,
+        q,
+        functions: if focus.is_none() { index.functions(q, 300) } else { Vec::new() },
+        graph_json: graph.as_ref().map(|g| serde_json::to_string(g).unwrap_or_default()).unwrap_or_default(),
+        graph: graph.as_ref(),
+        focus: focus.as_ref(),
+        depth,
+        dir,
+        loc,
+    };
+    let html = page.render().map_err(|_| i18n::reject(loc))?;
+    Ok(warp::reply::html(html).into_response())
+}
+
+// GET /api/apps/:id/calls?symbol=&depth=&dir=&q=&format=  ← daftar fungsi, atau caller/callee + graph (JSON / unduhan)
+pub async fn api_app_calls(app_id: i32, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let format = match export_format(&qs) {
+        Ok(f) => f,
+        Err(raw) => return Ok(invalid_format(&raw, loc)),
+    };
+    let app: Option<(String,)> = sqlx::query_as("SELECT nama_aplikasi FROM applications WHERE id=?")
+        .bind(app_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+    let Some((nama_aplikasi,)) = app else {
+        let body = json!({ "error": "not_found", "message": loc.tf("err.app_not_found", &[("id", &app_id.to_string())]) });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response());
+    };
+    let index = match crate::callgraph::load(&pool, app_id as i64).await {
+        Ok(index) => index,
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response());
+        }
+    };
+    let (symbol, depth, dir) = call_params(&qs);
+    let Some(id) = symbol else {
+        if format.is_some() {
+            let body = json!({ "error": "symbol_required", "message": loc.t("err.symbol_required") });
+            return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_REQUEST).into_response());
+        }
+        let q = qs.get("q").map(|v| v.trim()).unwrap_or("");
+        let (resolved, calls) = index.call_count();
+        let body = json!({
+            "functions": index.functions(q, usize::MAX),
+            "calls": calls,
+            "resolved_calls": resolved,
+        });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response());
+    };
+    let Some(focus) = index.focus(id).filter(|f| f.symbol.callable()) else {
+        let body = json!({ "error": "not_found", "message": loc.tf("err.symbol_not_found", &[("id", &id.to_string())]) });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response());
+    };
+    let graph = index.graph(id, depth, dir);
+    if let Some(format) = format {
+        let export = crate::export::from_call_graph(&focus.symbol.qualified, &graph);
+        return Ok(export_reply(&export, format, &format!("{nama_aplikasi}-calls-{}", focus.symbol.name), loc));
+    }
+    let body = json!({
+        "symbol": focus.symbol,
+        "callers": focus.callers,
+        "callees": focus.callees,
+        "unresolved": focus.unresolved,
+        "depth": depth,
+        "dir": dir,
+        "graph": graph,
+    });
+    Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+}
+
+// GET /apps/:id/symbols?q=&kind=  ← cari definisi (fungsi, method, class, struct, …) di seluruh aplikasi
+pub async fn symbols_page(app_id: i32, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let app: Option<AppRow> = sqlx::query_as(
+        "SELECT id, nama_aplikasi, created_at FROM applications WHERE id=?",
+    )
+    .bind(app_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| i18n::reject(loc))?;
+    let Some(app) = app else {
+        let html = loc.tf("err.app_not_found", &[("id", &app_id.to_string())]);
+        return Ok(warp::reply::with_status(warp::reply::html(html), StatusCode::NOT_FOUND).into_response());
+    };
+    let (q, kind, _) = crate::symbols::params(&qs);
+    let (symbols, total) = crate::symbols::search(&pool, app.id, q, kind, crate::symbols::SEARCH_LIMIT)
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+    let page = crate::models::SymbolsPage { app: &app, q, kind: kind.unwrap_or(""), symbols: &symbols, total, loc };
+    let html = page.render().map_err(|_| i18n::reject(loc))?;
+    Ok(warp::reply::html(html).into_response())
+}
+
+// GET /api/apps/:id/symbols?q=&kind=&limit=  ← sama dalam JSON
+pub async fn api_app_symbols(app_id: i32, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let exists: Option<(i64,)> = sqlx::query_as("SELECT id FROM applications WHERE id=?")
+        .bind(app_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+    if exists.is_none() {
+        let body = json!({ "error": "not_found", "message": loc.tf("err.app_not_found", &[("id", &app_id.to_string())]) });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response());
+    }
+    let (q, kind, limit) = crate::symbols::params(&qs);
+    match crate::symbols::search(&pool, app_id as i64, q, kind, limit).await {
+        Ok((symbols, total)) => {
+            let body = json!({ "q": q, "kind": kind, "total": total, "symbols": symbols });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+        }
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+// GET /files/:id/symbols  ← outline definisi satu file
+pub async fn file_symbols_page(file_id: i32, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let row: Option<(String, i64, String, Option<chrono::DateTime<Utc>>)> = sqlx::query_as(
+        "SELECT f.full_path, a.id, a.nama_aplikasi, a.created_at FROM files f JOIN applications a ON a.id = f.app_id WHERE f.id=?",
+    )
+    .bind(file_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| i18n::reject(loc))?;
+    let Some((path, app_id, nama_aplikasi, created_at)) = row else {
+        return Ok(
+            warp::reply::with_status(warp::reply::html(loc.t("err.file_not_found").to_string()), StatusCode::NOT_FOUND)
+                .into_response(),
+        );
+    };
+    let app = AppRow { id: app_id, nama_aplikasi, created_at: created_at.unwrap_or(Utc::now()) };
+    let symbols = crate::symbols::outline(&pool, file_id as i64)
+        .await
+        .map_err(|_| i18n::reject(loc))?
+        .unwrap_or_default();
+    let page = crate::models::OutlinePage {
+        app: &app,
+        file_id: file_id as i64,
+        path: &path,
+        file_href: crate::services::file_href(app.id, file_id as i64, &path),
+        symbols: &symbols,
+        loc,
+    };
+    let html = page.render().map_err(|_| i18n::reject(loc))?;
+    Ok(warp::reply::html(html).into_response())
+}
+
+// GET /api/files/:id/symbols  ← outline definisi satu file (JSON)
+pub async fn api_file_symbols(file_id: i32, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    match crate::symbols::outline(&pool, file_id as i64).await {
+        Ok(Some(symbols)) => {
+            let body = json!({ "file_id": file_id, "symbols": symbols });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+        }
+        Ok(None) => {
+            let body = json!({ "error": "not_found", "message": loc.t("err.file_not_found") });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response())
+        }
+        Err(e) => {
+            let body = json!({ "error": "db_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+// GET /files/:id?lang=  ← isi file dengan syntax highlighting, nomor baris (#L10-L20), outline dan hasil analisa
+pub async fn file_page(file_id: i32, qs: HashMap<String, String>, pool: MySqlPool, loc: Locale) -> HandlerResult {
+    let row: Option<(i64, String, Option<chrono::DateTime<Utc>>)> = sqlx::query_as(
+        "SELECT a.id, a.nama_aplikasi, a.created_at FROM files f JOIN applications a ON a.id = f.app_id WHERE f.id=?",
+    )
+    .bind(file_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| i18n::reject(loc))?;
+    let Some((app_id, nama_aplikasi, created_at)) = row else {
+        return Ok(
+            warp::reply::with_status(warp::reply::html(loc.t("err.file_not_found").to_string()), StatusCode::NOT_FOUND)
+                .into_response(),
+        );
+    };
+    let app = AppRow { id: app_id, nama_aplikasi, created_at: created_at.unwrap_or(Utc::now()) };
+    let file_id = file_id as i64;
+    let source = crate::services::load_file_source(&pool, file_id).await.map_err(|_| i18n::reject(loc))?;
+    let symbols = crate::symbols::outline(&pool, file_id)
+        .await
+        .map_err(|_| i18n::reject(loc))?
+        .unwrap_or_default();
+
+    // Hasil analisa semua jenis aktif dalam bahasa yang diminta / default aplikasi
+    let lang = crate::lang::resolve(&pool, app.id, qs.get("lang").map(String::as_str))
+        .await
+        .map_err(|_| i18n::reject(loc))?;
+    let kinds = crate::kinds::list(&pool, true).await.map_err(|_| i18n::reject(loc))?;
+    let mut results = crate::services::results_for_files(&pool, &[file_id], lang)
+        .await
+        .map_err(|_| i18n::reject(loc))?
+        .remove(&file_id)
+        .unwrap_or_default();
+    let analyses = kinds
+        .into_iter()
+        .map(|k| crate::models::FileAnalysis {
+            content: results.remove(&k.name),
+            kind: k.name,
+            label: k.label,
+            badge: k.badge,
+        })
+        .collect();
+
+    let page = crate::models::FilePage {
+        app: &app,
+        file_id,
+        path: &source.path,
+        language: crate::highlight::language(&source.path),
+        lines: if source.code.is_empty() { Vec::new() } else { crate::highlight::highlight(&source.path, &source.code) },
+        symbols: &symbols,
+        analyses,
+        lang,
+        file_href: crate::services::file_href(app.id, file_id, &source.path),
+        loc,
+    };
+    let html = page.render().map_err(|_| i18n::reject(loc))?;
+    Ok(warp::reply::html(html).into_response())
+}
+
+// GET /static/<path>?v=  ← aset tertanam; URL ber-versi di-cache setahun, lainnya divalidasi dengan ETag
+pub async fn static_asset(
+    tail: warp::path::Tail,
+    qs: HashMap<String, String>,
+    if_none_match: Option<String>,
+) -> HandlerResult {
+    let Some((bytes, hash)) = crate::assets::get(tail.as_str()) else {
+        return Err(warp::reject::not_found());
+    };
+    let etag = format!("\"{hash}\"");
+    let cache = if qs.get("v").map(String::as_str) == Some(hash) {
+        crate::assets::CACHE_IMMUTABLE
+    } else {
+        crate::assets::CACHE_SHORT
+    };
+    let fresh = if_none_match
+        .as_deref()
+        .is_some_and(|v| v.split(',').any(|t| t.trim() == etag || t.trim() == "*"));
+    let builder = warp::http::Response::builder()
+        .header("ETag", &etag)
+        .header("Cache-Control", cache);
+    let res = if fresh {
+        builder.status(StatusCode::NOT_MODIFIED).body(warp::hyper::Body::empty())
+    } else {
+        builder
+            .status(StatusCode::OK)
+            .header("Content-Type", crate::assets::content_type(tail.as_str()))
+            .body(warp::hyper::Body::from(bytes))
+    };
+    Ok(res.unwrap())
+}
+
+// GET /apps/:id/semantic_search?q=&limit=  ← file + rentang baris paling relevan (JSON)
+pub async fn api_semantic_search(
+    app_id: i32,
+    qs: HashMap<String, String>,
+    pool: MySqlPool,
+    user: Option<String>,
+    loc: Locale,
+) -> HandlerResult {
+    let q = qs.get("q").map(|q| q.trim()).unwrap_or("");
+    if q.is_empty() {
+        let body = json!({ "error": "bad_request", "message": loc.t("err.empty_query") });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_REQUEST).into_response());
+    }
+    let limit = qs.get("limit").and_then(|v| v.parse::<usize>().ok()).unwrap_or(10).clamp(1, 50);
+    match crate::semantic::search(&pool, app_id as i64, q, limit, user.as_deref()).await {
+        Ok(res) => {
+            let body = json!({
+                "query": q,
+                "backend": res.backend,
+                "model": res.model,
+                "chunks": res.chunks,
+                "files": res.files,
+            });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::OK).into_response())
+        }
+        Err(e) => {
+            if let Some(message) = crate::budget::exceeded_message(&e, loc) {
+                let body = json!({ "error": "budget_exceeded", "message": message });
+                return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::TOO_MANY_REQUESTS).into_response());
+            }
+            let body = json!({ "error": "search_error", "message": e.to_string() });
+            Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+// POST /apps/:id/lang  ← simpan bahasa output default aplikasi (form: lang, kosong = default server)
+pub async fn set_app_lang(app_id: i32, form: HashMap<String, String>, pool: MySqlPool) -> HandlerResult {
+    let lang = form.get("lang").map(String::as_str).filter(|l| !l.is_empty());
+    if let Err(e) = crate::lang::set_app_lang(&pool, app_id as i64, lang).await {
+        let body = json!({ "error": "db_error", "message": e.to_string() });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response());
+    }
+    let res = warp::http::Response::builder()
+        .status(StatusCode::SEE_OTHER)
+        .header("Location", format!("/apps/{app_id}"))
+        .body(warp::hyper::Body::empty())
+        .unwrap();
+    Ok(res)
+}
+
+// GET /locale/:code  ← simpan bahasa tampilan (cookie) lalu kembali ke halaman sebelumnya
+/// Path halaman asal dari header Referer; hanya path lokal, supaya tidak jadi open redirect.
+pub fn local_back(referer: Option<&str>) -> String {
+    referer
+        .and_then(|r| r.split_once("://").map(|(_, rest)| rest).or(Some(r)))
+        .and_then(|r| r.find('/').map(|i| &r[i..]))
+        .filter(|p| !p.starts_with("//"))
+        .unwrap_or("/")
+        .to_string()
+}
+
+pub async fn set_locale(code: String, referer: Option<String>) -> HandlerResult {
+    let Some(loc) = Locale::parse(&code) else {
+        let body = json!({
+            "error": "not_found",
+            "message": Locale::default().tf("err.unknown_locale", &[("code", &code)])
+        });
+        return Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND).into_response());
+    };
+    let res = warp::http::Response::builder()
+        .status(StatusCode::SEE_OTHER)
+        .header("Location", local_back(referer.as_deref()))
+        .header(
+            "Set-Cookie",
+            format!("{}={}; Path=/; Max-Age=31536000; SameSite=Lax", i18n::COOKIE, loc.code()),
+        )
+        .body(warp::hyper::Body::empty())
+        .unwrap();
+    Ok(res)
+}
+
+// POST /admin/login  ← form token di halaman 403 (lihat auth.rs)
+pub async fn admin_login(form: HashMap<String, String>, loc: Locale) -> HandlerResult {
+    let token = form.get("token").map(|t| t.trim()).unwrap_or("");
+    let back = local_back(form.get("back").map(String::as_str));
+    if !crate::auth::authorized(crate::auth::admin_token().as_deref(), &[Some(token)]) {
+        let page = warp::reply::html(crate::auth::forbidden_page(loc, &back));
+        return Ok(warp::reply::with_status(page, StatusCode::FORBIDDEN).into_response());
+    }
+    let res = warp::http::Response::builder()
+        .status(StatusCode::SEE_OTHER)
+        .header("Location", back)
+        .header(
+            "Set-Cookie",
+            format!("{}={token}; Path=/; HttpOnly; SameSite=Strict", crate::auth::COOKIE),
+        )
+        .body(warp::hyper::Body::empty())
+        .unwrap();
+    Ok(res)
 }
@@ -0,0 +1,378 @@
+// src/highlight.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Syntax highlighting di server untuk penampil file. Kode dipecah menjadi
+// token (komentar, string, angka, kata kunci, tipe, nama fungsi, anotasi)
+// menurut sintaks bahasa dari ekstensi file, lalu dirender sebagai HTML
+// ter-escape per baris: token yang melintasi baris (komentar blok, string
+// multi-baris) ditutup di akhir baris dan dibuka lagi di baris berikutnya,
+// jadi setiap baris bisa diberi nomor dan anchor sendiri.
+
+use crate::export::xml_escape;
+
+/// File lebih besar dari ini ditampilkan tanpa highlighting.
+const MAX_BYTES: usize = 1_000_000;
+
+struct Syntax {
+    name: &'static str,
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    /// `"""` / `'''` (Python, Java text block, Kotlin).
+    triple: bool,
+    keywords: &'static [&'static str],
+    /// Kata kunci tanpa beda huruf besar/kecil (SQL).
+    ignore_case: bool,
+    /// Identifier berhuruf besar di awal = nama tipe.
+    types: bool,
+    /// HTML/XML: nama tag setelah `<` / `</`.
+    markup: bool,
+}
+
+const C_LIKE: &[&str] = &[
+    "if", "else", "for", "while", "do", "switch", "case", "default", "break", "continue", "return", "goto", "struct",
+    "union", "enum", "typedef", "static", "const", "extern", "volatile", "inline", "sizeof", "void", "char", "short",
+    "int", "long", "float", "double", "signed", "unsigned", "bool", "true", "false", "NULL", "nullptr", "class",
+    "namespace", "template", "typename", "public", "private", "protected", "virtual", "override", "new", "delete",
+    "this", "try", "catch", "throw", "using", "auto", "constexpr", "operator", "friend",
+];
+
+const RUST: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static",
+    "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while", "union",
+];
+
+const PYTHON: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif",
+    "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal", "not", "or",
+    "pass", "raise", "return", "try", "while", "with", "yield", "self", "match", "case",
+];
+
+const JS: &[&str] = &[
+    "async", "await", "break", "case", "catch", "class", "const", "continue", "debugger", "default", "delete", "do",
+    "else", "export", "extends", "false", "finally", "for", "from", "function", "if", "import", "in", "instanceof",
+    "let", "new", "null", "of", "return", "static", "super", "switch", "this", "throw", "true", "try", "typeof",
+    "undefined", "var", "void", "while", "with", "yield", "interface", "type", "enum", "implements", "private",
+    "protected", "public", "readonly", "abstract", "declare", "namespace", "as", "keyof",
+];
+
+const PHP: &[&str] = &[
+    "abstract", "and", "array", "as", "break", "case", "catch", "class", "clone", "const", "continue", "declare",
+    "default", "do", "echo", "else", "elseif", "empty", "extends", "false", "final", "finally", "fn", "for", "foreach",
+    "function", "global", "if", "implements", "include", "include_once", "instanceof", "interface", "isset", "list",
+    "match", "namespace", "new", "null", "or", "print", "private", "protected", "public", "readonly", "require",
+    "require_once", "return", "static", "switch", "throw", "trait", "true", "try", "unset", "use", "var", "while",
+    "yield", "self", "parent",
+];
+
+const GO: &[&str] = &[
+    "break", "case", "chan", "const", "continue", "default", "defer", "else", "fallthrough", "for", "func", "go", "goto",
+    "if", "import", "interface", "map", "package", "range", "return", "select", "struct", "switch", "type", "var",
+    "true", "false", "nil", "iota",
+];
+
+const JAVA: &[&str] = &[
+    "abstract", "assert", "boolean", "break", "byte", "case", "catch", "char", "class", "const", "continue", "default",
+    "do", "double", "else", "enum", "extends", "final", "finally", "float", "for", "if", "implements", "import",
+    "instanceof", "int", "interface", "long", "native", "new", "package", "private", "protected", "public", "record",
+    "return", "short", "static", "super", "switch", "synchronized", "this", "throw", "throws", "try", "var", "void",
+    "volatile", "while", "true", "false", "null", "fun", "val", "object", "when", "is", "in", "data", "sealed",
+    "override", "companion", "suspend", "internal", "open",
+];
+
+const CSHARP: &[&str] = &[
+    "abstract", "as", "async", "await", "base", "bool", "break", "case", "catch", "class", "const", "continue",
+    "default", "delegate", "do", "else", "enum", "event", "false", "finally", "for", "foreach", "get", "if", "in",
+    "int", "interface", "internal", "is", "namespace", "new", "null", "object", "out", "override", "private",
+    "protected", "public", "readonly", "record", "ref", "return", "sealed", "set", "static", "string", "struct",
+    "switch", "this", "throw", "true", "try", "typeof", "using", "var", "virtual", "void", "while",
+];
+
+const RUBY: &[&str] = &[
+    "alias", "and", "begin", "break", "case", "class", "def", "defined?", "do", "else", "elsif", "end", "ensure",
+    "false", "for", "if", "in", "module", "next", "nil", "not", "or", "redo", "rescue", "retry", "return", "self",
+    "super", "then", "true", "undef", "unless", "until", "when", "while", "yield", "require", "attr_accessor",
+];
+
+const SHELL: &[&str] = &[
+    "if", "then", "else", "elif", "fi", "for", "in", "do", "done", "while", "until", "case", "esac", "function",
+    "return", "local", "export", "echo", "exit", "set", "unset", "source", "readonly", "shift",
+];
+
+const SQL: &[&str] = &[
+    "select", "from", "where", "and", "or", "not", "insert", "into", "values", "update", "set", "delete", "create",
+    "table", "alter", "drop", "add", "column", "index", "key", "primary", "foreign", "references", "unique", "join",
+    "left", "right", "inner", "outer", "on", "as", "group", "by", "order", "having", "limit", "offset", "distinct",
+    "null", "is", "in", "like", "between", "exists", "case", "when", "then", "else", "end", "default", "constraint",
+    "union", "all", "asc", "desc", "int", "bigint", "varchar", "text", "datetime", "timestamp", "decimal",
+    "engine", "charset", "collate", "if", "begin", "commit", "rollback", "view", "trigger", "procedure", "cascade",
+];
+
+const DATA: &[&str] = &["true", "false", "null", "yes", "no", "on", "off"];
+
+const BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+
+fn syntax(
+    name: &'static str,
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    keywords: &'static [&'static str],
+) -> Syntax {
+    Syntax { name, line_comments, block_comment, quotes, triple: false, keywords, ignore_case: false, types: true, markup: false }
+}
+
+fn syntax_of(path: &str) -> Option<Syntax> {
+    let ext = path.rsplit_once('.').map(|(_, e)| e.to_ascii_lowercase()).unwrap_or_default();
+    let s = match ext.as_str() {
+        "rs" => syntax("rust", &["//"], BLOCK, &['"'], RUST),
+        "py" | "pyw" => Syntax { triple: true, ..syntax("python", &["#"], None, &['"', '\''], PYTHON) },
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => syntax("javascript", &["//"], BLOCK, &['"', '\'', '`'], JS),
+        "php" => syntax("php", &["//", "#"], BLOCK, &['"', '\''], PHP),
+        "go" => syntax("go", &["//"], BLOCK, &['"', '\'', '`'], GO),
+        "java" | "kt" | "kts" | "scala" => Syntax { triple: true, ..syntax("java", &["//"], BLOCK, &['"', '\''], JAVA) },
+        "c" | "h" | "cpp" | "cc" | "cxx" | "hpp" | "hh" => syntax("c", &["//"], BLOCK, &['"', '\''], C_LIKE),
+        "cs" => syntax("csharp", &["//"], BLOCK, &['"', '\''], CSHARP),
+        "rb" => syntax("ruby", &["#"], None, &['"', '\''], RUBY),
+        "sh" | "bash" | "zsh" => Syntax { types: false, ..syntax("shell", &["#"], None, &['"', '\''], SHELL) },
+        "sql" => Syntax { ignore_case: true, types: false, ..syntax("sql", &["--", "#"], BLOCK, &['\'', '"', '`'], SQL) },
+        "css" | "scss" | "less" => Syntax { types: false, ..syntax("css", &[], BLOCK, &['"', '\''], &[]) },
+        "json" => Syntax { types: false, ..syntax("json", &[], None, &['"'], DATA) },
+        "yml" | "yaml" | "toml" | "ini" => Syntax { types: false, ..syntax("config", &["#"], None, &['"', '\''], DATA) },
+        // Markup: hanya kutip ganda (teks biasa sering memuat apostrof)
+        "html" | "htm" | "xml" | "svg" | "vue" => {
+            Syntax { types: false, markup: true, ..syntax("markup", &[], Some(("<!--", "-->")), &['"'], &[]) }
+        }
+        _ => return None,
+    };
+    Some(s)
+}
+
+/// Nama bahasa untuk file (None = tanpa highlighting).
+pub fn language(path: &str) -> Option<&'static str> {
+    syntax_of(path).map(|s| s.name)
+}
+
+/// Kelas token: `hl-c` komentar, `hl-s` string, `hl-n` angka, `hl-k` kata
+/// kunci, `hl-t` tipe / tag, `hl-f` nama fungsi, `hl-a` anotasi / makro.
+fn tokens(syn: &Syntax, code: &str) -> Vec<(Option<&'static str>, String)> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut out: Vec<(Option<&'static str>, String)> = Vec::new();
+    let mut plain = String::new();
+    let mut i = 0;
+    let starts = |i: usize, s: &str| s.chars().enumerate().all(|(k, c)| chars.get(i + k) == Some(&c));
+    let take = |from: usize, to: usize| chars[from..to.min(chars.len())].iter().collect::<String>();
+
+    macro_rules! push {
+        ($class:expr, $from:expr, $to:expr) => {{
+            if !plain.is_empty() {
+                out.push((None, std::mem::take(&mut plain)));
+            }
+            out.push((Some($class), take($from, $to)));
+        }};
+    }
+
+    while i < chars.len() {
+        let c = chars[i];
+        // Komentar
+        if let Some(lc) = syn.line_comments.iter().find(|lc| starts(i, lc)) {
+            // `#` PHP diikuti `[` = atribut; `#!` shebang tetap komentar
+            if !(*lc == "#" && syn.name == "php" && chars.get(i + 1) == Some(&'[')) {
+                let end = (i..chars.len()).find(|k| chars[*k] == '\n').unwrap_or(chars.len());
+                push!("hl-c", i, end);
+                i = end;
+                continue;
+            }
+        }
+        if let Some((open, close)) = syn.block_comment.filter(|(open, _)| starts(i, open)) {
+            let from = i + open.chars().count();
+            let end = (from..chars.len())
+                .find(|k| starts(*k, close))
+                .map(|k| k + close.chars().count())
+                .unwrap_or(chars.len());
+            push!("hl-c", i, end);
+            i = end;
+            continue;
+        }
+        // String
+        let rust_char = syn.name == "rust" && c == '\'' && (chars.get(i + 1) == Some(&'\\') || chars.get(i + 2) == Some(&'\''));
+        if syn.quotes.contains(&c) || rust_char {
+            let triple = syn.triple && chars.get(i + 1) == Some(&c) && chars.get(i + 2) == Some(&c);
+            let (from, close_len) = if triple { (i + 3, 3) } else { (i + 1, 1) };
+            let escapes = !(c == '`' && syn.name == "go");
+            let mut k = from;
+            let mut end = chars.len();
+            while k < chars.len() {
+                if escapes && chars[k] == '\\' {
+                    k += 2;
+                    continue;
+                }
+                if chars[k] == c && (!triple || (chars.get(k + 1) == Some(&c) && chars.get(k + 2) == Some(&c))) {
+                    end = k + close_len;
+                    break;
+                }
+                // String biasa berhenti di akhir baris (kecuali template / raw string)
+                if chars[k] == '\n' && !triple && c != '`' && syn.name != "rust" {
+                    end = k;
+                    break;
+                }
+                k += 1;
+            }
+            push!("hl-s", i, end);
+            i = end;
+            continue;
+        }
+        if c.is_ascii_digit() && !chars.get(i.wrapping_sub(1)).is_some_and(|p| p.is_alphanumeric() || *p == '_') {
+            let mut end = i + 1;
+            while end < chars.len()
+                && (chars[end].is_ascii_alphanumeric()
+                    || chars[end] == '_'
+                    || (chars[end] == '.' && chars.get(end + 1).is_some_and(|d| d.is_ascii_digit())))
+            {
+                end += 1;
+            }
+            push!("hl-n", i, end);
+            i = end;
+            continue;
+        }
+        // Anotasi / dekorator (`@Override`, `@app.route`) dan atribut Rust `#[...]`
+        if (c == '@' && chars.get(i + 1).is_some_and(|n| n.is_alphabetic())) || (syn.name == "rust" && starts(i, "#[")) {
+            let end = if c == '@' {
+                (i + 1..chars.len()).find(|k| !(chars[*k].is_alphanumeric() || matches!(chars[*k], '_' | '.'))).unwrap_or(chars.len())
+            } else {
+                (i..chars.len()).find(|k| chars[*k] == ']' || chars[*k] == '\n').map(|k| k + 1).unwrap_or(chars.len())
+            };
+            push!("hl-a", i, end);
+            i = end;
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' || c == '$' {
+            let mut end = i + 1;
+            while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_' || chars[end] == '$') {
+                end += 1;
+            }
+            let word = take(i, end);
+            let next = (end..chars.len()).map(|k| chars[k]).find(|ch| *ch != ' ' && *ch != '\t');
+            let keyword = if syn.ignore_case {
+                syn.keywords.iter().any(|k| k.eq_ignore_ascii_case(&word))
+            } else {
+                syn.keywords.contains(&word.as_str())
+            };
+            let class = if syn.markup {
+                (plain.ends_with('<') || plain.ends_with("</")).then_some("hl-t")
+            } else if keyword {
+                Some("hl-k")
+            } else if syn.name == "rust" && chars.get(end) == Some(&'!') {
+                Some("hl-a")
+            } else if next == Some('(') {
+                Some("hl-f")
+            } else if syn.types && word.starts_with(|ch: char| ch.is_uppercase()) {
+                Some("hl-t")
+            } else {
+                None
+            };
+            match class {
+                Some(cls) => push!(cls, i, end),
+                None => plain.push_str(&word),
+            }
+            i = end;
+            continue;
+        }
+        plain.push(c);
+        i += 1;
+    }
+    if !plain.is_empty() {
+        out.push((None, plain));
+    }
+    out
+}
+
+/// HTML per baris (sudah di-escape). Tanpa highlighting untuk bahasa yang
+/// tidak dikenal atau file yang terlalu besar.
+pub fn highlight(path: &str, code: &str) -> Vec<String> {
+    let code = code.replace("\r\n", "\n");
+    let syn = syntax_of(path).filter(|_| code.len() <= MAX_BYTES);
+    let toks = match &syn {
+        Some(syn) => tokens(syn, &code),
+        None => vec![(None, code.clone())],
+    };
+    let mut lines = vec![String::new()];
+    for (class, text) in toks {
+        for (n, part) in text.split('\n').enumerate() {
+            if n > 0 {
+                lines.push(String::new());
+            }
+            if part.is_empty() {
+                continue;
+            }
+            let line = lines.last_mut().expect("minimal satu baris");
+            match class {
+                Some(cls) => line.push_str(&format!("<span class=\"{cls}\">{}</span>", xml_escape(part))),
+                None => line.push_str(&xml_escape(part)),
+            }
+        }
+    }
+    // Baris kosong terakhir setelah newline penutup file tidak ditampilkan
+    if lines.len() > 1 && lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(cls: &str, text: &str) -> String {
+        format!("<span class=\"hl-{cls}\">{text}</span>")
+    }
+
+    #[test]
+    fn rust_tokens_are_classified_and_escaped() {
+        let code = "fn main() {\n    let x = \"a<b\"; // hi\n    #[derive(Debug)] Foo::new(1.5)\n}\n";
+        let lines = highlight("src/main.rs", code);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], format!("{} {}() {{", span("k", "fn"), span("f", "main")));
+        let string = span("s", "&quot;a&lt;b&quot;");
+        assert_eq!(lines[1], format!("    {} x = {string}; {}", span("k", "let"), span("c", "// hi")));
+        let attr = span("a", "#[derive(Debug)]");
+        assert_eq!(lines[2], format!("    {attr} {}::{}({})", span("t", "Foo"), span("f", "new"), span("n", "1.5")));
+    }
+
+    #[test]
+    fn multiline_tokens_reopen_on_each_line() {
+        let lines = highlight("a.js", "/* a\n b */ const s = `x\ny`;\r\n");
+        assert_eq!(lines[0], span("c", "/* a"));
+        assert_eq!(lines[1], format!("{} {} s = {}", span("c", " b */"), span("k", "const"), span("s", "`x")));
+        assert_eq!(lines[2], format!("{};", span("s", "y`")));
+        let py = highlight("a.py", "x = \"\"\"doc\nmore\"\"\"  # c\n");
+        assert_eq!(py[1], format!("{}  {}", span("s", "more&quot;&quot;&quot;"), span("c", "# c")));
+    }
+
+    #[test]
+    fn sql_keywords_ignore_case_and_markup_tags() {
+        let sql = highlight("q.sql", "select * FROM t -- x");
+        assert_eq!(sql[0], format!("{} * {} t {}", span("k", "select"), span("k", "FROM"), span("c", "-- x")));
+        let html = highlight("a.html", "<div class=\"a\">it's</div>");
+        let (tag, attr) = (span("t", "div"), span("s", "&quot;a&quot;"));
+        assert_eq!(html[0], format!("&lt;{tag} class={attr}&gt;it&apos;s&lt;/{tag}&gt;"));
+    }
+
+    #[test]
+    fn unknown_or_large_files_are_plain() {
+        assert_eq!(highlight("notes.txt", "<b>&\n\n"), ["&lt;b&gt;&amp;", ""]);
+        let big = "fn x() {}\n".repeat(MAX_BYTES / 10 + 1);
+        assert_eq!(highlight("big.rs", &big)[0], "fn x() {}");
+        assert_eq!(language("a.tsx"), Some("javascript"));
+        assert_eq!(language("README"), None);
+    }
+}
@@ -0,0 +1,646 @@
+// src/i18n.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Katalog pesan UI (bukan bahasa output LLM; itu lang.rs). Locale dipilih
+// per request: parameter ?locale=, cookie `locale` (diset lewat
+// /locale/:code), header Accept-Language, lalu env UI_LANG / "id".
+// Template memanggil `loc.t("kunci")`; teks untuk JavaScript dikirim sekali
+// sebagai objek I18N (semua kunci berawalan "js.") di base.html.
+
+use std::collections::BTreeMap;
+
+pub const COOKIE: &str = "locale";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    Id,
+    En,
+}
+
+pub const LOCALES: [Locale; 2] = [Locale::Id, Locale::En];
+
+impl Default for Locale {
+    fn default() -> Self {
+        std::env::var("UI_LANG")
+            .ok()
+            .and_then(|v| Locale::parse(&v))
+            .unwrap_or(Locale::Id)
+    }
+}
+
+impl Locale {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::Id => "id",
+            Locale::En => "en",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Locale::Id => "Bahasa Indonesia",
+            Locale::En => "English",
+        }
+    }
+
+    /// "en", "en-US", "EN_gb" → En.
+    pub fn parse(tag: &str) -> Option<Locale> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "id" | "in" => Some(Locale::Id),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    /// Pilihan locale untuk menu bahasa di base.html.
+    pub fn choices(&self) -> &'static [Locale] {
+        &LOCALES
+    }
+
+    // Untuk menandai locale aktif di template.
+    pub fn is(&self, other: &Locale) -> bool {
+        self == other
+    }
+
+    /// Teks untuk `key`; kunci yang tidak ada dikembalikan apa adanya
+    /// supaya mudah terlihat di UI.
+    pub fn t<'a>(&self, key: &'a str) -> &'a str {
+        match CATALOG.iter().find(|(k, _, _)| *k == key) {
+            Some((_, id, en)) => match self {
+                Locale::Id => id,
+                Locale::En => en,
+            },
+            None => key,
+        }
+    }
+
+    /// `t` dengan placeholder `{nama}`.
+    pub fn tf(&self, key: &str, vars: &[(&str, &str)]) -> String {
+        let mut out = self.t(key).to_string();
+        for (k, v) in vars {
+            out = out.replace(&format!("{{{k}}}"), v);
+        }
+        out
+    }
+
+    /// Semua teks "js.*" sebagai objek JSON (kunci tanpa awalan "js.").
+    pub fn js_catalog(&self) -> String {
+        let map: BTreeMap<&str, &str> = CATALOG
+            .iter()
+            .filter_map(|(k, id, en)| {
+                let k = k.strip_prefix("js.")?;
+                Some((k, if *self == Locale::Id { *id } else { *en }))
+            })
+            .collect();
+        // `</` di-escape supaya aman di dalam <script>
+        serde_json::to_string(&map).unwrap_or_else(|_| "{}".into()).replace("</", "<\\/")
+    }
+}
+
+/// Locale pertama yang didukung dari header Accept-Language (urut q).
+pub fn from_accept_language(header: &str) -> Option<Locale> {
+    let mut tags: Vec<(f32, Locale)> = header
+        .split(',')
+        .filter_map(|part| {
+            let mut it = part.split(';');
+            let locale = Locale::parse(it.next()?)?;
+            let q = it
+                .find_map(|p| p.trim().strip_prefix("q=").and_then(|q| q.parse::<f32>().ok()))
+                .unwrap_or(1.0);
+            (q > 0.0).then_some((q, locale))
+        })
+        .collect();
+    // sort stabil: urutan header dipertahankan untuk q yang sama
+    tags.sort_by(|a, b| b.0.total_cmp(&a.0));
+    tags.first().map(|(_, l)| *l)
+}
+
+pub fn detect(query: Option<&str>, cookie: Option<&str>, accept_language: Option<&str>) -> Locale {
+    query
+        .and_then(Locale::parse)
+        .or_else(|| cookie.and_then(Locale::parse))
+        .or_else(|| accept_language.and_then(from_accept_language))
+        .unwrap_or_default()
+}
+
+/// Rejection yang membawa locale request, supaya halaman error
+/// (handle_rejection di main.rs) memakai bahasa yang sama.
+#[derive(Debug)]
+pub struct ServerError(pub Locale);
+
+impl warp::reject::Reject for ServerError {}
+
+pub fn reject(loc: Locale) -> warp::Rejection {
+    warp::reject::custom(ServerError(loc))
+}
+
+/// (kunci, Bahasa Indonesia, English)
+const CATALOG: &[(&str, &str, &str)] = &[
+    // ----- umum -----
+    ("common.back", "← Kembali", "← Back"),
+    ("common.save", "Simpan", "Save"),
+    ("common.cancel", "Batal", "Cancel"),
+    ("common.continue", "Lanjutkan", "Continue"),
+    ("common.no_data", "Belum ada data.", "No data yet."),
+    ("common.reanalyze", "Analisa Ulang", "Re-analyze"),
+    ("common.abort", "Batalkan", "Cancel"),
+    ("common.file", "File", "File"),
+    ("common.folder", "Folder", "Folder"),
+    ("common.path", "Path", "Path"),
+    ("common.application", "Aplikasi", "Application"),
+    ("common.summary", "Summary", "Summary"),
+    ("common.summary_force", "Summary Ulang", "Regenerate Summary"),
+    // ----- navigasi -----
+    ("nav.upload", "Upload", "Upload"),
+    ("nav.apps", "Aplikasi", "Applications"),
+    ("nav.search", "Cari", "Search"),
+    ("nav.usage", "Biaya LLM", "LLM Cost"),
+    ("nav.kinds", "Jenis Analisa", "Analysis Kinds"),
+    ("nav.prompts", "Prompt", "Prompts"),
+    ("nav.locale", "Bahasa tampilan", "Display language"),
+    // ----- pesan handler -----
+    ("err.server", "Terjadi kesalahan di server (lihat log).", "Internal server error (see log)."),
+    ("err.server_api", "Terjadi kesalahan di server (api).", "Internal server error (api)."),
+    ("err.db_list", "Cek DATABASE_URL / DB & tabel.", "Check DATABASE_URL / database & tables."),
+    ("err.db_usage", "Cek tabel llm_calls (sql/llm_usage.sql).", "Check the llm_calls table (sql/llm_usage.sql)."),
+    ("err.app_not_found", "Aplikasi dengan id {id} tidak ditemukan.", "Application with id {id} not found."),
+    ("err.file_not_found", "File tidak ditemukan", "File not found"),
+    ("err.file_id_not_found", "File {id} tidak ditemukan.", "File {id} not found."),
+    ("err.unknown", "Tidak dikenal", "Unknown"),
+    ("err.unknown_kind", "Jenis analisa tidak dikenal", "Unknown analysis kind"),
+    ("err.prompt_unknown", "Prompt '{name}' tidak dikenal.", "Unknown prompt '{name}'."),
+    ("err.unknown_locale", "Bahasa tampilan '{code}' tidak didukung.", "Display language '{code}' is not supported."),
+    ("err.template_empty", "Template tidak boleh kosong.", "Template must not be empty."),
+    ("err.job_not_found", "Job tidak ditemukan.", "Job not found."),
+    ("err.empty_question", "Pertanyaan tidak boleh kosong.", "The question must not be empty."),
+    ("err.empty_query", "Parameter q (kata kunci pencarian) wajib diisi.", "The q parameter (search query) is required."),
+    ("err.invalid_graph", "Graph dari LLM ditolak: {reason}", "The graph returned by the LLM was rejected: {reason}"),
+    ("err.snapshot_not_found", "Snapshot graph #{id} tidak ditemukan.", "Graph snapshot #{id} not found."),
+    ("err.conversation_not_found", "Percakapan #{id} tidak ditemukan.", "Conversation #{id} not found."),
+    ("err.job_not_active", "Job tidak ditemukan atau sudah selesai.", "Job not found or already finished."),
+    (
+        "err.job_not_resumable",
+        "Hanya job yang dibatalkan atau terputus yang bisa dilanjutkan.",
+        "Only cancelled or interrupted jobs can be resumed.",
+    ),
+    ("msg.summary_title", "Ringkasan Aplikasi", "Application Summary"),
+    (
+        "msg.no_summary",
+        "Belum ada summary. Klik 'Summary' atau 'Summary Ulang' untuk membuatnya.",
+        "No summary yet. Click 'Summary' or 'Regenerate Summary' to create one.",
+    ),
+    ("msg.no_result", "Belum ada hasil. Jalankan analisa terlebih dahulu.", "No result yet. Run the analysis first."),
+    ("msg.app_summary", "Summary Aplikasi", "Application Summary"),
+    ("msg.analysis_title", "Hasil Analisa {label}", "{label} Analysis"),
+    ("msg.no_graph", "Belum ada graph", "No graph yet"),
+    ("msg.no_graph_hint", "Silakan klik Generate JSON terlebih dahulu.", "Click Generate JSON first."),
+    // ----- daftar aplikasi -----
+    ("index.title", "Daftar Aplikasi", "Applications"),
+    ("index.empty", "Belum ada aplikasi. Silakan", "No applications yet. Please"),
+    ("index.empty_link", "upload", "upload"),
+    ("index.empty_after", "ZIP.", "a ZIP."),
+    // ----- upload -----
+    ("upload.title", "Upload Zip", "Upload ZIP"),
+    ("upload.heading", "Upload Aplikasi (ZIP)", "Upload Application (ZIP)"),
+    ("upload.app_name", "Nama Aplikasi", "Application name"),
+    ("upload.zip", "File ZIP", "ZIP file"),
+    ("upload.submit", "Upload", "Upload"),
+    // ----- hasil analisa -----
+    ("analysis.title", "Hasil Analisa", "Analysis Result"),
+    ("js.stream.waiting", "Menunggu jawaban LLM…", "Waiting for the LLM…"),
+    ("js.stream.receiving", "Menerima jawaban…", "Receiving answer…"),
+    ("js.stream.retrying", "Jawaban tidak sesuai format, meminta ulang…", "Answer did not match the format, retrying…"),
+    ("js.stream.failed", "Analisa gagal: ", "Analysis failed: "),
+    ("js.stream.cancelled", "Dibatalkan — hasil tidak disimpan.", "Cancelled — result not saved."),
+    ("js.stream.interrupted", "Terputus karena server restart.", "Interrupted by a server restart."),
+    ("js.stream.done", "Selesai.", "Done."),
+    ("js.stream.reconnecting", "Koneksi stream terputus, mencoba lagi…", "Stream disconnected, retrying…"),
+    // ----- perkiraan biaya -----
+    ("estimate.title", "Perkiraan Biaya", "Cost Estimate"),
+    ("estimate.uses", "Proses ini akan memakan sekitar", "This will use about"),
+    ("estimate.tokens", "token", "tokens"),
+    ("estimate.calls", "panggilan LLM, perkiraan biaya", "LLM calls, estimated cost"),
+    ("estimate.prompt_tokens", "Token prompt", "Prompt tokens"),
+    ("estimate.completion_tokens", "token jawaban", "completion tokens"),
+    ("estimate.rough", "Angka ini perkiraan kasar (~4 karakter per token).", "This is a rough estimate (~4 characters per token)."),
+    ("estimate.budget", "Budget", "Budget"),
+    ("estimate.raise", "Naikkan batas di halaman", "Raise the limit on the"),
+    // ----- detail aplikasi -----
+    ("detail.title", "Detail Aplikasi", "Application Detail"),
+    ("detail.heading", "Detail Aplikasi:", "Application:"),
+    ("detail.all_analyses", "Lihat Semua Analisa", "View All Analyses"),
+    ("detail.output_lang", "Bahasa hasil", "Result language"),
+    ("detail.app_lang", "Default aplikasi", "Application default"),
+    ("detail.server_default", "(default server)", "(server default)"),
+    ("detail.search_placeholder", "Cari nama file / folder / path", "Search file name / folder / path"),
+    ("detail.search", "Search", "Search"),
+    ("detail.clear", "Clear", "Clear"),
+    ("detail.bulk", "Analisa Seluruh Aplikasi", "Analyze Whole Application"),
+    ("detail.scope_all", "Semua file", "All files"),
+    ("detail.scope_pending", "Hanya yang belum dianalisis", "Only files not analyzed yet"),
+    ("detail.scope_outdated", "Hanya yang prompt-nya usang", "Only results with an outdated prompt"),
+    ("detail.scope_filtered", "Hanya hasil filter", "Only filtered files"),
+    ("detail.force", "Abaikan cache (analisa ulang)", "Ignore cache (re-analyze)"),
+    ("detail.run", "Jalankan", "Run"),
+    ("detail.job", "Job", "Job"),
+    ("detail.kind", "Jenis", "Kind"),
+    ("detail.status", "Status", "Status"),
+    ("detail.progress", "Progress", "Progress"),
+    ("detail.created", "Dibuat", "Created"),
+    ("detail.rerun", "(ulang)", "(re-run)"),
+    ("detail.status_running", "berjalan", "running"),
+    ("detail.status_done", "selesai", "done"),
+    ("detail.status_cancelled", "dibatalkan", "cancelled"),
+    ("detail.status_interrupted", "terputus (server restart)", "interrupted (server restart)"),
+    ("detail.failed", "gagal", "failed"),
+    ("detail.watch", "Pantau", "Watch"),
+    ("detail.resume", "Lanjutkan", "Resume"),
+    ("detail.no_files", "Tidak ada file terdata.", "No files recorded."),
+    (
+        "detail.hint",
+        "Klik ringkasan untuk melihat konten lengkap di modal. Gunakan tombol “Generate JSON” untuk membuat diagram graph (nodes/edges JSON) dan “View Graph” untuk melihatnya.",
+        "Click a summary to see the full content in a dialog. Use “Generate JSON” to build a graph diagram (nodes/edges JSON) and “View Graph” to open it.",
+    ),
+    ("detail.lines", "Lines", "Lines"),
+    ("detail.summaries", "Ringkasan Analisa (50 kata)", "Analysis Summary (50 words)"),
+    ("detail.actions", "Aksi", "Actions"),
+    ("detail.not_analyzed", "belum dianalisis", "not analyzed yet"),
+    ("detail.generate_graph", "Generate JSON", "Generate JSON"),
+    ("detail.view_graph", "View Graph", "View Graph"),
+    ("detail.modal_title", "Analisa", "Analysis"),
+    ("detail.close", "Tutup", "Close"),
+    ("js.bulk.estimate", "Perkiraan: {calls} panggilan, ~{tokens} token (~${cost}).", "Estimate: {calls} calls, ~{tokens} tokens (~${cost})."),
+    ("js.bulk.confirm", "Jalankan semua analisa untuk: {scope}?", "Run every analysis for: {scope}?"),
+    (
+        "js.bulk.confirm_estimate",
+        "Perkiraan: {calls} panggilan LLM, sekitar {tokens} token (~${cost}).",
+        "Estimate: {calls} LLM calls, about {tokens} tokens (~${cost}).",
+    ),
+    ("js.bulk.start_failed", "Gagal memulai analisa: ", "Failed to start the analysis: "),
+    (
+        "js.bulk.status",
+        "{finished}/{total} selesai · dianalisis {done} · dari cache {skipped} · gagal {failed}",
+        "{finished}/{total} finished · analyzed {done} · from cache {skipped} · failed {failed}",
+    ),
+    ("js.bulk.cancelled", " — Dibatalkan.", " — Cancelled."),
+    (
+        "js.bulk.finished",
+        " — Selesai. Muat ulang halaman untuk melihat ringkasan terbaru.",
+        " — Done. Reload the page to see the latest summaries.",
+    ),
+    (
+        "js.job.cancel_confirm",
+        "Batalkan job #{id}? Item yang belum dianalisis tidak akan dijalankan.",
+        "Cancel job #{id}? Items not analyzed yet will not run.",
+    ),
+    ("js.modal.title", "Analisa", "Analysis"),
+    ("js.modal.empty", "(kosong)", "(empty)"),
+    ("js.modal.no_bootstrap", "Bootstrap JS belum dimuat", "Bootstrap JS is not loaded"),
+    ("js.modal.load_failed", "Gagal memuat analisa: ", "Failed to load the analysis: "),
+    ("js.graph.generating", "Generating...", "Generating..."),
+    ("js.graph.generated", "Generated", "Generated"),
+    ("js.graph.view", "View Graph", "View Graph"),
+    ("js.graph.failed", "Gagal generate graph: ", "Failed to generate the graph: "),
+    // ----- pager -----
+    ("pager.showing", "Menampilkan", "Showing"),
+    ("pager.of", "dari", "of"),
+    ("pager.files", "file", "files"),
+    ("pager.filter", "filter", "filter"),
+    ("pager.first", "First", "First"),
+    ("pager.prev", "Prev", "Prev"),
+    ("pager.next", "Next", "Next"),
+    ("pager.last", "Last", "Last"),
+    // ----- semua analisa -----
+    ("analysis_all.title", "Semua Analisa", "All Analyses"),
+    ("analysis_all.heading", "Semua Analisa:", "All Analyses:"),
+    // ----- graph -----
+    ("graph.title", "Graph", "Graph"),
+    ("graph.fit", "Fit", "Fit"),
+    ("graph.reset", "Reset", "Reset"),
+    // ----- pemakaian & budget -----
+    ("usage.title", "Pemakaian & Biaya LLM", "LLM Usage & Cost"),
+    ("usage.last_24h", "24 jam terakhir", "Last 24 hours"),
+    ("usage.days_7", "7 hari", "7 days"),
+    ("usage.days_30", "30 hari", "30 days"),
+    ("usage.days_90", "90 hari", "90 days"),
+    ("usage.year", "1 tahun", "1 year"),
+    ("usage.show", "Tampilkan", "Show"),
+    ("usage.all_apps", "Semua aplikasi", "All applications"),
+    ("usage.calls", "Panggilan", "Calls"),
+    ("usage.prompt_tokens", "Token prompt", "Prompt tokens"),
+    ("usage.completion_tokens", "Token completion", "Completion tokens"),
+    ("usage.tokens_pc", "Token prompt / completion", "Prompt / completion tokens"),
+    ("usage.cost_usd", "Biaya (USD)", "Cost (USD)"),
+    ("usage.cost_est", "Estimasi biaya", "Estimated cost"),
+    ("usage.avg_latency", "Rata-rata latency", "Average latency"),
+    ("usage.budget", "Budget", "Budget"),
+    ("usage.no_budget", "Belum ada budget; analisa tidak dibatasi.", "No budget yet; analysis is unlimited."),
+    ("usage.scope", "Cakupan", "Scope"),
+    ("usage.tokens", "Token", "Tokens"),
+    ("usage.exceeded", "terlampaui", "exceeded"),
+    ("usage.ok", "aman", "ok"),
+    ("usage.delete", "Hapus", "Delete"),
+    ("usage.from_env", "dari env", "from env"),
+    ("usage.global", "Global (semua aplikasi)", "Global (all applications)"),
+    ("usage.monthly", "Per bulan", "Monthly"),
+    ("usage.total", "Total", "Total"),
+    ("usage.token_limit", "Batas token", "Token limit"),
+    ("usage.usd_limit", "Batas USD", "USD limit"),
+    ("usage.save_budget", "Simpan budget", "Save budget"),
+    (
+        "usage.budget_hint",
+        "Kosongkan kedua batas untuk menghapus budget cakupan/periode tersebut. Analisa yang akan melewati budget ditolak.",
+        "Leave both limits empty to remove the budget for that scope/period. Analyses that would exceed a budget are refused.",
+    ),
+    ("usage.by_day", "Per hari", "Per day"),
+    ("usage.by_app", "Per aplikasi", "Per application"),
+    ("usage.by_kind", "Per jenis analisa", "Per analysis kind"),
+    ("usage.by_model", "Per model", "Per model"),
+    ("usage.by_user", "Per user", "Per user"),
+    ("usage.price_note", "Biaya adalah estimasi dari harga per 1 juta token (lihat", "Costs are estimated from the price per 1M tokens (see"),
+    ("budget.global", "Global", "Global"),
+    ("budget.app", "Aplikasi {name}", "Application {name}"),
+    ("budget.monthly", "bulan ini", "this month"),
+    ("budget.total", "total", "total"),
+    (
+        "err.budget_exceeded",
+        "Budget LLM terlampaui: {budget}. Naikkan batas di halaman Biaya LLM atau tunggu periode berikutnya.",
+        "LLM budget exceeded: {budget}. Raise the limit on the LLM Cost page or wait for the next period.",
+    ),
+    ("err.estimate_over_budget", "Perkiraan ini melewati budget: {budget}", "This estimate exceeds the budget: {budget}"),
+    // ----- akses admin -----
+    (
+        "admin.forbidden",
+        "Halaman ini hanya untuk admin. Masukkan token admin (ADMIN_TOKEN).",
+        "This page is for admins only. Enter the admin token (ADMIN_TOKEN).",
+    ),
+    (
+        "admin.disabled",
+        "Akses admin nonaktif: atur ADMIN_TOKEN di .env lalu jalankan ulang server.",
+        "Admin access is disabled: set ADMIN_TOKEN in .env and restart the server.",
+    ),
+    ("admin.token", "Token admin", "Admin token"),
+    ("admin.login", "Masuk", "Log in"),
+    // ----- jenis analisa -----
+    ("kinds.title", "Jenis Analisa", "Analysis Kinds"),
+    (
+        "kinds.intro",
+        "Setiap jenis aktif muncul sebagai kolom ringkasan dan tombol analisa di halaman aplikasi, dan ikut dijalankan oleh analisa bulk. Prompt jenis baru diisi saat dibuat; perubahan berikutnya lewat editor prompt supaya versinya tercatat.",
+        "Every enabled kind gets a summary column and an analyze button on the application page and is included in bulk analysis. A new kind's prompt is set when it is created; later changes go through the prompt editor so versions are recorded.",
+    ),
+    ("kinds.name", "Nama", "Name"),
+    ("kinds.label", "Label", "Label"),
+    ("kinds.format", "Format", "Format"),
+    ("kinds.schema", "JSON Schema", "JSON Schema"),
+    ("kinds.schema_placeholder", "JSON Schema (opsional, untuk format json)", "JSON Schema (optional, for the json format)"),
+    (
+        "kinds.schema_hint",
+        "Untuk format json: jawaban divalidasi terhadap schema dan diminta ulang bila tidak valid; datanya tersedia di /api/apps/{id}/structured/{jenis}.",
+        "For the json format: answers are validated against the schema and retried when invalid; the data is available at /api/apps/{id}/structured/{kind}.",
+    ),
+    ("kinds.badge", "Badge", "Badge"),
+    ("kinds.order", "Urutan", "Order"),
+    ("kinds.enabled", "Aktif", "Enabled"),
+    ("kinds.builtin", "bawaan", "built-in"),
+    ("kinds.prompt", "Prompt", "Prompt"),
+    ("kinds.add", "Tambah jenis analisa", "Add analysis kind"),
+    ("kinds.name_placeholder", "nama (mis. security)", "name (e.g. security)"),
+    ("kinds.label_placeholder", "Label (mis. Keamanan)", "Label (e.g. Security)"),
+    ("kinds.vars", "Variabel:", "Variables:"),
+    ("kinds.var_code", "(isi file)", "(file content)"),
+    ("kinds.var_path", "(path file)", "(file path)"),
+    ("kinds.submit", "Tambah", "Add"),
+    (
+        "err.kind_name",
+        "Nama jenis hanya boleh huruf kecil, angka dan _ (maks. 32 karakter).",
+        "A kind name may only contain lowercase letters, digits and _ (max. 32 characters).",
+    ),
+    ("err.kind_label", "Label tidak boleh kosong.", "The label must not be empty."),
+    ("err.kind_schema", "JSON Schema ditolak: {reason}", "JSON Schema rejected: {reason}"),
+    (
+        "err.kind_prompt",
+        "Prompt wajib diisi untuk jenis baru (gunakan {{kode}} untuk isi file).",
+        "A new kind needs a prompt (use {{kode}} for the file content).",
+    ),
+    // ----- template prompt -----
+    ("prompts.title", "Template Prompt", "Prompt Templates"),
+    (
+        "prompts.intro",
+        "Prompt default diambil dari folder prompts/ (versi 0). Setiap perubahan disimpan sebagai versi baru; hasil analisa mencatat versi prompt yang dipakai sehingga hasil dengan prompt usang bisa dianalisa ulang (pilih \"Hanya yang prompt-nya usang\" di halaman aplikasi).",
+        "Default prompts come from the prompts/ folder (version 0). Every change is saved as a new version; results record the prompt version they used, so results made with an outdated prompt can be re-run (choose \"Only results with an outdated prompt\" on the application page).",
+    ),
+    ("prompts.prompt", "Prompt", "Prompt"),
+    ("prompts.active", "Versi aktif", "Active version"),
+    ("prompts.outdated", "Hasil dengan prompt usang", "Results with an outdated prompt"),
+    ("prompts.edit", "Edit", "Edit"),
+    ("prompts.all", "← Semua prompt", "← All prompts"),
+    ("prompts.vars", "Variabel:", "Variables:"),
+    ("prompts.note", "Catatan perubahan (opsional)", "Change note (optional)"),
+    ("prompts.save", "Simpan sebagai versi baru", "Save as new version"),
+    ("prompts.load_default", "Muat default", "Load default"),
+    ("prompts.preview", "Preview", "Preview"),
+    ("prompts.app_id", "ID aplikasi (opsional)", "Application ID (optional)"),
+    ("prompts.file_id", "ID file (opsional)", "File ID (optional)"),
+    ("prompts.render", "Render", "Render"),
+    ("prompts.history", "Riwayat versi", "Version history"),
+    ("prompts.never_changed", "Belum pernah diubah; memakai template default (v0).", "Never changed; using the default template (v0)."),
+    ("prompts.version", "Versi", "Version"),
+    ("prompts.note_col", "Catatan", "Note"),
+    ("prompts.by", "Oleh", "By"),
+    ("prompts.time", "Waktu", "Time"),
+    ("prompts.load", "Muat ke editor", "Load into editor"),
+    ("js.prompts.tokens", "~{tokens} token prompt", "~{tokens} prompt tokens"),
+    ("search.title", "Pencarian", "Search"),
+    ("search.heading", "Cari di", "Search in"),
+    ("search.open", "Cari di isi & analisa", "Search contents & analyses"),
+    ("search.placeholder", "teks atau regex, mis. customer_orders", "text or regex, e.g. customer_orders"),
+    ("search.regex", "Regex", "Regex"),
+    ("search.case", "Peka huruf besar/kecil", "Match case"),
+    ("search.all_languages", "Semua bahasa", "All languages"),
+    ("search.folder", "Folder, mis. src/api", "Folder, e.g. src/api"),
+    ("search.scope_all", "Kode & analisa", "Code & analyses"),
+    ("search.scope_code", "Hanya kode", "Code only"),
+    ("search.scope_analysis", "Hanya hasil analisa", "Analyses only"),
+    ("search.submit", "Cari", "Search"),
+    ("search.summary", "{matches} baris cocok di {files} file", "{matches} matching lines in {files} files"),
+    ("search.truncated", "Hasil dibatasi; persempit pencarian dengan filter.", "Results were capped; narrow the search with filters."),
+    ("search.none", "Tidak ada yang cocok.", "No matches."),
+    ("search.more_lines", "+{n} baris cocok lainnya", "+{n} more matching lines"),
+    ("search.analysis", "Analisa", "Analysis"),
+    ("search.bad_regex", "Regex tidak valid: {error}", "Invalid regex: {error}"),
+    ("search.scope_sql", "Hanya tabel SQL", "SQL tables only"),
+    ("search.table", "Tabel", "Table"),
+    ("search.all_apps", "Cari di semua aplikasi", "Search all applications"),
+    ("search.in_app", "Hanya aplikasi ini →", "This application only →"),
+    ("search.global_heading", "Cari di semua aplikasi", "Search all applications"),
+    (
+        "search.global_intro",
+        "Isi file, hasil analisa dan tabel SQL yang diekstrak dari semua aplikasi, dikelompokkan per aplikasi. Mis. customer_orders dengan \"Hanya tabel SQL\" untuk aplikasi yang memakai tabel tersebut.",
+        "File contents, analyses and extracted SQL tables of every application, grouped by application. E.g. customer_orders with \"SQL tables only\" for the applications that use that table.",
+    ),
+    ("chat.title", "Chat", "Chat"),
+    ("chat.heading", "Tanya tentang", "Ask about"),
+    ("chat.open", "Chat", "Chat"),
+    ("chat.new", "+ Percakapan baru", "+ New conversation"),
+    ("chat.conversations", "Percakapan", "Conversations"),
+    ("chat.none", "Belum ada percakapan.", "No conversations yet."),
+    ("chat.empty", "Ajukan pertanyaan tentang kode aplikasi ini; jawaban menyertakan sitasi ke file sumber.", "Ask a question about this application's code; answers cite their source files."),
+    ("chat.placeholder", "mis. Di mana autentikasi ditangani?", "e.g. Where is authentication handled?"),
+    ("chat.send", "Kirim", "Send"),
+    ("chat.you", "Anda", "You"),
+    ("chat.assistant", "Asisten", "Assistant"),
+    ("chat.sources", "Sumber", "Sources"),
+    ("chat.lines", "baris", "lines"),
+    ("chat.sample_question", "Di mana autentikasi ditangani?", "Where is authentication handled?"),
+    ("js.chat.thinking", "Mencari sumber dan menyusun jawaban...", "Retrieving sources and composing an answer..."),
+    ("js.chat.failed", "Gagal: {message}", "Failed: {message}"),
+    ("js.chat.you", "Anda", "You"),
+    ("js.chat.assistant", "Asisten", "Assistant"),
+    ("js.chat.sources", "Sumber", "Sources"),
+    ("js.chat.lines", "baris", "lines"),
+    ("depgraph.title", "Graph Dependensi", "Dependency Graph"),
+    ("depgraph.open", "Graph Dependensi", "Dependency Graph"),
+    ("depgraph.heading", "Graph dependensi modul", "Module dependency graph of"),
+    (
+        "depgraph.intro",
+        "Dihitung dari import/include/use yang ter-resolve antar file (tanpa LLM). Node dikelompokkan per folder; klik cluster untuk membukanya, klik file untuk menuju barisnya.",
+        "Computed from imports/includes/uses resolved between files (no LLM). Nodes are clustered by folder; click a cluster to expand it, click a file to jump to its row.",
+    ),
+    ("depgraph.summary", "{files} file, {edges} dependensi, {external} import ke library luar", "{files} files, {edges} dependencies, {external} imports of external libraries"),
+    ("depgraph.folder", "Folder", "Folder"),
+    ("depgraph.all_folders", "Semua folder", "All folders"),
+    ("depgraph.root", "(akar)", "(root)"),
+    ("depgraph.neighbors", "Ikutkan file terkait di luar folder", "Include related files outside the folder"),
+    ("depgraph.snapshot", "Snapshot", "Snapshot"),
+    ("depgraph.apply", "Terapkan", "Apply"),
+    ("depgraph.cluster", "Cluster per folder", "Cluster by folder"),
+    ("depgraph.expand", "Buka semua", "Expand all"),
+    ("depgraph.empty", "Belum ada file di graph ini.", "This graph has no files."),
+    ("depgraph.files", "file", "files"),
+    ("js.depgraph.cluster_label", "{folder}\n({n} file)", "{folder}\n({n} files)"),
+    ("js.depgraph.root", "(akar)", "(root)"),
+    ("js.depgraph.imports", "mengimpor {n} file", "imports {n} files"),
+    ("js.depgraph.imported_by", "diimpor {n} file", "imported by {n} files"),
+    ("arch.title", "Arsitektur", "Architecture"),
+    ("arch.open", "Arsitektur", "Architecture"),
+    ("arch.heading", "Siklus & layering", "Cycles & layering of"),
+    (
+        "arch.intro",
+        "Diperiksa pada graph dependensi terbaru: siklus import (strongly connected component) dan edge yang melanggar aturan layering aplikasi ini.",
+        "Checked against the latest dependency graph: import cycles (strongly connected components) and edges that break this application's layering rules.",
+    ),
+    ("arch.cycles", "Siklus import", "Import cycles"),
+    ("arch.no_cycles", "Tidak ada siklus import.", "No import cycles."),
+    ("arch.cycle_title", "{files} file saling bergantung; siklus terpendek {steps} langkah:", "{files} files depend on each other; shortest cycle has {steps} steps:"),
+    ("arch.cycle_members", "Semua file di komponen ini", "All files in this component"),
+    ("arch.rules", "Aturan layering", "Layering rules"),
+    (
+        "arch.rules_intro",
+        "Pola berupa nama/awalan folder (mis. services, src/api) atau glob (*, **). Aturan larang: file sumber tidak boleh mengimpor file tujuan; aturan izinkan mengecualikan edge dari aturan larang.",
+        "Patterns are folder names/prefixes (e.g. services, src/api) or globs (*, **). A deny rule forbids source files from importing target files; an allow rule exempts edges from deny rules.",
+    ),
+    ("arch.no_rules", "Belum ada aturan.", "No rules yet."),
+    ("arch.rule_violations", "{n} pelanggaran", "{n} violations"),
+    ("arch.deny", "Larang", "Deny"),
+    ("arch.allow", "Izinkan", "Allow"),
+    ("arch.delete", "Hapus", "Delete"),
+    ("arch.add_rule", "Tambah aturan", "Add rule"),
+    ("arch.source_placeholder", "sumber, mis. services", "source, e.g. services"),
+    ("arch.target_placeholder", "tujuan, mis. controllers", "target, e.g. controllers"),
+    ("arch.note", "Catatan", "Note"),
+    ("arch.add", "Tambah", "Add"),
+    ("schema.title", "Skema database", "Database schema"),
+    ("schema.open", "Skema DB", "DB schema"),
+    ("schema.heading", "Skema database", "Database schema of"),
+    (
+        "schema.intro",
+        "Disusun tanpa LLM dari CREATE TABLE / ALTER TABLE di file .sql dan literal SQL di kode. Tabel bergaris putus-putus dipakai kode tetapi tidak dideklarasikan di file .sql.",
+        "Built without an LLM from CREATE TABLE / ALTER TABLE in .sql files and SQL literals in code. Dashed tables are used by code but not declared in any .sql file.",
+    ),
+    ("schema.summary", "{tables} tabel ({declared} dideklarasikan), {fks} foreign key, {sql_files} file .sql, {files} file memakai tabel", "{tables} tables ({declared} declared), {fks} foreign keys, {sql_files} .sql files, {files} files use tables"),
+    ("schema.empty", "Tidak ada tabel yang ditemukan.", "No tables found."),
+    ("schema.diagram", "Diagram ER", "ER diagram"),
+    ("schema.tables", "Tabel", "Tables"),
+    ("schema.column", "Kolom", "Column"),
+    ("schema.type", "Tipe", "Type"),
+    ("schema.flags", "Atribut", "Flags"),
+    ("schema.indexes", "Index", "Indexes"),
+    ("schema.foreign_keys", "Foreign key", "Foreign keys"),
+    ("schema.defined_in", "Didefinisikan di", "Defined in"),
+    ("schema.undeclared", "tidak dideklarasikan", "not declared"),
+    ("schema.matrix", "Matriks tabel × file", "Table × file matrix"),
+    ("schema.matrix_legend", "R = baca (SELECT), W = tulis (INSERT/UPDATE/DELETE), D = DDL di file .sql.", "R = read (SELECT), W = write (INSERT/UPDATE/DELETE), D = DDL in a .sql file."),
+    ("schema.file", "File", "File"),
+    ("schema.lines", "baris {lines}", "lines {lines}"),
+    ("js.schema.more_columns", "… {n} kolom lagi", "… {n} more columns"),
+    ("js.schema.undeclared", "(tidak dideklarasikan)", "(not declared)"),
+    ("export.label", "Unduh", "Download"),
+    ("err.invalid_format", "Format ekspor tidak dikenal: {format} (pilihan: svg, png, dot, mermaid, graphml, json)", "Unknown export format: {format} (choose svg, png, dot, mermaid, graphml or json)"),
+    ("err.render_too_large", "Graph terlalu besar untuk PNG ({width}×{height} px); gunakan SVG atau filter folder.", "Graph is too large for PNG ({width}×{height} px); use SVG or a folder filter."),
+    ("js.graph.static_alt", "Graph (render server)", "Graph (server-rendered)"),
+    ("calls.title", "Call graph", "Call graph"),
+    ("calls.open", "Call graph", "Call graph"),
+    ("calls.heading", "Call graph fungsi", "Function call graph of"),
+    (
+        "calls.intro",
+        "Disusun tanpa LLM dari hasil parsing Rust, Python, JavaScript/TypeScript, PHP, Go dan Java: definisi fungsi/method diindeks, lalu setiap pemanggilan di-resolve ke file yang sama, file yang diimpor, atau nama yang unik di aplikasi. Panggilan lewat objek yang tipenya tidak diketahui hanya di-resolve di file sendiri / yang diimpor.",
+        "Built without an LLM by parsing Rust, Python, JavaScript/TypeScript, PHP, Go and Java: function/method definitions are indexed, then every call is resolved to the same file, an imported file, or a name that is unique in the application. Calls on objects of unknown type are only resolved within the file itself or its imports.",
+    ),
+    ("calls.summary", "{functions} fungsi/method, {resolved} dari {calls} pemanggilan ter-resolve", "{functions} functions/methods, {resolved} of {calls} calls resolved"),
+    ("calls.all_functions", "Semua fungsi", "All functions"),
+    ("calls.filter", "Cari fungsi…", "Find a function…"),
+    ("calls.pick_root", "Pilih fungsi untuk melihat caller, callee dan call graph-nya.", "Pick a function to see its callers, callees and call graph."),
+    ("calls.empty", "Tidak ada fungsi yang ditemukan.", "No functions found."),
+    ("calls.function", "Fungsi", "Function"),
+    ("calls.kind", "Jenis", "Kind"),
+    ("calls.direction", "Arah", "Direction"),
+    ("calls.dir_both", "Caller & callee", "Callers & callees"),
+    ("calls.dir_callees", "Hanya callee", "Callees only"),
+    ("calls.dir_callers", "Hanya caller", "Callers only"),
+    ("calls.depth", "Kedalaman", "Depth"),
+    ("calls.truncated", "Graph dipotong karena terlalu banyak fungsi; kurangi kedalaman atau pilih satu arah.", "The graph was cut off because it has too many functions; lower the depth or pick one direction."),
+    ("calls.no_edges", "Fungsi ini tidak memanggil dan tidak dipanggil fungsi lain yang ter-resolve.", "This function neither calls nor is called by any resolved function."),
+    ("calls.graph_hint", "Caller di kiri, callee di kanan. Klik fungsi untuk menjadikannya akar.", "Callers on the left, callees on the right. Click a function to make it the root."),
+    ("calls.callers", "Dipanggil oleh", "Called by"),
+    ("calls.callees", "Memanggil", "Calls"),
+    ("calls.none", "Tidak ada.", "None."),
+    ("calls.unresolved", "Pemanggilan tidak ter-resolve", "Unresolved calls"),
+    ("calls.resolved_local", "file sama", "same file"),
+    ("calls.resolved_import", "lewat import", "via import"),
+    ("calls.resolved_global", "nama unik", "unique name"),
+    ("symbols.title", "Simbol", "Symbols"),
+    ("symbols.open", "Simbol", "Symbols"),
+    ("symbols.heading", "Simbol di", "Symbols in"),
+    (
+        "symbols.intro",
+        "Setiap fungsi, method, class, struct, trait dan modul hasil parsing file (tanpa LLM), beserta signature, baris dan visibilitasnya. Cari berdasarkan nama atau nama kontainernya.",
+        "Every function, method, class, struct, trait and module found by parsing the files (no LLM), with its signature, lines and visibility. Search by name or by the name of its container.",
+    ),
+    ("symbols.filter", "Cari nama…", "Find a name…"),
+    ("symbols.all_kinds", "Semua jenis", "All kinds"),
+    ("symbols.summary", "{shown} dari {total} simbol", "{shown} of {total} symbols"),
+    ("symbols.empty", "Tidak ada simbol yang cocok.", "No matching symbols."),
+    ("symbols.name", "Nama", "Name"),
+    ("symbols.kind", "Jenis", "Kind"),
+    ("symbols.visibility", "Visibilitas", "Visibility"),
+    ("symbols.signature", "Signature", "Signature"),
+    ("symbols.outline", "Outline", "Outline"),
+    ("symbols.none_in_file", "Tidak ada definisi yang dikenali di file ini.", "No definitions were recognized in this file."),
+    ("file.view_code", "Lihat kode", "View code"),
+    ("file.summary", "{lines} baris · {language} · {symbols} definisi", "{lines} lines · {language} · {symbols} definitions"),
+    ("file.empty", "File ini kosong.", "This file is empty."),
+    ("file.line_hint", "Klik nomor baris untuk menautkannya; Shift+klik untuk rentang baris.", "Click a line number to link to it; Shift+click to select a range."),
+    ("file.open_analysis", "Buka", "Open"),
+    ("file.run_analysis", "Analisa", "Analyze"),
+    ("err.symbol_not_found", "Fungsi dengan id {id} tidak ditemukan.", "Function with id {id} not found."),
+    ("err.symbol_required", "Parameter symbol wajib diisi untuk ekspor call graph.", "The symbol parameter is required to export a call graph."),
+];
@@ -0,0 +1,576 @@
+// src/imports.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Ekstraksi import antar file tanpa LLM. Setiap file di-scan dengan regex
+// per bahasa (Rust, Python, JS/TS, PHP, Go, Java/Kotlin, C/C++), lalu
+// spesifier import di-resolve ke file lain di aplikasi yang sama.
+// Hasilnya: file_metadata (line_count, daftar import) dan file_imports
+// (edge file → file). Diindeks saat upload; aplikasi lama diindeks saat
+// pertama kali dibutuhkan (lihat `ensure_indexed`).
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use sqlx::MySqlPool;
+
+/// Satu import yang ditemukan di file.
+#[derive(Debug, Clone)]
+pub struct Import {
+    pub spec: String,
+    pub line: usize,
+}
+
+fn ext(path: &str) -> &str {
+    path.rsplit_once('.').map(|(_, e)| e).unwrap_or("")
+}
+
+fn dir(path: &str) -> &str {
+    path.rsplit_once('/').map(|(d, _)| d).unwrap_or("")
+}
+
+fn re(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("regex import valid"))
+}
+
+/// Spesifier import mentah per baris, sesuai bahasa dari ekstensi file.
+pub fn extract(path: &str, code: &str) -> Vec<Import> {
+    static PY: OnceLock<Regex> = OnceLock::new();
+    static JS: OnceLock<Regex> = OnceLock::new();
+    static PHP: OnceLock<Regex> = OnceLock::new();
+    static GO: OnceLock<Regex> = OnceLock::new();
+    static JAVA: OnceLock<Regex> = OnceLock::new();
+    static C: OnceLock<Regex> = OnceLock::new();
+
+    let pattern = match ext(path) {
+        "rs" => return rust_imports(code),
+        "py" => re(&PY, r"^\s*(?:from\s+(\.*[\w.]*)\s+import\s+([\w, ]+)|import\s+([\w.]+))"),
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "vue" | "svelte" => re(
+            &JS,
+            r#"(?:import\s+(?:[^'"]*?\s+from\s+)?|export\s+[^'"]*?\s+from\s+|^\s*\}\s*from\s+|require\s*\(\s*|import\s*\(\s*)['"]([^'"]+)['"]"#,
+        ),
+        "php" => re(
+            &PHP,
+            r#"^\s*(?:(?:include|require)(?:_once)?\s*\(?\s*(?:__DIR__\s*\.\s*)?['"]([^'"]+)['"]|use\s+([\w\\]+)\s*(?:as\s+\w+\s*)?;)"#,
+        ),
+        "go" => re(&GO, r#"^\s*(?:import\s+)?(?:\w+\s+)?"([\w./-]+)"\s*$"#),
+        "java" | "kt" | "scala" => re(&JAVA, r"^\s*import\s+(?:static\s+)?([\w.]+)"),
+        "c" | "h" | "cc" | "cpp" | "hpp" | "cxx" => re(&C, r#"^\s*#\s*include\s+"([^"]+)""#),
+        _ => return Vec::new(),
+    };
+
+    let is_go = ext(path) == "go";
+    let mut in_go_block = false;
+    let mut out = Vec::new();
+    for (i, line) in code.lines().enumerate() {
+        if is_go {
+            // Import Go: `import "x"` atau blok `import ( ... )`
+            let t = line.trim();
+            if t.starts_with("import (") {
+                in_go_block = true;
+                continue;
+            }
+            if in_go_block && t.starts_with(')') {
+                in_go_block = false;
+                continue;
+            }
+            if !in_go_block && !t.starts_with("import ") {
+                continue;
+            }
+        }
+        for caps in pattern.captures_iter(line) {
+            let line_no = i + 1;
+            if ext(path) == "py" {
+                if let Some(module) = caps.get(1) {
+                    // `from a.b import c, d` → a.b, a.b.c, a.b.d (submodul atau nama di modul)
+                    let module = module.as_str();
+                    let names = caps.get(2).map(|m| m.as_str()).unwrap_or("");
+                    out.push(Import { spec: module.to_string(), line: line_no });
+                    for name in names.split(',').map(str::trim).filter(|n| !n.is_empty() && *n != "*") {
+                        let name = name.split_whitespace().next().unwrap_or(name);
+                        let sep = if module.ends_with('.') { "" } else { "." };
+                        out.push(Import { spec: format!("{module}{sep}{name}"), line: line_no });
+                    }
+                    continue;
+                }
+            }
+            if let Some(m) = caps.iter().skip(1).flatten().next() {
+                out.push(Import { spec: m.as_str().to_string(), line: line_no });
+            }
+        }
+    }
+    out
+}
+
+/// Import Rust: `mod x;` dan `use crate|self|super::...;`, termasuk grup
+/// `{...}` yang bisa bersarang dan melintasi beberapa baris.
+fn rust_imports(code: &str) -> Vec<Import> {
+    static RUST: OnceLock<Regex> = OnceLock::new();
+    let pattern = re(
+        &RUST,
+        r"(?m)^[ \t]*(?:pub(?:\([^)]*\))?\s+)?(?:use\s+((?:crate|self|super)\b[^;]*);|mod\s+(\w+)\s*;)",
+    );
+    let mut out = Vec::new();
+    for caps in pattern.captures_iter(code) {
+        let Some(m) = caps.get(0) else { continue };
+        let line = code[..m.start()].matches('\n').count() + 1;
+        if let Some(name) = caps.get(2) {
+            out.push(Import { spec: format!("mod {}", name.as_str()), line });
+            continue;
+        }
+        // komentar baris di dalam grup dibuang dulu
+        let tree: String = caps[1].lines().map(|l| l.split("//").next().unwrap_or("")).collect::<Vec<_>>().join(" ");
+        let mut specs = Vec::new();
+        expand_use("", &tree, &mut specs);
+        out.extend(specs.into_iter().filter(|s| s.contains("::")).map(|spec| Import { spec, line }));
+    }
+    out
+}
+
+/// Pohon `use` → satu path per nama: `a::{b, c::{d, self}, e as f, g::*}`
+/// → a::b, a::c::d, a::c, a::e, a::g.
+fn expand_use(prefix: &str, tree: &str, out: &mut Vec<String>) {
+    let tree = tree.trim();
+    let with = |rest: &str| match (prefix.is_empty(), rest.is_empty()) {
+        (_, true) => prefix.to_string(),
+        (true, false) => rest.to_string(),
+        (false, false) => format!("{prefix}::{rest}"),
+    };
+    if let Some(open) = tree.find('{') {
+        let base = with(tree[..open].trim().trim_end_matches("::").trim());
+        let inner = tree[open + 1..].trim_end();
+        let inner = inner.strip_suffix('}').unwrap_or(inner);
+        let mut depth = 0;
+        let mut start = 0;
+        for (i, c) in inner.char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    expand_use(&base, &inner[start..i], out);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        expand_use(&base, &inner[start..], out);
+        return;
+    }
+    // alias `as` dibuang; `self` dan glob `*` menunjuk modul itu sendiri
+    let path = tree.split_whitespace().next().unwrap_or("").trim_end_matches("::*");
+    let path = match path {
+        "" if tree.is_empty() => return,
+        "self" | "*" => "",
+        p => p.strip_suffix("::self").unwrap_or(p),
+    };
+    let full = with(path);
+    if !full.is_empty() && !out.contains(&full) {
+        out.push(full);
+    }
+}
+
+/// Indeks path file aplikasi untuk resolusi import.
+pub struct PathIndex {
+    by_path: HashMap<String, i64>,
+    paths: Vec<(String, i64)>,
+}
+
+impl PathIndex {
+    pub fn new(files: &[(i64, String)]) -> Self {
+        let paths: Vec<(String, i64)> = files.iter().map(|(id, p)| (normalize(&p.replace('\\', "/")), *id)).collect();
+        let by_path = paths.iter().cloned().collect();
+        PathIndex { by_path, paths }
+    }
+
+    fn exact(&self, path: &str) -> Option<i64> {
+        self.by_path.get(&normalize(path)).copied()
+    }
+
+    /// File yang path-nya berakhiran `suffix`; bila lebih dari satu, pilih
+    /// yang paling dekat dengan `from` (prefix folder bersama terpanjang).
+    fn suffix(&self, suffix: &str, from: &str) -> Option<i64> {
+        let suffix = suffix.trim_start_matches('/');
+        self.paths
+            .iter()
+            .filter(|(p, _)| p == suffix || p.ends_with(&format!("/{suffix}")))
+            .max_by_key(|(p, _)| common_prefix(p, from))
+            .map(|(_, id)| *id)
+    }
+
+    /// Semua file langsung di folder yang berakhiran `suffix` (paket Go).
+    fn dir_files(&self, suffix: &str, ext_: &str) -> Vec<i64> {
+        self.paths
+            .iter()
+            .filter(|(p, _)| {
+                let d = dir(p);
+                ext(p) == ext_ && !p.ends_with("_test.go") && (d == suffix || d.ends_with(&format!("/{suffix}")))
+            })
+            .map(|(_, id)| *id)
+            .collect()
+    }
+
+    fn first(&self, candidates: &[String], from: &str) -> Option<i64> {
+        candidates
+            .iter()
+            .find_map(|c| self.exact(c))
+            .or_else(|| candidates.iter().find_map(|c| self.suffix(c, from)))
+    }
+}
+
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.split('/').zip(b.split('/')).take_while(|(x, y)| x == y).count()
+}
+
+/// Gabungkan segmen `.` dan `..`.
+fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for seg in path.split('/') {
+        match seg {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            s => parts.push(s),
+        }
+    }
+    parts.join("/")
+}
+
+fn join(base: &str, rel: &str) -> String {
+    if base.is_empty() {
+        normalize(rel)
+    } else {
+        normalize(&format!("{base}/{rel}"))
+    }
+}
+
+/// Folder modul Rust untuk file `path`: folder file itu sendiri untuk
+/// main.rs / lib.rs / mod.rs, selain itu folder bernama sama dengan file.
+fn rust_module_dir(path: &str) -> String {
+    let d = dir(path);
+    let stem = path.rsplit('/').next().unwrap_or(path).trim_end_matches(".rs");
+    if matches!(stem, "main" | "lib" | "mod") {
+        d.to_string()
+    } else {
+        join(d, stem)
+    }
+}
+
+/// Folder `src` terdekat (akar crate) untuk resolusi `crate::`.
+fn rust_crate_root(path: &str) -> String {
+    let mut d = dir(path);
+    loop {
+        if d.ends_with("/src") || d == "src" {
+            return d.to_string();
+        }
+        match d.rsplit_once('/') {
+            Some((parent, _)) => d = parent,
+            None => return dir(path).to_string(),
+        }
+    }
+}
+
+fn rust_candidates(base: &str, segs: &[&str]) -> Vec<String> {
+    // use a::b::c → a/b/c.rs, a/b/c/mod.rs, lalu a/b.rs (c item di modul b), ...
+    let mut out = Vec::new();
+    for n in (1..=segs.len()).rev() {
+        let rel = segs[..n].join("/");
+        out.push(join(base, &format!("{rel}.rs")));
+        out.push(join(base, &format!("{rel}/mod.rs")));
+    }
+    out
+}
+
+const JS_EXTS: [&str; 14] = [
+    "", ".ts", ".tsx", ".js", ".jsx", ".mjs", ".cjs", ".vue", ".svelte",
+    "/index.ts", "/index.tsx", "/index.js", "/index.jsx", "/index.vue",
+];
+
+/// Resolve satu spesifier import dari file `from` ke id file tujuan.
+/// Import ke library luar menghasilkan daftar kosong.
+pub fn resolve(index: &PathIndex, from: &str, spec: &str) -> Vec<i64> {
+    let from = normalize(&from.replace('\\', "/"));
+    let d = dir(&from);
+    let one = |id: Option<i64>| id.into_iter().collect::<Vec<_>>();
+    match ext(&from) {
+        "rs" => {
+            if let Some(name) = spec.strip_prefix("mod ") {
+                let base = rust_module_dir(&from);
+                return one(index.first(&[join(&base, &format!("{name}.rs")), join(&base, &format!("{name}/mod.rs"))], &from));
+            }
+            let segs: Vec<&str> = spec.split("::").filter(|s| !s.is_empty()).collect();
+            let (base, rest) = match segs.first() {
+                Some(&"crate") => (rust_crate_root(&from), &segs[1..]),
+                Some(&"self") => (rust_module_dir(&from), &segs[1..]),
+                Some(&"super") => {
+                    let mut base = rust_module_dir(&from);
+                    let mut rest = &segs[..];
+                    while rest.first() == Some(&"super") {
+                        base = dir(&base).to_string();
+                        rest = &rest[1..];
+                    }
+                    (base, rest)
+                }
+                _ => return Vec::new(),
+            };
+            if rest.is_empty() {
+                return Vec::new();
+            }
+            let found = rust_candidates(&base, rest).iter().find_map(|c| index.exact(c));
+            one(found)
+        }
+        "py" => {
+            let dots = spec.chars().take_while(|c| *c == '.').count();
+            let module = &spec[dots..];
+            let rel = module.replace('.', "/");
+            if dots > 0 {
+                let mut base = d.to_string();
+                for _ in 1..dots {
+                    base = dir(&base).to_string();
+                }
+                if rel.is_empty() {
+                    return Vec::new();
+                }
+                return one(index.first(
+                    &[join(&base, &format!("{rel}.py")), join(&base, &format!("{rel}/__init__.py"))],
+                    &from,
+                ));
+            }
+            one(index.first(&[format!("{rel}.py"), format!("{rel}/__init__.py")], &from))
+        }
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "vue" | "svelte" => {
+            let base = if spec.starts_with("./") || spec.starts_with("../") {
+                join(d, spec)
+            } else if let Some(rest) = spec.strip_prefix("@/").or_else(|| spec.strip_prefix("~/")) {
+                // alias umum ke folder src/
+                format!("src/{rest}")
+            } else if spec.starts_with('/') {
+                normalize(spec)
+            } else {
+                return Vec::new();
+            };
+            let cands: Vec<String> = JS_EXTS.iter().map(|e| format!("{base}{e}")).collect();
+            let exact = cands.iter().find_map(|c| index.exact(c));
+            one(exact.or_else(|| if spec.starts_with('.') { None } else { cands.iter().find_map(|c| index.suffix(c, &from)) }))
+        }
+        "php" => {
+            if spec.contains('\\') || !spec.contains('.') {
+                // use App\Models\User → Models/User.php (namespace akar biasanya = folder src/app)
+                let rel = spec.trim_start_matches('\\').replace('\\', "/");
+                let segs: Vec<&str> = rel.split('/').collect();
+                let cands: Vec<String> = (0..segs.len().saturating_sub(1))
+                    .map(|skip| format!("{}.php", segs[skip..].join("/")))
+                    .collect();
+                return one(cands.iter().find_map(|c| index.suffix(c, &from)));
+            }
+            one(index.exact(&join(d, spec)).or_else(|| index.suffix(&normalize(spec), &from)))
+        }
+        "go" => {
+            // paket = folder; cocokkan akhiran path import dengan folder di aplikasi
+            let segs: Vec<&str> = spec.split('/').collect();
+            for skip in 0..segs.len() {
+                let files = index.dir_files(&segs[skip..].join("/"), "go");
+                if !files.is_empty() {
+                    return files;
+                }
+            }
+            Vec::new()
+        }
+        "java" | "kt" | "scala" => {
+            let rel = spec.trim_end_matches(".*").replace('.', "/");
+            let cands = [format!("{rel}.java"), format!("{rel}.kt"), format!("{rel}.scala")];
+            one(cands.iter().find_map(|c| index.suffix(c, &from)))
+        }
+        "c" | "h" | "cc" | "cpp" | "hpp" | "cxx" => {
+            one(index.exact(&join(d, spec)).or_else(|| index.suffix(&normalize(spec), &from)))
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Indeks ulang import seluruh file aplikasi: tulis file_metadata
+/// (line_count, imports) dan file_imports.
+pub async fn index_app(pool: &MySqlPool, app_id: i64) -> anyhow::Result<()> {
+    let files: Vec<(i64, String, Option<String>)> =
+        sqlx::query_as("SELECT id, full_path, content_file FROM files WHERE app_id=? ORDER BY id")
+            .bind(app_id)
+            .fetch_all(pool)
+            .await?;
+    let index = PathIndex::new(&files.iter().map(|(id, p, _)| (*id, p.clone())).collect::<Vec<_>>());
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE i FROM file_imports i JOIN files f ON f.id = i.file_id WHERE f.app_id=?")
+        .bind(app_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE m FROM file_metadata m JOIN files f ON f.id = m.file_id WHERE f.app_id=?")
+        .bind(app_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for (file_id, path, content) in &files {
+        let code = content.as_deref().unwrap_or("");
+        let found = extract(path, code);
+        let specs: Vec<&str> = found.iter().map(|i| i.spec.as_str()).collect();
+        sqlx::query("INSERT INTO file_metadata (file_id, line_count, imports) VALUES (?, ?, ?)")
+            .bind(file_id)
+            .bind(code.lines().count() as i32)
+            .bind(if specs.is_empty() { None } else { Some(specs.join("\n")) })
+            .execute(&mut *tx)
+            .await?;
+
+        let mut seen = std::collections::HashSet::new();
+        for imp in &found {
+            let targets = resolve(&index, path, &imp.spec);
+            if targets.is_empty() {
+                if seen.insert((imp.spec.clone(), None)) {
+                    sqlx::query("INSERT INTO file_imports (file_id, target_file_id, spec, line_no) VALUES (?, NULL, ?, ?)")
+                        .bind(file_id)
+                        .bind(&imp.spec)
+                        .bind(imp.line as i32)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+                continue;
+            }
+            for target in targets.into_iter().filter(|t| t != file_id) {
+                if seen.insert((imp.spec.clone(), Some(target))) {
+                    sqlx::query("INSERT INTO file_imports (file_id, target_file_id, spec, line_no) VALUES (?, ?, ?, ?)")
+                        .bind(file_id)
+                        .bind(target)
+                        .bind(&imp.spec)
+                        .bind(imp.line as i32)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+            }
+        }
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Indeks aplikasi bila belum pernah (mis. diunggah sebelum fitur ini ada).
+pub async fn ensure_indexed(pool: &MySqlPool, app_id: i64) -> anyhow::Result<()> {
+    let (n,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM file_metadata m JOIN files f ON f.id = m.file_id WHERE f.app_id=?",
+    )
+    .bind(app_id)
+    .fetch_one(pool)
+    .await?;
+    if n == 0 {
+        index_app(pool, app_id).await?;
+    }
+    Ok(())
+}
+
+/// File yang diimpor oleh `file_id` (edge keluar yang ter-resolve).
+pub async fn imports_of(pool: &MySqlPool, file_id: i64) -> anyhow::Result<Vec<(i64, String)>> {
+    Ok(sqlx::query_as(
+        "SELECT DISTINCT f.id, f.full_path FROM file_imports i JOIN files f ON f.id = i.target_file_id
+         WHERE i.file_id=? ORDER BY f.full_path",
+    )
+    .bind(file_id)
+    .fetch_all(pool)
+    .await?)
+}
+
+/// File yang mengimpor `file_id` (edge masuk).
+pub async fn importers_of(pool: &MySqlPool, file_id: i64) -> anyhow::Result<Vec<(i64, String)>> {
+    Ok(sqlx::query_as(
+        "SELECT DISTINCT f.id, f.full_path FROM file_imports i JOIN files f ON f.id = i.file_id
+         WHERE i.target_file_id=? ORDER BY f.full_path",
+    )
+    .bind(file_id)
+    .fetch_all(pool)
+    .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specs(path: &str, code: &str) -> Vec<(String, usize)> {
+        extract(path, code).into_iter().map(|i| (i.spec, i.line)).collect()
+    }
+
+    fn s(spec: &str, line: usize) -> (String, usize) {
+        (spec.to_string(), line)
+    }
+
+    #[test]
+    fn rust_use_groups_expand_to_one_path_each() {
+        assert_eq!(specs("src/a.rs", "use super::{a, b};"), [s("super::a", 1), s("super::b", 1)]);
+        assert_eq!(specs("src/a.rs", "use crate::{x::Y, z};"), [s("crate::x::Y", 1), s("crate::z", 1)]);
+        let code = "mod util;\npub(crate) use crate::db::{\n    self,\n    models::{User, Role as R}, // komentar\n    query::*,\n};\n\
+                    use std::{fs, io};\nuse super::*;\n";
+        assert_eq!(
+            specs("src/a.rs", code),
+            [
+                s("mod util", 1),
+                s("crate::db", 2),
+                s("crate::db::models::User", 2),
+                s("crate::db::models::Role", 2),
+                s("crate::db::query", 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn rust_group_paths_resolve() {
+        let index = PathIndex::new(&[(1, "src/main.rs".into()), (2, "src/x.rs".into()), (3, "src/z/mod.rs".into())]);
+        let ids: Vec<i64> = extract("src/main.rs", "use crate::{x::Y, z};")
+            .iter()
+            .flat_map(|i| resolve(&index, "src/main.rs", &i.spec))
+            .collect();
+        assert_eq!(ids, [2, 3]);
+    }
+
+    #[test]
+    fn other_languages_extract_specs() {
+        let py = specs("app/views.py", "from .models import User, Role as R\nimport os.path\nfrom . import util\n");
+        assert_eq!(
+            py,
+            [s(".models", 1), s(".models.User", 1), s(".models.Role", 1), s("os.path", 2), s(".", 3), s(".util", 3)]
+        );
+        let code = "import x from './x';\nexport { y } from \"../y\";\n\
+                    const z = require('z');\nawait import('./lazy');\n";
+        let js = specs("src/a.ts", code);
+        assert_eq!(js, [s("./x", 1), s("../y", 2), s("z", 3), s("./lazy", 4)]);
+        let go = specs("main.go", "import (\n\t\"fmt\"\n\tdb \"example.com/app/db\"\n)\nvar s = \"x\"\n");
+        assert_eq!(go, [s("fmt", 2), s("example.com/app/db", 3)]);
+        let php = specs("a.php", "<?php\nrequire_once __DIR__ . '/lib/util.php';\nuse App\\Models\\User;\n");
+        assert_eq!(php, [s("/lib/util.php", 2), s("App\\Models\\User", 3)]);
+        assert_eq!(specs("A.java", "import com.x.Util;\n"), [s("com.x.Util", 1)]);
+        assert_eq!(specs("a.c", "#include \"util.h\"\n#include <stdio.h>\n"), [s("util.h", 1)]);
+        assert!(specs("notes.txt", "import x").is_empty());
+    }
+
+    #[test]
+    fn other_languages_resolve_to_files() {
+        let files = [
+            "app/models.py", "app/views.py", "src/a.ts", "src/x/index.ts", "src/y.vue", "db/conn.go", "db/conn_test.go",
+            "src/Models/User.php", "lib/util.php", "src/com/x/Util.java", "include/util.h",
+        ];
+        let files: Vec<(i64, String)> = files.iter().enumerate().map(|(i, p)| (i as i64 + 1, p.to_string())).collect();
+        let index = PathIndex::new(&files);
+        assert_eq!(resolve(&index, "app/views.py", ".models"), [1]);
+        assert_eq!(resolve(&index, "app/views.py", "os.path"), Vec::<i64>::new());
+        assert_eq!(resolve(&index, "src/a.ts", "./x"), [4]);
+        assert_eq!(resolve(&index, "src/a.ts", "@/y"), [5]);
+        assert_eq!(resolve(&index, "src/a.ts", "react"), Vec::<i64>::new());
+        assert_eq!(resolve(&index, "main.go", "example.com/app/db"), [6]);
+        assert_eq!(resolve(&index, "index.php", "App\\Models\\User"), [8]);
+        assert_eq!(resolve(&index, "index.php", "lib/util.php"), [9]);
+        assert_eq!(resolve(&index, "A.java", "com.x.Util"), [10]);
+        assert_eq!(resolve(&index, "src/main.c", "util.h"), [11]);
+    }
+}
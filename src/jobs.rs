@@ -0,0 +1,824 @@
+// src/jobs.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Job analisa yang berjalan di background:
+// - bulk    : semua jenis analisa untuk banyak file, paralel dengan batas
+//             konkurensi (ANALYZE_CONCURRENCY)
+// - file    : satu analisa untuk satu file (halaman /analyze)
+// - summary : ringkasan aplikasi
+// Setiap perubahan dikirim sebagai JobEvent ke subscriber SSE (/jobs/:id/events).
+// Job + item-nya disimpan di tabel analysis_jobs / analysis_job_items sebagai
+// checkpoint: job bisa dibatalkan, dan saat server restart job yang masih
+// berjalan ditandai "interrupted" lalu dilanjutkan dari item yang belum selesai.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use sqlx::MySqlPool;
+use tokio::sync::{broadcast, watch, Semaphore};
+
+use crate::models::JobRow;
+
+const DEFAULT_CONCURRENCY: usize = 4;
+const MAX_ERRORS_KEPT: usize = 50;
+const EVENT_BUFFER: usize = 1024;
+/// Job yang sudah selesai tetap di memori selama ini (untuk subscriber
+/// yang terlambat), lalu dibuang; progress-nya tetap bisa dibaca dari DB.
+const FINISHED_TTL: Duration = Duration::from_secs(10 * 60);
+
+pub type Jobs = Arc<JobRegistry>;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobProgress {
+    pub id: i64,
+    pub app_id: i64,
+    pub job_kind: String, // bulk | file | summary
+    pub status: String,   // running | done | cancelled | interrupted
+    /// Bahasa output analisa (lihat lang.rs).
+    pub lang: String,
+    pub requested_by: Option<String>,
+    pub total: usize,
+    pub done: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub errors: Vec<String>,
+    /// Teks yang sudah di-stream sejauh ini (hanya job file/summary yang
+    /// masih berjalan; dikosongkan setelah selesai karena hasilnya sudah
+    /// tersimpan di DB).
+    pub output: String,
+}
+
+impl JobProgress {
+    fn finished(&self) -> usize {
+        self.done + self.skipped + self.failed
+    }
+
+    fn from_row(r: JobRow, errors: Vec<String>) -> Self {
+        JobProgress {
+            id: r.id,
+            app_id: r.app_id,
+            job_kind: r.job_kind,
+            status: r.status,
+            lang: r.lang,
+            requested_by: r.requested_by,
+            total: r.total.max(0) as usize,
+            done: r.done.max(0) as usize,
+            skipped: r.skipped.max(0) as usize,
+            failed: r.failed.max(0) as usize,
+            errors,
+            output: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobEvent {
+    Progress(JobProgress),
+    /// Satu (file, kind) selesai: status = done | skipped | failed.
+    File {
+        file_id: Option<i64>,
+        kind: String,
+        status: String,
+        error: Option<String>,
+    },
+    /// Potongan jawaban LLM.
+    Token {
+        file_id: Option<i64>,
+        kind: String,
+        delta: String,
+    },
+    /// Jawaban yang sudah di-stream ditolak (mis. JSON tidak sesuai schema)
+    /// dan LLM diminta ulang: token sebelumnya dibuang.
+    Reset {
+        file_id: Option<i64>,
+        kind: String,
+    },
+}
+
+impl JobEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JobEvent::Progress(p) if p.status != "running" => "done",
+            JobEvent::Progress(_) => "progress",
+            JobEvent::File { .. } => "file",
+            JobEvent::Token { .. } => "token",
+            JobEvent::Reset { .. } => "reset",
+        }
+    }
+}
+
+/// Satu unit kerja job: (file, kind), atau kind "summary" tanpa file.
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct JobItem {
+    file_id: Option<i64>,
+    kind: String,
+}
+
+struct JobEntry {
+    progress: JobProgress,
+    tx: broadcast::Sender<JobEvent>,
+    cancel: watch::Sender<bool>,
+    /// Kapan job berhenti berjalan (dasar FINISHED_TTL).
+    finished_at: Option<Instant>,
+    /// (file, kind) yang dianalisa job "file", untuk menyambungkan
+    /// permintaan yang sama ke job yang sedang berjalan.
+    target: Option<(i64, String)>,
+}
+
+impl JobEntry {
+    /// Dipanggil setelah status berubah: job yang tidak lagi berjalan
+    /// dicatat waktu selesainya dan teks stream-nya dilepas.
+    fn settle(&mut self) {
+        if self.progress.status != "running" && self.finished_at.is_none() {
+            self.finished_at = Some(Instant::now());
+            self.progress.output = String::new();
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct JobRegistry {
+    jobs: Mutex<HashMap<i64, JobEntry>>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Jobs {
+        Arc::new(JobRegistry::default())
+    }
+
+    pub fn get(&self, id: i64) -> Option<JobProgress> {
+        self.jobs.lock().unwrap().get(&id).map(|j| j.progress.clone())
+    }
+
+    /// Snapshot progress + receiver event berikutnya (diambil atomik
+    /// supaya subscriber tidak kehilangan event di antaranya).
+    pub fn subscribe(&self, id: i64) -> Option<(JobProgress, broadcast::Receiver<JobEvent>)> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|j| (j.progress.clone(), j.tx.subscribe()))
+    }
+
+    /// Daftarkan job, kecuali job dengan id yang sama masih berjalan.
+    /// Cek dan pendaftaran dalam satu lock supaya dua resume bersamaan
+    /// tidak menjalankan item yang sama dua kali. Job selesai yang
+    /// melewati FINISHED_TTL dibuang di sini.
+    fn register(&self, progress: JobProgress, target: Option<(i64, String)>) -> Option<watch::Receiver<bool>> {
+        let mut jobs = self.jobs.lock().unwrap();
+        evict_finished(&mut jobs, Instant::now());
+        if jobs.get(&progress.id).is_some_and(|j| j.progress.status == "running") {
+            return None;
+        }
+        let (tx, _) = broadcast::channel(EVENT_BUFFER);
+        let (cancel, cancel_rx) = watch::channel(false);
+        let mut entry = JobEntry { progress, tx, cancel, finished_at: None, target };
+        entry.settle();
+        jobs.insert(entry.progress.id, entry);
+        Some(cancel_rx)
+    }
+
+    /// Job "file" yang sedang berjalan untuk (file, kind, bahasa) yang sama.
+    pub fn running_file(&self, file_id: i64, kind: &str, lang: &str) -> Option<i64> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .find(|j| {
+                j.progress.status == "running"
+                    && j.progress.lang == lang
+                    && j.target.as_ref().is_some_and(|(f, k)| *f == file_id && k == kind)
+            })
+            .map(|j| j.progress.id)
+    }
+
+    /// Tandai job dibatalkan dan hentikan tugas yang sedang berjalan.
+    fn cancel(&self, id: i64) {
+        if let Some(j) = self.jobs.lock().unwrap().get_mut(&id) {
+            let _ = j.cancel.send(true);
+            j.progress.status = "cancelled".into();
+            j.settle();
+            let _ = j.tx.send(JobEvent::Progress(j.progress.clone()));
+        }
+    }
+    fn token(&self, id: i64, file_id: Option<i64>, kind: &str, delta: &str) {
+        if let Some(j) = self.jobs.lock().unwrap().get_mut(&id) {
+            if j.progress.job_kind != "bulk" {
+                j.progress.output.push_str(delta);
+            }
+            let _ = j.tx.send(JobEvent::Token {
+                file_id,
+                kind: kind.into(),
+                delta: delta.into(),
+            });
+        }
+    }
+
+    fn reset(&self, id: i64, file_id: Option<i64>, kind: &str) {
+        if let Some(j) = self.jobs.lock().unwrap().get_mut(&id) {
+            j.progress.output.clear();
+            let _ = j.tx.send(JobEvent::Reset { file_id, kind: kind.into() });
+        }
+    }
+
+    /// Catat hasil satu tugas, lalu kirim event file + progress.
+    fn finish_task(&self, id: i64, file_id: Option<i64>, kind: &str, outcome: Outcome) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let Some(j) = jobs.get_mut(&id) else { return };
+        let p = &mut j.progress;
+        let (status, error) = match outcome {
+            Outcome::Done => {
+                p.done += 1;
+                ("done", None)
+            }
+            Outcome::Skipped => {
+                p.skipped += 1;
+                ("skipped", None)
+            }
+            Outcome::Failed(e) => {
+                p.failed += 1;
+                if p.errors.len() < MAX_ERRORS_KEPT {
+                    let label = file_id.map(|f| format!("file {f} ")).unwrap_or_default();
+                    p.errors.push(format!("{label}({kind}): {e}"));
+                }
+                ("failed", Some(e))
+            }
+        };
+        if p.status == "running" && p.finished() >= p.total {
+            p.status = "done".into();
+        }
+        j.settle();
+        let _ = j.tx.send(JobEvent::File {
+            file_id,
+            kind: kind.into(),
+            status: status.into(),
+            error,
+        });
+        let _ = j.tx.send(JobEvent::Progress(j.progress.clone()));
+    }
+}
+
+/// Buang job yang sudah selesai lebih dari FINISHED_TTL sebelum `now`.
+fn evict_finished(jobs: &mut HashMap<i64, JobEntry>, now: Instant) {
+    jobs.retain(|_, j| j.finished_at.is_none_or(|t| now.duration_since(t) < FINISHED_TTL));
+}
+
+enum Outcome {
+    Done,
+    Skipped,
+    Failed(String),
+}
+
+fn concurrency() -> usize {
+    std::env::var("ANALYZE_CONCURRENCY")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|n: &usize| *n > 0)
+        .unwrap_or(DEFAULT_CONCURRENCY)
+}
+
+impl Outcome {
+    fn status(&self) -> &'static str {
+        match self {
+            Outcome::Done => "done",
+            Outcome::Skipped => "skipped",
+            Outcome::Failed(_) => "failed",
+        }
+    }
+}
+
+/// Simpan hasil satu item ke DB (checkpoint) + naikkan counter job.
+async fn checkpoint(pool: &MySqlPool, id: i64, item: &JobItem, outcome: &Outcome) -> anyhow::Result<()> {
+    let error = match outcome {
+        Outcome::Failed(e) => Some(e.as_str()),
+        _ => None,
+    };
+    sqlx::query(
+        "UPDATE analysis_job_items SET status=?, error=?
+         WHERE job_id=? AND kind=? AND file_id <=> ?",
+    )
+    .bind(outcome.status())
+    .bind(error)
+    .bind(id)
+    .bind(&item.kind)
+    .bind(item.file_id)
+    .execute(pool)
+    .await?;
+
+    // Counter dinaikkan di SQL (bukan dari snapshot) supaya urutan
+    // checkpoint antar tugas paralel tidak saling menimpa.
+    let col = outcome.status();
+    let q = format!(
+        "UPDATE analysis_jobs
+         SET {col}={col}+1,
+             status=IF(status='running' AND done+skipped+failed>=total, 'done', status)
+         WHERE id=?"
+    );
+    sqlx::query(&q).bind(id).execute(pool).await?;
+    Ok(())
+}
+
+/// Parameter yang sama untuk semua item dalam satu job.
+struct RunCtx {
+    id: i64,
+    app_id: i64,
+    lang: String,
+    user: Option<String>,
+    force: bool,
+}
+
+/// File yang hasil tersimpannya dicek sebelum item dijalankan: hasil yang
+/// sudah ada membuat item dilewati, kecuali job diminta menganalisa ulang
+/// (force). Ringkasan aplikasi (tanpa file) selalu dijalankan.
+fn cache_lookup(force: bool, item: &JobItem) -> Option<i64> {
+    item.file_id.filter(|_| !force)
+}
+
+/// Jalankan satu item job dan laporkan hasilnya ke registry + DB.
+/// Bila job dibatalkan di tengah jalan, panggilan LLM dihentikan dan
+/// tidak ada hasil analisa yang ditulis.
+async fn run_item(
+    pool: &MySqlPool,
+    jobs: &Jobs,
+    run: &RunCtx,
+    item: &JobItem,
+    mut cancel: watch::Receiver<bool>,
+) {
+    if *cancel.borrow() {
+        return;
+    }
+    let RunCtx { id, app_id, force, .. } = *run;
+    let user = run.user.as_deref();
+    let lang = run.lang.as_str();
+    let kind = item.kind.as_str();
+
+    let work = async {
+        if let Some(file_id) = cache_lookup(force, item) {
+            if let Ok(Some(_)) = crate::services::cached_analysis(pool, file_id, kind, lang).await {
+                return (Outcome::Skipped, false);
+            }
+        }
+        let mut on_token = |delta: &str| jobs.token(id, item.file_id, kind, delta);
+        let mut on_retry = || jobs.reset(id, item.file_id, kind);
+        let result = match item.file_id {
+            Some(file_id) => {
+                crate::services::run_analysis(pool, file_id, kind, lang, user, &mut on_token, &mut on_retry).await
+            }
+            None => crate::services::run_summary(pool, app_id, lang, user, &mut on_token).await,
+        };
+        match result {
+            Ok(_) => (Outcome::Done, false),
+            Err(e) => {
+                eprintln!("job {id}: {item:?} gagal: {e:?}");
+                let over_budget = e.is::<crate::budget::BudgetExceeded>();
+                (Outcome::Failed(e.to_string()), over_budget)
+            }
+        }
+    };
+
+    let (outcome, over_budget) = tokio::select! {
+        res = work => res,
+        _ = cancel.wait_for(|c| *c) => return,
+    };
+
+    if let Err(e) = checkpoint(pool, id, item, &outcome).await {
+        eprintln!("job {id}: gagal menyimpan checkpoint {item:?}: {e:?}");
+    }
+    jobs.finish_task(id, item.file_id, kind, outcome);
+
+    // Budget habis: item berikutnya pasti ditolak juga, jadi sisa job
+    // dibatalkan (bisa dilanjutkan setelah budget dinaikkan).
+    if over_budget {
+        if let Err(e) = self::cancel(pool, jobs, id).await {
+            eprintln!("job {id}: gagal membatalkan job setelah budget habis: {e:?}");
+        }
+    }
+}
+
+/// Daftarkan progress di registry lalu jalankan item-item yang tersisa.
+/// Mengembalikan false (tanpa menjalankan apa pun) bila job dengan id
+/// yang sama masih berjalan.
+fn start(pool: MySqlPool, jobs: Jobs, progress: JobProgress, items: Vec<JobItem>, force: bool) -> bool {
+    let run = Arc::new(RunCtx {
+        id: progress.id,
+        app_id: progress.app_id,
+        lang: progress.lang.clone(),
+        user: progress.requested_by.clone(),
+        force,
+    });
+    let target = match (progress.job_kind.as_str(), items.as_slice()) {
+        ("file", [JobItem { file_id: Some(f), kind }]) => Some((*f, kind.clone())),
+        _ => None,
+    };
+    let Some(cancel) = jobs.register(progress, target) else {
+        return false;
+    };
+
+    let sem = Arc::new(Semaphore::new(concurrency()));
+    for item in items {
+        let (pool, jobs, sem, cancel) = (pool.clone(), jobs.clone(), sem.clone(), cancel.clone());
+        let run = run.clone();
+        tokio::spawn(async move {
+            let Ok(_permit) = sem.acquire_owned().await else { return };
+            run_item(&pool, &jobs, &run, &item, cancel).await;
+        });
+    }
+    true
+}
+
+/// Atribut job baru yang berlaku untuk semua item-nya.
+struct NewJob<'a> {
+    app_id: i64,
+    job_kind: &'a str,
+    lang: &'a str,
+    force: bool,
+    user: Option<String>,
+}
+
+/// Simpan job + semua item-nya ke DB lalu jalankan. Mengembalikan id job.
+async fn create(pool: MySqlPool, jobs: Jobs, job: NewJob<'_>, items: Vec<JobItem>) -> anyhow::Result<i64> {
+    let NewJob { app_id, job_kind, lang, force, user } = job;
+    let mut tx = pool.begin().await?;
+    let status = if items.is_empty() { "done" } else { "running" };
+    let res = sqlx::query(
+        "INSERT INTO analysis_jobs (app_id, job_kind, status, lang, force_refresh, total, requested_by)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(app_id)
+    .bind(job_kind)
+    .bind(status)
+    .bind(lang)
+    .bind(force)
+    .bind(items.len() as i32)
+    .bind(&user)
+    .execute(&mut *tx)
+    .await?;
+    let id = res.last_insert_id() as i64;
+
+    for chunk in items.chunks(500) {
+        let mut qb = sqlx::QueryBuilder::new("INSERT INTO analysis_job_items (job_id, file_id, kind) ");
+        qb.push_values(chunk, |mut b, it| {
+            b.push_bind(id).push_bind(it.file_id).push_bind(&it.kind);
+        });
+        qb.build().execute(&mut *tx).await?;
+    }
+    tx.commit().await?;
+
+    let progress = JobProgress {
+        id,
+        app_id,
+        job_kind: job_kind.into(),
+        status: status.into(),
+        lang: lang.into(),
+        requested_by: user,
+        total: items.len(),
+        done: 0,
+        skipped: 0,
+        failed: 0,
+        errors: Vec::new(),
+        output: String::new(),
+    };
+    start(pool, jobs, progress, items, force);
+    Ok(id)
+}
+
+/// Job bulk: semua jenis analisa yang aktif untuk `file_ids`.
+/// Hasil yang sudah ada di cache dilewati kecuali `force`.
+pub async fn spawn_bulk(
+    pool: MySqlPool,
+    jobs: Jobs,
+    app_id: i64,
+    file_ids: Vec<i64>,
+    lang: &str,
+    force: bool,
+    user: Option<String>,
+) -> anyhow::Result<i64> {
+    let kinds = crate::kinds::list(&pool, true).await?;
+    let items = file_ids
+        .iter()
+        .flat_map(|f| {
+            kinds
+                .iter()
+                .map(move |k| JobItem { file_id: Some(*f), kind: k.name.clone() })
+        })
+        .collect();
+    let job = NewJob { app_id, job_kind: "bulk", lang, force, user };
+    create(pool, jobs, job, items).await
+}
+
+/// Job bulk untuk item yang hasilnya dibuat dengan versi prompt lama
+/// (lihat prompts::outdated_items); selalu dianalisa ulang.
+pub async fn spawn_items(
+    pool: MySqlPool,
+    jobs: Jobs,
+    app_id: i64,
+    items: Vec<(Option<i64>, String)>,
+    lang: &str,
+    user: Option<String>,
+) -> anyhow::Result<i64> {
+    let items = items
+        .into_iter()
+        .map(|(file_id, kind)| JobItem { file_id, kind })
+        .collect();
+    let job = NewJob { app_id, job_kind: "bulk", lang, force: true, user };
+    create(pool, jobs, job, items).await
+}
+
+/// Job satu analisa (tanpa cek cache; pemanggil sudah memutuskan). Bila
+/// analisa yang sama sedang berjalan, id job itu yang dikembalikan supaya
+/// halaman kedua ikut menonton stream-nya, bukan memanggil LLM lagi.
+pub async fn spawn_file(
+    pool: MySqlPool,
+    jobs: Jobs,
+    app_id: i64,
+    file_id: i64,
+    kind: String,
+    lang: &str,
+    user: Option<String>,
+) -> anyhow::Result<i64> {
+    if let Some(id) = jobs.running_file(file_id, &kind, lang) {
+        return Ok(id);
+    }
+    let items = vec![JobItem { file_id: Some(file_id), kind }];
+    let job = NewJob { app_id, job_kind: "file", lang, force: true, user };
+    create(pool, jobs, job, items).await
+}
+
+/// Job summary aplikasi.
+pub async fn spawn_summary(
+    pool: MySqlPool,
+    jobs: Jobs,
+    app_id: i64,
+    lang: &str,
+    user: Option<String>,
+) -> anyhow::Result<i64> {
+    let items = vec![JobItem { file_id: None, kind: "summary".into() }];
+    let job = NewJob { app_id, job_kind: "summary", lang, force: true, user };
+    create(pool, jobs, job, items).await
+}
+
+async fn load_row(pool: &MySqlPool, id: i64) -> anyhow::Result<Option<JobRow>> {
+    Ok(sqlx::query_as::<_, JobRow>(
+        "SELECT id, app_id, job_kind, status, lang, requested_by, force_refresh, total, done, skipped, failed, created_at
+         FROM analysis_jobs WHERE id=?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?)
+}
+
+/// Progress job: dari memori bila sedang/pernah berjalan di proses ini,
+/// selain itu dari DB (mis. job lama atau yang "interrupted").
+pub async fn load_progress(pool: &MySqlPool, jobs: &Jobs, id: i64) -> anyhow::Result<Option<JobProgress>> {
+    if let Some(p) = jobs.get(id) {
+        return Ok(Some(p));
+    }
+    let Some(row) = load_row(pool, id).await? else {
+        return Ok(None);
+    };
+    let errors: Vec<(Option<i64>, String, Option<String>)> = sqlx::query_as(
+        "SELECT file_id, kind, error FROM analysis_job_items
+         WHERE job_id=? AND status='failed' ORDER BY id LIMIT ?",
+    )
+    .bind(id)
+    .bind(MAX_ERRORS_KEPT as i64)
+    .fetch_all(pool)
+    .await?;
+    let errors = errors
+        .into_iter()
+        .map(|(file_id, kind, e)| {
+            let label = file_id.map(|f| format!("file {f} ")).unwrap_or_default();
+            format!("{label}({kind}): {}", e.unwrap_or_default())
+        })
+        .collect();
+    Ok(Some(JobProgress::from_row(row, errors)))
+}
+
+/// Job terbaru untuk satu aplikasi (riwayat di halaman detail).
+pub async fn recent_jobs(pool: &MySqlPool, app_id: i64, limit: i64) -> anyhow::Result<Vec<JobRow>> {
+    Ok(sqlx::query_as::<_, JobRow>(
+        "SELECT id, app_id, job_kind, status, lang, requested_by, force_refresh, total, done, skipped, failed, created_at
+         FROM analysis_jobs WHERE app_id=? ORDER BY id DESC LIMIT ?",
+    )
+    .bind(app_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?)
+}
+
+/// Batalkan job: item yang belum jalan ditandai "cancelled", panggilan
+/// LLM yang sedang berjalan dihentikan. Mengembalikan false bila job
+/// sudah tidak aktif.
+pub async fn cancel(pool: &MySqlPool, jobs: &Jobs, id: i64) -> anyhow::Result<bool> {
+    let res = sqlx::query(
+        "UPDATE analysis_jobs SET status='cancelled' WHERE id=? AND status IN ('running','interrupted')",
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+    if res.rows_affected() == 0 {
+        return Ok(false);
+    }
+    sqlx::query("UPDATE analysis_job_items SET status='cancelled' WHERE job_id=? AND status='pending'")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    jobs.cancel(id);
+    Ok(true)
+}
+
+/// Lanjutkan job yang "interrupted" / "cancelled" dari item yang belum
+/// selesai. Mengembalikan false bila job tidak ada atau masih berjalan.
+pub async fn resume(pool: &MySqlPool, jobs: &Jobs, id: i64) -> anyhow::Result<bool> {
+    let Some(row) = load_row(pool, id).await? else {
+        return Ok(false);
+    };
+    if row.status != "interrupted" && row.status != "cancelled" {
+        return Ok(false);
+    }
+
+    sqlx::query("UPDATE analysis_job_items SET status='pending' WHERE job_id=? AND status='cancelled'")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    let items: Vec<JobItem> = sqlx::query_as(
+        "SELECT file_id, kind FROM analysis_job_items WHERE job_id=? AND status='pending' ORDER BY id",
+    )
+    .bind(id)
+    .fetch_all(pool)
+    .await?;
+    sqlx::query(
+        "UPDATE analysis_jobs SET status=IF(done+skipped+failed>=total, 'done', 'running') WHERE id=?",
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    let Some(mut progress) = load_progress(pool, jobs, id).await? else {
+        return Ok(false);
+    };
+    progress.status = if items.is_empty() { "done".into() } else { "running".into() };
+    // Resume lain yang mendahului sudah mendaftarkan job ini: item yang
+    // sama tidak dijalankan dua kali.
+    Ok(start(pool.clone(), jobs.clone(), progress, items, row.force_refresh))
+}
+
+/// Dipanggil saat startup: job yang masih "running" di DB berarti
+/// terputus oleh restart. Tandai "interrupted", lalu lanjutkan otomatis
+/// kecuali JOBS_AUTO_RESUME=0.
+pub async fn recover(pool: &MySqlPool, jobs: &Jobs) -> anyhow::Result<()> {
+    sqlx::query("UPDATE analysis_jobs SET status='interrupted' WHERE status='running'")
+        .execute(pool)
+        .await?;
+
+    let auto_resume = std::env::var("JOBS_AUTO_RESUME").map(|v| v != "0").unwrap_or(true);
+    if !auto_resume {
+        return Ok(());
+    }
+    let ids: Vec<(i64,)> =
+        sqlx::query_as("SELECT id FROM analysis_jobs WHERE status='interrupted' ORDER BY id")
+            .fetch_all(pool)
+            .await?;
+    for (id,) in ids {
+        if resume(pool, jobs, id).await? {
+            eprintln!("job {id}: dilanjutkan setelah restart");
+        }
+    }
+    Ok(())
+}
+
+/// Stream event job untuk SSE: snapshot progress dulu, lalu event live
+/// sampai job selesai.
+pub fn event_stream(
+    progress: JobProgress,
+    rx: broadcast::Receiver<JobEvent>,
+) -> impl futures_util::Stream<Item = JobEvent> {
+    let finished = progress.status != "running";
+    futures_util::stream::unfold(
+        (Some(JobEvent::Progress(progress)), rx, finished),
+        |(first, mut rx, finished)| async move {
+            if let Some(ev) = first {
+                return Some((ev, (None, rx, finished)));
+            }
+            if finished {
+                return None;
+            }
+            loop {
+                match rx.recv().await {
+                    Ok(ev) => {
+                        let done = matches!(&ev, JobEvent::Progress(p) if p.status != "running");
+                        return Some((ev, (None, rx, done)));
+                    }
+                    // Subscriber tertinggal: lewati event lama, lanjut.
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(id: i64, status: &str) -> JobProgress {
+        JobProgress {
+            id,
+            app_id: 1,
+            job_kind: "file".into(),
+            status: status.into(),
+            lang: "id".into(),
+            requested_by: None,
+            total: 1,
+            done: 0,
+            skipped: 0,
+            failed: 0,
+            errors: Vec::new(),
+            output: String::new(),
+        }
+    }
+
+    #[test]
+    fn cached_results_are_skipped_unless_forced() {
+        let file = JobItem { file_id: Some(7), kind: "fungsi".into() };
+        let summary = JobItem { file_id: None, kind: "summary".into() };
+        assert_eq!(cache_lookup(false, &file), Some(7));
+        assert_eq!(cache_lookup(true, &file), None);
+        assert_eq!(cache_lookup(false, &summary), None);
+
+        // item yang dilewati tetap dihitung sampai job selesai
+        let jobs = JobRegistry::new();
+        let _cancel = jobs.register(JobProgress { total: 2, ..progress(1, "running") }, None);
+        jobs.finish_task(1, Some(7), "fungsi", Outcome::Skipped);
+        assert_eq!(jobs.get(1).unwrap().status, "running");
+        jobs.finish_task(1, Some(8), "fungsi", Outcome::Done);
+        let p = jobs.get(1).unwrap();
+        assert_eq!((p.status.as_str(), p.skipped, p.done), ("done", 1, 1));
+    }
+
+    #[test]
+    fn register_refuses_running_job() {
+        let jobs = JobRegistry::new();
+        assert!(jobs.register(progress(1, "running"), None).is_some());
+        assert!(jobs.register(progress(1, "running"), None).is_none());
+        jobs.cancel(1);
+        assert!(jobs.register(progress(1, "running"), None).is_some());
+    }
+
+    #[test]
+    fn finished_job_drops_output() {
+        let jobs = JobRegistry::new();
+        let _cancel = jobs.register(progress(1, "running"), None);
+        jobs.token(1, Some(5), "bug", "halo");
+        assert_eq!(jobs.get(1).unwrap().output, "halo");
+        jobs.finish_task(1, Some(5), "bug", Outcome::Done);
+        let p = jobs.get(1).unwrap();
+        assert_eq!((p.status.as_str(), p.output.as_str()), ("done", ""));
+    }
+
+    #[test]
+    fn reset_clears_output_and_notifies() {
+        let jobs = JobRegistry::new();
+        let _cancel = jobs.register(progress(1, "running"), None);
+        let (_, mut rx) = jobs.subscribe(1).unwrap();
+        jobs.token(1, Some(5), "bug", "{bad");
+        jobs.reset(1, Some(5), "bug");
+        assert_eq!(jobs.get(1).unwrap().output, "");
+        assert_eq!(rx.try_recv().unwrap().name(), "token");
+        assert_eq!(rx.try_recv().unwrap().name(), "reset");
+    }
+
+    #[test]
+    fn running_file_job_is_found_by_target() {
+        let jobs = JobRegistry::new();
+        let _cancel = jobs.register(progress(7, "running"), Some((5, "bug".into())));
+        assert_eq!(jobs.running_file(5, "bug", "id"), Some(7));
+        assert_eq!(jobs.running_file(5, "bug", "en"), None);
+        assert_eq!(jobs.running_file(5, "security", "id"), None);
+        jobs.finish_task(7, Some(5), "bug", Outcome::Done);
+        assert_eq!(jobs.running_file(5, "bug", "id"), None);
+    }
+
+    #[test]
+    fn expired_jobs_are_evicted() {
+        let jobs = JobRegistry::new();
+        let _ = jobs.register(progress(1, "done"), None);
+        let _ = jobs.register(progress(2, "running"), None);
+        let later = Instant::now() + FINISHED_TTL + Duration::from_secs(1);
+        evict_finished(&mut jobs.jobs.lock().unwrap(), later);
+        assert!(jobs.get(1).is_none());
+        assert!(jobs.get(2).is_some());
+    }
+}
@@ -0,0 +1,224 @@
+// src/kinds.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Registry jenis analisa per file (tabel `analysis_kinds`). Jenis bawaan
+// (fungsi, relasi_file, relasi_db) memakai template di folder prompts/;
+// jenis tambahan (mis. security, performance) membawa prompt awalnya
+// sendiri. Hasil semua jenis disimpan di `analysis_results`
+// (file_id, kind, lang, content). Jenis berformat json boleh membawa JSON
+// Schema; jawabannya divalidasi dan disimpan juga di kolom `structured`
+// (lihat schema.rs).
+
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+use crate::i18n::Locale;
+
+/// Format jawaban yang diminta dari LLM dan cara menampilkannya.
+pub const OUTPUT_FORMATS: [&str; 3] = ["text", "markdown", "json"];
+
+/// Warna badge Bootstrap yang boleh dipakai di halaman detail.
+pub const BADGES: [&str; 7] = ["primary", "secondary", "success", "danger", "warning", "info", "dark"];
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AnalysisKind {
+    pub name: String,
+    pub label: String,
+    /// Template prompt awal (versi 0); NULL untuk jenis bawaan.
+    pub prompt: Option<String>,
+    pub output_format: String,
+    /// JSON Schema jawaban untuk format json (opsional).
+    pub json_schema: Option<String>,
+    pub badge: String,
+    pub sort_order: i32,
+    pub enabled: bool,
+    pub builtin: bool,
+}
+
+impl AnalysisKind {
+    // Untuk <select> di halaman admin.
+    pub fn has_format(&self, f: &str) -> bool {
+        self.output_format == f
+    }
+
+    pub fn has_badge(&self, b: &str) -> bool {
+        self.badge == b
+    }
+
+    pub fn schema_text(&self) -> &str {
+        self.json_schema.as_deref().unwrap_or("")
+    }
+
+    /// Instruksi format yang ditambahkan di akhir prompt.
+    pub fn format_instruction(&self) -> String {
+        match (self.output_format.as_str(), self.json_schema.as_deref()) {
+            ("markdown", _) => "\n\nFormat jawaban dalam Markdown.".to_string(),
+            ("json", Some(schema)) => format!(
+                "\n\nJawab hanya dengan satu objek JSON yang valid sesuai JSON Schema berikut, tanpa teks lain:\n{schema}"
+            ),
+            ("json", None) => "\n\nJawab hanya dengan satu objek JSON yang valid, tanpa teks lain.".to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// Data terstruktur dari jawaban LLM. None untuk format text/markdown;
+    /// Err berisi alasan bila jawaban bukan JSON atau tidak sesuai schema
+    /// (dipakai sebagai umpan balik saat mencoba ulang).
+    pub fn structured_output(&self, raw: &str) -> Result<Option<serde_json::Value>, String> {
+        if self.output_format != "json" {
+            return Ok(None);
+        }
+        let value = crate::schema::extract(raw)?;
+        if let Some(text) = self.json_schema.as_deref() {
+            let schema = crate::schema::parse_schema(text)?;
+            crate::schema::validate(&schema, &value)?;
+        }
+        Ok(Some(value))
+    }
+}
+
+const SELECT: &str =
+    "SELECT name, label, prompt, output_format, json_schema, badge, sort_order, enabled, builtin FROM analysis_kinds";
+
+/// Semua jenis analisa, urut sesuai `sort_order`.
+pub async fn list(pool: &MySqlPool, only_enabled: bool) -> anyhow::Result<Vec<AnalysisKind>> {
+    let q = format!(
+        "{SELECT} {} ORDER BY sort_order, name",
+        if only_enabled { "WHERE enabled=1" } else { "" }
+    );
+    Ok(sqlx::query_as::<_, AnalysisKind>(&q).fetch_all(pool).await?)
+}
+
+pub async fn get(pool: &MySqlPool, name: &str) -> anyhow::Result<Option<AnalysisKind>> {
+    let q = format!("{SELECT} WHERE name=?");
+    Ok(sqlx::query_as::<_, AnalysisKind>(&q)
+        .bind(name)
+        .fetch_optional(pool)
+        .await?)
+}
+
+/// Nama jenis dipakai di URL (/analyze/:file_id/:kind): huruf kecil,
+/// angka dan garis bawah saja.
+pub fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 32
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        // summary, chat & graph adalah nama prompt non-file (lihat prompts.rs)
+        && name != "summary"
+        && name != "chat"
+        && name != "graph"
+}
+
+pub struct KindForm<'a> {
+    pub name: &'a str,
+    pub label: &'a str,
+    pub prompt: Option<&'a str>,
+    pub output_format: &'a str,
+    /// None = tidak diubah; Some("") = hapus schema.
+    pub json_schema: Option<&'a str>,
+    pub badge: &'a str,
+    pub sort_order: i32,
+    pub enabled: bool,
+}
+
+/// Isian form jenis analisa yang ditolak `save`.
+#[derive(Debug)]
+pub enum InvalidKind {
+    Name,
+    Label,
+    /// Alasan dari schema::parse_schema.
+    Schema(String),
+    /// Jenis baru tanpa prompt.
+    Prompt,
+}
+
+impl InvalidKind {
+    pub fn message(&self, loc: Locale) -> String {
+        match self {
+            InvalidKind::Name => loc.t("err.kind_name").to_string(),
+            InvalidKind::Label => loc.t("err.kind_label").to_string(),
+            InvalidKind::Schema(reason) => loc.tf("err.kind_schema", &[("reason", reason)]),
+            InvalidKind::Prompt => loc.t("err.kind_prompt").to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for InvalidKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message(Locale::default()))
+    }
+}
+
+impl std::error::Error for InvalidKind {}
+
+/// Tambah jenis baru atau ubah label/format/urutan jenis yang ada.
+/// Prompt hanya diisi saat jenis dibuat; perubahan berikutnya lewat
+/// editor prompt supaya versinya tercatat.
+pub async fn save(pool: &MySqlPool, f: KindForm<'_>) -> anyhow::Result<()> {
+    if !valid_name(f.name) {
+        return Err(InvalidKind::Name.into());
+    }
+    if f.label.trim().is_empty() {
+        return Err(InvalidKind::Label.into());
+    }
+    let output_format = if OUTPUT_FORMATS.contains(&f.output_format) { f.output_format } else { "text" };
+    let badge = if BADGES.contains(&f.badge) { f.badge } else { "secondary" };
+    let json_schema = f.json_schema.map(str::trim);
+    if let Some(text) = json_schema.filter(|s| !s.is_empty()) {
+        if let Err(e) = crate::schema::parse_schema(text) {
+            return Err(InvalidKind::Schema(e).into());
+        }
+    }
+
+    match get(pool, f.name).await? {
+        Some(_) => {
+            sqlx::query(
+                "UPDATE analysis_kinds SET label=?, output_format=?, badge=?, sort_order=?, enabled=?
+                 WHERE name=?",
+            )
+            .bind(f.label.trim())
+            .bind(output_format)
+            .bind(badge)
+            .bind(f.sort_order)
+            .bind(f.enabled)
+            .bind(f.name)
+            .execute(pool)
+            .await?;
+            if let Some(text) = json_schema {
+                sqlx::query("UPDATE analysis_kinds SET json_schema=? WHERE name=?")
+                    .bind(Some(text).filter(|s| !s.is_empty()))
+                    .bind(f.name)
+                    .execute(pool)
+                    .await?;
+            }
+        }
+        None => {
+            let Some(prompt) = f.prompt.filter(|p| !p.trim().is_empty()) else {
+                return Err(InvalidKind::Prompt.into());
+            };
+            sqlx::query(
+                "INSERT INTO analysis_kinds (name, label, prompt, output_format, json_schema, badge, sort_order, enabled, builtin)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, 0)",
+            )
+            .bind(f.name)
+            .bind(f.label.trim())
+            .bind(prompt)
+            .bind(output_format)
+            .bind(json_schema.filter(|s| !s.is_empty()))
+            .bind(badge)
+            .bind(f.sort_order)
+            .bind(f.enabled)
+            .execute(pool)
+            .await?;
+        }
+    }
+    Ok(())
+}
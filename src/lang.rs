@@ -0,0 +1,120 @@
+// src/lang.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Bahasa output analisa LLM. Urutan prioritas: parameter request (?lang=),
+// setelan aplikasi (applications.output_lang), env OUTPUT_LANG, lalu "id".
+// Hasil analisa & summary disimpan per bahasa, jadi berganti bahasa tidak
+// menimpa hasil bahasa lain.
+
+use sqlx::MySqlPool;
+
+pub struct Language {
+    pub code: &'static str,
+    /// Nama dalam bahasa itu sendiri (untuk pilihan di UI).
+    pub label: &'static str,
+    /// Nama dalam instruksi prompt.
+    pub prompt_name: &'static str,
+    /// Pesan system untuk LLM, dalam bahasa itu sendiri.
+    pub system: &'static str,
+}
+
+pub const LANGUAGES: [Language; 8] = [
+    Language {
+        code: "id",
+        label: "Bahasa Indonesia",
+        prompt_name: "Bahasa Indonesia",
+        system: "Anda adalah code reviewer profesional.",
+    },
+    Language { code: "en", label: "English", prompt_name: "English", system: "You are a professional code reviewer." },
+    Language {
+        code: "ms",
+        label: "Bahasa Melayu",
+        prompt_name: "Bahasa Melayu (Malay)",
+        system: "Anda ialah penyemak kod profesional.",
+    },
+    Language { code: "ja", label: "日本語", prompt_name: "Japanese (日本語)", system: "あなたはプロのコードレビュアーです。" },
+    Language { code: "zh", label: "中文", prompt_name: "Simplified Chinese (简体中文)", system: "你是一名专业的代码审查员。" },
+    Language {
+        code: "es",
+        label: "Español",
+        prompt_name: "Spanish (Español)",
+        system: "Eres un revisor de código profesional.",
+    },
+    Language {
+        code: "fr",
+        label: "Français",
+        prompt_name: "French (Français)",
+        system: "Vous êtes un relecteur de code professionnel.",
+    },
+    Language {
+        code: "de",
+        label: "Deutsch",
+        prompt_name: "German (Deutsch)",
+        system: "Du bist ein professioneller Code-Reviewer.",
+    },
+];
+
+pub fn get(code: &str) -> Option<&'static Language> {
+    LANGUAGES.iter().find(|l| l.code == code)
+}
+
+/// Kode bahasa yang valid (huruf kecil) atau None.
+pub fn normalize(code: Option<&str>) -> Option<&'static str> {
+    let code = code?.trim().to_ascii_lowercase();
+    get(&code).map(|l| l.code)
+}
+
+/// Bahasa default server (OUTPUT_LANG, default "id").
+pub fn default_lang() -> &'static str {
+    normalize(std::env::var("OUTPUT_LANG").ok().as_deref()).unwrap_or("id")
+}
+
+/// Setelan bahasa aplikasi (None bila mengikuti default server).
+pub async fn app_setting(pool: &MySqlPool, app_id: i64) -> anyhow::Result<Option<&'static str>> {
+    let row: Option<(Option<String>,)> = sqlx::query_as("SELECT output_lang FROM applications WHERE id=?")
+        .bind(app_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(normalize(row.and_then(|(l,)| l).as_deref()))
+}
+
+/// Bahasa yang dipakai untuk satu request.
+pub async fn resolve(pool: &MySqlPool, app_id: i64, requested: Option<&str>) -> anyhow::Result<&'static str> {
+    if let Some(l) = normalize(requested) {
+        return Ok(l);
+    }
+    Ok(app_setting(pool, app_id).await?.unwrap_or_else(default_lang))
+}
+
+/// Simpan bahasa default aplikasi; None = ikut default server.
+pub async fn set_app_lang(pool: &MySqlPool, app_id: i64, code: Option<&str>) -> anyhow::Result<()> {
+    sqlx::query("UPDATE applications SET output_lang=? WHERE id=?")
+        .bind(normalize(code))
+        .bind(app_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Nama bahasa untuk variabel `{{bahasa}}` di template prompt.
+pub fn prompt_name(code: &str) -> &'static str {
+    get(code).map(|l| l.prompt_name).unwrap_or("Bahasa Indonesia")
+}
+
+/// Pesan system LLM untuk bahasa output `code`.
+pub fn system_message(code: &str) -> &'static str {
+    get(code).map(|l| l.system).unwrap_or(LANGUAGES[0].system)
+}
+
+/// Instruksi bahasa yang ditambahkan di akhir setiap prompt analisa.
+pub fn instruction(code: &str) -> String {
+    format!("\n\nTulis seluruh jawaban dalam {}.", prompt_name(code))
+}
@@ -10,6 +10,8 @@ cargo run
 /rust/codereview/cargo build
 
 */
+// Rantai `.or(...)` route cukup panjang untuk batas rekursi default compiler
+#![recursion_limit = "256"]
 // src/main.rs
 /*
 cd /rust/codereview
@@ -30,6 +32,30 @@ mod services;
 mod openai;
 mod handlers;
 mod models;
+mod jobs;
+mod usage;
+mod budget;
+mod prompts;
+mod kinds;
+mod lang;
+mod schema;
+mod imports;
+mod context;
+mod i18n;
+mod chat;
+mod semantic;
+mod fulltext;
+mod graph;
+mod depgraph;
+mod architecture;
+mod dbschema;
+mod export;
+mod render;
+mod assets;
+mod callgraph;
+mod symbols;
+mod highlight;
+mod auth;
 
 use std::convert::Infallible;
 use warp::{http::StatusCode, Filter, Rejection, Reply};
@@ -39,6 +65,10 @@ use std::collections::HashMap; // ⟵ TAMBAH
 async fn main() {
     dotenv::dotenv().ok();
     let pool = db::init_pool().await;
+    let jobs = jobs::JobRegistry::new();
+    if let Err(e) = jobs::recover(&pool, &jobs).await {
+        eprintln!("Gagal memulihkan job yang terputus: {e:?}");
+    }
 
     // GET /health
     let health = warp::path!("health")
@@ -51,14 +81,24 @@ async fn main() {
         .and(warp::get())
         .map(|| warp::reply::with_status("no favicon", StatusCode::NOT_FOUND));
 
+    // GET /static/<path>?v=  ← aset front-end yang ditanam di binary (lihat assets.rs)
+    let static_assets = warp::path("static")
+        .and(warp::path::tail())
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(warp::header::optional::<String>("if-none-match"))
+        .and_then(handlers::static_asset);
+
     // GET /
     let index = warp::path::end()
         .and(with_db(pool.clone()))
-        .and_then(|pool| handlers::list_apps(pool));
+        .and(with_locale())
+        .and_then(handlers::list_apps);
 
     // GET /upload
     let upload_page = warp::path("upload")
         .and(warp::get())
+        .and(with_locale())
         .and_then(handlers::upload_page);
 
     // POST /upload
@@ -66,6 +106,7 @@ async fn main() {
         .and(warp::post())
         .and(warp::multipart::form().max_length(100_000_000))
         .and(with_db(pool.clone()))
+        .and(with_locale())
         .and_then(handlers::upload_zip);
 
     // GET /apps  (WAJIB pakai path::end() supaya tak bentrok dengan /apps/:id)
@@ -73,44 +114,63 @@ async fn main() {
         .and(warp::path::end())
         .and(warp::get())
         .and(with_db(pool.clone()))
+        .and(with_locale())
         .and_then(handlers::list_apps);
 
   let app_detail = warp::path!("apps" / i32)
     .and(warp::get())
     .and(warp::query::<HashMap<String, String>>())
     .and(with_db(pool.clone()))
-    .and_then(|id, qs: HashMap<String,String>, pool| {
+    .and(with_locale())
+    .and_then(|id, qs: HashMap<String,String>, pool, loc| {
         let page = qs.get("page").and_then(|v| v.parse::<usize>().ok());
         let q = qs.get("q").cloned();
-        let qobj = handlers::PageQ { page, q };
-        handlers::app_detail(id, qobj, pool)
+        let lang = qs.get("lang").cloned();
+        let qobj = handlers::PageQ { page, q, lang };
+        handlers::app_detail(id, qobj, pool, loc)
     });
 
 
 
-    // GET /analyze/:file_id/:kind
+    // GET /analyze/:file_id/:kind?lang=
     let analyze = warp::path!("analyze" / i32 / String)
         .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
         .and(with_db(pool.clone()))
-        .and_then(|id, kind, pool| handlers::analyze_file(id, kind, pool, false));
+        .and(with_jobs(jobs.clone()))
+        .and(with_user())
+        .and(with_locale())
+        .and_then(|id, kind, qs, pool, jobs, user, loc| handlers::analyze_file(id, kind, qs, pool, jobs, user, loc, false));
 
-    // GET /analyze/:file_id/:kind/force
+    // GET /analyze/:file_id/:kind/force?lang=
     let analyze_force = warp::path!("analyze" / i32 / String / "force")
         .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
         .and(with_db(pool.clone()))
-        .and_then(|id, kind, pool| handlers::analyze_file(id, kind, pool, true));
+        .and(with_jobs(jobs.clone()))
+        .and(with_user())
+        .and(with_locale())
+        .and_then(|id, kind, qs, pool, jobs, user, loc| handlers::analyze_file(id, kind, qs, pool, jobs, user, loc, true));
 
-    // GET /apps/:id/summary
+    // GET /apps/:id/summary  (belum ada summary → halaman perkiraan biaya, ?confirm=1 untuk jalan)
     let summary = warp::path!("apps" / i32 / "summary")
         .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
         .and(with_db(pool.clone()))
-        .and_then(|id, pool| handlers::app_summary(id, pool, false));
+        .and(with_jobs(jobs.clone()))
+        .and(with_user())
+        .and(with_locale())
+        .and_then(|id, qs, pool, jobs, user, loc| handlers::app_summary(id, qs, pool, jobs, user, loc, false));
 
     // GET /apps/:id/summary/force
     let summary_force = warp::path!("apps" / i32 / "summary" / "force")
         .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
         .and(with_db(pool.clone()))
-        .and_then(|id, pool| handlers::app_summary(id, pool, true));
+        .and(with_jobs(jobs.clone()))
+        .and(with_user())
+        .and(with_locale())
+        .and_then(|id, qs, pool, jobs, user, loc| handlers::app_summary(id, qs, pool, jobs, user, loc, true));
 
     // src/main.rs (di dalam main())
 
@@ -118,7 +178,9 @@ async fn main() {
 // GET /apps/:id/analysis
     let analysis_all = warp::path!("apps" / i32 / "analysis")
         .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
         .and(with_db(pool.clone()))
+        .and(with_locale())
         .and_then(handlers::app_analysis_all);
 
   // GET /api/analysis/:file_id/:kind
@@ -126,37 +188,390 @@ async fn main() {
 
     let api_analysis = warp::path!("api" / "analysis" / i32 / String)
     .and(warp::get())
+    .and(warp::query::<HashMap<String, String>>())
     .and(with_db(pool.clone()))
+    .and(with_locale())
     .and_then(handlers::api_get_analysis);
 
+    // GET /api/apps/:id/structured/:kind?lang=&contains=  ← data JSON jenis analisa berformat json
+    let api_structured = warp::path!("api" / "apps" / i32 / "structured" / String)
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_structured);
+
+    // GET /apps/:id/chat?c=  ← chat tentang kode aplikasi (c = id percakapan)
+    let chat_page = warp::path!("apps" / i32 / "chat")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::chat_page);
+
+    // POST /api/apps/:id/chat  ← form: question, conversation_id, lang
+    let api_chat_ask = warp::path!("api" / "apps" / i32 / "chat")
+        .and(warp::post())
+        .and(warp::body::form::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_user())
+        .and(with_locale())
+        .and_then(handlers::api_chat_ask);
+
+    // GET /api/apps/:id/chat/:conversation_id  ← riwayat percakapan
+    let api_chat_history = warp::path!("api" / "apps" / i32 / "chat" / i64)
+        .and(warp::get())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_chat_history);
+
+    // GET /apps/:id/search?q=&regex=&case=&language=&folder=&scope=  ← pencarian teks penuh
+    let app_search = warp::path!("apps" / i32 / "search")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::app_search);
+
+    // GET /api/apps/:id/search?...  ← hasil yang sama dalam JSON
+    let api_app_search = warp::path!("api" / "apps" / i32 / "search")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_app_search);
+
+    // GET /search?q=...  ← pencarian lintas semua aplikasi
+    let global_search = warp::path("search")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::global_search);
+
+    // GET /api/search?...  ← hasil yang sama dalam JSON, per aplikasi
+    let api_global_search = warp::path!("api" / "search")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_global_search);
+
+    // GET /apps/:id/graph?folder=&neighbors=1&snapshot=  ← graph dependensi modul aplikasi
+    let app_graph = warp::path!("apps" / i32 / "graph")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::app_graph);
+
+    // GET /api/apps/:id/graph?format=  ← sama, dalam JSON atau unduhan svg|png|dot|mermaid|graphml|json
+    let api_app_graph = warp::path!("api" / "apps" / i32 / "graph")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_app_graph);
+
+    // GET /apps/:id/architecture  ← siklus import + aturan layering
+    let architecture_page = warp::path!("apps" / i32 / "architecture")
+        .and(warp::get())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::architecture_page);
+
+    // POST /apps/:id/layer_rules  ← tambah aturan layering
+    let layer_rule_add = warp::path!("apps" / i32 / "layer_rules")
+        .and(warp::post())
+        .and(warp::body::form::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::layer_rule_add);
+
+    // POST /apps/:id/layer_rules/:rule_id/delete
+    let layer_rule_delete = warp::path!("apps" / i32 / "layer_rules" / i64 / "delete")
+        .and(warp::post())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::layer_rule_delete);
+
+    // GET /api/apps/:id/architecture  ← temuan dalam JSON
+    let api_architecture = warp::path!("api" / "apps" / i32 / "architecture")
+        .and(warp::get())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_architecture);
+
+    // GET /apps/:id/schema  ← diagram ER + matriks tabel × file
+    let schema_page = warp::path!("apps" / i32 / "schema")
+        .and(warp::get())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::schema_page);
+
+    // GET /api/apps/:id/schema?format=  ← model skema dalam JSON atau unduhan svg|png|dot|mermaid|graphml|json
+    let api_app_schema = warp::path!("api" / "apps" / i32 / "schema")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_app_schema);
+
+    // GET /apps/:id/calls?q=&symbol=&depth=&dir=  ← caller/callee + call graph berakar di satu fungsi
+    let calls_page = warp::path!("apps" / i32 / "calls")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::calls_page);
+
+    // GET /api/apps/:id/calls?symbol=&depth=&dir=&q=&format=  ← sama dalam JSON atau unduhan svg|png|dot|mermaid|graphml|json
+    let api_app_calls = warp::path!("api" / "apps" / i32 / "calls")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_app_calls);
+
+    // GET /apps/:id/symbols?q=&kind=  ← cari definisi fungsi/class/struct/method di seluruh aplikasi
+    let symbols_page = warp::path!("apps" / i32 / "symbols")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::symbols_page);
+
+    // GET /api/apps/:id/symbols?q=&kind=&limit=  ← sama dalam JSON
+    let api_app_symbols = warp::path!("api" / "apps" / i32 / "symbols")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_app_symbols);
+
+    // GET /files/:id?lang=  ← penampil file: kode ber-highlight, anchor #L10-L20, outline & analisa
+    let file_page = warp::path!("files" / i32)
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::file_page);
+
+    // GET /files/:id/symbols  ← outline definisi satu file
+    let file_symbols = warp::path!("files" / i32 / "symbols")
+        .and(warp::get())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::file_symbols_page);
+
+    // GET /api/files/:id/symbols  ← outline dalam JSON
+    let api_file_symbols = warp::path!("api" / "files" / i32 / "symbols")
+        .and(warp::get())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_file_symbols);
+
+    // GET /apps/:id/semantic_search?q=&limit=  ← pencarian semantik (BM25 / embedding)
+    let semantic_search = warp::path!("apps" / i32 / "semantic_search")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_user())
+        .and(with_locale())
+        .and_then(handlers::api_semantic_search);
+
     // ==== BARU: generate graph dan view graph ====
     // POST /files/:id/generate_graph
     let generate_graph = warp::path!("files" / i32 / "generate_graph")
         .and(warp::post())
         .and(with_db(pool.clone()))
+        .and(with_user())
+        .and(with_locale())
         .and_then(handlers::generate_graph);
 
     // GET /files/:id/graph
     let view_graph = warp::path!("files" / i32 / "graph")
         .and(warp::get())
         .and(with_db(pool.clone()))
+        .and(with_locale())
         .and_then(handlers::view_graph);
 
+    // GET /api/files/:id/graph?format=  ← dokumen graph (nodes/edges) tervalidasi; format=svg|png|dot|mermaid|graphml|json = unduhan
+    let api_file_graph = warp::path!("api" / "files" / i32 / "graph")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_file_graph);
+
+    // POST /apps/:id/analyze_all  ← analisa bulk seluruh aplikasi (form: scope, q, force)
+    let analyze_all = warp::path!("apps" / i32 / "analyze_all")
+        .and(warp::post())
+        .and(warp::body::form::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_jobs(jobs.clone()))
+        .and(with_user())
+        .and(with_locale())
+        .and_then(handlers::analyze_all);
+
+    // GET /api/jobs/:id  ← progress job bulk
+    let api_job_status = warp::path!("api" / "jobs" / i64)
+        .and(warp::get())
+        .and(with_db(pool.clone()))
+        .and(with_jobs(jobs.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_job_status);
+
+    // POST /api/jobs/:id/cancel  ← hentikan job (item tersisa tidak dijalankan)
+    let api_job_cancel = warp::path!("api" / "jobs" / i64 / "cancel")
+        .and(warp::post())
+        .and(with_db(pool.clone()))
+        .and(with_jobs(jobs.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_job_cancel);
+
+    // POST /api/jobs/:id/resume  ← lanjutkan job cancelled / interrupted
+    let api_job_resume = warp::path!("api" / "jobs" / i64 / "resume")
+        .and(warp::post())
+        .and(with_db(pool.clone()))
+        .and(with_jobs(jobs.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_job_resume);
+
+    // GET /jobs/:id/events  ← SSE progress + token LLM
+    let job_events = warp::path!("jobs" / i64 / "events")
+        .and(warp::get())
+        .and(with_db(pool.clone()))
+        .and(with_jobs(jobs.clone()))
+        .and(with_locale())
+        .and_then(handlers::job_events);
+
+    // GET /usage  ← dashboard pemakaian & biaya LLM (?days=30&app_id=)
+    let usage_page = warp::path("usage")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::usage_page);
+
+    // GET /api/usage  ← JSON total per hari / aplikasi
+    let api_usage = warp::path!("api" / "usage")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and_then(handlers::api_usage);
+
+    // POST /usage/budgets  ← simpan / hapus budget LLM
+    let save_budget = warp::path!("usage" / "budgets")
+        .and(warp::post())
+        .and(with_admin())
+        .and(warp::body::form::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::save_budget);
+
+    // GET /api/apps/:id/estimate?kind=bulk|summary  ← perkiraan token & biaya sebelum jalan
+    let api_estimate = warp::path!("api" / "apps" / i32 / "estimate")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::api_estimate);
+
+    // GET /admin/kinds  ← registry jenis analisa (bawaan + tambahan)
+    let admin_kinds = warp::path!("admin" / "kinds")
+        .and(warp::get())
+        .and(with_admin())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::admin_kinds);
+
+    // POST /admin/kinds  ← tambah / ubah jenis analisa
+    let admin_kind_save = warp::path!("admin" / "kinds")
+        .and(warp::post())
+        .and(with_admin())
+        .and(warp::body::form::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::admin_kind_save);
+
+    // GET /admin/prompts  ← daftar template prompt + versi aktif
+    let admin_prompts = warp::path!("admin" / "prompts")
+        .and(warp::get())
+        .and(with_admin())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::admin_prompts);
+
+    // GET /admin/prompts/:name  ← edit + riwayat versi
+    let admin_prompt_edit = warp::path!("admin" / "prompts" / String)
+        .and(warp::get())
+        .and(with_admin())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::admin_prompt_edit);
+
+    // POST /admin/prompts/:name  ← simpan versi baru
+    let admin_prompt_save = warp::path!("admin" / "prompts" / String)
+        .and(warp::post())
+        .and(with_admin())
+        .and(warp::body::form::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_user())
+        .and(with_locale())
+        .and_then(handlers::admin_prompt_save);
+
+    // POST /admin/prompts/:name/preview  ← render template dengan contoh data
+    let admin_prompt_preview = warp::path!("admin" / "prompts" / String / "preview")
+        .and(warp::post())
+        .and(with_admin())
+        .and(warp::body::form::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and(with_locale())
+        .and_then(handlers::admin_prompt_preview);
+
    // GET /api/apps/:id/summary_preview  ← ringkasan 50 kata untuk modal
 let api_app_summary_preview = warp::path!("api" / "apps" / i32 / "summary_preview")
     .and(warp::get())
+    .and(warp::query::<HashMap<String, String>>())
     .and(with_db(pool.clone()))
+    .and(with_locale())
     .and_then(handlers::api_get_app_summary_preview);
 
 // GET /api/apps/:id/summary  ← konten utuh untuk modal
 let api_app_summary_full = warp::path!("api" / "apps" / i32 / "summary")
     .and(warp::get())
+    .and(warp::query::<HashMap<String, String>>())
     .and(with_db(pool.clone()))
+    .and(with_locale())
     .and_then(handlers::api_get_app_summary_full);
 
+    // POST /apps/:id/lang  ← bahasa output default aplikasi
+    let set_app_lang = warp::path!("apps" / i32 / "lang")
+        .and(warp::post())
+        .and(warp::body::form::<HashMap<String, String>>())
+        .and(with_db(pool.clone()))
+        .and_then(handlers::set_app_lang);
+
+
+    // POST /admin/login  ← simpan token admin di cookie lalu kembali ke halaman admin
+    let admin_login = warp::path!("admin" / "login")
+        .and(warp::post())
+        .and(warp::body::form::<HashMap<String, String>>())
+        .and(with_locale())
+        .and_then(handlers::admin_login);
+
+    // GET /locale/:code  ← simpan bahasa tampilan di cookie lalu kembali ke halaman asal
+    let set_locale = warp::path!("locale" / String)
+        .and(warp::get())
+        .and(warp::header::optional::<String>("referer"))
+        .and_then(handlers::set_locale);
 
     // SATU-SATUNYA komposisi routes
     let routes = favicon
+        .or(static_assets)
         .or(health)
         .or(index)
         .or(upload_page)
@@ -166,13 +581,56 @@ let api_app_summary_full = warp::path!("api" / "apps" / i32 / "summary")
         .or(analysis_all)     // ⟵ masukkan route baru di sini
         .or(analyze)
         .or(api_analysis)
+        .or(api_structured)
+        .or(chat_page)
+        .or(api_chat_ask)
+        .or(api_chat_history)
+        .or(semantic_search)
+        .or(app_graph)
+        .or(api_app_graph)
+        .or(architecture_page)
+        .or(layer_rule_add)
+        .or(layer_rule_delete)
+        .or(api_architecture)
+        .or(schema_page)
+        .or(api_app_schema)
+        .or(calls_page)
+        .or(api_app_calls)
+        .or(symbols_page)
+        .or(api_app_symbols)
+        .or(file_page)
+        .or(file_symbols)
+        .or(api_file_symbols)
+        .or(app_search)
+        .or(api_app_search)
+        .or(global_search)
+        .or(api_global_search)
         .or(generate_graph)
         .or(view_graph)
+        .or(api_file_graph)
         .or(analyze_force)
         .or(summary)
         .or(summary_force)
         .or(api_app_summary_full)   // ⟵ tambah ini
         .or(api_app_summary_preview)   // ⟵ tambahkan ini
+        .or(analyze_all)
+        .or(api_job_status)
+        .or(job_events)
+        .or(api_job_cancel)
+        .or(api_job_resume)
+        .or(usage_page)
+        .or(api_usage)
+        .or(save_budget)
+        .or(api_estimate)
+        .or(admin_kinds)
+        .or(admin_kind_save)
+        .or(admin_prompts)
+        .or(admin_prompt_edit)
+        .or(admin_prompt_save)
+        .or(admin_prompt_preview)
+        .or(set_app_lang)
+        .or(set_locale)
+        .or(admin_login)
         .recover(handle_rejection)
         .with(warp::log("code_review_ssr"));
 
@@ -191,17 +649,80 @@ fn with_db(
     warp::any().map(move || pool.clone())
 }
 
-async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
+fn with_jobs(
+    jobs: jobs::Jobs,
+) -> impl Filter<Extract = (jobs::Jobs,), Error = Infallible> + Clone {
+    warp::any().map(move || jobs.clone())
+}
+
+// Nama user pemicu panggilan LLM (untuk atribusi biaya), mis. diisi
+// reverse proxy yang melakukan autentikasi.
+fn with_user() -> impl Filter<Extract = (Option<String>,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("x-user")
+}
+
+// Rute admin (lihat auth.rs): token dari header Authorization / X-Admin-Token
+// atau cookie login harus cocok dengan ADMIN_TOKEN.
+fn with_admin() -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    let credentials = warp::header::optional::<String>("authorization")
+        .and(warp::header::optional::<String>("x-admin-token"))
+        .and(warp::cookie::optional::<String>(auth::COOKIE));
+    // Setelah login: halaman itu sendiri untuk GET, halaman asal form untuk POST
+    let back = warp::method()
+        .and(warp::path::full())
+        .and(warp::header::optional::<String>("referer"))
+        .map(|method: warp::http::Method, path: warp::path::FullPath, referer: Option<String>| {
+            if method == warp::http::Method::GET {
+                path.as_str().to_string()
+            } else {
+                handlers::local_back(referer.as_deref())
+            }
+        });
+    credentials
+        .and(back)
+        .and(with_locale())
+        .and_then(
+            |bearer: Option<String>, header: Option<String>, cookie: Option<String>, back: String, loc| async move {
+                let presented = [bearer.as_deref(), header.as_deref(), cookie.as_deref()];
+                if auth::authorized(auth::admin_token().as_deref(), &presented) {
+                    Ok(())
+                } else {
+                    Err(warp::reject::custom(auth::Forbidden { loc, back }))
+                }
+            },
+        )
+        .untuple_one()
+}
+
+// Bahasa tampilan (lihat i18n.rs): ?locale=, cookie, lalu Accept-Language.
+fn with_locale() -> impl Filter<Extract = (i18n::Locale,), Error = Rejection> + Clone {
+    warp::query::<HashMap<String, String>>()
+        .or(warp::any().map(HashMap::new))
+        .unify()
+        .and(warp::cookie::optional::<String>(i18n::COOKIE))
+        .and(warp::header::optional::<String>("accept-language"))
+        .map(|qs: HashMap<String, String>, cookie: Option<String>, accept: Option<String>| {
+            i18n::detect(qs.get("locale").map(String::as_str), cookie.as_deref(), accept.as_deref())
+        })
+}
+
+async fn handle_rejection(err: Rejection) -> Result<warp::reply::Response, Infallible> {
+    if let Some(auth::Forbidden { loc, back }) = err.find() {
+        let page = warp::reply::html(auth::forbidden_page(*loc, back));
+        return Ok(warp::reply::with_status(page, StatusCode::FORBIDDEN).into_response());
+    }
     eprintln!("Rejection: {:?}", err);
-    let msg = "Terjadi kesalahan di server (lihat log).";
-    Ok(warp::reply::with_status(msg, StatusCode::INTERNAL_SERVER_ERROR))
+    let loc = err.find::<i18n::ServerError>().map(|e| e.0).unwrap_or_default();
+    let msg = loc.t("err.server");
+    Ok(warp::reply::with_status(msg, StatusCode::INTERNAL_SERVER_ERROR).into_response())
 }
 
 async fn api_recover(err: Rejection) -> Result<impl Reply, Infallible> {
     eprintln!("API Rejection: {:?}", err);
+    let loc = err.find::<i18n::ServerError>().map(|e| e.0).unwrap_or_default();
     let body = serde_json::json!({
         "error": "rejected",
-        "message": "Terjadi kesalahan di server (api)."
+        "message": loc.t("err.server_api")
     });
     Ok(warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR))
 }
@@ -3,6 +3,15 @@ use askama::Template;
 use serde::Serialize;
 use chrono::{DateTime, Utc};
 
+use crate::i18n::Locale;
+
+// Filter askama: {{ "vendor/bootstrap/bootstrap.min.css"|asset|safe }} → URL aset (lihat assets.rs)
+mod filters {
+    pub fn asset<T: std::fmt::Display>(path: T) -> ::askama::Result<String> {
+        Ok(crate::assets::url(&path.to_string()))
+    }
+}
+
 #[derive(Debug, Serialize, sqlx::FromRow)]
 pub struct AppRow {
     pub id: i64,
@@ -15,11 +24,14 @@ pub struct AppRow {
 pub struct IndexPage<'a> {
     pub apps: &'a [AppRow],
     pub json: String,
+    pub loc: Locale,
 }
 
 #[derive(Template)]
 #[template(path="upload.html")]
-pub struct UploadPage;
+pub struct UploadPage {
+    pub loc: Locale,
+}
 
 #[derive(Debug, Serialize)]
 pub struct FileWithAnalyses {
@@ -29,13 +41,19 @@ pub struct FileWithAnalyses {
     pub nama_folder: Option<String>,
     pub full_path: String,
     pub line_count: Option<i32>,
-    pub fungsi_preview: Option<String>,
-    pub relasi_file_preview: Option<String>,
-    pub relasi_db_preview: Option<String>,
+    pub analyses: Vec<AnalysisPreview>, // ⟵ satu per jenis analisa aktif
     pub has_graph: bool,
      pub row_no: Option<i64>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct AnalysisPreview {
+    pub kind: String,
+    pub label: String,
+    pub badge: String,
+    pub preview: Option<String>,
+}
+
 pub struct Pagination {
     pub page: usize,
     pub per_page: usize,
@@ -53,6 +71,22 @@ pub struct Pagination {
     pub to: i64,   // index akhir di halaman ini
 }
 
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct JobRow {
+    pub id: i64,
+    pub app_id: i64,
+    pub job_kind: String,
+    pub status: String,
+    pub lang: String,
+    pub requested_by: Option<String>,
+    pub force_refresh: bool,
+    pub total: i32,
+    pub done: i32,
+    pub skipped: i32,
+    pub failed: i32,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Template)]
 #[template(path="detail.html")]
 pub struct DetailPage<'a> {
@@ -60,6 +94,12 @@ pub struct DetailPage<'a> {
     pub files: &'a [FileWithAnalyses],
     pub pagination: Pagination, // ⟵ BARU
     pub search: Option<String>,
+    pub jobs: &'a [JobRow],     // ⟵ riwayat job analisa
+    pub kinds: &'a [crate::kinds::AnalysisKind],
+    pub lang: &'a str,          // bahasa hasil analisa yang ditampilkan
+    pub app_lang: &'a str,      // setelan aplikasi ("" = default server)
+    pub languages: &'a [crate::lang::Language],
+    pub loc: Locale,
 }
 
 #[derive(Template)]
@@ -69,6 +109,8 @@ pub struct AnalysisPage <'a> {
     pub content: &'a str,
     pub back_href: &'a str,
     pub force_href: Option<&'a str>,
+    pub job_id: Option<i64>, // ⟵ Some = hasil sedang di-stream dari job ini
+    pub loc: Locale,
 }
 
 #[derive(Debug, Serialize, sqlx::FromRow)]
@@ -101,22 +143,504 @@ pub struct AnalysisJoinRow {
     pub file_id: i64,
     pub nama_file: String,
     pub full_path: String,
-    pub analisa_fungsi: Option<String>,
-    pub analisa_relasi_file: Option<String>,
-    pub analisa_relasi_db: Option<String>,
+    /// Hasil per jenis, urut sama dengan `AnalysisAllPage::kinds`.
+    #[sqlx(skip)]
+    pub results: Vec<Option<String>>,
 }
 
 #[derive(Template)]
 #[template(path="analysis_all.html")]
 pub struct AnalysisAllPage<'a> {
     pub app: &'a AppRow,
+    pub kinds: &'a [crate::kinds::AnalysisKind],
     pub rows: &'a [AnalysisJoinRow],
+    pub lang: &'a str,
+    pub languages: &'a [crate::lang::Language],
+    pub loc: Locale,
 }
 
 #[derive(Template)]
 #[template(path="graph.html")]
 pub struct GraphPage<'a> {
     pub app: &'a AppRow,
+    pub file_id: i32,
     pub file_name: &'a str,
-    pub graph_js: &'a str,
+    /// Dokumen graph tervalidasi (JSON), dirender ter-escape di atribut data-graph.
+    pub graph_json: String,
+    pub loc: Locale,
+}
+
+impl GraphPage<'_> {
+    fn export_api(&self) -> String {
+        format!("/api/files/{}/graph", self.file_id)
+    }
+
+    pub fn export_links(&self) -> Vec<(&'static str, String)> {
+        crate::export::links(&self.export_api())
+    }
+
+    /// SVG dari server, dipakai bila vis-network tidak bisa dimuat.
+    pub fn svg_url(&self) -> String {
+        crate::export::url(&self.export_api(), "svg")
+    }
+}
+
+#[derive(Template)]
+#[template(path="usage.html")]
+pub struct UsagePage<'a> {
+    pub report: &'a crate::usage::UsageReport,
+    pub budgets: &'a [crate::budget::BudgetStatus],
+    pub apps: &'a [AppRow],
+    pub loc: Locale,
+}
+
+#[derive(Template)]
+#[template(path="estimate.html")]
+pub struct EstimatePage<'a> {
+    pub title: &'a str,
+    pub estimate: &'a crate::budget::Estimate,
+    pub confirm_href: &'a str,
+    pub back_href: &'a str,
+    pub loc: Locale,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PromptListRow {
+    pub name: String,
+    pub label: String,
+    pub version_label: String,
+    pub outdated: i64,
+}
+
+#[derive(Template)]
+#[template(path="prompts.html")]
+pub struct PromptsPage<'a> {
+    pub rows: &'a [PromptListRow],
+    pub loc: Locale,
+}
+
+#[derive(Template)]
+#[template(path="prompt_edit.html")]
+pub struct PromptEditPage<'a> {
+    pub name: &'a str,
+    pub label: &'a str,
+    pub vars: &'a [&'a str],
+    pub active: &'a crate::prompts::ActivePrompt,
+    pub default_template: &'a str,
+    pub history: &'a [crate::prompts::PromptVersion],
+    pub loc: Locale,
+}
+
+#[derive(Template)]
+#[template(path="kinds.html")]
+pub struct KindsPage<'a> {
+    pub kinds: &'a [crate::kinds::AnalysisKind],
+    pub formats: &'a [&'a str],
+    pub badges: &'a [&'a str],
+    pub error: Option<&'a str>,
+    pub loc: Locale,
+}
+
+/// Satu pesan chat beserta potongan teks/sitasinya untuk ditampilkan.
+pub struct ChatMessageView {
+    pub message: crate::chat::Message,
+    pub segments: Vec<crate::chat::Segment>,
+    pub sources: Vec<(crate::chat::Source, String)>, // (sumber, href ke baris file)
+}
+
+#[derive(Template)]
+#[template(path="chat.html")]
+pub struct ChatPage<'a> {
+    pub app: &'a AppRow,
+    pub conversations: &'a [crate::chat::Conversation],
+    pub current: Option<i64>,
+    pub messages: &'a [ChatMessageView],
+    pub lang: &'a str,
+    pub languages: &'a [crate::lang::Language],
+    pub loc: Locale,
+}
+
+impl ChatPage<'_> {
+    pub fn is_current(&self, id: &i64) -> bool {
+        self.current == Some(*id)
+    }
+}
+
+#[derive(Template)]
+#[template(path="search.html")]
+pub struct SearchPage<'a> {
+    /// None = pencarian lintas semua aplikasi (/search)
+    pub app: Option<&'a AppRow>,
+    pub opts: &'a crate::fulltext::Options,
+    pub report: Option<&'a crate::fulltext::Report>,
+    pub groups: &'a [crate::fulltext::AppGroup],
+    pub error: Option<&'a str>,
+    pub languages: &'a [&'a str],
+    pub loc: Locale,
+}
+
+impl SearchPage<'_> {
+    pub fn action(&self) -> String {
+        match self.app {
+            Some(a) => format!("/apps/{}/search", a.id),
+            None => "/search".to_string(),
+        }
+    }
+
+    pub fn is_language(&self, l: &str) -> bool {
+        self.opts.language.as_deref() == Some(l)
+    }
+
+    /// Parameter pencarian saat ini (untuk tautan ke halaman lain).
+    pub fn query_string(&self) -> String {
+        let o = self.opts;
+        let mut pairs = vec![("q", o.query.clone()), ("scope", o.scope.clone())];
+        if o.regex {
+            pairs.push(("regex", "1".into()));
+        }
+        if o.case_sensitive {
+            pairs.push(("case", "1".into()));
+        }
+        if let Some(l) = &o.language {
+            pairs.push(("language", l.clone()));
+        }
+        if let Some(f) = &o.folder {
+            pairs.push(("folder", f.clone()));
+        }
+        pairs
+            .into_iter()
+            .map(|(k, v)| format!("{k}={}", crate::services::urlencode(&v)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    pub fn summary(&self, r: &crate::fulltext::Report) -> String {
+        let files = match self.app {
+            Some(_) => r.files.len(),
+            None => self.groups.iter().map(|g| g.files.len()).sum(),
+        };
+        self.loc.tf(
+            "search.summary",
+            &[("matches", &r.total_matches.to_string()), ("files", &files.to_string())],
+        )
+    }
+
+    pub fn group_summary(&self, g: &crate::fulltext::AppGroup) -> String {
+        self.loc.tf(
+            "search.summary",
+            &[("matches", &g.match_count().to_string()), ("files", &g.files.len().to_string())],
+        )
+    }
+
+    pub fn more_lines(&self, n: &usize) -> String {
+        self.loc.tf("search.more_lines", &[("n", &n.to_string())])
+    }
+}
+
+#[derive(Template)]
+#[template(path="app_graph.html")]
+pub struct AppGraphPage<'a> {
+    pub app: &'a AppRow,
+    pub snapshot: &'a crate::depgraph::Snapshot,
+    pub snapshots: &'a [crate::depgraph::SnapshotInfo],
+    /// Graph setelah filter folder.
+    pub graph: &'a crate::depgraph::DepGraph,
+    /// JSON `graph`, dirender ter-escape di atribut data-graph.
+    pub graph_json: String,
+    pub folder: &'a str,
+    pub neighbors: bool,
+    pub loc: Locale,
+}
+
+impl AppGraphPage<'_> {
+    pub fn is_folder(&self, f: &str) -> bool {
+        self.folder == f
+    }
+
+    pub fn is_snapshot(&self, id: &i64) -> bool {
+        self.snapshot.id == *id
+    }
+
+    /// URL API graph yang sedang tampil (snapshot + filter folder yang sama).
+    fn export_api(&self) -> String {
+        let mut url = format!("/api/apps/{}/graph?snapshot={}", self.app.id, self.snapshot.id);
+        if !self.folder.is_empty() {
+            url.push_str(&format!("&folder={}", crate::services::urlencode(self.folder)));
+            if self.neighbors {
+                url.push_str("&neighbors=1");
+            }
+        }
+        url
+    }
+
+    pub fn export_links(&self) -> Vec<(&'static str, String)> {
+        crate::export::links(&self.export_api())
+    }
+
+    pub fn svg_url(&self) -> String {
+        crate::export::url(&self.export_api(), "svg")
+    }
+
+    pub fn summary(&self) -> String {
+        self.loc.tf(
+            "depgraph.summary",
+            &[
+                ("files", &self.graph.nodes.len().to_string()),
+                ("edges", &self.graph.edges.len().to_string()),
+                ("external", &self.graph.external_imports.to_string()),
+            ],
+        )
+    }
+}
+
+#[derive(Template)]
+#[template(path="architecture.html")]
+pub struct ArchitecturePage<'a> {
+    pub app: &'a AppRow,
+    pub report: &'a crate::architecture::Report,
+    pub error: Option<&'a str>,
+    pub loc: Locale,
+}
+
+impl ArchitecturePage<'_> {
+    pub fn cycle_title(&self, c: &crate::architecture::Cycle) -> String {
+        self.loc.tf(
+            "arch.cycle_title",
+            &[("files", &c.files.len().to_string()), ("steps", &c.shortest.len().to_string())],
+        )
+    }
+
+    pub fn rule_summary(&self, id: &i64) -> String {
+        let n = self.report.violations_of(id).len();
+        self.loc.tf("arch.rule_violations", &[("n", &n.to_string())])
+    }
+}
+
+#[derive(Template)]
+#[template(path="schema.html")]
+pub struct SchemaPage<'a> {
+    pub app: &'a AppRow,
+    pub schema: &'a crate::dbschema::Schema,
+    /// Schema dalam JSON untuk diagram ER (di-escape sebagai atribut).
+    pub schema_json: String,
+    pub loc: Locale,
+}
+
+impl SchemaPage<'_> {
+    pub fn summary(&self) -> String {
+        let declared = self.schema.tables.iter().filter(|t| t.declared).count();
+        let fks: usize = self.schema.tables.iter().map(|t| t.foreign_keys.len()).sum();
+        self.loc.tf(
+            "schema.summary",
+            &[
+                ("tables", &self.schema.tables.len().to_string()),
+                ("declared", &declared.to_string()),
+                ("fks", &fks.to_string()),
+                ("sql_files", &self.schema.sql_files.to_string()),
+                ("files", &self.schema.files().len().to_string()),
+            ],
+        )
+    }
+
+    pub fn export_links(&self) -> Vec<(&'static str, String)> {
+        crate::export::links(&format!("/api/apps/{}/schema", self.app.id))
+    }
+
+    pub fn svg_url(&self) -> String {
+        crate::export::url(&format!("/api/apps/{}/schema", self.app.id), "svg")
+    }
+
+    /// Isi sel matriks file × tabel (R / W / RW / D).
+    pub fn cell(&self, file_id: &i64, table: &str) -> String {
+        self.schema.usage_of(*file_id, table).map(|u| u.code()).unwrap_or_default()
+    }
+
+    /// Tooltip sel: nomor baris tempat query ditemukan.
+    pub fn cell_title(&self, file_id: &i64, table: &str) -> String {
+        match self.schema.usage_of(*file_id, table) {
+            Some(u) if !u.lines.is_empty() => {
+                let lines: Vec<String> = u.lines.iter().map(|l| l.to_string()).collect();
+                self.loc.tf("schema.lines", &[("lines", &lines.join(", "))])
+            }
+            _ => String::new(),
+        }
+    }
+
+    pub fn column_flags(&self, c: &crate::dbschema::Column) -> String {
+        let mut flags = Vec::new();
+        if c.primary {
+            flags.push("PK");
+        }
+        if c.unique {
+            flags.push("UQ");
+        }
+        if !c.nullable {
+            flags.push("NOT NULL");
+        }
+        flags.join(" ")
+    }
+}
+
+#[derive(Template)]
+#[template(path="calls.html")]
+pub struct CallsPage<'a> {
+    pub app: &'a AppRow,
+    pub index: &'a crate::callgraph::CallIndex,
+    pub q: &'a str,
+    /// Fungsi yang cocok dengan `q`, untuk memilih akar graph (kosong bila ada `focus`).
+    pub functions: Vec<&'a crate::callgraph::SymbolInfo>,
+    pub focus: Option<&'a crate::callgraph::Focus>,
+    pub graph: Option<&'a crate::callgraph::CallGraph>,
+    /// JSON `graph`, dirender ter-escape di atribut data-graph.
+    pub graph_json: String,
+    pub depth: usize,
+    pub dir: crate::callgraph::Direction,
+    pub loc: Locale,
+}
+
+impl CallsPage<'_> {
+    pub fn summary(&self) -> String {
+        let (resolved, calls) = self.index.call_count();
+        self.loc.tf(
+            "calls.summary",
+            &[
+                ("functions", &self.index.function_count().to_string()),
+                ("resolved", &resolved.to_string()),
+                ("calls", &calls.to_string()),
+            ],
+        )
+    }
+
+    /// Halaman call graph berakar di `id` dengan kedalaman & arah yang sama.
+    pub fn root_url(&self, id: &i64) -> String {
+        format!("/apps/{}/calls?symbol={id}&depth={}&dir={}", self.app.id, self.depth, self.dir.as_str())
+    }
+
+    fn export_api(&self) -> String {
+        let id = self.focus.map(|f| f.symbol.id).unwrap_or_default();
+        format!("/api/apps/{}/calls?symbol={id}&depth={}&dir={}", self.app.id, self.depth, self.dir.as_str())
+    }
+
+    pub fn export_links(&self) -> Vec<(&'static str, String)> {
+        crate::export::links(&self.export_api())
+    }
+
+    pub fn svg_url(&self) -> String {
+        crate::export::url(&self.export_api(), "svg")
+    }
+
+    pub fn depths(&self) -> Vec<usize> {
+        (1..=crate::callgraph::MAX_DEPTH).collect()
+    }
+
+    pub fn is_depth(&self, d: &usize) -> bool {
+        self.depth == *d
+    }
+
+    pub fn is_dir(&self, d: &str) -> bool {
+        self.dir.as_str() == d
+    }
+
+    /// Baris pemanggilan, mis. "L12, L40".
+    pub fn lines(&self, lines: &[i32]) -> String {
+        lines.iter().map(|l| format!("L{l}")).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Keterangan cara resolusi (file sama / lewat import / nama unik).
+    pub fn resolution(&self, how: &str) -> String {
+        self.loc.t(&format!("calls.resolved_{how}")).to_string()
+    }
+}
+
+#[derive(Template)]
+#[template(path="symbols.html")]
+pub struct SymbolsPage<'a> {
+    pub app: &'a AppRow,
+    pub q: &'a str,
+    /// Filter jenis ("" = semua).
+    pub kind: &'a str,
+    pub symbols: &'a [crate::symbols::Symbol],
+    /// Jumlah seluruh yang cocok (yang tampil dibatasi SEARCH_LIMIT).
+    pub total: i64,
+    pub loc: Locale,
+}
+
+impl SymbolsPage<'_> {
+    pub fn kinds(&self) -> &'static [&'static str] {
+        crate::symbols::KINDS
+    }
+
+    pub fn is_kind(&self, k: &&str) -> bool {
+        self.kind == *k
+    }
+
+    pub fn summary(&self) -> String {
+        self.loc.tf(
+            "symbols.summary",
+            &[("shown", &self.symbols.len().to_string()), ("total", &self.total.to_string())],
+        )
+    }
+}
+
+#[derive(Template)]
+#[template(path="outline.html")]
+pub struct OutlinePage<'a> {
+    pub app: &'a AppRow,
+    pub file_id: i64,
+    pub path: &'a str,
+    /// Baris file di halaman aplikasi.
+    pub file_href: String,
+    pub symbols: &'a [crate::symbols::Symbol],
+    pub loc: Locale,
+}
+
+impl OutlinePage<'_> {
+    /// Indentasi baris outline sesuai kedalaman pohon definisi.
+    pub fn indent(&self, depth: &usize) -> String {
+        format!("padding-left:{:.2}rem", 0.5 + *depth as f64 * 1.25)
+    }}
+
+/// Hasil satu jenis analisa untuk penampil file (None = belum dianalisis).
+pub struct FileAnalysis {
+    pub kind: String,
+    pub label: String,
+    pub badge: String,
+    pub content: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path="file.html")]
+pub struct FilePage<'a> {
+    pub app: &'a AppRow,
+    pub file_id: i64,
+    pub path: &'a str,
+    /// Nama sintaks yang dipakai highlighting (None = teks biasa).
+    pub language: Option<&'static str>,
+    /// HTML per baris hasil src/highlight.rs (sudah di-escape).
+    pub lines: Vec<String>,
+    pub symbols: &'a [crate::symbols::Symbol],
+    pub analyses: Vec<FileAnalysis>,
+    /// Bahasa hasil analisa yang ditampilkan.
+    pub lang: &'a str,
+    /// Baris file di halaman aplikasi.
+    pub file_href: String,
+    pub loc: Locale,
+}
+
+impl FilePage<'_> {
+    /// Indentasi baris outline sesuai kedalaman pohon definisi.
+    pub fn indent(&self, depth: &usize) -> String {
+        format!("padding-left:{:.2}rem", 0.5 + *depth as f64 * 1.25)
+    }
+
+    pub fn summary(&self) -> String {
+        self.loc.tf(
+            "file.summary",
+            &[
+                ("lines", &self.lines.len().to_string()),
+                ("language", self.language.unwrap_or("text")),
+                ("symbols", &self.symbols.len().to_string()),
+            ],
+        )
+    }
 }
@@ -13,129 +13,241 @@ use anyhow::{Context, Result};
 use reqwest::Client;
 use serde_json::json;
 
-async fn call_chatgpt(api_key: &str, prompt: &str) -> Result<String> {
+use crate::usage::{CallRecorder, LlmCtx, TokenUsage};
+
+/// Pesan error dari balasan OpenAI yang tidak sukses (field error.message).
+fn api_error(status: reqwest::StatusCode, body: &serde_json::Value) -> String {
+    body.pointer("/error/message")
+        .and_then(|m| m.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| status.to_string())
+}
+
+async fn call_chatgpt(api_key: &str, prompt: &str, ctx: &LlmCtx<'_>) -> Result<String> {
     if api_key.is_empty() {
         anyhow::bail!("OPENAI_API_KEY kosong");
     }
 
+    let model = crate::usage::model();
+    let reservation = crate::budget::reserve_prompt(ctx, &model, prompt).await?;
+    let recorder = CallRecorder::start(ctx, &model, prompt, reservation);
     let client = Client::new();
-    let resp: serde_json::Value = client
-        .post("https://api.openai.com/v1/chat/completions")
-        .bearer_auth(api_key)
-        .json(&json!({
-            "model": "gpt-5",
-            "messages": [
-                {"role": "system", "content": "Anda adalah code reviewer profesional."},
-                {"role": "user", "content": prompt}
-            ]
-        }))
-        .send()
-        .await
-        .context("gagal mengirim request ke OpenAI")?
-        .json()
-        .await
-        .context("gagal parsing JSON balasan OpenAI")?;
+    let resp: Result<(reqwest::StatusCode, serde_json::Value)> = async {
+        let resp = client
+            .post("https://api.openai.com/v1/chat/completions")
+            .bearer_auth(api_key)
+            .json(&json!({
+                "model": model,
+                "messages": [
+                    {"role": "system", "content": crate::lang::system_message(ctx.lang)},
+                    {"role": "user", "content": prompt}
+                ]
+            }))
+            .send()
+            .await
+            .context("gagal mengirim request ke OpenAI")?;
+        let status = resp.status();
+        let body = resp.json().await.context("gagal parsing JSON balasan OpenAI")?;
+        Ok((status, body))
+    }
+    .await;
+
+    let resp = match resp {
+        Ok((status, v)) if status.is_success() => v,
+        Ok((status, v)) => {
+            let msg = api_error(status, &v);
+            recorder.finish(Some(TokenUsage::default()), Some(&msg)).await;
+            anyhow::bail!("OpenAI membalas {status}: {msg}")
+        }
+        Err(e) => {
+            recorder.finish(Some(TokenUsage::default()), Some(&e.to_string())).await;
+            return Err(e);
+        }
+    };
 
     let content = resp
         .get("choices")
         .and_then(|c| c.as_array())
-        .and_then(|arr| arr.get(0))
+        .and_then(|arr| arr.first())
         .and_then(|c0| c0.get("message"))
         .and_then(|m| m.get("content"))
         .and_then(|s| s.as_str())
         .unwrap_or("");
 
+    let usage = TokenUsage::from_response(&resp)
+        .unwrap_or_else(|| TokenUsage::estimate(prompt, content));
+    recorder.finish(Some(usage), None).await;
     Ok(content.to_string())
 }
 
-pub async fn analisa_fungsi(api_key: &str, kode: &str) -> Result<String> {
-    call_chatgpt(api_key, &format!("Jelaskan fungsi utama file ini:\n{}", kode)).await
-}
+/// Callback untuk potongan teks (token) yang datang selama streaming.
+pub type OnToken<'a> = &'a mut (dyn FnMut(&str) + Send);
 
-pub async fn analisa_relasi_file(api_key: &str, kode: &str) -> Result<String> {
-    call_chatgpt(
-        api_key,
-        &format!("File ini menggunakan/memanggil file lain apa saja? Jelaskan:\n{}", kode),
-    )
-    .await
-}
+/// Sama seperti `call_chatgpt`, tetapi memakai `stream: true` dan memanggil
+/// `on_token` untuk setiap potongan teks. Mengembalikan teks lengkap.
+async fn call_chatgpt_stream(
+    api_key: &str,
+    prompt: &str,
+    ctx: &LlmCtx<'_>,
+    on_token: OnToken<'_>,
+) -> Result<String> {
+    if api_key.is_empty() {
+        anyhow::bail!("OPENAI_API_KEY kosong");
+    }
 
-pub async fn analisa_relasi_db(api_key: &str, kode: &str) -> Result<String> {
-    call_chatgpt(
-        api_key,
-        &format!("Database apa yang terlibat di file ini? Cari query SQL / koneksi DB:\n{}", kode),
-    )
-    .await
+    let model = crate::usage::model();
+    let reservation = crate::budget::reserve_prompt(ctx, &model, prompt).await?;
+    let mut recorder = CallRecorder::start(ctx, &model, prompt, reservation);
+    let mut usage: Option<TokenUsage> = None;
+    let system = crate::lang::system_message(ctx.lang);
+    let result = stream_completion(api_key, &model, system, prompt, &mut recorder, &mut usage, on_token).await;
+    match &result {
+        Ok(_) => recorder.finish(usage, None).await,
+        Err(e) => recorder.finish(usage, Some(&e.to_string())).await,
+    }
+    result
 }
 
-pub async fn analisa_file(api_key: &str, kode: &str) -> Result<String> {
-    call_chatgpt(api_key, &format!("Analisa file berikut:\n{}", kode)).await
+async fn stream_completion(
+    api_key: &str,
+    model: &str,
+    system: &str,
+    prompt: &str,
+    recorder: &mut CallRecorder,
+    usage: &mut Option<TokenUsage>,
+    on_token: OnToken<'_>,
+) -> Result<String> {
+    let client = Client::new();
+    let mut resp = client
+        .post("https://api.openai.com/v1/chat/completions")
+        .bearer_auth(api_key)
+        .json(&json!({
+            "model": model,
+            "stream": true,
+            // chunk terakhir membawa field `usage` (token prompt/completion)
+            "stream_options": { "include_usage": true },
+            "messages": [
+                {"role": "system", "content": system},
+                {"role": "user", "content": prompt}
+            ]
+        }))
+        .send()
+        .await
+        .context("gagal mengirim request ke OpenAI")?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        anyhow::bail!("OpenAI membalas {status}: {body}");
+    }
+
+    // Balasan berupa baris SSE: "data: {json}" ... "data: [DONE]"
+    let mut full = String::new();
+    let mut buf: Vec<u8> = Vec::new();
+    while let Some(chunk) = resp.chunk().await.context("gagal membaca stream OpenAI")? {
+        buf.extend_from_slice(&chunk);
+        while let Some(pos) = buf.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buf.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim().strip_prefix("data:") else { continue };
+            let data = data.trim();
+            if data == "[DONE]" {
+                return Ok(full);
+            }
+            let Ok(v) = serde_json::from_str::<serde_json::Value>(data) else { continue };
+            if let Some(u) = TokenUsage::from_response(&v) {
+                *usage = Some(u);
+            }
+            if let Some(delta) = v
+                .pointer("/choices/0/delta/content")
+                .and_then(|s| s.as_str())
+            {
+                full.push_str(delta);
+                recorder.push_output(delta);
+                on_token(delta);
+            }
+        }
+    }
+    Ok(full)
 }
 
-pub async fn summary_app(api: &str, payload: &str) -> Result<String> {
-    call_chatgpt(
-        api,
-        &format!(
-            "Analisa payload aplikasi berikut dan buat ringkasan:\n\
-             1) hitung jumlah file; 2) sebutkan tabel & field database; \
-             3) identifikasi file paling core/vital; 4) ringkas arsitektur.\n\n{}",
-            payload
-        ),
-    )
-    .await
+/// Jalankan prompt analisa (sudah dirender dari template, lihat prompts.rs)
+/// dengan streaming.
+pub async fn analisa(
+    api_key: &str,
+    prompt: &str,
+    ctx: &LlmCtx<'_>,
+    on_token: OnToken<'_>,
+) -> Result<String> {
+    call_chatgpt_stream(api_key, prompt, ctx, on_token).await
 }
 
-// ======== BARU: generator JS untuk vis-network ========
-
-pub async fn generate_graph_js(api_key: &str, kode: &str) -> Result<String> {
-    let prompt = format!(
-        concat!(
-            "Jelaskan lengkap detail dan buat kode JavaScript (tanpa tag HTML, head, dan body) ",
-            "untuk menggambar diagram graph menggunakan vis-network.\n",
-            "Pada node, sebutkan semua actor yang terlibat.\n",
-            "Pada edges, sebutkan semua event dan atribut. Tambahkan arrow bila ada.\n",
-            "   var options = {{\n",
-            "       width: '100%',\n",
-            "       interaction: {{\n",
-            "           dragNodes: true,\n",
-            "           dragView: true,\n",
-            "           zoomView: true\n",
-            "       }},\n",
-            "       physics: false,\n",
-            "       edges: {{\n",
-            "           arrows: {{\n",
-            "               to: {{ enabled: true, scaleFactor: 1 }},\n",
-            "               from: {{ enabled: false }}\n",
-            "           }}\n",
-            "       }}\n",
-            "   }};\n\n",
-            "Berikan new line bila info lebih dari 2 kata.\n\n",
-            "Gunakan div dengan id `mynetwork` yang sudah ada di halaman.\n",
-            "Gunakan tag ```javascript untuk kode JavaScript.\n",
-            "Pastikan width=100%.\n\n",
-            "Basiskan diagram pada file berikut:\n{}"
-        ),
-        kode
-    );
-
-    let raw = call_chatgpt(api_key, &prompt).await?;
-    // UBAH: kirim &raw, lalu fallback ke raw (memindahkan raw) jika None
-    let js = extract_code_block(&raw).unwrap_or(raw);
-    Ok(js)
-    
-    
+/// Pertanyaan chat aplikasi (prompt sudah berisi sumber, lihat chat.rs).
+pub async fn tanya(api_key: &str, prompt: &str, ctx: &LlmCtx<'_>) -> Result<String> {
+    call_chatgpt(api_key, prompt, ctx).await
 }
 
-fn extract_code_block(s: &str) -> Option<String> {
-    let start_tag = "```javascript";
-    let alt_start = "```js";
-    let backticks = "```";
+/// Embedding untuk beberapa teks sekaligus (endpoint /v1/embeddings),
+/// urutannya sama dengan `inputs`. Dicatat di llm_calls seperti panggilan lain.
+pub async fn embed(api_key: &str, model: &str, inputs: &[String], ctx: &LlmCtx<'_>) -> Result<Vec<Vec<f32>>> {
+    if api_key.is_empty() {
+        anyhow::bail!("OPENAI_API_KEY kosong");
+    }
+    let joined = inputs.join("\n");
+    let reservation = crate::budget::reserve_prompt(ctx, model, &joined).await?;
+    let recorder = CallRecorder::start(ctx, model, &joined, reservation);
+    let resp: Result<(reqwest::StatusCode, serde_json::Value)> = async {
+        let resp = Client::new()
+            .post("https://api.openai.com/v1/embeddings")
+            .bearer_auth(api_key)
+            .json(&json!({ "model": model, "input": inputs }))
+            .send()
+            .await
+            .context("gagal mengirim request embedding ke OpenAI")?;
+        let status = resp.status();
+        let body = resp.json().await.context("gagal parsing JSON balasan embedding OpenAI")?;
+        Ok((status, body))
+    }
+    .await;
+    let resp = match resp {
+        Ok((status, v)) if status.is_success() => v,
+        Ok((status, v)) => {
+            let msg = api_error(status, &v);
+            recorder.finish(Some(TokenUsage::default()), Some(&msg)).await;
+            anyhow::bail!("OpenAI membalas {status} untuk embedding: {msg}")
+        }
+        Err(e) => {
+            recorder.finish(Some(TokenUsage::default()), Some(&e.to_string())).await;
+            return Err(e);
+        }
+    };
 
-    if let Some(start) = s.find(start_tag).or_else(|| s.find(alt_start)) {
-        let after = &s[start..];
-        let after = after.splitn(2, '\n').nth(1)?; // konten setelah baris pertama
-        let end_idx = after.find(backticks)?;
-        return Some(after[..end_idx].to_string());
+    let vectors: Option<Vec<Vec<f32>>> = resp.get("data").and_then(|d| d.as_array()).map(|items| {
+        items
+            .iter()
+            .map(|item| {
+                item.get("embedding")
+                    .and_then(|e| e.as_array())
+                    .map(|a| a.iter().filter_map(|x| x.as_f64()).map(|x| x as f32).collect())
+                    .unwrap_or_default()
+            })
+            .collect()
+    });
+    let usage = TokenUsage::from_response(&resp).unwrap_or_else(|| TokenUsage::estimate(&joined, ""));
+    match vectors {
+        Some(v) if v.len() == inputs.len() => {
+            recorder.finish(Some(usage), None).await;
+            Ok(v)
+        }
+        _ => {
+            let msg = "jumlah embedding tidak sesuai input";
+            recorder.finish(Some(usage), Some(msg)).await;
+            anyhow::bail!("Embedding gagal: {msg}")
+        }
     }
-    None
-}
\ No newline at end of file
+}
+
+/// Graph nodes/edges untuk satu file; jawaban mentah divalidasi di graph.rs.
+pub async fn generate_graph_json(api_key: &str, prompt: &str, ctx: &LlmCtx<'_>) -> Result<String> {
+    call_chatgpt(api_key, prompt, ctx).await
+}
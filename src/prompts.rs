@@ -0,0 +1,321 @@
+// src/prompts.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Template prompt LLM per jenis analisa. Default-nya file di folder
+// `prompts/` (versi 0) untuk prompt bawaan, atau kolom `analysis_kinds.prompt`
+// untuk jenis analisa tambahan; hasil edit di halaman admin disimpan ke tabel
+// `prompts` sebagai versi baru (1, 2, ...). Versi terbaru = versi aktif.
+// Setiap hasil analisa menyimpan nomor versi prompt yang dipakai, sehingga
+// hasil dengan prompt usang bisa dicari dan dianalisa ulang.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+pub struct PromptDef {
+    pub name: &'static str,
+    pub label: &'static str,
+    /// Variabel yang tersedia, dipakai sebagai `{{nama}}` di template.
+    pub vars: &'static [&'static str],
+    pub default: &'static str,
+}
+
+pub const PROMPTS: [PromptDef; 6] = [
+    PromptDef {
+        name: "fungsi",
+        label: "Fungsi File",
+        vars: &["kode", "path", "bahasa", "konteks"],
+        default: include_str!("../prompts/fungsi.txt"),
+    },
+    PromptDef {
+        name: "relasi_file",
+        label: "Relasi File",
+        vars: &["kode", "path", "bahasa", "konteks"],
+        default: include_str!("../prompts/relasi_file.txt"),
+    },
+    PromptDef {
+        name: "relasi_db",
+        label: "Relasi DB",
+        vars: &["kode", "path", "bahasa", "konteks"],
+        default: include_str!("../prompts/relasi_db.txt"),
+    },
+    PromptDef {
+        name: "summary",
+        label: "Summary Aplikasi",
+        vars: &["payload", "app", "bahasa"],
+        default: include_str!("../prompts/summary.txt"),
+    },
+    PromptDef {
+        name: "chat",
+        label: "Chat Aplikasi",
+        vars: &["pertanyaan", "sumber", "riwayat", "app", "bahasa"],
+        default: include_str!("../prompts/chat.txt"),
+    },
+    PromptDef {
+        name: "graph",
+        label: "Graph (nodes/edges JSON)",
+        vars: &["kode", "path"],
+        default: include_str!("../prompts/graph.txt"),
+    },
+];
+
+pub fn def(name: &str) -> Option<&'static PromptDef> {
+    PROMPTS.iter().find(|p| p.name == name)
+}
+
+/// Prompt yang bisa diedit: bawaan atau milik jenis analisa tambahan.
+#[derive(Debug, Clone)]
+pub struct PromptInfo {
+    pub name: String,
+    pub label: String,
+    pub vars: &'static [&'static str],
+    pub default: String,
+}
+
+const FILE_VARS: &[&str] = &["kode", "path", "bahasa", "konteks"];
+
+pub async fn info(pool: &MySqlPool, name: &str) -> anyhow::Result<Option<PromptInfo>> {
+    if let Some(d) = def(name) {
+        return Ok(Some(PromptInfo {
+            name: d.name.to_string(),
+            label: d.label.to_string(),
+            vars: d.vars,
+            default: d.default.to_string(),
+        }));
+    }
+    Ok(crate::kinds::get(pool, name).await?.map(|k| PromptInfo {
+        name: k.name,
+        label: k.label,
+        vars: FILE_VARS,
+        default: k.prompt.unwrap_or_default(),
+    }))
+}
+
+/// Semua prompt: jenis analisa per file (urut registry), lalu summary, chat & graph.
+pub async fn all(pool: &MySqlPool) -> anyhow::Result<Vec<PromptInfo>> {
+    let mut out = Vec::new();
+    for k in crate::kinds::list(pool, false).await? {
+        if let Some(i) = info(pool, &k.name).await? {
+            out.push(i);
+        }
+    }
+    for name in ["summary", "chat", "graph"] {
+        out.extend(info(pool, name).await?);
+    }
+    Ok(out)
+}
+
+/// Template yang berlaku saat ini.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivePrompt {
+    pub name: String,
+    pub version: i32,
+    pub template: String,
+}
+
+impl ActivePrompt {
+    /// Identitas versi yang ditampilkan, mis. `fungsi@v3`.
+    pub fn label(&self) -> String {
+        version_label(&self.name, self.version)
+    }
+}
+
+pub fn version_label(name: &str, version: i32) -> String {
+    format!("{name}@v{version}")
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PromptVersion {
+    pub id: i64,
+    pub name: String,
+    pub version: i32,
+    pub template: String,
+    pub note: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Versi terbaru dari tabel `prompts`, atau default file (versi 0).
+pub async fn active(pool: &MySqlPool, name: &str) -> anyhow::Result<ActivePrompt> {
+    let Some(d) = info(pool, name).await? else {
+        anyhow::bail!("Prompt tidak dikenal: {name}");
+    };
+    let row: Option<(i32, String)> =
+        sqlx::query_as("SELECT version, template FROM prompts WHERE name=? ORDER BY version DESC LIMIT 1")
+            .bind(name)
+            .fetch_optional(pool)
+            .await?;
+    let (version, template) = row.unwrap_or((0, d.default));
+    Ok(ActivePrompt { name: name.to_string(), version, template })
+}
+
+/// Ganti `{{var}}` dengan nilainya dalam satu kali lewat: nilai disisipkan
+/// apa adanya (`{{...}}` di dalam kode yang disisipkan tidak ikut diganti).
+/// Variabel yang tidak dikenal dibiarkan.
+pub fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 2..];
+        let value = after
+            .find("}}")
+            .and_then(|close| vars.iter().find(|(k, _)| *k == &after[..close]).map(|(_, v)| (close, *v)));
+        match value {
+            Some((close, v)) => {
+                out.push_str(v);
+                rest = &after[close + 2..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[open + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+pub async fn history(pool: &MySqlPool, name: &str) -> anyhow::Result<Vec<PromptVersion>> {
+    Ok(sqlx::query_as::<_, PromptVersion>(
+        "SELECT id, name, version, template, note, created_by, created_at
+         FROM prompts WHERE name=? ORDER BY version DESC",
+    )
+    .bind(name)
+    .fetch_all(pool)
+    .await?)
+}
+
+/// Simpan template sebagai versi baru (menjadi versi aktif).
+pub async fn save(
+    pool: &MySqlPool,
+    name: &str,
+    template: &str,
+    note: Option<&str>,
+    user: Option<&str>,
+) -> anyhow::Result<i32> {
+    let current = active(pool, name).await?;
+    if current.template == template {
+        return Ok(current.version);
+    }
+    let version = current.version + 1;
+    sqlx::query("INSERT INTO prompts (name, version, template, note, created_by) VALUES (?, ?, ?, ?, ?)")
+        .bind(name)
+        .bind(version)
+        .bind(template)
+        .bind(note)
+        .bind(user)
+        .execute(pool)
+        .await?;
+    Ok(version)
+}
+
+/// Jumlah hasil tersimpan yang dibuat dengan versi prompt lama. Versi NULL
+/// (hasil sebelum versi dicatat) dianggap versi 0 = template default.
+pub async fn outdated_count(pool: &MySqlPool, name: &str, current: i32) -> anyhow::Result<i64> {
+    let (n,): (i64,) = match name {
+        "summary" => {
+            sqlx::query_as(
+                "SELECT COUNT(*) FROM app_summary WHERE summary IS NOT NULL AND COALESCE(prompt_version,0) <> ?",
+            )
+            .bind(current)
+            .fetch_one(pool)
+            .await?
+        }
+        "chat" => {
+            sqlx::query_as(
+                "SELECT COUNT(*) FROM chat_messages WHERE role='assistant' AND COALESCE(prompt_version,0) <> ?",
+            )
+            .bind(current)
+            .fetch_one(pool)
+            .await?
+        }
+        "graph" => {
+            sqlx::query_as(
+                "SELECT COUNT(*) FROM files WHERE json_graph IS NOT NULL AND COALESCE(json_graph_prompt,0) <> ?",
+            )
+            .bind(current)
+            .fetch_one(pool)
+            .await?
+        }
+        _ => {
+            sqlx::query_as(
+                "SELECT COUNT(*) FROM analysis_results WHERE kind=? AND COALESCE(prompt_version,0) <> ?",
+            )
+            .bind(name)
+            .bind(current)
+            .fetch_one(pool)
+            .await?
+        }
+    };
+    Ok(n)
+}
+
+/// Item (file, jenis) di satu aplikasi yang hasilnya dibuat dengan prompt
+/// usang dalam bahasa `lang`; file None = summary aplikasi. Graph tidak
+/// termasuk (dibuat manual).
+pub async fn outdated_items(
+    pool: &MySqlPool,
+    app_id: i64,
+    lang: &str,
+) -> anyhow::Result<Vec<(Option<i64>, String)>> {
+    let mut items = Vec::new();
+    for kind in crate::kinds::list(pool, true).await? {
+        let current = active(pool, &kind.name).await?.version;
+        let rows: Vec<(i64,)> = sqlx::query_as(
+            "SELECT f.id FROM files f JOIN analysis_results r ON r.file_id = f.id
+             WHERE f.app_id=? AND r.kind=? AND r.lang=? AND COALESCE(r.prompt_version,0) <> ?
+             ORDER BY f.id",
+        )
+        .bind(app_id)
+        .bind(&kind.name)
+        .bind(lang)
+        .bind(current)
+        .fetch_all(pool)
+        .await?;
+        items.extend(rows.into_iter().map(|(id,)| (Some(id), kind.name.clone())));
+    }
+
+    let current = active(pool, "summary").await?.version;
+    let (n,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM app_summary
+         WHERE app_id=? AND lang=? AND summary IS NOT NULL AND COALESCE(prompt_version,0) <> ?",
+    )
+    .bind(app_id)
+    .bind(lang)
+    .bind(current)
+    .fetch_one(pool)
+    .await?;
+    if n > 0 {
+        items.push((None, "summary".to_string()));
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_replaces_each_placeholder_once() {
+        let out = render(
+            "File {{path}}:\n{{kode}}\n({{bahasa}}, {{lain}})",
+            &[("kode", "let s = \"{{path}}\";"), ("path", "src/{{kode}}.rs"), ("bahasa", "Indonesia")],
+        );
+        assert_eq!(out, "File src/{{kode}}.rs:\nlet s = \"{{path}}\";\n(Indonesia, {{lain}})");
+    }
+
+    #[test]
+    fn render_keeps_unclosed_and_nested_braces() {
+        assert_eq!(render("a {{{kode}}} b {{kode", &[("kode", "X")]), "a {X} b {{kode");
+        assert_eq!(render("{{}}{{kode}}", &[("kode", "")]), "{{}}");
+    }
+}
@@ -0,0 +1,852 @@
+// src/render.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Render graph di server tanpa browser, untuk laporan offline / email:
+// - layout berlapis (kiri → kanan): siklus dipatahkan dengan DFS, lapisan =
+//   jalur terpanjang dari sumber, urutan dalam lapisan dirapikan dengan
+//   heuristik barycenter; node tanpa edge disusun sebagai grid di bawah;
+// - SVG ditulis langsung dari layout;
+// - PNG digambar di kanvas RGB sendiri (font bitmap 5×7 bawaan) lalu
+//   di-encode dengan zlib (flate2).
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+use crate::export::{ExportGraph, Kind};
+
+const CHAR_W: f64 = 6.0;
+const LINE_H: f64 = 12.0;
+const PAD: f64 = 6.0;
+const RANK_GAP: f64 = 70.0;
+const NODE_GAP: f64 = 14.0;
+const MARGIN: f64 = 20.0;
+const TITLE_H: f64 = 24.0;
+/// Panjang label maksimum (karakter); sisanya dipotong dengan "…".
+const MAX_LABEL: usize = 48;
+/// Putaran heuristik barycenter.
+const SWEEPS: usize = 4;
+/// Batas kanvas PNG (piksel) agar memori tetap wajar.
+const MAX_PNG_PIXELS: f64 = 40_000_000.0;
+/// Skala PNG: 1 unit layout = 2 piksel (font 5×7 jadi 10×14).
+const PNG_SCALE: f64 = 2.0;
+
+const PALETTE: [&str; 10] = [
+    "#dbeafe", "#dcfce7", "#fef9c3", "#fce7f3", "#ede9fe", "#ffedd5", "#cffafe", "#e5e7eb", "#fee2e2", "#d1fae5",
+];
+const DEFAULT_FILL: &str = "#f8f9fa";
+const STROKE: &str = "#6c757d";
+const EDGE: &str = "#8a8f98";
+const TEXT: &str = "#212529";
+
+/// Graph terlalu besar untuk dirasterisasi ke PNG.
+#[derive(Debug)]
+pub struct TooLarge {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl std::fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "graph terlalu besar untuk PNG ({}×{} px)", self.width, self.height)
+    }
+}
+
+impl std::error::Error for TooLarge {}
+
+struct Placed {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+    /// Baris teks: [0] = judul node, sisanya kolom (diagram ER).
+    lines: Vec<String>,
+    fill: &'static str,
+}
+
+struct Layout {
+    width: f64,
+    height: f64,
+    nodes: Vec<Placed>,
+    /// (node asal, node tujuan, label, arrows) dengan indeks node.
+    edges: Vec<(usize, usize, Option<String>, String)>,
+    legend: Vec<(String, &'static str)>,
+}
+
+fn truncate(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() <= MAX_LABEL {
+        return s.to_string();
+    }
+    // Potong dari kiri agar akhir path / nama tetap terbaca
+    let tail: String = chars[chars.len() - (MAX_LABEL - 1)..].iter().collect();
+    format!("…{tail}")
+}
+
+fn node_lines(n: &crate::export::ExportNode) -> Vec<String> {
+    let mut lines: Vec<String> = n.label.lines().map(truncate).collect();
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    if !n.fields.is_empty() {
+        lines.truncate(1);
+        for f in &n.fields {
+            let key = f.key.as_deref().map(|k| format!(" {k}")).unwrap_or_default();
+            lines.push(truncate(&format!("{} {}{key}", f.name, f.data_type)));
+        }
+    }
+    lines
+}
+
+/// Lapisan tiap node: DFS menandai edge balik (siklus), lalu jalur
+/// terpanjang dari sumber pada edge sisanya.
+fn layers(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut out: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (i, (a, b)) in edges.iter().enumerate() {
+        if a != b {
+            out[*a].push(i);
+        }
+    }
+    // 0 = belum, 1 = di stack, 2 = selesai
+    let mut state = vec![0u8; n];
+    let mut back = vec![false; edges.len()];
+    for root in 0..n {
+        if state[root] != 0 {
+            continue;
+        }
+        let mut stack = vec![(root, 0usize)];
+        state[root] = 1;
+        while let Some((v, next)) = stack.last_mut() {
+            let v = *v;
+            if let Some(&ei) = out[v].get(*next) {
+                *next += 1;
+                let w = edges[ei].1;
+                match state[w] {
+                    0 => {
+                        state[w] = 1;
+                        stack.push((w, 0));
+                    }
+                    1 => back[ei] = true,
+                    _ => {}
+                }
+            } else {
+                state[v] = 2;
+                stack.pop();
+            }
+        }
+    }
+
+    let mut indeg = vec![0usize; n];
+    let mut succ: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (i, (a, b)) in edges.iter().enumerate() {
+        if a == b || back[i] {
+            continue;
+        }
+        succ[*a].push(*b);
+        indeg[*b] += 1;
+    }
+    let mut layer = vec![0usize; n];
+    let mut queue: Vec<usize> = (0..n).filter(|v| indeg[*v] == 0).collect();
+    while let Some(v) = queue.pop() {
+        for &w in &succ[v] {
+            layer[w] = layer[w].max(layer[v] + 1);
+            indeg[w] -= 1;
+            if indeg[w] == 0 {
+                queue.push(w);
+            }
+        }
+    }
+    layer
+}
+
+fn layout(g: &ExportGraph) -> Layout {
+    let index: HashMap<&str, usize> = g.nodes.iter().enumerate().map(|(i, n)| (n.id.as_str(), i)).collect();
+    let edges: Vec<(usize, usize, Option<String>, String)> = g
+        .edges
+        .iter()
+        .filter_map(|e| {
+            Some((*index.get(e.from.as_str())?, *index.get(e.to.as_str())?, e.label.clone(), e.arrows.clone()))
+        })
+        .collect();
+    let pairs: Vec<(usize, usize)> = edges.iter().map(|(a, b, _, _)| (*a, *b)).collect();
+
+    // Warna per group (urutan kemunculan)
+    let mut groups: BTreeMap<&str, &'static str> = BTreeMap::new();
+    let mut legend = Vec::new();
+    for n in &g.nodes {
+        if let Some(group) = n.group.as_deref() {
+            if !groups.contains_key(group) {
+                let color = PALETTE[groups.len() % PALETTE.len()];
+                groups.insert(group, color);
+                legend.push((group.to_string(), color));
+            }
+        }
+    }
+
+    let mut nodes: Vec<Placed> = g
+        .nodes
+        .iter()
+        .map(|n| {
+            let lines = node_lines(n);
+            let chars = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as f64;
+            let extra = if n.fields.is_empty() { 0.0 } else { PAD };
+            Placed {
+                x: 0.0,
+                y: 0.0,
+                w: chars * CHAR_W + 2.0 * PAD,
+                h: lines.len() as f64 * LINE_H + 2.0 * PAD + extra,
+                lines,
+                fill: n.group.as_deref().and_then(|gr| groups.get(gr).copied()).unwrap_or(DEFAULT_FILL),
+            }
+        })
+        .collect();
+
+    let connected: Vec<bool> = {
+        let mut c = vec![false; nodes.len()];
+        for (a, b) in &pairs {
+            if a != b {
+                c[*a] = true;
+                c[*b] = true;
+            }
+        }
+        c
+    };
+    let layer = layers(nodes.len(), &pairs);
+    let depth = (0..nodes.len()).filter(|v| connected[*v]).map(|v| layer[v]).max().map(|m| m + 1).unwrap_or(0);
+    let mut ranks: Vec<Vec<usize>> = vec![Vec::new(); depth];
+    for v in (0..nodes.len()).filter(|v| connected[*v]) {
+        ranks[layer[v]].push(v);
+    }
+
+    // Barycenter: urutkan node menurut rata-rata posisi tetangga di lapisan sebelah
+    let mut pos = vec![0.0f64; nodes.len()];
+    let refresh = |ranks: &Vec<Vec<usize>>, pos: &mut Vec<f64>| {
+        for rank in ranks {
+            for (i, v) in rank.iter().enumerate() {
+                pos[*v] = i as f64;
+            }
+        }
+    };
+    refresh(&ranks, &mut pos);
+    for sweep in 0..SWEEPS {
+        let down = sweep % 2 == 0;
+        let order: Vec<usize> = if down { (1..depth).collect() } else { (0..depth.saturating_sub(1)).rev().collect() };
+        for r in order {
+            let mut keyed: Vec<(f64, usize)> = ranks[r]
+                .iter()
+                .map(|&v| {
+                    let neigh: Vec<f64> = pairs
+                        .iter()
+                        .filter_map(|&(a, b)| {
+                            let other = if a == v { b } else if b == v { a } else { return None };
+                            let want = if down { r.checked_sub(1) } else { Some(r + 1) };
+                            (Some(layer[other]) == want && connected[other]).then_some(pos[other])
+                        })
+                        .collect();
+                    let key = if neigh.is_empty() { pos[v] } else { neigh.iter().sum::<f64>() / neigh.len() as f64 };
+                    (key, v)
+                })
+                .collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            ranks[r] = keyed.into_iter().map(|(_, v)| v).collect();
+            refresh(&ranks, &mut pos);
+        }
+    }
+
+    // Koordinat: lapisan sebagai kolom, node ditumpuk dan dipusatkan vertikal
+    let top = MARGIN + TITLE_H + if legend.is_empty() { 0.0 } else { LINE_H + PAD };
+    let heights: Vec<f64> = ranks
+        .iter()
+        .map(|rank| rank.iter().map(|v| nodes[*v].h).sum::<f64>() + NODE_GAP * rank.len().saturating_sub(1) as f64)
+        .collect();
+    let tallest = heights.iter().copied().fold(0.0, f64::max);
+    let mut x = MARGIN;
+    for (rank, column_h) in ranks.iter().zip(&heights) {
+        let mut y = top + (tallest - column_h) / 2.0;
+        let col_w = rank.iter().map(|v| nodes[*v].w).fold(0.0, f64::max);
+        for &v in rank {
+            nodes[v].x = x + (col_w - nodes[v].w) / 2.0;
+            nodes[v].y = y;
+            y += nodes[v].h + NODE_GAP;
+        }
+        x += col_w + RANK_GAP;
+    }
+    let layered_w = if ranks.is_empty() { 0.0 } else { x - RANK_GAP - MARGIN };
+
+    // Node tanpa edge: grid di bawah graph berlapis
+    let grid_w = layered_w.max(900.0);
+    let (mut gx, mut gy) = (MARGIN, top + tallest + if tallest > 0.0 { RANK_GAP / 2.0 } else { 0.0 });
+    let mut row_h: f64 = 0.0;
+    for v in (0..nodes.len()).filter(|v| !connected[*v]) {
+        if gx > MARGIN && gx + nodes[v].w > MARGIN + grid_w {
+            gx = MARGIN;
+            gy += row_h + NODE_GAP;
+            row_h = 0.0;
+        }
+        nodes[v].x = gx;
+        nodes[v].y = gy;
+        gx += nodes[v].w + NODE_GAP;
+        row_h = row_h.max(nodes[v].h);
+    }
+
+    let title_w = g.title.chars().count() as f64 * CHAR_W * 1.4;
+    let legend_w: f64 = legend.iter().map(|(l, _)| (l.chars().count() as f64 + 4.0) * CHAR_W).sum();
+    let right = nodes.iter().map(|n| n.x + n.w).fold(0.0, f64::max).max(MARGIN + title_w).max(MARGIN + legend_w);
+    let bottom = nodes.iter().map(|n| n.y + n.h).fold(top, f64::max);
+    Layout { width: right + MARGIN, height: bottom + MARGIN, nodes, edges, legend }
+}
+
+/// Titik kontrol kurva edge: sisi kanan → sisi kiri bila tujuan di kanan,
+/// sebaliknya bila di kiri; satu kolom = melengkung di sisi kanan.
+fn edge_curve(a: &Placed, b: &Placed) -> [(f64, f64); 4] {
+    let (ay, by) = (a.y + a.h / 2.0, b.y + b.h / 2.0);
+    let (acx, bcx) = (a.x + a.w / 2.0, b.x + b.w / 2.0);
+    if (acx - bcx).abs() < 1.0 {
+        let x1 = a.x + a.w;
+        let x2 = b.x + b.w;
+        let bulge = x1.max(x2) + 40.0;
+        [(x1, ay), (bulge, ay), (bulge, by), (x2, by)]
+    } else if bcx > acx {
+        let (x1, x2) = (a.x + a.w, b.x);
+        let dx = (x2 - x1).abs().max(30.0) / 2.0;
+        [(x1, ay), (x1 + dx, ay), (x2 - dx, by), (x2, by)]
+    } else {
+        let (x1, x2) = (a.x, b.x + b.w);
+        let dx = (x1 - x2).abs().max(30.0) / 2.0;
+        [(x1, ay), (x1 - dx, ay), (x2 + dx, by), (x2, by)]
+    }
+}
+
+fn bezier(p: &[(f64, f64); 4], t: f64) -> (f64, f64) {
+    let u = 1.0 - t;
+    let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+    (
+        a * p[0].0 + b * p[1].0 + c * p[2].0 + d * p[3].0,
+        a * p[0].1 + b * p[1].1 + c * p[2].1 + d * p[3].1,
+    )
+}
+
+/// Segitiga panah di ujung `tip`, menghadap dari `from`.
+fn arrow_head(from: (f64, f64), tip: (f64, f64)) -> [(f64, f64); 3] {
+    let (dx, dy) = (tip.0 - from.0, tip.1 - from.1);
+    let len = (dx * dx + dy * dy).sqrt().max(0.001);
+    let (ux, uy) = (dx / len, dy / len);
+    let (bx, by) = (tip.0 - ux * 8.0, tip.1 - uy * 8.0);
+    [tip, (bx - uy * 4.0, by + ux * 4.0), (bx + uy * 4.0, by - ux * 4.0)]
+}
+
+/// Panah yang digambar: (di ujung tujuan, di ujung asal).
+fn arrow_ends(arrows: &str) -> (bool, bool) {
+    match arrows {
+        "from" => (false, true),
+        "both" => (true, true),
+        "none" => (false, false),
+        _ => (true, false),
+    }
+}
+
+pub fn svg(g: &ExportGraph) -> String {
+    let l = layout(g);
+    let esc = crate::export::xml_escape;
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.0} {h:.0}\" \
+         font-family=\"monospace\" font-size=\"10\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>\n\
+         <text x=\"{MARGIN}\" y=\"{ty}\" font-size=\"14\" font-weight=\"bold\" fill=\"{TEXT}\">{title}</text>\n",
+        w = l.width,
+        h = l.height,
+        ty = MARGIN + 12.0,
+        title = esc(&g.title),
+    );
+    let mut lx = MARGIN;
+    for (label, color) in &l.legend {
+        let y = MARGIN + TITLE_H;
+        out.push_str(&format!(
+            "<rect x=\"{lx:.1}\" y=\"{y:.1}\" width=\"10\" height=\"10\" fill=\"{color}\" stroke=\"{STROKE}\"/>\
+             <text x=\"{:.1}\" y=\"{:.1}\" fill=\"{TEXT}\">{}</text>\n",
+            lx + 14.0,
+            y + 9.0,
+            esc(label)
+        ));
+        lx += (label.chars().count() as f64 + 4.0) * CHAR_W;
+    }
+
+    for (a, b, label, arrows) in &l.edges {
+        if a == b {
+            continue;
+        }
+        let p = edge_curve(&l.nodes[*a], &l.nodes[*b]);
+        out.push_str(&format!(
+            "<path d=\"M{:.1},{:.1} C{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}\" fill=\"none\" stroke=\"{EDGE}\" stroke-width=\"1.2\"/>\n",
+            p[0].0, p[0].1, p[1].0, p[1].1, p[2].0, p[2].1, p[3].0, p[3].1
+        ));
+        let (at_end, at_start) = arrow_ends(arrows);
+        let mut heads = Vec::new();
+        if at_end {
+            heads.push(arrow_head(bezier(&p, 0.92), p[3]));
+        }
+        if at_start {
+            heads.push(arrow_head(bezier(&p, 0.08), p[0]));
+        }
+        for h in heads {
+            out.push_str(&format!(
+                "<polygon points=\"{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}\" fill=\"{EDGE}\"/>\n",
+                h[0].0, h[0].1, h[1].0, h[1].1, h[2].0, h[2].1
+            ));
+        }
+        if let Some(label) = label.as_deref().filter(|s| !s.is_empty()) {
+            let (mx, my) = bezier(&p, 0.5);
+            let text = truncate(label);
+            let w = text.chars().count() as f64 * CHAR_W + 4.0;
+            out.push_str(&format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{w:.1}\" height=\"12\" fill=\"#ffffff\" opacity=\"0.85\"/>\
+                 <text x=\"{mx:.1}\" y=\"{:.1}\" text-anchor=\"middle\" fill=\"#495057\">{}</text>\n",
+                mx - w / 2.0,
+                my - 6.0,
+                my + 3.5,
+                esc(&text)
+            ));
+        }
+    }
+
+    for n in &l.nodes {
+        out.push_str(&format!(
+            "<g><rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" rx=\"4\" fill=\"{}\" stroke=\"{STROKE}\"/>\n",
+            n.x, n.y, n.w, n.h, n.fill
+        ));
+        let mut y = n.y + PAD + LINE_H - 3.0;
+        for (i, line) in n.lines.iter().enumerate() {
+            let weight = if i == 0 && n.lines.len() > 1 { " font-weight=\"bold\"" } else { "" };
+            out.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{y:.1}\" fill=\"{TEXT}\"{weight}>{}</text>\n",
+                n.x + PAD,
+                esc(line)
+            ));
+            if i == 0 && n.lines.len() > 1 && g.kind == Kind::Er {
+                let sep = n.y + PAD + LINE_H + PAD / 2.0;
+                out.push_str(&format!(
+                    "<line x1=\"{:.1}\" y1=\"{sep:.1}\" x2=\"{:.1}\" y2=\"{sep:.1}\" stroke=\"{STROKE}\"/>\n",
+                    n.x,
+                    n.x + n.w
+                ));
+                y += PAD;
+            }
+            y += LINE_H;
+        }
+        out.push_str("</g>\n");
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+// ====== Rasterisasi PNG ======
+
+/// Font bitmap 5×7 untuk ASCII 32..=126; tiap baris 5 bit (bit 4 = kiri).
+const FONT: [[u8; 7]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04], // !
+    [0x0A, 0x0A, 0x0A, 0x00, 0x00, 0x00, 0x00], // "
+    [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A], // #
+    [0x04, 0x0F, 0x14, 0x0E, 0x05, 0x1E, 0x04], // $
+    [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03], // %
+    [0x0C, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0D], // &
+    [0x0C, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00], // '
+    [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02], // (
+    [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08], // )
+    [0x00, 0x04, 0x15, 0x0E, 0x15, 0x04, 0x00], // *
+    [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00], // +
+    [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08], // ,
+    [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00], // -
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C], // .
+    [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00], // /
+    [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E], // 0
+    [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E], // 1
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F], // 2
+    [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E], // 3
+    [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02], // 4
+    [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E], // 5
+    [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E], // 6
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08], // 7
+    [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E], // 8
+    [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C], // 9
+    [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00], // :
+    [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x04, 0x08], // ;
+    [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02], // <
+    [0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00], // =
+    [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08], // >
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04], // ?
+    [0x0E, 0x11, 0x01, 0x0D, 0x15, 0x15, 0x0E], // @
+    [0x0E, 0x11, 0x11, 0x11, 0x1F, 0x11, 0x11], // A
+    [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E], // B
+    [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E], // C
+    [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C], // D
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F], // E
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10], // F
+    [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F], // G
+    [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11], // H
+    [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E], // I
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C], // J
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11], // K
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F], // L
+    [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11], // M
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11], // N
+    [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E], // O
+    [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10], // P
+    [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D], // Q
+    [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11], // R
+    [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E], // S
+    [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // T
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E], // U
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04], // V
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A], // W
+    [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11], // X
+    [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04], // Y
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F], // Z
+    [0x0E, 0x08, 0x08, 0x08, 0x08, 0x08, 0x0E], // [
+    [0x00, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00], // \
+    [0x0E, 0x02, 0x02, 0x02, 0x02, 0x02, 0x0E], // ]
+    [0x04, 0x0A, 0x11, 0x00, 0x00, 0x00, 0x00], // ^
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F], // _
+    [0x08, 0x04, 0x02, 0x00, 0x00, 0x00, 0x00], // `
+    [0x00, 0x00, 0x0E, 0x01, 0x0F, 0x11, 0x0F], // a
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x1E], // b
+    [0x00, 0x00, 0x0E, 0x10, 0x10, 0x11, 0x0E], // c
+    [0x01, 0x01, 0x0D, 0x13, 0x11, 0x11, 0x0F], // d
+    [0x00, 0x00, 0x0E, 0x11, 0x1F, 0x10, 0x0E], // e
+    [0x06, 0x09, 0x08, 0x1C, 0x08, 0x08, 0x08], // f
+    [0x00, 0x0F, 0x11, 0x11, 0x0F, 0x01, 0x0E], // g
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x11], // h
+    [0x04, 0x00, 0x0C, 0x04, 0x04, 0x04, 0x0E], // i
+    [0x02, 0x00, 0x06, 0x02, 0x02, 0x12, 0x0C], // j
+    [0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12], // k
+    [0x0C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E], // l
+    [0x00, 0x00, 0x1A, 0x15, 0x15, 0x11, 0x11], // m
+    [0x00, 0x00, 0x16, 0x19, 0x11, 0x11, 0x11], // n
+    [0x00, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x0E], // o
+    [0x00, 0x00, 0x1E, 0x11, 0x1E, 0x10, 0x10], // p
+    [0x00, 0x00, 0x0D, 0x13, 0x0F, 0x01, 0x01], // q
+    [0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10], // r
+    [0x00, 0x00, 0x0E, 0x10, 0x0E, 0x01, 0x1E], // s
+    [0x08, 0x08, 0x1C, 0x08, 0x08, 0x09, 0x06], // t
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0D], // u
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x0A, 0x04], // v
+    [0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0A], // w
+    [0x00, 0x00, 0x11, 0x0A, 0x04, 0x0A, 0x11], // x
+    [0x00, 0x00, 0x11, 0x11, 0x0F, 0x01, 0x0E], // y
+    [0x00, 0x00, 0x1F, 0x02, 0x04, 0x08, 0x1F], // z
+    [0x02, 0x04, 0x04, 0x08, 0x04, 0x04, 0x02], // {
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // |
+    [0x08, 0x04, 0x04, 0x02, 0x04, 0x04, 0x08], // }
+    [0x00, 0x00, 0x08, 0x15, 0x02, 0x00, 0x00], // ~
+];
+
+fn rgb(hex: &str) -> [u8; 3] {
+    let v = u32::from_str_radix(hex.trim_start_matches('#'), 16).unwrap_or(0);
+    [(v >> 16) as u8, (v >> 8) as u8, v as u8]
+}
+
+struct Canvas {
+    w: usize,
+    h: usize,
+    px: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(w: usize, h: usize) -> Self {
+        Canvas { w, h, px: vec![255; w * h * 3] }
+    }
+
+    fn set(&mut self, x: i64, y: i64, c: [u8; 3]) {
+        if x < 0 || y < 0 || x as usize >= self.w || y as usize >= self.h {
+            return;
+        }
+        let i = (y as usize * self.w + x as usize) * 3;
+        self.px[i..i + 3].copy_from_slice(&c);
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, w: f64, h: f64, c: [u8; 3]) {
+        for py in y.round() as i64..(y + h).round() as i64 {
+            for px in x.round() as i64..(x + w).round() as i64 {
+                self.set(px, py, c);
+            }
+        }
+    }
+
+    fn stroke_rect(&mut self, x: f64, y: f64, w: f64, h: f64, c: [u8; 3]) {
+        self.line((x, y), (x + w, y), c);
+        self.line((x + w, y), (x + w, y + h), c);
+        self.line((x + w, y + h), (x, y + h), c);
+        self.line((x, y + h), (x, y), c);
+    }
+
+    /// Garis Bresenham setebal 2 piksel.
+    fn line(&mut self, a: (f64, f64), b: (f64, f64), c: [u8; 3]) {
+        let (mut x0, mut y0) = (a.0.round() as i64, a.1.round() as i64);
+        let (x1, y1) = (b.0.round() as i64, b.1.round() as i64);
+        let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+        let (sx, sy) = (if x0 < x1 { 1 } else { -1 }, if y0 < y1 { 1 } else { -1 });
+        let mut err = dx + dy;
+        loop {
+            self.set(x0, y0, c);
+            self.set(x0 + 1, y0, c);
+            self.set(x0, y0 + 1, c);
+            if x0 == x1 && y0 == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x0 += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y0 += sy;
+            }
+        }
+    }
+
+    fn triangle(&mut self, t: &[(f64, f64); 3], c: [u8; 3]) {
+        let min_x = t.iter().map(|p| p.0).fold(f64::MAX, f64::min).floor() as i64;
+        let max_x = t.iter().map(|p| p.0).fold(f64::MIN, f64::max).ceil() as i64;
+        let min_y = t.iter().map(|p| p.1).fold(f64::MAX, f64::min).floor() as i64;
+        let max_y = t.iter().map(|p| p.1).fold(f64::MIN, f64::max).ceil() as i64;
+        let edge = |a: (f64, f64), b: (f64, f64), p: (f64, f64)| (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0);
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let p = (x as f64 + 0.5, y as f64 + 0.5);
+                let (e0, e1, e2) = (edge(t[0], t[1], p), edge(t[1], t[2], p), edge(t[2], t[0], p));
+                if (e0 >= 0.0 && e1 >= 0.0 && e2 >= 0.0) || (e0 <= 0.0 && e1 <= 0.0 && e2 <= 0.0) {
+                    self.set(x, y, c);
+                }
+            }
+        }
+    }
+
+    /// Teks dengan font 5×7; `px` = ukuran satu titik font dalam piksel.
+    fn text(&mut self, x: f64, y: f64, s: &str, px: i64, bold: bool, c: [u8; 3]) {
+        let (x0, y0) = (x.round() as i64, y.round() as i64);
+        for (i, ch) in s.chars().enumerate() {
+            let code = ch as u32;
+            let glyph = match code {
+                32..=126 => &FONT[(code - 32) as usize],
+                _ => &FONT[('?' as u32 - 32) as usize],
+            };
+            let gx = x0 + i as i64 * 6 * px;
+            for (row, bits) in glyph.iter().enumerate() {
+                for col in 0..5 {
+                    if bits & (0x10 >> col) == 0 {
+                        continue;
+                    }
+                    for dy in 0..px {
+                        for dx in 0..px + i64::from(bold) {
+                            self.set(gx + col * px + dx, y0 + row as i64 * px + dy, c);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn encode_png(&self) -> Vec<u8> {
+        fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+            out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            out.extend_from_slice(kind);
+            out.extend_from_slice(data);
+            let mut crc = flate2::Crc::new();
+            crc.update(kind);
+            crc.update(data);
+            out.extend_from_slice(&crc.sum().to_be_bytes());
+        }
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&(self.w as u32).to_be_bytes());
+        ihdr.extend_from_slice(&(self.h as u32).to_be_bytes());
+        // 8 bit, RGB, kompresi/filter/interlace standar
+        ihdr.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut z = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        for row in self.px.chunks(self.w * 3) {
+            // filter 0 (None) per baris
+            let _ = z.write_all(&[0]);
+            let _ = z.write_all(row);
+        }
+        let idat = z.finish().unwrap_or_default();
+
+        let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+        chunk(&mut out, b"IHDR", &ihdr);
+        chunk(&mut out, b"IDAT", &idat);
+        chunk(&mut out, b"IEND", &[]);
+        out
+    }
+}
+
+pub fn png(g: &ExportGraph) -> Result<Vec<u8>, TooLarge> {
+    let l = layout(g);
+    let s = PNG_SCALE;
+    let (w, h) = ((l.width * s).ceil(), (l.height * s).ceil());
+    if w * h > MAX_PNG_PIXELS {
+        return Err(TooLarge { width: w as u32, height: h as u32 });
+    }
+    let mut c = Canvas::new(w as usize, h as usize);
+    let sc = |p: (f64, f64)| (p.0 * s, p.1 * s);
+    let (text, edge, stroke) = (rgb(TEXT), rgb(EDGE), rgb(STROKE));
+    // Font 5×7 dengan titik 2 px: tinggi huruf 14 px ≈ teks 10 unit di SVG
+    let dot = s as i64;
+
+    c.text(MARGIN * s, MARGIN * s, &g.title, dot, true, text);
+    let mut lx = MARGIN;
+    for (label, color) in &l.legend {
+        let y = MARGIN + TITLE_H;
+        c.fill_rect(lx * s, y * s, 10.0 * s, 10.0 * s, rgb(color));
+        c.stroke_rect(lx * s, y * s, 10.0 * s, 10.0 * s, stroke);
+        c.text((lx + 14.0) * s, (y + 1.5) * s, label, dot, false, text);
+        lx += (label.chars().count() as f64 + 4.0) * CHAR_W;
+    }
+
+    for (a, b, label, arrows) in &l.edges {
+        if a == b {
+            continue;
+        }
+        let p = edge_curve(&l.nodes[*a], &l.nodes[*b]);
+        let steps = 24;
+        let mut prev = sc(p[0]);
+        for i in 1..=steps {
+            let next = sc(bezier(&p, i as f64 / steps as f64));
+            c.line(prev, next, edge);
+            prev = next;
+        }
+        let (at_end, at_start) = arrow_ends(arrows);
+        if at_end {
+            let t = arrow_head(bezier(&p, 0.92), p[3]);
+            c.triangle(&[sc(t[0]), sc(t[1]), sc(t[2])], edge);
+        }
+        if at_start {
+            let t = arrow_head(bezier(&p, 0.08), p[0]);
+            c.triangle(&[sc(t[0]), sc(t[1]), sc(t[2])], edge);
+        }
+        if let Some(label) = label.as_deref().filter(|s| !s.is_empty()) {
+            let (mx, my) = bezier(&p, 0.5);
+            let text_label = truncate(label);
+            let tw = text_label.chars().count() as f64 * CHAR_W;
+            c.fill_rect((mx - tw / 2.0 - 2.0) * s, (my - 6.0) * s, (tw + 4.0) * s, 12.0 * s, [255, 255, 255]);
+            c.text((mx - tw / 2.0) * s, (my - 3.5) * s, &text_label, dot, false, rgb("#495057"));
+        }
+    }
+
+    for n in &l.nodes {
+        c.fill_rect(n.x * s, n.y * s, n.w * s, n.h * s, rgb(n.fill));
+        c.stroke_rect(n.x * s, n.y * s, n.w * s, n.h * s, stroke);
+        let mut y = n.y + PAD + 1.5;
+        for (i, line) in n.lines.iter().enumerate() {
+            let header = i == 0 && n.lines.len() > 1;
+            c.text((n.x + PAD) * s, y * s, line, dot, header, text);
+            if header && g.kind == Kind::Er {
+                let sep = n.y + PAD + LINE_H + PAD / 2.0;
+                c.line((n.x * s, sep * s), ((n.x + n.w) * s, sep * s), stroke);
+                y += PAD;
+            }
+            y += LINE_H;
+        }
+    }
+    Ok(c.encode_png())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+    use crate::export::{ExportEdge, ExportNode};
+
+    fn graph(nodes: &[&str], edges: &[(&str, &str)]) -> ExportGraph {
+        ExportGraph {
+            title: "<judul>".into(),
+            kind: Kind::Flow,
+            nodes: nodes
+                .iter()
+                .map(|n| ExportNode { id: n.to_string(), label: n.to_string(), group: None, fields: Vec::new() })
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|(a, b)| ExportEdge { from: a.to_string(), to: b.to_string(), label: None, arrows: "to".into() })
+                .collect(),
+        }
+    }
+
+    fn overlap(a: &Placed, b: &Placed) -> bool {
+        a.x < b.x + b.w && b.x < a.x + a.w && a.y < b.y + b.h && b.y < a.y + a.h
+    }
+
+    #[test]
+    fn layers_follow_edges_and_break_cycles() {
+        assert_eq!(layers(3, &[(0, 1), (1, 2)]), [0, 1, 2]);
+        assert_eq!(layers(3, &[(0, 1), (1, 2), (2, 0), (1, 1)]), [0, 1, 2]);
+        assert_eq!(layers(3, &[(0, 2), (1, 2)]), [0, 0, 1]);
+    }
+
+    #[test]
+    fn layout_places_ranks_left_to_right_without_overlap() {
+        let g = graph(&["a", "b", "c", "d", "lone"], &[("a", "b"), ("b", "c"), ("a", "d")]);
+        let l = layout(&g);
+        let n = &l.nodes;
+        assert!(n[0].x + n[0].w < n[1].x && n[1].x + n[1].w < n[2].x);
+        assert_eq!(n[1].x, n[3].x);
+        // node tanpa edge di bawah graph berlapis
+        assert!((0..4).all(|i| n[4].y >= n[i].y + n[i].h));
+        for i in 0..n.len() {
+            for j in i + 1..n.len() {
+                assert!(!overlap(&n[i], &n[j]), "node {i} dan {j} bertumpuk");
+            }
+            assert!(n[i].x + n[i].w <= l.width && n[i].y + n[i].h <= l.height);
+        }
+    }
+
+    #[test]
+    fn long_labels_keep_their_tail() {
+        let long = format!("src/{}/main.rs", "x".repeat(100));
+        let t = truncate(&long);
+        assert_eq!(t.chars().count(), MAX_LABEL);
+        assert!(t.starts_with('…') && t.ends_with("/main.rs"));
+        assert_eq!(truncate("short"), "short");
+    }
+
+    #[test]
+    fn svg_escapes_text() {
+        let out = svg(&graph(&["a&b", "c"], &[("a&b", "c")]));
+        assert!(out.starts_with("<svg ") && out.ends_with("</svg>\n"));
+        assert!(out.contains("&lt;judul&gt;") && out.contains(">a&amp;b</text>"));
+        assert_eq!(out.matches("<path ").count(), 1);
+    }
+
+    #[test]
+    fn png_has_layout_size_and_valid_pixels() {
+        let g = graph(&["a", "b"], &[("a", "b")]);
+        let l = layout(&g);
+        let png = png(&g).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        let w = u32::from_be_bytes(png[16..20].try_into().unwrap()) as usize;
+        let h = u32::from_be_bytes(png[20..24].try_into().unwrap()) as usize;
+        assert_eq!((w, h), ((l.width * PNG_SCALE).ceil() as usize, (l.height * PNG_SCALE).ceil() as usize));
+        let len = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+        assert_eq!(&png[37..41], b"IDAT");
+        let mut raw = Vec::new();
+        flate2::read::ZlibDecoder::new(&png[41..41 + len]).read_to_end(&mut raw).unwrap();
+        assert_eq!(raw.len(), h * (w * 3 + 1));
+    }
+
+    #[test]
+    fn png_refuses_huge_graphs() {
+        let names: Vec<String> = (0..3000).map(|i| format!("n{i}")).collect();
+        let mut nodes: Vec<&str> = vec!["root"];
+        nodes.extend(names.iter().map(String::as_str));
+        let edges: Vec<(&str, &str)> = names.iter().map(|n| ("root", n.as_str())).collect();
+        assert!(png(&graph(&nodes, &edges)).is_err());
+    }
+}
@@ -0,0 +1,366 @@
+// src/schema.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Output terstruktur untuk jenis analisa berformat json. Jawaban LLM
+// diambil JSON-nya, divalidasi terhadap JSON Schema milik jenis analisa
+// (subset: type, properties, required, items, enum, additionalProperties),
+// lalu dirender menjadi teks Markdown untuk tampilan. Data JSON-nya
+// disimpan di analysis_results.structured dan bisa di-query lewat API.
+
+use serde_json::{Map, Value};
+
+/// Jumlah percobaan ulang bila jawaban bukan JSON valid (env LLM_JSON_RETRIES, default 2).
+pub fn max_retries() -> usize {
+    std::env::var("LLM_JSON_RETRIES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(2)
+}
+
+/// Ambil JSON dari jawaban LLM: isi blok ```json / ``` bila ada, atau
+/// objek/array pertama di teks yang valid (sampai kurung penutup
+/// pasangannya, bukan kurung terakhir di teks).
+pub fn extract(raw: &str) -> Result<Value, String> {
+    let body = raw
+        .split_once("```json")
+        .or_else(|| raw.split_once("```"))
+        .and_then(|(_, rest)| rest.split_once("```").map(|(b, _)| b))
+        .unwrap_or(raw)
+        .trim();
+    if let Ok(v) = serde_json::from_str::<Value>(body) {
+        return Ok(v);
+    }
+    // Kandidat tingkat atas saja: isi objek yang gagal di-parse tidak dicoba
+    let mut first_error = None;
+    let mut pos = 0;
+    while let Some(start) = body[pos..].find(['{', '[']).map(|i| pos + i) {
+        let Some(len) = closing_bracket(&body[start..]) else {
+            pos = start + 1;
+            continue;
+        };
+        match serde_json::from_str(&body[start..start + len]) {
+            Ok(v) => return Ok(v),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+        pos = start + len;
+    }
+    match first_error {
+        Some(e) => Err(format!("JSON tidak valid: {e}")),
+        None => Err("jawaban tidak berisi JSON".to_string()),
+    }
+}
+
+/// Panjang `s` (diawali `{` atau `[`) sampai kurung penutup pasangannya,
+/// melewati isi string JSON. None bila kurungnya tidak pernah tertutup.
+fn closing_bracket(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Validasi `value` terhadap `schema`. Error pertama dikembalikan beserta
+/// path-nya, mis. `$.functions[2].name: wajib ada`.
+pub fn validate(schema: &Value, value: &Value) -> Result<(), String> {
+    check(schema, value, "$")
+}
+
+fn type_matches(ty: &str, value: &Value) -> bool {
+    match ty {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn check(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    let Some(schema) = schema.as_object() else {
+        return Ok(());
+    };
+
+    match schema.get("type") {
+        Some(Value::String(ty)) if !type_matches(ty, value) => {
+            return Err(format!("{path}: harus bertipe {ty}"));
+        }
+        Some(Value::Array(types)) if !types.iter().filter_map(Value::as_str).any(|t| type_matches(t, value)) => {
+            let names: Vec<&str> = types.iter().filter_map(Value::as_str).collect();
+            return Err(format!("{path}: harus bertipe {}", names.join(" | ")));
+        }
+        _ => {}
+    }
+
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        if !options.contains(value) {
+            return Err(format!("{path}: harus salah satu dari {}", Value::Array(options.clone())));
+        }
+    }
+
+    if let Value::Object(obj) = value {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !obj.contains_key(key) {
+                    return Err(format!("{path}.{key}: wajib ada"));
+                }
+            }
+        }
+        let props = schema.get("properties").and_then(Value::as_object);
+        for (key, v) in obj {
+            match props.and_then(|p| p.get(key)) {
+                Some(sub) => check(sub, v, &format!("{path}.{key}"))?,
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    return Err(format!("{path}.{key}: properti tidak dikenal"));
+                }
+                None => {}
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(sub)) = (value, schema.get("items")) {
+        for (i, v) in items.iter().enumerate() {
+            check(sub, v, &format!("{path}[{i}]"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Schema yang disimpan admin harus berupa objek JSON.
+pub fn parse_schema(text: &str) -> Result<Value, String> {
+    match serde_json::from_str::<Value>(text) {
+        Ok(v @ Value::Object(_)) => Ok(v),
+        Ok(_) => Err("JSON Schema harus berupa objek".to_string()),
+        Err(e) => Err(format!("JSON Schema tidak valid: {e}")),
+    }
+}
+
+/// Kata tetap di tampilan teks, dalam bahasa output analisa.
+struct Words {
+    lang: &'static str,
+    yes: &'static str,
+    no: &'static str,
+    /// Judul field `ringkasan` di schema bawaan.
+    summary: &'static str,
+}
+
+const WORDS: [Words; 8] = [
+    Words { lang: "id", yes: "ya", no: "tidak", summary: "Ringkasan" },
+    Words { lang: "en", yes: "yes", no: "no", summary: "Summary" },
+    Words { lang: "ms", yes: "ya", no: "tidak", summary: "Ringkasan" },
+    Words { lang: "ja", yes: "はい", no: "いいえ", summary: "概要" },
+    Words { lang: "zh", yes: "是", no: "否", summary: "摘要" },
+    Words { lang: "es", yes: "sí", no: "no", summary: "Resumen" },
+    Words { lang: "fr", yes: "oui", no: "non", summary: "Résumé" },
+    Words { lang: "de", yes: "ja", no: "nein", summary: "Zusammenfassung" },
+];
+
+/// Render data terstruktur menjadi Markdown: objek → daftar "**kunci**: nilai",
+/// array → butir bertingkat. Dipakai sebagai `content` hasil analisa dalam
+/// bahasa output `lang`.
+pub fn render_prose(value: &Value, lang: &str) -> String {
+    let w = WORDS.iter().find(|w| w.lang == lang).unwrap_or(&WORDS[0]);
+    let mut out = String::new();
+    match value {
+        Value::Object(obj) => render_object(obj, 0, w, &mut out),
+        other => render_value(other, 0, w, &mut out),
+    }
+    out.trim_end().to_string()
+}
+
+fn label(key: &str, w: &Words) -> String {
+    if key == "ringkasan" {
+        return w.summary.to_string();
+    }
+    let mut s = key.replace('_', " ");
+    if let Some(c) = s.get(..1) {
+        let upper = c.to_uppercase();
+        s.replace_range(..1, &upper);
+    }
+    s
+}
+
+fn scalar(value: &Value, w: &Words) -> Option<String> {
+    match value {
+        Value::Null => Some("-".to_string()),
+        Value::String(s) => Some(s.clone()),
+        Value::Bool(b) => Some(if *b { w.yes } else { w.no }.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Array(a) if a.iter().all(|v| v.is_string() || v.is_number()) => Some(
+            a.iter()
+                .map(|v| match v {
+                    Value::String(s) => format!("`{s}`"),
+                    other => other.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        _ => None,
+    }
+}
+
+fn render_object(obj: &Map<String, Value>, depth: usize, w: &Words, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    for (key, v) in obj {
+        match scalar(v, w) {
+            Some(s) if depth == 0 && v.is_string() && s.contains('\n') => {
+                out.push_str(&format!("**{}**\n\n{s}\n\n", label(key, w)));
+            }
+            Some(s) => out.push_str(&format!("{indent}- **{}**: {s}\n", label(key, w))),
+            None if depth == 0 => {
+                out.push_str(&format!("### {}\n\n", label(key, w)));
+                render_value(v, 0, w, out);
+                out.push('\n');
+            }
+            None => {
+                out.push_str(&format!("{indent}- **{}**:\n", label(key, w)));
+                render_value(v, depth + 1, w, out);
+            }
+        }
+    }
+}
+
+/// Field yang dipakai sebagai judul butir objek di dalam array.
+const TITLE_KEYS: [&str; 6] = ["name", "nama", "table", "path", "file", "title"];
+
+fn render_value(value: &Value, depth: usize, w: &Words, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    match value {
+        Value::Array(items) if items.is_empty() => out.push_str(&format!("{indent}- -\n")),
+        Value::Array(items) => {
+            for item in items {
+                match item {
+                    Value::Object(obj) => {
+                        // Butir objek: field nama (name, table, path, ...) jadi judul
+                        // butir, field lain bertingkat di bawahnya.
+                        let title = TITLE_KEYS
+                            .iter()
+                            .find(|k| obj.get(**k).is_some_and(|v| v.is_string()))
+                            .copied();
+                        match title {
+                            Some(k) => {
+                                out.push_str(&format!("{indent}- **{}**\n", scalar(&obj[k], w).unwrap_or_default()));
+                                let rest: Map<String, Value> =
+                                    obj.iter().filter(|(key, _)| *key != k).map(|(k, v)| (k.clone(), v.clone())).collect();
+                                render_object(&rest, depth + 1, w, out);
+                            }
+                            None => {
+                                out.push_str(&format!("{indent}-\n"));
+                                render_object(obj, depth + 1, w, out);
+                            }
+                        }
+                    }
+                    other => match scalar(other, w) {
+                        Some(s) => out.push_str(&format!("{indent}- {s}\n")),
+                        None => render_value(other, depth + 1, w, out),
+                    },
+                }
+            }
+        }
+        Value::Object(obj) => render_object(obj, depth, w, out),
+        other => out.push_str(&format!("{indent}{}\n", scalar(other, w).unwrap_or_default())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn extract_stops_at_matching_brace() {
+        assert_eq!(extract(r#"x {"a":"}"} y {"b":1}"#), Ok(json!({"a": "}"})));
+        assert_eq!(extract(r#"Hasil: [1, {"s": "a\"]"}] selesai."#), Ok(json!([1, {"s": "a\"]"}])));
+    }
+
+    #[test]
+    fn extract_code_block_and_fallbacks() {
+        assert_eq!(extract("teks\n```json\n{\"a\": 1}\n```\nakhir"), Ok(json!({"a": 1})));
+        assert_eq!(extract(r#"{"a": 1,,} lalu {"b": 2}"#), Ok(json!({"b": 2})));
+        assert!(extract(r#"{"a": {"b": 1}, oops}"#).unwrap_err().starts_with("JSON tidak valid"));
+        assert_eq!(extract("tanpa json {"), Err("jawaban tidak berisi JSON".to_string()));
+    }
+
+    #[test]
+    fn validate_reports_path() {
+        let schema = json!({
+            "type": "object",
+            "required": ["functions"],
+            "properties": {
+                "functions": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": { "severity": { "enum": ["low", "high"] } }
+                    }
+                }
+            },
+            "additionalProperties": false
+        });
+        assert_eq!(validate(&schema, &json!({"functions": []})), Ok(()));
+        assert_eq!(validate(&schema, &json!({})), Err("$.functions: wajib ada".to_string()));
+        assert_eq!(
+            validate(&schema, &json!({"functions": [{"name": "a"}, {}]})),
+            Err("$.functions[1].name: wajib ada".to_string())
+        );
+        assert!(validate(&schema, &json!({"functions": [{"name": "a", "severity": "mid"}]}))
+            .unwrap_err()
+            .starts_with("$.functions[0].severity: harus salah satu dari"));
+        assert_eq!(
+            validate(&schema, &json!({"functions": [], "x": 1})),
+            Err("$.x: properti tidak dikenal".to_string())
+        );
+    }
+
+    #[test]
+    fn render_prose_follows_output_language() {
+        let data = json!({"ringkasan": "Modul login.", "functions": [{"name": "login", "async": true}]});
+        assert_eq!(
+            render_prose(&data, "id"),
+            "### Functions\n\n- **login**\n  - **Async**: ya\n\n- **Ringkasan**: Modul login."
+        );
+        assert_eq!(
+            render_prose(&data, "en"),
+            "### Functions\n\n- **login**\n  - **Async**: yes\n\n- **Summary**: Modul login."
+        );
+        assert!(render_prose(&data, "de").contains("**Async**: ja\n"));
+        assert!(render_prose(&json!({"aktif": false}), "fr").contains("non"));
+        // bahasa tidak dikenal memakai kata bahasa Indonesia
+        assert!(render_prose(&json!({"aktif": false}), "xx").contains("tidak"));
+    }
+}
@@ -0,0 +1,649 @@
+// src/semantic.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Indeks pencarian semantik per aplikasi. Isi file dipecah menjadi potongan
+// baris yang saling tumpang tindih, dan setiap hasil analisa tersimpan menjadi
+// potongan tersendiri (tabel search_chunks). Peringkat memakai BM25 di memori;
+// bila EMBEDDING_MODEL diisi, setiap potongan juga diberi embedding dari
+// provider LLM dan skornya gabungan cosine + BM25. Indeks dibangun ulang
+// otomatis di latar belakang saat file / hasil analisa aplikasi berubah
+// (indeks lama tetap dipakai sementara), dan embedding potongan yang isinya
+// sama dipakai ulang supaya tidak ditagih dua kali.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+use crate::usage::LlmCtx;
+
+const CHUNK_LINES: usize = 40;
+const CHUNK_OVERLAP: usize = 10;
+const EMBED_BATCH: usize = 64;
+/// Batas karakter per potongan yang dikirim ke endpoint embedding.
+const EMBED_INPUT_CHARS: usize = 6000;
+const MAX_RANGES: usize = 3;
+// Parameter BM25
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+/// Bobot cosine pada skor gabungan (sisanya BM25 ternormalisasi).
+const VECTOR_WEIGHT: f64 = 0.7;
+/// Akhiran penanda indeks yang sebagian embedding-nya gagal dibuat.
+const PARTIAL: &str = ":partial";
+
+/// Model embedding (env EMBEDDING_MODEL, mis. text-embedding-3-small);
+/// kosong = hanya BM25, tanpa panggilan ke provider.
+pub fn embedding_model() -> Option<String> {
+    std::env::var("EMBEDDING_MODEL")
+        .ok()
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+}
+
+/// Pecah identifier snake_case / camelCase menjadi kata (huruf kecil).
+fn split_identifier(word: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut cur = String::new();
+    let mut prev_lower = false;
+    for c in word.chars() {
+        if c == '_' {
+            if !cur.is_empty() {
+                parts.push(std::mem::take(&mut cur).to_lowercase());
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !cur.is_empty() {
+            parts.push(std::mem::take(&mut cur).to_lowercase());
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        cur.push(c);
+    }
+    if !cur.is_empty() {
+        parts.push(cur.to_lowercase());
+    }
+    parts
+}
+
+/// Term untuk BM25: kata utuh (huruf kecil) plus bagian-bagian identifier,
+/// sehingga `getUserById` juga cocok dengan "user".
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    for word in text.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+        if word.is_empty() {
+            continue;
+        }
+        let parts = split_identifier(word);
+        if parts.len() > 1 {
+            out.extend(parts.into_iter().filter(|p| p.chars().count() >= 2));
+        }
+        let lower = word.to_lowercase();
+        if lower.chars().count() >= 2 {
+            out.push(lower);
+        }
+    }
+    out
+}
+
+/// Potongan baris (1-based, inklusif) dengan tumpang tindih CHUNK_OVERLAP.
+fn chunk_code(code: &str) -> Vec<(usize, usize, String)> {
+    let lines: Vec<&str> = code.lines().collect();
+    let mut out = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start + CHUNK_LINES).min(lines.len());
+        let text = lines[start..end].join("\n");
+        if !text.trim().is_empty() {
+            out.push((start + 1, end, text));
+        }
+        if end == lines.len() {
+            break;
+        }
+        start = end - CHUNK_OVERLAP;
+    }
+    out
+}
+
+/// FNV-1a 64 bit: kunci pemakaian ulang embedding untuk isi yang sama.
+fn content_hash(s: &str) -> String {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in s.bytes() {
+        h ^= b as u64;
+        h = h.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{h:016x}")
+}
+
+struct NewChunk {
+    file_id: i64,
+    source: &'static str,
+    kind: Option<String>,
+    line_start: usize,
+    line_end: usize,
+    content: String,
+    hash: String,
+    embed_input: String,
+}
+
+/// Penanda isi aplikasi; indeks dibangun ulang bila berbeda dengan yang tersimpan.
+pub(crate) async fn source_signature(pool: &MySqlPool, app_id: i64) -> anyhow::Result<String> {
+    let (files, max_file): (i64, Option<i64>) =
+        sqlx::query_as("SELECT COUNT(*), MAX(id) FROM files WHERE app_id=?")
+            .bind(app_id)
+            .fetch_one(pool)
+            .await?;
+    let (results, updated): (i64, Option<i64>) = sqlx::query_as(
+        "SELECT COUNT(*), CAST(UNIX_TIMESTAMP(MAX(r.updated_at)) AS SIGNED)
+         FROM analysis_results r JOIN files f ON f.id = r.file_id WHERE f.app_id=?",
+    )
+    .bind(app_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(format!(
+        "{files}:{}:{results}:{}:{}",
+        max_file.unwrap_or(0),
+        updated.unwrap_or(0),
+        embedding_model().unwrap_or_default()
+    ))
+}
+
+/// Bangun ulang potongan (dan embedding bila aktif) seluruh aplikasi.
+pub async fn index_app(pool: &MySqlPool, app_id: i64, user: Option<&str>) -> anyhow::Result<()> {
+    let signature = source_signature(pool, app_id).await?;
+    let model = embedding_model();
+
+    let files: Vec<(i64, String, Option<String>)> =
+        sqlx::query_as("SELECT id, full_path, content_file FROM files WHERE app_id=? ORDER BY id")
+            .bind(app_id)
+            .fetch_all(pool)
+            .await?;
+    let mut chunks: Vec<NewChunk> = Vec::new();
+    for (file_id, path, content) in &files {
+        for (line_start, line_end, text) in chunk_code(content.as_deref().unwrap_or("")) {
+            let embed_input: String = format!("{path}\n{text}").chars().take(EMBED_INPUT_CHARS).collect();
+            chunks.push(NewChunk {
+                file_id: *file_id,
+                source: "code",
+                kind: None,
+                line_start,
+                line_end,
+                hash: content_hash(&embed_input),
+                content: text,
+                embed_input,
+            });
+        }
+    }
+
+    let results: Vec<(i64, String, String, String, Option<i32>)> = sqlx::query_as(
+        "SELECT r.file_id, f.full_path, r.kind, r.content, m.line_count
+         FROM analysis_results r
+         JOIN files f ON f.id = r.file_id
+         LEFT JOIN file_metadata m ON m.file_id = r.file_id
+         WHERE f.app_id=? ORDER BY r.file_id, r.kind",
+    )
+    .bind(app_id)
+    .fetch_all(pool)
+    .await?;
+    for (file_id, path, kind, content, line_count) in results {
+        if content.trim().is_empty() {
+            continue;
+        }
+        let embed_input: String = format!("{path} ({kind})\n{content}").chars().take(EMBED_INPUT_CHARS).collect();
+        chunks.push(NewChunk {
+            file_id,
+            source: "analysis",
+            kind: Some(kind),
+            line_start: 1,
+            line_end: line_count.unwrap_or(1).max(1) as usize,
+            hash: content_hash(&embed_input),
+            content,
+            embed_input,
+        });
+    }
+
+    // Embedding: pakai ulang yang isinya sama, sisanya diminta per batch
+    let mut vectors: Vec<Option<String>> = vec![None; chunks.len()];
+    let mut partial = false;
+    if let Some(model) = &model {
+        let old: Vec<(String, String)> = sqlx::query_as(
+            "SELECT c.content_hash, CAST(c.embedding AS CHAR)
+             FROM search_chunks c JOIN search_index_state s ON s.app_id = c.app_id
+             WHERE c.app_id=? AND s.model=? AND c.embedding IS NOT NULL",
+        )
+        .bind(app_id)
+        .bind(model)
+        .fetch_all(pool)
+        .await?;
+        let old: HashMap<String, String> = old.into_iter().collect();
+        let mut missing = Vec::new();
+        for (i, c) in chunks.iter().enumerate() {
+            match old.get(&c.hash) {
+                Some(v) => vectors[i] = Some(v.clone()),
+                None => missing.push(i),
+            }
+        }
+        let api_key = std::env::var("OPENAI_API_KEY").unwrap_or_default();
+        let lang = crate::lang::default_lang();
+        let ctx = LlmCtx { pool, app_id: Some(app_id), file_id: None, kind: "embedding", user, lang };
+        for batch in missing.chunks(EMBED_BATCH) {
+            let inputs: Vec<String> = batch.iter().map(|&i| chunks[i].embed_input.clone()).collect();
+            match crate::openai::embed(&api_key, model, &inputs, &ctx).await {
+                Ok(embedded) => {
+                    for (&i, v) in batch.iter().zip(embedded) {
+                        vectors[i] = Some(serde_json::to_string(&v)?);
+                    }
+                }
+                Err(e) => {
+                    // Potongan yang sudah ter-embed tetap disimpan; tanda "partial"
+                    // membuat pencarian berikutnya mencoba sisanya lagi.
+                    eprintln!("Gagal membuat embedding indeks aplikasi {app_id}: {e:?}");
+                    partial = true;
+                    break;
+                }
+            }
+        }
+    }
+    let signature = if partial { format!("{signature}{PARTIAL}") } else { signature };
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM search_chunks WHERE app_id=?")
+        .bind(app_id)
+        .execute(&mut *tx)
+        .await?;
+    for (c, vector) in chunks.iter().zip(&vectors) {
+        sqlx::query(
+            "INSERT INTO search_chunks (app_id, file_id, source, kind, line_start, line_end, content, content_hash, embedding)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(app_id)
+        .bind(c.file_id)
+        .bind(c.source)
+        .bind(&c.kind)
+        .bind(c.line_start as i32)
+        .bind(c.line_end as i32)
+        .bind(&c.content)
+        .bind(&c.hash)
+        .bind(vector)
+        .execute(&mut *tx)
+        .await?;
+    }
+    sqlx::query(
+        "INSERT INTO search_index_state (app_id, signature, backend, model, chunks) VALUES (?, ?, ?, ?, ?)
+         ON DUPLICATE KEY UPDATE signature=VALUES(signature), backend=VALUES(backend), model=VALUES(model),
+           chunks=VALUES(chunks), indexed_at=CURRENT_TIMESTAMP",
+    )
+    .bind(app_id)
+    .bind(&signature)
+    .bind(if model.is_some() { "embedding" } else { "bm25" })
+    .bind(&model)
+    .bind(chunks.len() as i32)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    cache().lock().expect("cache indeks").remove(&app_id);
+    Ok(())
+}
+
+struct IndexedChunk {
+    file_id: i64,
+    path: String,
+    source: String,
+    kind: Option<String>,
+    line_start: usize,
+    line_end: usize,
+    tf: HashMap<String, u32>,
+    len: usize,
+    vector: Option<Vec<f32>>,
+}
+
+/// Indeks satu aplikasi di memori (statistik BM25 + vektor).
+struct AppIndex {
+    signature: String,
+    model: Option<String>,
+    chunks: Vec<IndexedChunk>,
+    df: HashMap<String, usize>,
+    avg_len: f64,
+}
+
+fn cache() -> &'static Mutex<HashMap<i64, Arc<AppIndex>>> {
+    static CACHE: OnceLock<Mutex<HashMap<i64, Arc<AppIndex>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[derive(sqlx::FromRow)]
+struct ChunkRow {
+    file_id: i64,
+    full_path: String,
+    source: String,
+    kind: Option<String>,
+    line_start: i32,
+    line_end: i32,
+    content: String,
+    embedding: Option<String>,
+}
+
+/// Kunci pembangunan ulang per aplikasi, supaya satu aplikasi tidak diindeks
+/// dua kali bersamaan.
+fn build_lock(app_id: i64) -> Arc<tokio::sync::Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<HashMap<i64, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+    let mut locks = LOCKS.get_or_init(|| Mutex::new(HashMap::new())).lock().expect("kunci indeks");
+    locks.entry(app_id).or_default().clone()
+}
+
+async fn stored_state(pool: &MySqlPool, app_id: i64) -> anyhow::Result<Option<(String, Option<String>)>> {
+    Ok(sqlx::query_as("SELECT signature, model FROM search_index_state WHERE app_id=?")
+        .bind(app_id)
+        .fetch_optional(pool)
+        .await?)
+}
+
+/// Bangun ulang bila indeks tersimpan masih berbeda dengan isi aplikasi;
+/// pemanggil harus memegang `build_lock(app_id)`.
+async fn rebuild_if_stale(pool: &MySqlPool, app_id: i64, user: Option<&str>) -> anyhow::Result<()> {
+    let signature = source_signature(pool, app_id).await?;
+    match stored_state(pool, app_id).await? {
+        Some((s, _)) if s == signature => Ok(()),
+        _ => index_app(pool, app_id, user).await,
+    }
+}
+
+/// Indeks aplikasi di memori. Bila isi aplikasi berubah, indeks dibangun
+/// ulang di latar belakang dan indeks sebelumnya tetap dipakai sementara;
+/// hanya aplikasi yang belum pernah diindeks yang menunggu pembangunan.
+async fn load(pool: &MySqlPool, app_id: i64, user: Option<&str>) -> anyhow::Result<Arc<AppIndex>> {
+    let signature = source_signature(pool, app_id).await?;
+    let (stored, model) = match stored_state(pool, app_id).await? {
+        Some((stored, model)) => {
+            if stored != signature {
+                if let Ok(guard) = build_lock(app_id).try_lock_owned() {
+                    let pool = pool.clone();
+                    let user = user.map(str::to_string);
+                    tokio::spawn(async move {
+                        if let Err(e) = rebuild_if_stale(&pool, app_id, user.as_deref()).await {
+                            eprintln!("Gagal membangun ulang indeks semantik aplikasi {app_id}: {e:?}");
+                        }
+                        drop(guard);
+                    });
+                }
+            }
+            (stored, model)
+        }
+        None => {
+            let _guard = build_lock(app_id).lock_owned().await;
+            rebuild_if_stale(pool, app_id, user).await?;
+            stored_state(pool, app_id).await?.unwrap_or((signature, embedding_model()))
+        }
+    };
+    if let Some(idx) = cache().lock().expect("cache indeks").get(&app_id) {
+        if idx.signature == stored {
+            return Ok(idx.clone());
+        }
+    }
+
+    let rows: Vec<ChunkRow> = sqlx::query_as(
+        "SELECT c.file_id, f.full_path, c.source, c.kind, c.line_start, c.line_end, c.content,
+                CAST(c.embedding AS CHAR) AS embedding
+         FROM search_chunks c JOIN files f ON f.id = c.file_id
+         WHERE c.app_id=? ORDER BY c.id",
+    )
+    .bind(app_id)
+    .fetch_all(pool)
+    .await?;
+    let idx = Arc::new(build_index(stored, model, rows));
+    cache().lock().expect("cache indeks").insert(app_id, idx.clone());
+    Ok(idx)
+}
+
+/// Statistik BM25 dan vektor dari potongan tersimpan.
+fn build_index(signature: String, model: Option<String>, rows: Vec<ChunkRow>) -> AppIndex {
+    let mut df: HashMap<String, usize> = HashMap::new();
+    let mut total_len = 0;
+    let chunks: Vec<IndexedChunk> = rows
+        .into_iter()
+        .map(|r| {
+            let terms = tokenize(&r.content);
+            let mut tf: HashMap<String, u32> = HashMap::new();
+            for t in &terms {
+                *tf.entry(t.clone()).or_default() += 1;
+            }
+            for t in tf.keys() {
+                *df.entry(t.clone()).or_default() += 1;
+            }
+            total_len += terms.len();
+            IndexedChunk {
+                file_id: r.file_id,
+                path: r.full_path,
+                source: r.source,
+                kind: r.kind,
+                line_start: r.line_start.max(0) as usize,
+                line_end: r.line_end.max(0) as usize,
+                len: terms.len(),
+                tf,
+                vector: r.embedding.and_then(|e| serde_json::from_str(&e).ok()),
+            }
+        })
+        .collect();
+    let avg_len = if chunks.is_empty() { 0.0 } else { total_len as f64 / chunks.len() as f64 };
+    AppIndex { signature, model, chunks, df, avg_len }
+}
+
+fn bm25(idx: &AppIndex, terms: &[String]) -> Vec<f64> {
+    let n = idx.chunks.len() as f64;
+    idx.chunks
+        .iter()
+        .map(|c| {
+            terms
+                .iter()
+                .map(|t| {
+                    let Some(&f) = c.tf.get(t) else {
+                        return 0.0;
+                    };
+                    let df = *idx.df.get(t).unwrap_or(&0) as f64;
+                    let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+                    let f = f as f64;
+                    let norm = if idx.avg_len > 0.0 { c.len as f64 / idx.avg_len } else { 1.0 };
+                    idf * f * (K1 + 1.0) / (f + K1 * (1.0 - B + B * norm))
+                })
+                .sum()
+        })
+        .collect()
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut na, mut nb) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        dot += (*x as f64) * (*y as f64);
+        na += (*x as f64) * (*x as f64);
+        nb += (*y as f64) * (*y as f64);
+    }
+    if na == 0.0 || nb == 0.0 {
+        0.0
+    } else {
+        dot / (na.sqrt() * nb.sqrt())
+    }
+}
+
+/// Rentang baris yang cocok di satu file.
+#[derive(Debug, Clone, Serialize)]
+pub struct Range {
+    pub line_start: usize,
+    pub line_end: usize,
+    /// "code" = potongan isi file, "analysis" = hasil analisa `kind`.
+    pub source: String,
+    pub kind: Option<String>,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Hit {
+    pub file_id: i64,
+    pub path: String,
+    pub score: f64,
+    pub ranges: Vec<Range>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResult {
+    pub backend: &'static str,
+    pub model: Option<String>,
+    pub chunks: usize,
+    pub files: Vec<Hit>,
+}
+
+/// File paling relevan untuk `query` beserta rentang barisnya, urut skor.
+pub async fn search(
+    pool: &MySqlPool,
+    app_id: i64,
+    query: &str,
+    limit: usize,
+    user: Option<&str>,
+) -> anyhow::Result<SearchResult> {
+    let idx = load(pool, app_id, user).await?;
+    let mut terms = tokenize(query);
+    terms.sort();
+    terms.dedup();
+
+    let mut scores = bm25(&idx, &terms);
+    let max = scores.iter().cloned().fold(0.0, f64::max);
+    if max > 0.0 {
+        scores.iter_mut().for_each(|s| *s /= max);
+    }
+
+    let mut backend = "bm25";
+    let has_vectors = idx.chunks.iter().any(|c| c.vector.is_some());
+    if let (Some(model), true) = (&idx.model, has_vectors) {
+        let api_key = std::env::var("OPENAI_API_KEY").unwrap_or_default();
+        let lang = crate::lang::default_lang();
+        let ctx = LlmCtx { pool, app_id: Some(app_id), file_id: None, kind: "embedding", user, lang };
+        let query_vec = crate::openai::embed(&api_key, model, &[query.to_string()], &ctx)
+            .await?
+            .pop()
+            .unwrap_or_default();
+        for (s, c) in scores.iter_mut().zip(&idx.chunks) {
+            let sim = c.vector.as_deref().map(|v| cosine(&query_vec, v)).unwrap_or(0.0);
+            *s = VECTOR_WEIGHT * sim + (1.0 - VECTOR_WEIGHT) * *s;
+        }
+        backend = "embedding";
+    }
+
+    let mut per_file: HashMap<i64, Vec<(usize, f64)>> = HashMap::new();
+    for (i, s) in scores.iter().enumerate() {
+        if *s > 0.0 {
+            per_file.entry(idx.chunks[i].file_id).or_default().push((i, *s));
+        }
+    }
+    let mut files: Vec<Hit> = per_file
+        .into_values()
+        .map(|mut hits| {
+            hits.sort_by(|a, b| b.1.total_cmp(&a.1));
+            let first = &idx.chunks[hits[0].0];
+            let mut ranges: Vec<Range> = hits
+                .iter()
+                .take(MAX_RANGES)
+                .map(|&(i, score)| {
+                    let c = &idx.chunks[i];
+                    Range {
+                        line_start: c.line_start,
+                        line_end: c.line_end,
+                        source: c.source.clone(),
+                        kind: c.kind.clone(),
+                        score,
+                    }
+                })
+                .collect();
+            ranges.sort_by_key(|r| (r.source != "code", r.line_start));
+            Hit { file_id: first.file_id, path: first.path.clone(), score: hits[0].1, ranges }
+        })
+        .collect();
+    files.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.path.cmp(&b.path)));
+    files.truncate(limit);
+
+    Ok(SearchResult { backend, model: idx.model.clone(), chunks: idx.chunks.len(), files })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(texts: &[&str]) -> AppIndex {
+        let rows = texts
+            .iter()
+            .enumerate()
+            .map(|(i, t)| ChunkRow {
+                file_id: i as i64,
+                full_path: format!("f{i}.rs"),
+                source: "code".into(),
+                kind: None,
+                line_start: 1,
+                line_end: 1,
+                content: t.to_string(),
+                embedding: None,
+            })
+            .collect();
+        build_index(String::new(), None, rows)
+    }
+
+    #[test]
+    fn identifiers_split_on_case_and_underscore() {
+        assert_eq!(split_identifier("getUserById"), ["get", "user", "by", "id"]);
+        assert_eq!(split_identifier("load_app_index"), ["load", "app", "index"]);
+        assert_eq!(split_identifier("__init__"), ["init"]);
+        assert_eq!(split_identifier("HTTPServer"), ["httpserver"]);
+        assert_eq!(split_identifier("v2Api"), ["v2", "api"]);
+    }
+
+    #[test]
+    fn tokenize_keeps_whole_words_and_parts() {
+        assert_eq!(tokenize("fn getUser(x)"), ["fn", "get", "user", "getuser"]);
+        assert_eq!(tokenize("a = b_c + 1;"), ["b_c"]);
+        assert!(tokenize("  ;; ").is_empty());
+    }
+
+    #[test]
+    fn chunks_overlap_and_cover_every_line() {
+        let code: String = (1..=100).map(|i| format!("line {i}\n")).collect();
+        let chunks = chunk_code(&code);
+        let ranges: Vec<(usize, usize)> = chunks.iter().map(|c| (c.0, c.1)).collect();
+        assert_eq!(ranges, [(1, 40), (31, 70), (61, 100)]);
+        assert!(chunks[1].2.starts_with("line 31\n"));
+        assert!(chunks[2].2.ends_with("line 100"));
+
+        assert_eq!(chunk_code("a\nb").iter().map(|c| (c.0, c.1)).collect::<Vec<_>>(), [(1, 2)]);
+        assert!(chunk_code("").is_empty());
+        // potongan yang hanya berisi spasi dilewati
+        let blank = format!("{}x", "\n".repeat(60));
+        assert_eq!(chunk_code(&blank).iter().map(|c| (c.0, c.1)).collect::<Vec<_>>(), [(31, 61)]);
+    }
+
+    #[test]
+    fn bm25_prefers_rare_and_frequent_terms() {
+        let idx = index(&[
+            "fn load_user() { user user }",
+            "fn load_app() { app }",
+            "fn render() { html }",
+            "fn load_user_profile() { profile }",
+        ]);
+        let scores = bm25(&idx, &tokenize("user"));
+        assert_eq!(scores[1], 0.0);
+        assert_eq!(scores[2], 0.0);
+        assert!(scores[0] > scores[3] && scores[3] > 0.0);
+
+        // "render" hanya ada di satu potongan, "load" di tiga: idf lebih tinggi
+        let scores = bm25(&idx, &tokenize("load render"));
+        assert!(scores[2] > scores[1]);
+        assert!(bm25(&idx, &tokenize("missing")).iter().all(|s| *s == 0.0));
+    }
+}
@@ -9,11 +9,15 @@ LinkedIn : https://id.linkedin.com/in/kukuhtw
 =============================================================================/
 */
 
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read; // <- untuk read_to_end
 use zip::ZipArchive;
 use sqlx::MySqlPool;
 
+use crate::openai::OnToken;
+use crate::usage::LlmCtx;
+
 const MAX_FILE_BYTES: usize = 512 * 1024; // 512 KB per file untuk disimpan ke DB
 
 pub async fn extract_and_store(
@@ -83,5 +87,418 @@ pub async fn extract_and_store(
     }
 
     tx.commit().await?;
+
+    // Indeks import antar file (konteks analisa & graph dependensi)
+    if let Err(e) = crate::imports::index_app(pool, app_id).await {
+        eprintln!("Gagal mengindeks import aplikasi {app_id}: {e:?}");
+    }
+    // Indeks simbol & call graph (setelah import, dipakai untuk resolusi)
+    if let Err(e) = crate::callgraph::index_app(pool, app_id).await {
+        eprintln!("Gagal mengindeks call graph aplikasi {app_id}: {e:?}");
+    }
     Ok(app_id)
 }
+
+// ===== Analisa per file (dipakai handler tunggal & job bulk) =====
+
+/// Hasil analisa yang sudah tersimpan (None bila belum pernah dianalisis).
+pub async fn cached_analysis(
+    pool: &MySqlPool,
+    file_id: i64,
+    kind: &str,
+    lang: &str,
+) -> anyhow::Result<Option<String>> {
+    let row: Option<(String,)> =
+        sqlx::query_as("SELECT content FROM analysis_results WHERE file_id=? AND kind=? AND lang=?")
+            .bind(file_id)
+            .bind(kind)
+            .bind(lang)
+            .fetch_optional(pool)
+            .await?;
+    Ok(row.map(|(s,)| s))
+}
+
+pub struct FileSource {
+    pub app_id: i64,
+    pub path: String,
+    pub code: String,
+}
+
+pub fn urlencode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Tautan ke baris file di halaman aplikasi (disaring nama file, lalu anchor baris).
+pub fn file_href(app_id: i64, file_id: i64, path: &str) -> String {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    format!("/apps/{app_id}?q={}#file-{file_id}", urlencode(name))
+}
+
+/// Tautan ke penampil file, baris `start`–`end` disorot.
+pub fn line_href(file_id: i64, start: i32, end: i32) -> String {
+    if start == end {
+        format!("/files/{file_id}#L{start}")
+    } else {
+        format!("/files/{file_id}#L{start}-L{end}")
+    }
+}
+
+/// Ambil konten file dari DB; jika kosong, fallback ke filesystem.
+pub async fn load_file_source(pool: &MySqlPool, file_id: i64) -> anyhow::Result<FileSource> {
+    let (app_id, full_path, content_file): (i64, String, Option<String>) =
+        sqlx::query_as("SELECT app_id, full_path, content_file FROM files WHERE id=?")
+            .bind(file_id)
+            .fetch_one(pool)
+            .await?;
+    let code = match content_file {
+        Some(c) => c,
+        None => std::fs::read_to_string(&full_path).unwrap_or_default(),
+    };
+    Ok(FileSource { app_id, path: full_path, code })
+}
+
+/// Jalankan analisa `kind` untuk satu file dalam bahasa `lang` lalu
+/// simpan/update hasilnya. `on_token` menerima potongan jawaban LLM
+/// selama streaming; `on_retry` dipanggil sebelum jawaban yang ditolak
+/// schema diminta ulang, supaya penerima membuang token sebelumnya.
+pub async fn run_analysis(
+    pool: &MySqlPool,
+    file_id: i64,
+    kind: &str,
+    lang: &str,
+    user: Option<&str>,
+    on_token: OnToken<'_>,
+    on_retry: &mut (dyn FnMut() + Send),
+) -> anyhow::Result<String> {
+    let Some(def) = crate::kinds::get(pool, kind).await? else {
+        anyhow::bail!("Jenis analisa tidak dikenal: {kind}");
+    };
+    let src = load_file_source(pool, file_id).await?;
+    let prompt = crate::prompts::active(pool, kind).await?;
+    let bahasa = crate::lang::prompt_name(lang);
+    let konteks = crate::context::related(pool, file_id, src.app_id, lang).await?;
+    let rendered = crate::prompts::render(
+        &prompt.template,
+        &[("kode", &src.code), ("path", &src.path), ("bahasa", bahasa), ("konteks", &konteks)],
+    );
+    let mut text = crate::context::apply(&prompt.template, rendered, &konteks, lang);
+    text.push_str(&def.format_instruction());
+    text.push_str(&crate::lang::instruction(lang));
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or_default();
+    let ctx = LlmCtx { pool, app_id: Some(src.app_id), file_id: Some(file_id), kind, user, lang };
+
+    // Format json: validasi terhadap schema, minta ulang bila tidak valid
+    let mut prompt_text = text.clone();
+    let mut attempt = 0;
+    let (result, structured) = loop {
+        let raw = crate::openai::analisa(&api_key, &prompt_text, &ctx, &mut *on_token).await?;
+        match def.structured_output(&raw) {
+            Ok(None) => break (raw, None),
+            Ok(Some(value)) => break (crate::schema::render_prose(&value, lang), Some(value.to_string())),
+            Err(reason) if attempt < crate::schema::max_retries() => {
+                attempt += 1;
+                on_retry();
+                prompt_text = format!(
+                    "{text}\n\nJawaban sebelumnya:\n{raw}\n\nJawaban tersebut ditolak ({reason}). \
+                     Perbaiki dan jawab ulang hanya dengan JSON yang valid sesuai schema."
+                );
+            }
+            Err(reason) => {
+                anyhow::bail!("Jawaban LLM tidak valid setelah {} percobaan: {reason}", attempt + 1);
+            }
+        }
+    };
+
+    // Simpan hasil + data terstruktur + versi prompt yang dipakai
+    sqlx::query(
+        "INSERT INTO analysis_results (file_id, kind, lang, content, structured, prompt_version) VALUES (?, ?, ?, ?, ?, ?)
+         ON DUPLICATE KEY UPDATE content=VALUES(content), structured=VALUES(structured),
+             prompt_version=VALUES(prompt_version), updated_at=CURRENT_TIMESTAMP",
+    )
+    .bind(file_id)
+    .bind(kind)
+    .bind(lang)
+    .bind(&result)
+    .bind(structured)
+    .bind(prompt.version)
+    .execute(pool)
+    .await?;
+    Ok(result)
+}
+
+/// Hasil analisa semua jenis dalam bahasa `lang` untuk sekumpulan file:
+/// file_id -> (kind -> content).
+pub async fn results_for_files(
+    pool: &MySqlPool,
+    file_ids: &[i64],
+    lang: &str,
+) -> anyhow::Result<HashMap<i64, HashMap<String, String>>> {
+    let mut out: HashMap<i64, HashMap<String, String>> = HashMap::new();
+    for chunk in file_ids.chunks(500) {
+        let mut qb = sqlx::QueryBuilder::new("SELECT file_id, kind, content FROM analysis_results WHERE lang = ");
+        qb.push_bind(lang);
+        qb.push(" AND file_id IN (");
+        let mut sep = qb.separated(", ");
+        for id in chunk {
+            sep.push_bind(id);
+        }
+        qb.push(")");
+        let rows: Vec<(i64, String, String)> = qb.build_query_as().fetch_all(pool).await?;
+        for (file_id, kind, content) in rows {
+            out.entry(file_id).or_default().insert(kind, content);
+        }
+    }
+    Ok(out)
+}
+
+/// File aplikasi beserta jumlah jenis analisa aktif yang sudah punya hasil
+/// dalam bahasa target.
+#[derive(sqlx::FromRow)]
+struct BulkCandidate {
+    id: i64,
+    nama_file: String,
+    nama_folder: Option<String>,
+    full_path: String,
+    analyzed: i64,
+}
+
+/// Aturan pilihan analisa bulk: all = semua file, filtered = nama, folder
+/// atau path memuat `q` (tanpa beda huruf besar/kecil), pending = masih ada
+/// jenis analisa aktif yang belum punya hasil.
+fn bulk_selected(scope: &str, q: &str, c: &BulkCandidate, enabled_kinds: i64) -> bool {
+    match scope {
+        "filtered" => {
+            let q = q.to_lowercase();
+            [c.nama_file.as_str(), c.nama_folder.as_deref().unwrap_or(""), c.full_path.as_str()]
+                .iter()
+                .any(|s| s.to_lowercase().contains(&q))
+        }
+        "pending" => c.analyzed < enabled_kinds,
+        _ => true,
+    }
+}
+
+/// File yang dipilih untuk analisa bulk: scope all | filtered (cocok `q`)
+/// | pending (masih ada jenis analisa yang kosong dalam bahasa `lang`).
+pub async fn select_bulk_files(
+    pool: &MySqlPool,
+    app_id: i64,
+    scope: &str,
+    q: &str,
+    lang: &str,
+) -> anyhow::Result<Vec<i64>> {
+    let (enabled_kinds,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM analysis_kinds WHERE enabled = 1")
+        .fetch_one(pool)
+        .await?;
+    let rows: Vec<BulkCandidate> = sqlx::query_as(
+        r#"SELECT f.id, f.nama_file, f.nama_folder, f.full_path, (
+                SELECT COUNT(*) FROM analysis_results r
+                JOIN analysis_kinds k ON k.name = r.kind AND k.enabled = 1
+                WHERE r.file_id = f.id AND r.lang = ?
+           ) AS analyzed
+           FROM files f WHERE f.app_id=? ORDER BY f.id"#,
+    )
+    .bind(lang)
+    .bind(app_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .filter(|c| bulk_selected(scope, q, c, enabled_kinds))
+        .map(|c| c.id)
+        .collect())
+}
+
+// ===== Hasil analisa terstruktur (jenis berformat json) =====
+
+/// Data JSON hasil analisa satu file (None bila jenisnya bukan json atau
+/// hasil dibuat sebelum output terstruktur).
+pub async fn cached_structured(
+    pool: &MySqlPool,
+    file_id: i64,
+    kind: &str,
+    lang: &str,
+) -> anyhow::Result<Option<serde_json::Value>> {
+    let row: Option<(Option<String>,)> = sqlx::query_as(
+        "SELECT CAST(structured AS CHAR) FROM analysis_results WHERE file_id=? AND kind=? AND lang=?",
+    )
+    .bind(file_id)
+    .bind(kind)
+    .bind(lang)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.and_then(|(s,)| s).and_then(|s| serde_json::from_str(&s).ok()))
+}
+
+#[derive(serde::Serialize)]
+pub struct StructuredRow {
+    pub file_id: i64,
+    pub path: String,
+    pub data: serde_json::Value,
+}
+
+/// Data terstruktur jenis `kind` untuk semua file aplikasi. `contains`
+/// menyaring file yang datanya memuat string itu (mis. nama tabel), tanpa
+/// membedakan huruf besar/kecil.
+pub async fn structured_results(
+    pool: &MySqlPool,
+    app_id: i64,
+    kind: &str,
+    lang: &str,
+    contains: Option<&str>,
+) -> anyhow::Result<Vec<StructuredRow>> {
+    let mut qb = sqlx::QueryBuilder::new(
+        "SELECT f.id, f.full_path, CAST(r.structured AS CHAR) FROM analysis_results r
+         JOIN files f ON f.id = r.file_id
+         WHERE r.structured IS NOT NULL AND f.app_id = ",
+    );
+    qb.push_bind(app_id);
+    qb.push(" AND r.kind = ");
+    qb.push_bind(kind);
+    qb.push(" AND r.lang = ");
+    qb.push_bind(lang);
+    if let Some(needle) = contains.filter(|s| !s.is_empty()) {
+        let escaped = needle.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+        qb.push(" AND LOWER(CAST(r.structured AS CHAR)) LIKE ");
+        qb.push_bind(format!("%{}%", escaped.to_lowercase()));
+    }
+    qb.push(" ORDER BY f.id");
+    let rows: Vec<(i64, String, String)> = qb.build_query_as().fetch_all(pool).await?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(file_id, path, data)| {
+            let data = serde_json::from_str(&data).ok()?;
+            Some(StructuredRow { file_id, path, data })
+        })
+        .collect())
+}
+
+// ===== Summary aplikasi =====
+
+pub async fn cached_summary(pool: &MySqlPool, app_id: i64, lang: &str) -> anyhow::Result<Option<String>> {
+    let row: Option<(Option<String>,)> =
+        sqlx::query_as("SELECT summary FROM app_summary WHERE app_id=? AND lang=?")
+            .bind(app_id)
+            .bind(lang)
+            .fetch_optional(pool)
+            .await?;
+    Ok(row.and_then(|(s,)| s))
+}
+
+#[derive(sqlx::FromRow)]
+struct SummarySourceRow {
+    full_path: String,
+    content_file: Option<String>,
+    line_count: Option<i32>,
+    imports: Option<String>,
+    sql_queries: Option<String>,
+}
+
+/// Payload untuk summary: path, metadata, dan potongan konten tiap file.
+pub async fn summary_payload(pool: &MySqlPool, app_id: i64) -> anyhow::Result<String> {
+    let rows: Vec<SummarySourceRow> =
+        sqlx::query_as(
+            r#"SELECT f.full_path, f.content_file, m.line_count, m.imports, m.sql_queries
+               FROM files f LEFT JOIN file_metadata m ON m.file_id=f.id
+               WHERE f.app_id=? ORDER BY f.id"#,
+        )
+        .bind(app_id)
+        .fetch_all(pool)
+        .await?;
+
+    let mut payload = String::new();
+    const SNIPPET_CHARS: usize = 2000;
+    for r in rows {
+        let snippet = r
+            .content_file
+            .as_deref()
+            .map(|s| {
+                if s.len() > SNIPPET_CHARS {
+                    let cut = (0..=SNIPPET_CHARS).rev().find(|i| s.is_char_boundary(*i)).unwrap_or(0);
+                    format!("{}...\n[truncated]", &s[..cut])
+                } else {
+                    s.to_string()
+                }
+            })
+            .unwrap_or_default();
+
+        payload.push_str(&format!(
+            "- {} | lines={:?}\nimports:\n{}\nsql:\n{}\ncontent:\n{}\n\n",
+            r.full_path,
+            r.line_count,
+            r.imports.unwrap_or_default(),
+            r.sql_queries.unwrap_or_default(),
+            snippet
+        ));
+    }
+    Ok(payload)
+}
+
+/// Buat summary aplikasi via LLM dalam bahasa `lang` lalu simpan ke `app_summary`.
+pub async fn run_summary(
+    pool: &MySqlPool,
+    app_id: i64,
+    lang: &str,
+    user: Option<&str>,
+    on_token: OnToken<'_>,
+) -> anyhow::Result<String> {
+    let payload = summary_payload(pool, app_id).await?;
+    let (app_name,): (String,) = sqlx::query_as("SELECT nama_aplikasi FROM applications WHERE id=?")
+        .bind(app_id)
+        .fetch_one(pool)
+        .await?;
+    let prompt = crate::prompts::active(pool, "summary").await?;
+    let bahasa = crate::lang::prompt_name(lang);
+    let mut text = crate::prompts::render(
+        &prompt.template,
+        &[("payload", &payload), ("app", &app_name), ("bahasa", bahasa)],
+    );
+    text.push_str(&crate::lang::instruction(lang));
+    let api_key = std::env::var("OPENAI_API_KEY").unwrap_or_default();
+    let ctx = LlmCtx { pool, app_id: Some(app_id), file_id: None, kind: "summary", user, lang };
+    let summary = crate::openai::analisa(&api_key, &text, &ctx, on_token).await?;
+
+    sqlx::query(
+        "INSERT INTO app_summary (app_id, lang, summary, prompt_version) VALUES (?, ?, ?, ?)
+         ON DUPLICATE KEY UPDATE summary=VALUES(summary), prompt_version=VALUES(prompt_version),
+             created_at=CURRENT_TIMESTAMP",
+    )
+    .bind(app_id)
+    .bind(lang)
+    .bind(&summary)
+    .bind(prompt.version)
+    .execute(pool)
+    .await?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(id: i64, path: &str, analyzed: i64) -> BulkCandidate {
+        let (folder, name) = path.rsplit_once('/').map(|(d, n)| (Some(d.to_string()), n)).unwrap_or((None, path));
+        BulkCandidate { id, nama_file: name.into(), nama_folder: folder, full_path: path.into(), analyzed }
+    }
+
+    fn selected(scope: &str, q: &str) -> Vec<i64> {
+        let files = [file(1, "src/Api/users.rs", 3), file(2, "src/db.rs", 1), file(3, "README.md", 0)];
+        files.iter().filter(|c| bulk_selected(scope, q, c, 3)).map(|c| c.id).collect()
+    }
+
+    #[test]
+    fn bulk_scope_selects_files() {
+        assert_eq!(selected("all", ""), [1, 2, 3]);
+        assert_eq!(selected("filtered", "api"), [1]);
+        assert_eq!(selected("filtered", "SRC/"), [1, 2]);
+        assert_eq!(selected("filtered", "readme"), [3]);
+        assert!(selected("filtered", "nothing").is_empty());
+        // file 1 sudah punya ketiga jenis analisa aktif
+        assert_eq!(selected("pending", ""), [2, 3]);
+    }
+}
@@ -0,0 +1,245 @@
+// src/symbols.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Indeks simbol: setiap definisi (fungsi, method, class, struct, trait, …)
+// beserta signature, rentang baris dan visibilitasnya. Tabel code_symbols
+// diisi src/callgraph.rs saat upload (parsing tanpa LLM); modul ini
+// menampilkannya sebagai outline satu file dan pencarian definisi di seluruh
+// aplikasi.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+/// Batas hasil pencarian simbol.
+pub const SEARCH_LIMIT: usize = 200;
+
+/// Batas `limit` yang boleh diminta lewat API.
+const MAX_LIMIT: usize = 1000;
+
+/// Jenis simbol yang bisa dipakai sebagai filter `kind`.
+pub const KINDS: &[&str] = &["function", "method", "class", "interface", "trait", "struct", "enum", "impl", "module"];
+
+/// Parameter pencarian dari query string: `q`, `kind` (hanya jenis yang
+/// dikenal) dan `limit` (default SEARCH_LIMIT, 1..=MAX_LIMIT).
+pub fn params(qs: &HashMap<String, String>) -> (&str, Option<&'static str>, usize) {
+    let q = qs.get("q").map(|v| v.trim()).unwrap_or("");
+    let kind = qs.get("kind").and_then(|k| KINDS.iter().find(|known| **known == k.trim()).copied());
+    let limit = qs
+        .get("limit")
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(SEARCH_LIMIT)
+        .clamp(1, MAX_LIMIT);
+    (q, kind, limit)
+}
+
+#[derive(sqlx::FromRow)]
+struct SymbolRow {
+    id: i64,
+    file_id: i64,
+    path: String,
+    parent_id: Option<i64>,
+    name: String,
+    kind: String,
+    container: Option<String>,
+    visibility: String,
+    signature: String,
+    line_start: i32,
+    line_end: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Symbol {
+    pub id: i64,
+    pub file_id: i64,
+    pub path: String,
+    pub parent_id: Option<i64>,
+    pub name: String,
+    /// function | method | class | interface | trait | struct | enum | impl | module
+    pub kind: String,
+    pub container: Option<String>,
+    /// `Kontainer::nama` / `Kontainer.nama`, atau `nama`.
+    pub qualified: String,
+    /// public | protected | private | internal
+    pub visibility: String,
+    pub signature: String,
+    pub line_start: i32,
+    pub line_end: i32,
+    /// Kedalaman di pohon definisi file (0 = tingkat atas).
+    pub depth: usize,
+    /// Penampil file dengan baris definisi ini disorot.
+    pub href: String,
+}
+
+impl Symbol {
+    pub fn callable(&self) -> bool {
+        matches!(self.kind.as_str(), "function" | "method")
+    }
+
+    /// Warna badge visibilitas.
+    pub fn badge(&self) -> &'static str {
+        match self.visibility.as_str() {
+            "public" => "success",
+            "protected" => "warning",
+            "internal" => "info",
+            _ => "secondary",
+        }
+    }
+
+    fn from_row(r: SymbolRow, depth: usize) -> Symbol {
+        Symbol {
+            qualified: crate::callgraph::qualified(&r.path, &r.kind, r.container.as_deref(), &r.name),
+            href: crate::services::line_href(r.file_id, r.line_start, r.line_end),
+            id: r.id,
+            file_id: r.file_id,
+            path: r.path,
+            parent_id: r.parent_id,
+            name: r.name,
+            kind: r.kind,
+            container: r.container,
+            visibility: r.visibility,
+            signature: r.signature,
+            line_start: r.line_start,
+            line_end: r.line_end,
+            depth,
+        }
+    }
+}
+
+const COLUMNS: &str = "s.id, s.file_id, f.full_path AS path, s.parent_id, s.name, s.kind, s.container, s.visibility,
+     s.signature, s.line_start, s.line_end";
+
+/// Outline satu file: definisi urut baris, dengan kedalaman pohonnya.
+/// None = file tidak ada.
+pub async fn outline(pool: &MySqlPool, file_id: i64) -> anyhow::Result<Option<Vec<Symbol>>> {
+    let app: Option<(i64,)> = sqlx::query_as("SELECT app_id FROM files WHERE id=?")
+        .bind(file_id)
+        .fetch_optional(pool)
+        .await?;
+    let Some((app_id,)) = app else {
+        return Ok(None);
+    };
+    crate::callgraph::ensure_indexed(pool, app_id).await?;
+    let rows: Vec<SymbolRow> = sqlx::query_as(&format!(
+        "SELECT {COLUMNS} FROM code_symbols s JOIN files f ON f.id = s.file_id
+         WHERE s.file_id=? ORDER BY s.line_start, s.id"
+    ))
+    .bind(file_id)
+    .fetch_all(pool)
+    .await?;
+    // Induk selalu dimulai sebelum (atau sebaris dengan) anaknya
+    let mut depths: HashMap<i64, usize> = HashMap::new();
+    let symbols = rows
+        .into_iter()
+        .map(|r| {
+            let depth = r.parent_id.and_then(|p| depths.get(&p)).map(|d| d + 1).unwrap_or(0);
+            depths.insert(r.id, depth);
+            Symbol::from_row(r, depth)
+        })
+        .collect();
+    Ok(Some(symbols))
+}
+
+/// Definisi di aplikasi yang namanya (atau kontainernya) memuat `q`; nama
+/// yang sama persis lebih dulu, lalu yang berawalan `q`. Mengembalikan
+/// paling banyak `limit` simbol dan jumlah seluruh yang cocok.
+pub async fn search(
+    pool: &MySqlPool,
+    app_id: i64,
+    q: &str,
+    kind: Option<&str>,
+    limit: usize,
+) -> anyhow::Result<(Vec<Symbol>, i64)> {
+    crate::callgraph::ensure_indexed(pool, app_id).await?;
+    let q = q.trim();
+    let pattern = crate::fulltext::like(q);
+    let filter = "s.app_id=? AND (s.name LIKE ? OR s.container LIKE ?) AND (? = '' OR s.kind = ?)";
+    let (total,): (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM code_symbols s WHERE {filter}"))
+        .bind(app_id)
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(kind.unwrap_or(""))
+        .bind(kind.unwrap_or(""))
+        .fetch_one(pool)
+        .await?;
+    let rows: Vec<SymbolRow> = sqlx::query_as(&format!(
+        "SELECT {COLUMNS} FROM code_symbols s JOIN files f ON f.id = s.file_id
+         WHERE {filter}
+         ORDER BY s.name = ? DESC, LOCATE(?, s.name) = 1 DESC, s.name, f.full_path, s.line_start
+         LIMIT ?"
+    ))
+    .bind(app_id)
+    .bind(&pattern)
+    .bind(&pattern)
+    .bind(kind.unwrap_or(""))
+    .bind(kind.unwrap_or(""))
+    .bind(q)
+    .bind(q)
+    .bind(limit as i64)
+    .fetch_all(pool)
+    .await?;
+    Ok((rows.into_iter().map(|r| Symbol::from_row(r, 0)).collect(), total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qs(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn params_keep_only_known_kinds() {
+        assert_eq!(params(&qs(&[("q", " load "), ("kind", " method")])), ("load", Some("method"), SEARCH_LIMIT));
+        assert_eq!(params(&qs(&[("kind", "Method")])).1, None);
+        assert_eq!(params(&qs(&[("kind", "variable")])).1, None);
+        assert_eq!(params(&qs(&[])), ("", None, SEARCH_LIMIT));
+    }
+
+    #[test]
+    fn params_clamp_limit() {
+        assert_eq!(params(&qs(&[("limit", "25")])).2, 25);
+        assert_eq!(params(&qs(&[("limit", "0")])).2, 1);
+        assert_eq!(params(&qs(&[("limit", "50000")])).2, MAX_LIMIT);
+        assert_eq!(params(&qs(&[("limit", "-3")])).2, SEARCH_LIMIT);
+        assert_eq!(params(&qs(&[("limit", "banyak")])).2, SEARCH_LIMIT);
+    }
+
+    #[test]
+    fn visibility_badges() {
+        let badge = |visibility: &str| {
+            let mut s = Symbol {
+                id: 1,
+                file_id: 1,
+                path: "a.rs".into(),
+                parent_id: None,
+                name: "f".into(),
+                kind: "function".into(),
+                container: None,
+                qualified: "f".into(),
+                visibility: String::new(),
+                signature: String::new(),
+                line_start: 1,
+                line_end: 1,
+                depth: 0,
+                href: String::new(),
+            };
+            s.visibility = visibility.into();
+            s.badge()
+        };
+        assert_eq!(badge("public"), "success");
+        assert_eq!(badge("protected"), "warning");
+        assert_eq!(badge("internal"), "info");
+        assert_eq!(badge("private"), "secondary");
+    }
+}
@@ -0,0 +1,334 @@
+// src/usage.rs
+/*
+=============================================================================
+Project : AI CodeReview Rust
+Author : Kukuh Tripamungkas Wicaksono (Kukuh TW)
+Email : kukuhtw@gmail.com
+WhatsApp : https://wa.me/628129893706
+LinkedIn : https://id.linkedin.com/in/kukuhtw
+=============================================================================/
+*/
+
+// Pencatatan pemakaian LLM: setiap panggilan lewat openai.rs dicatat ke tabel
+// `llm_calls` (model, token prompt/completion, latency, estimasi biaya) dan
+// ditautkan ke aplikasi / file / jenis analisa / user yang memicunya.
+
+use std::time::Instant;
+
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+/// Konteks sebuah panggilan LLM (untuk atribusi biaya).
+#[derive(Clone)]
+pub struct LlmCtx<'a> {
+    pub pool: &'a MySqlPool,
+    pub app_id: Option<i64>,
+    pub file_id: Option<i64>,
+    pub kind: &'a str,
+    pub user: Option<&'a str>,
+    /// Bahasa output; menentukan pesan system ke LLM.
+    pub lang: &'a str,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TokenUsage {
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+}
+
+impl TokenUsage {
+    /// Ambil field `usage` dari balasan OpenAI (bila ada).
+    pub fn from_response(v: &serde_json::Value) -> Option<Self> {
+        let u = v.get("usage").filter(|u| !u.is_null())?;
+        Some(TokenUsage {
+            prompt_tokens: u.get("prompt_tokens").and_then(|n| n.as_i64()).unwrap_or(0),
+            completion_tokens: u.get("completion_tokens").and_then(|n| n.as_i64()).unwrap_or(0),
+        })
+    }
+
+    /// Perkiraan kasar (~4 karakter per token) bila API tidak mengirim usage.
+    pub fn estimate(prompt: &str, completion: &str) -> Self {
+        TokenUsage {
+            prompt_tokens: estimate_tokens(prompt),
+            completion_tokens: estimate_tokens(completion),
+        }
+    }
+}
+
+pub fn estimate_tokens(s: &str) -> i64 {
+    (s.chars().count() as i64 + 3) / 4
+}
+
+/// Model yang dipakai untuk semua panggilan (OPENAI_MODEL, default gpt-5).
+pub fn model() -> String {
+    std::env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-5".to_string())
+}
+
+/// Harga daftar USD per 1 juta token (input, output) menurut prefix model.
+fn list_price(model: &str) -> (f64, f64) {
+    match model {
+        m if m.starts_with("gpt-5-nano") => (0.05, 0.40),
+        m if m.starts_with("gpt-5-mini") => (0.25, 2.00),
+        m if m.starts_with("gpt-5") => (1.25, 10.00),
+        m if m.starts_with("gpt-4.1-nano") => (0.10, 0.40),
+        m if m.starts_with("gpt-4.1-mini") => (0.40, 1.60),
+        m if m.starts_with("gpt-4.1") => (2.00, 8.00),
+        m if m.starts_with("gpt-4o-mini") => (0.15, 0.60),
+        m if m.starts_with("gpt-4o") => (2.50, 10.00),
+        m if m.starts_with("text-embedding-3-small") => (0.02, 0.0),
+        m if m.starts_with("text-embedding-3-large") => (0.13, 0.0),
+        m if m.starts_with("text-embedding-ada") => (0.10, 0.0),
+        _ => (1.25, 10.00),
+    }
+}
+
+/// Harga USD per 1 juta token (input, output). Bisa dioverride lewat
+/// LLM_PRICE_INPUT_PER_1M / LLM_PRICE_OUTPUT_PER_1M.
+pub fn price_per_million(model: &str) -> (f64, f64) {
+    let (input, output) = list_price(model);
+    let env_price = |key: &str| std::env::var(key).ok().and_then(|s| s.parse::<f64>().ok());
+    (
+        env_price("LLM_PRICE_INPUT_PER_1M").unwrap_or(input),
+        env_price("LLM_PRICE_OUTPUT_PER_1M").unwrap_or(output),
+    )
+}
+
+pub fn estimate_cost(model: &str, usage: TokenUsage) -> f64 {
+    let (input, output) = price_per_million(model);
+    (usage.prompt_tokens as f64 * input + usage.completion_tokens as f64 * output) / 1_000_000.0
+}
+
+/// Pencatat satu panggilan. Panggil `finish` saat selesai; bila future
+/// panggilan di-drop (mis. job dibatalkan) sebelum selesai, Drop tetap
+/// mencatat panggilan sebagai "cancelled" dengan token hasil estimasi,
+/// karena token yang sudah terpakai tetap ditagih. Pesanan budget panggilan
+/// baru dilepas setelah barisnya masuk ke llm_calls.
+pub struct CallRecorder {
+    pool: MySqlPool,
+    app_id: Option<i64>,
+    file_id: Option<i64>,
+    kind: String,
+    user: Option<String>,
+    model: String,
+    started: Instant,
+    prompt: String,
+    partial: String,
+    finished: bool,
+    reservation: Option<crate::budget::Reservation>,
+}
+
+impl CallRecorder {
+    pub fn start(ctx: &LlmCtx<'_>, model: &str, prompt: &str, reservation: crate::budget::Reservation) -> Self {
+        CallRecorder {
+            pool: ctx.pool.clone(),
+            app_id: ctx.app_id,
+            file_id: ctx.file_id,
+            kind: ctx.kind.to_string(),
+            user: ctx.user.map(str::to_string),
+            model: model.to_string(),
+            started: Instant::now(),
+            prompt: prompt.to_string(),
+            partial: String::new(),
+            finished: false,
+            reservation: Some(reservation),
+        }
+    }
+
+    /// Teks yang sudah diterima (untuk estimasi bila dibatalkan).
+    pub fn push_output(&mut self, delta: &str) {
+        self.partial.push_str(delta);
+    }
+
+    pub async fn finish(mut self, usage: Option<TokenUsage>, error: Option<&str>) {
+        self.finished = true;
+        let usage = usage.unwrap_or_else(|| TokenUsage::estimate(&self.prompt, &self.partial));
+        let status = if error.is_some() { "error" } else { "ok" };
+        if let Err(e) = self.insert(usage, status, error).await {
+            eprintln!("Gagal mencatat llm_calls: {e:?}");
+        }
+        self.reservation = None;
+    }
+
+    async fn insert(&self, usage: TokenUsage, status: &str, error: Option<&str>) -> anyhow::Result<()> {
+        let latency_ms = self.started.elapsed().as_millis() as i64;
+        let cost = estimate_cost(&self.model, usage);
+        sqlx::query(
+            "INSERT INTO llm_calls
+               (app_id, file_id, kind, model, prompt_tokens, completion_tokens,
+                latency_ms, cost_usd, status, error, requested_by)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(self.app_id)
+        .bind(self.file_id)
+        .bind(&self.kind)
+        .bind(&self.model)
+        .bind(usage.prompt_tokens)
+        .bind(usage.completion_tokens)
+        .bind(latency_ms)
+        .bind(cost)
+        .bind(status)
+        .bind(error)
+        .bind(&self.user)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+impl Drop for CallRecorder {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let rec = CallRecorder {
+            pool: self.pool.clone(),
+            app_id: self.app_id,
+            file_id: self.file_id,
+            kind: std::mem::take(&mut self.kind),
+            user: self.user.take(),
+            model: std::mem::take(&mut self.model),
+            started: self.started,
+            prompt: String::new(),
+            partial: String::new(),
+            finished: true,
+            reservation: self.reservation.take(),
+        };
+        let usage = TokenUsage::estimate(&self.prompt, &self.partial);
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                if let Err(e) = rec.insert(usage, "cancelled", None).await {
+                    eprintln!("Gagal mencatat llm_calls (cancelled): {e:?}");
+                }
+            });
+        }
+    }
+}
+
+// ===== Laporan pemakaian =====
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct UsageTotals {
+    pub calls: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cost_usd: f64,
+    pub avg_latency_ms: f64,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct UsageGroup {
+    pub label: String,
+    pub calls: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageReport {
+    pub days: i64,
+    pub app_id: Option<i64>,
+    pub totals: UsageTotals,
+    pub by_day: Vec<UsageGroup>,
+    pub by_app: Vec<UsageGroup>,
+    pub by_kind: Vec<UsageGroup>,
+    pub by_model: Vec<UsageGroup>,
+    pub by_user: Vec<UsageGroup>,
+}
+
+const SUMS: &str = "COUNT(*) AS calls,
+    CAST(COALESCE(SUM(c.prompt_tokens),0) AS SIGNED) AS prompt_tokens,
+    CAST(COALESCE(SUM(c.completion_tokens),0) AS SIGNED) AS completion_tokens,
+    CAST(COALESCE(SUM(c.cost_usd),0) AS DOUBLE) AS cost_usd";
+
+async fn grouped(
+    pool: &MySqlPool,
+    label_expr: &str,
+    join: &str,
+    order: &str,
+    days: i64,
+    app_id: Option<i64>,
+) -> anyhow::Result<Vec<UsageGroup>> {
+    let q = format!(
+        "SELECT {label_expr} AS label, {SUMS}
+         FROM llm_calls c {join}
+         WHERE c.created_at >= NOW() - INTERVAL ? DAY AND (? IS NULL OR c.app_id = ?)
+         GROUP BY label ORDER BY {order}"
+    );
+    Ok(sqlx::query_as::<_, UsageGroup>(&q)
+        .bind(days)
+        .bind(app_id)
+        .bind(app_id)
+        .fetch_all(pool)
+        .await?)
+}
+
+/// Ringkasan pemakaian `days` hari terakhir, opsional untuk satu aplikasi.
+pub async fn report(pool: &MySqlPool, days: i64, app_id: Option<i64>) -> anyhow::Result<UsageReport> {
+    let q = format!(
+        "SELECT {SUMS}, CAST(COALESCE(AVG(c.latency_ms),0) AS DOUBLE) AS avg_latency_ms
+         FROM llm_calls c
+         WHERE c.created_at >= NOW() - INTERVAL ? DAY AND (? IS NULL OR c.app_id = ?)"
+    );
+    let totals = sqlx::query_as::<_, UsageTotals>(&q)
+        .bind(days)
+        .bind(app_id)
+        .bind(app_id)
+        .fetch_one(pool)
+        .await?;
+
+    let app_label = "COALESCE(a.nama_aplikasi, CONCAT('(app ', COALESCE(c.app_id, '-'), ')'))";
+    let app_join = "LEFT JOIN applications a ON a.id = c.app_id";
+    Ok(UsageReport {
+        days,
+        app_id,
+        totals,
+        by_day: grouped(pool, "DATE_FORMAT(c.created_at, '%Y-%m-%d')", "", "label DESC", days, app_id).await?,
+        by_app: grouped(pool, app_label, app_join, "cost_usd DESC", days, app_id).await?,
+        by_kind: grouped(pool, "c.kind", "", "cost_usd DESC", days, app_id).await?,
+        by_model: grouped(pool, "c.model", "", "cost_usd DESC", days, app_id).await?,
+        by_user: grouped(pool, "COALESCE(c.requested_by, '(anonim)')", "", "cost_usd DESC", days, app_id).await?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn list_price_matches_longest_model_prefix() {
+        assert_eq!(list_price("gpt-5"), (1.25, 10.00));
+        assert_eq!(list_price("gpt-5-mini-2025-08-07"), (0.25, 2.00));
+        assert_eq!(list_price("gpt-5-nano"), (0.05, 0.40));
+        assert_eq!(list_price("gpt-4.1-mini"), (0.40, 1.60));
+        assert_eq!(list_price("gpt-4o-mini"), (0.15, 0.60));
+        assert_eq!(list_price("gpt-4o-2024-08-06"), (2.50, 10.00));
+        assert_eq!(list_price("text-embedding-3-small"), (0.02, 0.0));
+        // model tak dikenal memakai harga gpt-5
+        assert_eq!(list_price("llama-3"), (1.25, 10.00));
+    }
+
+    #[test]
+    fn usage_from_response() {
+        let u = TokenUsage::from_response(&json!({"usage": {"prompt_tokens": 120, "completion_tokens": 30}})).unwrap();
+        assert_eq!((u.prompt_tokens, u.completion_tokens), (120, 30));
+        // balasan embedding hanya punya prompt_tokens
+        let u = TokenUsage::from_response(&json!({"usage": {"prompt_tokens": 8, "total_tokens": 8}})).unwrap();
+        assert_eq!((u.prompt_tokens, u.completion_tokens), (8, 0));
+        assert!(TokenUsage::from_response(&json!({"usage": null})).is_none());
+        assert!(TokenUsage::from_response(&json!({"choices": []})).is_none());
+    }
+
+    #[test]
+    fn estimate_rounds_up_per_four_chars() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abc"), 1);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+        // dihitung per karakter, bukan per byte
+        assert_eq!(estimate_tokens("日本語です"), 2);
+        let u = TokenUsage::estimate(&"x".repeat(40), "yy");
+        assert_eq!((u.prompt_tokens, u.completion_tokens), (10, 1));
+    }
+}
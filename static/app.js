@@ -0,0 +1,12 @@
+// static/app.js — helper bersama; dimuat setelah window.tr didefinisikan di base.html
+
+// Tanpa vis-network: tampilkan gambar SVG hasil render server
+window.graphFallback = (wrapId, src) => {
+  const wrap = document.getElementById(wrapId);
+  if (!wrap || !src) return;
+  const img = document.createElement('img');
+  img.src = src;
+  img.alt = tr('graph.static_alt');
+  img.className = 'img-fluid border rounded';
+  wrap.replaceChildren(img);
+};